@@ -0,0 +1,107 @@
+//! `shadowbid-bench`: measures the compute units of every ShadowBid
+//! instruction under a local SVM and flags regressions against a baseline
+
+mod report;
+mod scenarios;
+
+use std::path::PathBuf;
+
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+
+use crate::report::{Report, DEFAULT_INSTRUCTION_LIMIT};
+
+#[derive(Parser)]
+#[command(name = "shadowbid-bench", version, about = "Benchmarks ShadowBid compute units")]
+struct Args {
+    /// Directory holding the compiled shadowbid.so and mock_inco.so
+    #[arg(long, env = "SBF_OUT_DIR", default_value = "target/deploy")]
+    sbf_out_dir: PathBuf,
+
+    /// Bidders per benchmarked auction
+    #[arg(long, default_value_t = 4)]
+    bids: usize,
+
+    /// Where to write the JSON report
+    #[arg(long, default_value = "target/bench/compute-units.json")]
+    json: PathBuf,
+
+    /// Where to write the Markdown report
+    #[arg(long, default_value = "target/bench/compute-units.md")]
+    markdown: PathBuf,
+
+    /// JSON report of a previous run to compare against
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Percent increase over the baseline that fails the run
+    #[arg(long, default_value_t = 5.0)]
+    threshold: f64,
+
+    /// Units any single instruction may consume before the run fails
+    #[arg(long, default_value_t = DEFAULT_INSTRUCTION_LIMIT)]
+    max_units: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    ensure!(args.bids >= 2, "--bids must be at least 2");
+
+    // Native builtins aren't metered, so only the compiled programs
+    // give real numbers
+    for program in ["shadowbid.so", "mock_inco.so"] {
+        let path = args.sbf_out_dir.join(program);
+        ensure!(
+            path.is_file(),
+            "{} not found; build the programs with `anchor build` or set --sbf-out-dir",
+            path.display()
+        );
+    }
+    std::env::set_var("SBF_OUT_DIR", &args.sbf_out_dir);
+    if std::env::var_os("RUST_LOG").is_none() {
+        std::env::set_var("RUST_LOG", "error");
+    }
+
+    let baseline = args.baseline.as_deref().map(Report::read).transpose()?;
+    let report = Report {
+        program: shadowbid::ID.to_string(),
+        backend: "mock-inco".to_string(),
+        bids: args.bids,
+        measurements: scenarios::run(args.bids).await?,
+    };
+    if let Some(baseline) = &baseline {
+        if baseline.bids != report.bids {
+            eprintln!(
+                "warning: baseline ran with {} bids, this run with {}",
+                baseline.bids, report.bids
+            );
+        }
+    }
+
+    let comparisons = report.compare(baseline.as_ref());
+    report.write_json(&args.json)?;
+    report.write_markdown(&args.markdown, &comparisons, args.threshold)?;
+    print!("{}", report.markdown(&comparisons, args.threshold));
+
+    let mut failures = Vec::new();
+    for comparison in &comparisons {
+        let m = comparison.measurement;
+        if m.units > args.max_units {
+            failures.push(format!("{} uses {} CU, over the {} limit", m.key(), m.units, args.max_units));
+        }
+        if let (true, Some(baseline), Some(change)) =
+            (comparison.regressed(args.threshold), comparison.baseline, comparison.change())
+        {
+            failures.push(format!(
+                "{} regressed {change:.1}% ({baseline} -> {} CU)",
+                m.key(),
+                m.units
+            ));
+        }
+    }
+    if !failures.is_empty() {
+        bail!("compute budget exceeded:\n  {}", failures.join("\n  "));
+    }
+    Ok(())
+}
@@ -0,0 +1,166 @@
+//! Benchmark report: JSON for baselines, Markdown for humans
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Per-instruction compute budget when a transaction doesn't request one
+pub const DEFAULT_INSTRUCTION_LIMIT: u64 = 200_000;
+
+/// Per-transaction compute ceiling
+pub const MAX_TRANSACTION_LIMIT: u64 = 1_400_000;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Measurement {
+    pub instruction: String,
+    /// Which path through the instruction was exercised
+    pub scenario: String,
+    /// Highest units consumed across the samples
+    pub units: u64,
+    pub samples: u32,
+}
+
+impl Measurement {
+    pub fn key(&self) -> String {
+        format!("{} ({})", self.instruction, self.scenario)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Report {
+    pub program: String,
+    /// Inco backend the CPIs ran against
+    pub backend: String,
+    /// Bidders per auction
+    pub bids: usize,
+    pub measurements: Vec<Measurement>,
+}
+
+/// A measurement against its baseline
+pub struct Comparison<'a> {
+    pub measurement: &'a Measurement,
+    pub baseline: Option<u64>,
+}
+
+impl Comparison<'_> {
+    /// Change from the baseline, in percent
+    pub fn change(&self) -> Option<f64> {
+        self.baseline
+            .filter(|&baseline| baseline > 0)
+            .map(|baseline| (self.measurement.units as f64 - baseline as f64) * 100.0 / baseline as f64)
+    }
+
+    pub fn regressed(&self, threshold: f64) -> bool {
+        self.change().is_some_and(|change| change > threshold)
+    }
+}
+
+impl Report {
+    pub fn read(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        write(path, &serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn compare<'a>(&'a self, baseline: Option<&Report>) -> Vec<Comparison<'a>> {
+        self.measurements
+            .iter()
+            .map(|measurement| Comparison {
+                measurement,
+                baseline: baseline.and_then(|baseline| {
+                    baseline
+                        .measurements
+                        .iter()
+                        .find(|b| b.key() == measurement.key())
+                        .map(|b| b.units)
+                }),
+            })
+            .collect()
+    }
+
+    /// Units to determine the winner over `bids` sealed bids
+    fn sealed_determine_total(&self) -> Option<u64> {
+        let units = |scenario: &str| {
+            self.measurements
+                .iter()
+                .find(|m| m.instruction == "determine_winner" && m.scenario == scenario)
+                .map(|m| m.units)
+        };
+        let first = units("sealed, first bid")?;
+        let later = units("sealed, later bid").unwrap_or(first);
+        Some(first + later * self.bids.saturating_sub(1) as u64)
+    }
+
+    pub fn markdown(&self, comparisons: &[Comparison], threshold: f64) -> String {
+        let mut out = String::new();
+        let has_baseline = comparisons.iter().any(|c| c.baseline.is_some());
+
+        let _ = writeln!(out, "# ShadowBid compute units\n");
+        let _ = writeln!(
+            out,
+            "Program `{}` against the `{}` backend with {} bids per auction.\n",
+            self.program, self.backend, self.bids
+        );
+
+        if has_baseline {
+            let _ = writeln!(out, "| Instruction | Scenario | CU | % of 200k | Baseline | Change |");
+            let _ = writeln!(out, "|---|---|---:|---:|---:|---:|");
+        } else {
+            let _ = writeln!(out, "| Instruction | Scenario | CU | % of 200k |");
+            let _ = writeln!(out, "|---|---|---:|---:|");
+        }
+        for comparison in comparisons {
+            let m = comparison.measurement;
+            let share = m.units as f64 * 100.0 / DEFAULT_INSTRUCTION_LIMIT as f64;
+            let _ = write!(out, "| `{}` | {} | {} | {share:.1}% |", m.instruction, m.scenario, m.units);
+            if has_baseline {
+                let baseline = comparison
+                    .baseline
+                    .map_or_else(|| "-".to_string(), |b| b.to_string());
+                let change = match comparison.change() {
+                    Some(change) if comparison.regressed(threshold) => format!("**{change:+.1}%**"),
+                    Some(change) => format!("{change:+.1}%"),
+                    None => "new".to_string(),
+                };
+                let _ = write!(out, " {baseline} | {change} |");
+            }
+            let _ = writeln!(out);
+        }
+
+        if let Some(total) = self.sealed_determine_total() {
+            let per_bid = self
+                .measurements
+                .iter()
+                .filter(|m| m.instruction == "determine_winner" && m.scenario.starts_with("sealed"))
+                .map(|m| m.units)
+                .max()
+                .unwrap_or(total);
+            let _ = writeln!(
+                out,
+                "\nDetermining the winner over {} sealed bids takes {total} CU in total. \
+                 At most {} `determine_winner` instructions fit one transaction's \
+                 {MAX_TRANSACTION_LIMIT} CU, before the transaction size limit.",
+                self.bids,
+                MAX_TRANSACTION_LIMIT / per_bid.max(1)
+            );
+        }
+        out
+    }
+
+    pub fn write_markdown(&self, path: &Path, comparisons: &[Comparison], threshold: f64) -> Result<()> {
+        write(path, &self.markdown(comparisons, threshold))
+    }
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    std::fs::write(path, contents).with_context(|| format!("writing {}", path.display()))
+}
@@ -0,0 +1,550 @@
+//! Drives every ShadowBid instruction through a local SVM and records the
+//! compute units each one consumed
+
+use std::collections::BTreeMap;
+
+use anchor_lang::system_program;
+use anyhow::{anyhow, Context, Result};
+use mock_inco::codec;
+use shadowbid::constants::{MAX_METADATA_DESCRIPTION_LENGTH, MAX_METADATA_TITLE_LENGTH, MAX_TAGS, MAX_TAG_LENGTH};
+use shadowbid::instructions::{
+    ArbitrationParams, AuctionMetadataParams, CommitRevealParams, CreateAuctionParams,
+};
+use shadowbid::state::{Auction, Bid, CancellationReason, Permission, Versioned};
+use shadowbid_client::{accounts, instructions as ix, pda};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::clock::Clock;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::report::Measurement;
+
+/// Per-transaction compute limit, so measurement never fails on the
+/// default 200k budget
+const COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+const RESERVE_PRICE: u64 = 1_000;
+const DURATION: i64 = 600;
+const REVEAL_DURATION: i64 = 300;
+const BID_COLLATERAL: u64 = 50_000;
+const SELLER_BOND: u64 = 1_000_000;
+const DELIVERY_TIMEOUT: i64 = 86_400;
+const ARBITRATION_FEE_BPS: u16 = 250;
+const EVIDENCE_URI: &str = "ar://Qm4bYh8kVvSx3rXoGKpZ1nD7yE2cFwTtLmU9aHjR6sPe";
+
+/// Runs every scenario with `bids` bidders per auction
+pub async fn run(bids: usize) -> Result<Vec<Measurement>> {
+    let mut bench = Bench::new().await?;
+    sealed(&mut bench, bids).await?;
+    commit_reveal(&mut bench, bids).await?;
+    metadata(&mut bench).await?;
+    cancel(&mut bench).await?;
+    escrow(&mut bench).await?;
+    authority(&mut bench).await?;
+    migration(&mut bench).await?;
+    Ok(bench.measurements.into_values().collect())
+}
+
+/// Sealed auction from creation through settlement and stats publishing
+async fn sealed(bench: &mut Bench, bids: usize) -> Result<()> {
+    let auction = bench.create(false, None, None).await?;
+
+    // Ascending amounts, so every bid after the first takes the lead
+    let mut bidders = Vec::new();
+    for i in 0..bids {
+        let bidder = bench.keypair();
+        let amount = RESERVE_PRICE * (i as u64 + 2);
+        let place = ix::place_bid(&bidder.pubkey(), &auction, codec::encrypt(amount));
+        bench.measure("place_bid", "sealed, new bid", place, &[&bidder]).await?;
+        bidders.push(bidder);
+    }
+    let update = ix::place_bid(&bidders[0].pubkey(), &auction, codec::encrypt(RESERVE_PRICE));
+    bench.measure("place_bid", "sealed, update", update, &[&bidders[0]]).await?;
+
+    let state = bench.auction(&auction).await?;
+    bench.warp_to(state.end_time).await?;
+    let close = ix::close_bidding(&bench.payer(), &auction, &state);
+    bench.measure("close_bidding", "sealed", close, &[]).await?;
+
+    for (i, bidder) in bidders.iter().enumerate() {
+        let state = bench.auction(&auction).await?;
+        let determine = ix::determine_winner(&bench.payer(), &auction, &state, &bidder.pubkey());
+        let scenario = if i == 0 { "sealed, first bid" } else { "sealed, later bid" };
+        bench.measure("determine_winner", scenario, determine, &[]).await?;
+    }
+
+    let state = bench.auction(&auction).await?;
+    let finalize = ix::finalize_winner(&bench.payer(), &auction, &state, true);
+    bench.measure("finalize_winner", "sealed, winner and seller", finalize, &[]).await?;
+
+    let state = bench.auction(&auction).await?;
+    let winner = bidders
+        .iter()
+        .find(|bidder| bidder.pubkey() == state.winner)
+        .context("winner is not a benchmark bidder")?;
+    let plaintext = codec::decrypt(state.highest_bid_handle).context("winning handle")?;
+    let settle = ix::settle_auction(
+        &auction,
+        &state,
+        &state.seller,
+        shadowbid::confidential::handle_to_bytes(state.highest_bid_handle),
+        codec::plaintext_bytes(plaintext),
+    );
+    bench.measure("settle_auction", "sealed", settle, &[winner]).await?;
+
+    let seller = bench.seller.insecure_clone();
+    let state = bench.auction(&auction).await?;
+    let reveal = ix::reveal_auction_stats(&auction, &state);
+    bench.measure("reveal_auction_stats", "sealed", reveal, &[&seller]).await?;
+
+    let plaintexts = [
+        state.bid_sum_handle,
+        state.lowest_bid_handle,
+        state.above_reserve_count_handle,
+    ]
+    .into_iter()
+    .map(|handle| codec::decrypt(handle).map(codec::plaintext_bytes))
+    .collect::<Option<Vec<_>>>()
+    .context("stats handles")?;
+    let publish = ix::publish_auction_stats(&auction, &state, plaintexts);
+    bench.measure("publish_auction_stats", "sealed", publish, &[&seller]).await?;
+    Ok(())
+}
+
+/// Bonded commit-reveal auction where the last bidder never reveals
+async fn commit_reveal(bench: &mut Bench, bids: usize) -> Result<()> {
+    let auction = bench.create(true, Some(SELLER_BOND), None).await?;
+
+    let mut bidders = Vec::new();
+    for i in 0..bids {
+        let bidder = bench.keypair();
+        let amount = RESERVE_PRICE * (i as u64 + 2);
+        let salt = [i as u8; 32];
+        let commitment = Bid::commitment_hash(amount, &salt, &bidder.pubkey());
+        let place = ix::place_bid(&bidder.pubkey(), &auction, commitment.to_vec());
+        bench.measure("place_bid", "commit-reveal, new bid", place, &[&bidder]).await?;
+        bidders.push((bidder, amount, salt));
+    }
+
+    let state = bench.auction(&auction).await?;
+    bench.warp_to(state.end_time).await?;
+    let close = ix::close_bidding(&bench.payer(), &auction, &state);
+    bench.measure("close_bidding", "commit-reveal", close, &[]).await?;
+
+    for (bidder, amount, salt) in &bidders[..bidders.len() - 1] {
+        let reveal = ix::reveal_bid(&bidder.pubkey(), &auction, *amount, *salt);
+        bench.measure("reveal_bid", "commit-reveal", reveal, &[bidder]).await?;
+    }
+
+    let reveal_end_time = bench.auction(&auction).await?.reveal_end_time;
+    bench.warp_to(reveal_end_time).await?;
+    let close = ix::close_reveal(&bench.payer(), &auction);
+    bench.measure("close_reveal", "commit-reveal", close, &[]).await?;
+
+    for (i, (bidder, _, _)) in bidders.iter().enumerate() {
+        let state = bench.auction(&auction).await?;
+        let determine = ix::determine_winner(&bench.payer(), &auction, &state, &bidder.pubkey());
+        let scenario = if i + 1 == bidders.len() {
+            "commit-reveal, forfeit"
+        } else {
+            "commit-reveal, revealed"
+        };
+        bench.measure("determine_winner", scenario, determine, &[]).await?;
+    }
+
+    let state = bench.auction(&auction).await?;
+    let finalize = ix::finalize_winner(&bench.payer(), &auction, &state, false);
+    bench.measure("finalize_winner", "commit-reveal", finalize, &[]).await?;
+
+    let state = bench.auction(&auction).await?;
+    let (winner, _, _) = bidders
+        .iter()
+        .find(|(bidder, _, _)| bidder.pubkey() == state.winner)
+        .context("winner is not a benchmark bidder")?;
+    let settle = ix::settle_auction(&auction, &state, &state.seller, Vec::new(), Vec::new());
+    bench.measure("settle_auction", "commit-reveal", settle, &[winner]).await?;
+
+    let seller = bench.seller.insecure_clone();
+    let release = ix::release_seller_bond(&auction, &state);
+    bench.measure("release_seller_bond", "settled", release, &[&seller]).await
+}
+
+/// Seller creates metadata, grows it to the size limits, then closes it
+async fn metadata(bench: &mut Bench) -> Result<()> {
+    let auction = bench.create(false, None, None).await?;
+    let seller = bench.seller.insecure_clone();
+    let mut params = AuctionMetadataParams {
+        title: "Benchmark item".to_string(),
+        description: "Compute unit benchmark".to_string(),
+        uri: "ar://benchmark".to_string(),
+        content_hash: [7; 32],
+        tags: vec!["bench".to_string()],
+    };
+    let create = ix::create_auction_metadata(&seller.pubkey(), &auction, params.clone());
+    bench.measure("create_auction_metadata", "new", create, &[&seller]).await?;
+
+    params.title = "t".repeat(MAX_METADATA_TITLE_LENGTH);
+    params.description = "d".repeat(MAX_METADATA_DESCRIPTION_LENGTH);
+    params.tags = vec!["t".repeat(MAX_TAG_LENGTH); MAX_TAGS];
+    let update = ix::update_auction_metadata(&seller.pubkey(), &auction, &seller.pubkey(), params);
+    bench.measure("update_auction_metadata", "grow to limits", update, &[&seller]).await?;
+
+    let close = ix::close_auction_metadata(&seller.pubkey(), &auction, &seller.pubkey());
+    bench.measure("close_auction_metadata", "no bids", close, &[&seller]).await
+}
+
+/// Seller cancels an open auction, a bonded one without bids, then a
+/// bonded one with bids, whose bidders claim their compensation
+async fn cancel(bench: &mut Bench) -> Result<()> {
+    let auction = bench.create(false, None, None).await?;
+    let seller = bench.seller.insecure_clone();
+    let state = bench.auction(&auction).await?;
+    let cancel = ix::cancel_auction(
+        &seller.pubkey(),
+        &auction,
+        &state,
+        "Item withdrawn".to_string(),
+        CancellationReason::ItemUnavailable,
+    );
+    bench.measure("cancel_auction", "open", cancel, &[&seller]).await?;
+
+    let auction = bench.create(false, Some(SELLER_BOND), None).await?;
+    let state = bench.auction(&auction).await?;
+    let cancel = ix::cancel_auction(
+        &seller.pubkey(),
+        &auction,
+        &state,
+        "Item withdrawn".to_string(),
+        CancellationReason::ItemUnavailable,
+    );
+    bench.measure("cancel_auction", "bonded, no bids", cancel, &[&seller]).await?;
+
+    let auction = bench.create(false, Some(SELLER_BOND), None).await?;
+    let bidders: Vec<_> = (0..3).map(|_| bench.keypair()).collect();
+    for bidder in &bidders {
+        let place = ix::place_bid(&bidder.pubkey(), &auction, codec::encrypt(RESERVE_PRICE));
+        bench.measure("place_bid", "sealed, new bid", place, &[bidder]).await?;
+    }
+    let state = bench.auction(&auction).await?;
+    bench.warp_to(state.end_time).await?;
+    let cancel = ix::cancel_auction(
+        &seller.pubkey(),
+        &auction,
+        &state,
+        "Item withdrawn".to_string(),
+        CancellationReason::ItemUnavailable,
+    );
+    bench.measure("cancel_auction", "bonded, with bids", cancel, &[&seller]).await?;
+
+    for (i, bidder) in bidders.iter().enumerate() {
+        let claim = ix::claim_compensation(&bidder.pubkey(), &auction, &state);
+        let scenario = if i + 1 == bidders.len() { "last, closes vault" } else { "sealed" };
+        bench.measure("claim_compensation", scenario, claim, &[bidder]).await?;
+    }
+    Ok(())
+}
+
+/// Escrowed auctions settled by one bidder each, then confirmed, released
+/// after the delivery window, or disputed and resolved
+async fn escrow(bench: &mut Bench) -> Result<()> {
+    let (auction, winner) = escrowed_sale(bench).await?;
+    let state = bench.auction(&auction).await?;
+    let confirm = ix::confirm_delivery(&auction, &state, &state.seller);
+    bench.measure("confirm_delivery", "escrowed", confirm, &[&winner]).await?;
+
+    let (auction, _) = escrowed_sale(bench).await?;
+    let state = bench.auction(&auction).await?;
+    bench.warp_to(state.delivery_deadline).await?;
+    let release = ix::release_escrow(&bench.payer(), &auction, &state, &state.seller);
+    bench.measure("release_escrow", "window ended", release, &[]).await?;
+
+    let (auction, winner) = escrowed_sale(bench).await?;
+    let state = bench.auction(&auction).await?;
+    let dispute = ix::dispute(&auction, &state, EVIDENCE_URI.to_string());
+    bench.measure("dispute", "escrowed", dispute, &[&winner]).await?;
+    let evidence = ix::submit_evidence(&bench.seller.pubkey(), &auction, EVIDENCE_URI.to_string());
+    let seller = bench.seller.insecure_clone();
+    bench.measure("submit_evidence", "seller", evidence, &[&seller]).await?;
+    let arbitrator = bench.arbitrator.insecure_clone();
+    let resolve = ix::resolve_dispute(&auction, &state, &state.seller, 5_000);
+    bench.measure("resolve_dispute", "split", resolve, &[&arbitrator]).await
+}
+
+/// Seller names a delegate, creating the authority record, the delegate
+/// cancels, and authority is handed on
+async fn authority(bench: &mut Bench) -> Result<()> {
+    let seller = bench.seller.insecure_clone();
+    let delegate = bench.keypair();
+    let auction = bench.create(false, None, None).await?;
+    let grant = ix::set_delegate(&seller.pubkey(), &auction, &delegate.pubkey(), Permission::Cancel.bit());
+    bench.measure("set_delegate", "creates record", grant, &[&seller]).await?;
+
+    let state = bench.auction(&auction).await?;
+    let cancel = ix::cancel_auction(
+        &delegate.pubkey(),
+        &auction,
+        &state,
+        "Item withdrawn".to_string(),
+        CancellationReason::ItemUnavailable,
+    );
+    bench.measure("cancel_auction", "by delegate", cancel, &[&delegate]).await?;
+
+    let transfer = ix::transfer_authority(&seller.pubkey(), &auction, &delegate.pubkey());
+    bench.measure("transfer_authority", "existing record", transfer, &[&seller]).await
+}
+
+/// Runs an escrowed auction with a single bidder through settlement,
+/// returning the auction and its winner
+async fn escrowed_sale(bench: &mut Bench) -> Result<(Pubkey, Keypair)> {
+    let auction = bench.create(false, None, Some(DELIVERY_TIMEOUT)).await?;
+    let winner = bench.keypair();
+    let amount = RESERVE_PRICE * 2;
+    let place = ix::place_bid(&winner.pubkey(), &auction, codec::encrypt(amount));
+    bench.measure("place_bid", "sealed, new bid", place, &[&winner]).await?;
+
+    let state = bench.auction(&auction).await?;
+    bench.warp_to(state.end_time).await?;
+    let close = ix::close_bidding(&bench.payer(), &auction, &state);
+    bench.measure("close_bidding", "sealed", close, &[]).await?;
+    let state = bench.auction(&auction).await?;
+    let determine = ix::determine_winner(&bench.payer(), &auction, &state, &winner.pubkey());
+    bench.measure("determine_winner", "sealed, first bid", determine, &[]).await?;
+    let state = bench.auction(&auction).await?;
+    let finalize = ix::finalize_winner(&bench.payer(), &auction, &state, false);
+    bench.measure("finalize_winner", "sealed, winner only", finalize, &[]).await?;
+
+    let state = bench.auction(&auction).await?;
+    let settle = ix::settle_auction(
+        &auction,
+        &state,
+        &state.seller,
+        shadowbid::confidential::handle_to_bytes(state.highest_bid_handle),
+        codec::plaintext_bytes(amount),
+    );
+    bench.measure("settle_auction", "sealed, escrowed", settle, &[&winner]).await?;
+    Ok((auction, winner))
+}
+
+/// Auction and bid rewritten in the pre-versioning layout, then migrated
+async fn migration(bench: &mut Bench) -> Result<()> {
+    let auction = bench.create(false, None, None).await?;
+    let bidder = bench.keypair();
+    let place = ix::place_bid(&bidder.pubkey(), &auction, codec::encrypt(RESERVE_PRICE));
+    bench.measure("place_bid", "sealed, new bid", place, &[&bidder]).await?;
+    let (bid, _) = pda::bid_address(&auction, &bidder.pubkey());
+
+    bench.make_legacy::<Auction>(&auction).await?;
+    let migrate = ix::migrate_auction(&bench.payer(), &auction);
+    bench.measure("migrate_auction", "legacy", migrate, &[]).await?;
+
+    bench.make_legacy::<Bid>(&bid).await?;
+    let migrate = ix::migrate_bid(&bench.payer(), &bid);
+    bench.measure("migrate_bid", "legacy", migrate, &[]).await
+}
+
+struct Bench {
+    ctx: ProgramTestContext,
+    seller: Keypair,
+    /// Arbitrator of every escrowed auction
+    arbitrator: Keypair,
+    /// Compute unit price bumped per transaction so identical
+    /// transactions within one blockhash aren't deduplicated
+    nonce: u64,
+    /// Highest units seen per (instruction, scenario)
+    measurements: BTreeMap<(String, String), Measurement>,
+}
+
+impl Bench {
+    async fn new() -> Result<Self> {
+        let mut program_test = ProgramTest::default();
+        program_test.prefer_bpf(true);
+        program_test.add_program("shadowbid", shadowbid::ID, None);
+        program_test.add_program("mock_inco", mock_inco::ID, None);
+
+        let mut bench = Self {
+            ctx: program_test.start_with_context().await,
+            seller: Keypair::new(),
+            arbitrator: Keypair::new(),
+            nonce: 0,
+            measurements: BTreeMap::new(),
+        };
+        bench.seller = bench.keypair();
+        bench.arbitrator = bench.keypair();
+        let initialize = ix::initialize_registry(&bench.payer());
+        bench.measure("initialize_registry", "new", initialize, &[]).await?;
+        let initialize = ix::initialize_treasury(&bench.payer());
+        bench.measure("initialize_treasury", "new", initialize, &[]).await?;
+        Ok(bench)
+    }
+
+    fn payer(&self) -> Pubkey {
+        self.ctx.payer.pubkey()
+    }
+
+    /// A fresh keypair with enough lamports for any bid or collateral
+    fn keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        self.ctx.set_account(
+            &keypair.pubkey(),
+            &AccountSharedData::new(10_000_000_000, 0, &system_program::ID),
+        );
+        keypair
+    }
+
+    async fn create(
+        &mut self,
+        commit_reveal: bool,
+        seller_bond: Option<u64>,
+        delivery_timeout: Option<i64>,
+    ) -> Result<Pubkey> {
+        let registry = self.account(&pda::registry_address().0, accounts::decode_registry).await?;
+        let auction_id = registry.auction_count;
+        let params = CreateAuctionParams {
+            title: format!("Benchmark auction {auction_id}"),
+            description: "Compute unit benchmark".to_string(),
+            reserve_price: RESERVE_PRICE,
+            duration: DURATION,
+            item_mint: None,
+            commit_reveal: commit_reveal.then_some(CommitRevealParams {
+                reveal_duration: REVEAL_DURATION,
+                bid_collateral: BID_COLLATERAL,
+            }),
+            bid_deposit: None,
+            seller_bond,
+            delivery_timeout,
+            arbitration: delivery_timeout.map(|_| ArbitrationParams {
+                arbitrator: self.arbitrator.pubkey(),
+                fee_bps: ARBITRATION_FEE_BPS,
+            }),
+            settlement_callback: None,
+            hook: None,
+            issue_receipt: false,
+            max_bidder_defaults: None,
+            authority: None,
+        };
+        let seller = self.seller.insecure_clone();
+        let (address, _) = pda::auction_address(&seller.pubkey(), auction_id);
+        let mut scenario = if commit_reveal { "commit-reveal" } else { "sealed" }.to_string();
+        if seller_bond.is_some() {
+            scenario.push_str(", bonded");
+        }
+        if delivery_timeout.is_some() {
+            scenario.push_str(", escrowed");
+        }
+        let create = ix::create_auction(&seller.pubkey(), &registry, params);
+        self.measure("create_auction", &scenario, create, &[&seller]).await?;
+        Ok(address)
+    }
+
+    async fn auction(&mut self, address: &Pubkey) -> Result<Auction> {
+        self.account(address, accounts::decode_auction).await
+    }
+
+    async fn account<T>(
+        &mut self,
+        address: &Pubkey,
+        decode: fn(&[u8]) -> shadowbid_client::Result<T>,
+    ) -> Result<T> {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(*address)
+            .await?
+            .with_context(|| format!("account {address} missing"))?;
+        Ok(decode(&account.data)?)
+    }
+
+    /// Rewrites an account in its original, pre-versioning layout
+    async fn make_legacy<T: Versioned>(&mut self, address: &Pubkey) -> Result<()> {
+        let mut account = self
+            .ctx
+            .banks_client
+            .get_account(*address)
+            .await?
+            .with_context(|| format!("account {address} missing"))?;
+        let mut legacy = vec![0; T::LEGACY_SPACE];
+        for field in T::LEGACY_FIELDS {
+            legacy[field.legacy..field.legacy + field.len]
+                .copy_from_slice(&account.data[field.current..field.current + field.len]);
+        }
+        account.data = legacy;
+        self.ctx.set_account(address, &account.into());
+        Ok(())
+    }
+
+    async fn warp_to(&mut self, unix_timestamp: i64) -> Result<()> {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await?;
+        clock.unix_timestamp = unix_timestamp;
+        self.ctx.set_sysvar(&clock);
+        Ok(())
+    }
+
+    /// Sends one instruction and records the units ShadowBid consumed,
+    /// including its CPIs into Inco
+    async fn measure(
+        &mut self,
+        instruction: &str,
+        scenario: &str,
+        ix: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<()> {
+        self.nonce += 1;
+        let payer = self.ctx.payer.insecure_clone();
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+
+        let blockhash = self.ctx.banks_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+                ComputeBudgetInstruction::set_compute_unit_price(self.nonce),
+                ix,
+            ],
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let outcome = self
+            .ctx
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        let logs = outcome
+            .metadata
+            .map(|metadata| metadata.log_messages)
+            .unwrap_or_default();
+        if let Err(err) = outcome.result {
+            return Err(anyhow!("{instruction} ({scenario}) failed: {err}\n{}", logs.join("\n")));
+        }
+        let units = consumed_units(&logs)
+            .with_context(|| format!("no compute units logged for {instruction} ({scenario})"))?;
+
+        let entry = self
+            .measurements
+            .entry((instruction.to_string(), scenario.to_string()))
+            .or_insert_with(|| Measurement {
+                instruction: instruction.to_string(),
+                scenario: scenario.to_string(),
+                units: 0,
+                samples: 0,
+            });
+        entry.units = entry.units.max(units);
+        entry.samples += 1;
+        Ok(())
+    }
+}
+
+/// Units from the last top-level "Program <shadowbid> consumed N of M
+/// compute units" line, which covers every nested CPI
+fn consumed_units(logs: &[String]) -> Option<u64> {
+    let prefix = format!("Program {} consumed ", shadowbid::ID);
+    logs.iter()
+        .rev()
+        .find_map(|line| line.strip_prefix(&prefix))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|units| units.parse().ok())
+}
@@ -0,0 +1,156 @@
+//! Pluggable bid encryption and attested decryption
+//!
+//! Inco's client-side encryption and attested decryption only ship in
+//! the JavaScript SDK, so the CLI delegates them:
+//! - `mock`: the plaintext codec of the test-only mock Inco program,
+//!   for throwaway validators that load `mock-inco` at the Inco address
+//! - `cmd:<program>`: an external program (e.g. a small Node script
+//!   around `@inco/solana-sdk`) invoked as
+//!   `<program> encrypt <amount>` → hex ciphertext on stdout, and
+//!   `<program> decrypt <handle>` → JSON `Decryption` on stdout
+
+use std::process::Command;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Deserialize;
+use mock_inco::codec as mock;
+use shadowbid::confidential::handle_to_bytes;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+
+/// An attested decryption of a handle
+pub struct Decryption {
+    /// The decrypted value (16 bytes, little-endian)
+    pub plaintext: Vec<u8>,
+    /// Signature verification instructions that must precede the
+    /// instruction consuming the decryption
+    pub instructions: Vec<Instruction>,
+}
+
+/// Encrypts bid amounts and decrypts handles for the CLI
+pub trait Encryptor {
+    /// Encrypts a bid amount for `place_bid`
+    fn encrypt(&self, amount: u64) -> Result<Vec<u8>>;
+
+    /// Decrypts `handle` as `signer`, who must have been granted access
+    fn decrypt(&self, handle: u128, signer: &Keypair) -> Result<Decryption>;
+}
+
+/// Plaintext encryptor for the mock Inco program
+pub struct MockEncryptor;
+
+impl Encryptor for MockEncryptor {
+    fn encrypt(&self, amount: u64) -> Result<Vec<u8>> {
+        Ok(mock::encrypt(amount))
+    }
+
+    fn decrypt(&self, handle: u128, _signer: &Keypair) -> Result<Decryption> {
+        let value = mock::decrypt(handle).ok_or_else(|| anyhow!("handle {handle} is not a mock handle"))?;
+        Ok(Decryption {
+            plaintext: mock::plaintext_bytes(value),
+            instructions: Vec::new(),
+        })
+    }
+}
+
+/// Delegates to an external program (see the module docs)
+pub struct CommandEncryptor {
+    program: String,
+}
+
+#[derive(Deserialize)]
+struct CommandDecryption {
+    /// Hex-encoded plaintext
+    plaintext: String,
+    instructions: Vec<CommandInstruction>,
+}
+
+#[derive(Deserialize)]
+struct CommandInstruction {
+    program_id: String,
+    #[serde(default)]
+    accounts: Vec<CommandAccount>,
+    /// Base64-encoded instruction data
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct CommandAccount {
+    pubkey: String,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+impl CommandEncryptor {
+    fn run(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new(&self.program)
+            .args(args)
+            .output()
+            .with_context(|| format!("failed to run encryptor `{}`", self.program))?;
+        if !output.status.success() {
+            bail!(
+                "encryptor `{}` failed: {}",
+                self.program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    }
+}
+
+impl Encryptor for CommandEncryptor {
+    fn encrypt(&self, amount: u64) -> Result<Vec<u8>> {
+        let ciphertext = self.run(&["encrypt", &amount.to_string()])?;
+        hex::decode(ciphertext.trim_start_matches("0x")).context("encryptor returned invalid hex")
+    }
+
+    fn decrypt(&self, handle: u128, _signer: &Keypair) -> Result<Decryption> {
+        let handle = hex::encode(handle_to_bytes(handle));
+        let output: CommandDecryption = serde_json::from_str(&self.run(&["decrypt", &handle])?)
+            .context("encryptor returned invalid decryption JSON")?;
+
+        let instructions = output
+            .instructions
+            .into_iter()
+            .map(|ix| {
+                Ok(Instruction {
+                    program_id: Pubkey::from_str(&ix.program_id)?,
+                    accounts: ix
+                        .accounts
+                        .into_iter()
+                        .map(|meta| {
+                            Ok(AccountMeta {
+                                pubkey: Pubkey::from_str(&meta.pubkey)?,
+                                is_signer: meta.is_signer,
+                                is_writable: meta.is_writable,
+                            })
+                        })
+                        .collect::<Result<_>>()?,
+                    data: STANDARD.decode(ix.data)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Decryption {
+            plaintext: hex::decode(output.plaintext.trim_start_matches("0x"))?,
+            instructions,
+        })
+    }
+}
+
+/// Parses `--encryptor`: `mock` or `cmd:<program>`
+pub fn from_arg(arg: &str) -> Result<Box<dyn Encryptor>> {
+    match arg {
+        "mock" => Ok(Box::new(MockEncryptor)),
+        _ => match arg.strip_prefix("cmd:") {
+            Some(program) if !program.is_empty() => Ok(Box::new(CommandEncryptor {
+                program: program.to_string(),
+            })),
+            _ => bail!("unknown encryptor `{arg}` (expected `mock` or `cmd:<program>`)"),
+        },
+    }
+}
@@ -0,0 +1,933 @@
+//! `shadowbid`: command-line tool for ShadowBid sellers and bidders
+
+mod encryptor;
+mod output;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use rand::RngCore;
+use serde_json::{json, Map, Value};
+use shadowbid_client::{
+    accounts, instructions, pda, ArbitrationParams, Auction, AuctionState, AuthorityParams, Bid,
+    CancellationReason, CommitRevealParams, CreateAuctionParams, HookMode, HookParams, Lifecycle, Permission,
+};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::encryptor::Encryptor;
+use crate::output::{
+    AuctionView, AuthorityView, BidView, Format, MigrationView, RegistryView, ShowView, TreasuryView, TxView,
+};
+
+/// Migration instructions sent per transaction (3 accounts each)
+const MIGRATIONS_PER_TRANSACTION: usize = 8;
+
+#[derive(Parser)]
+#[command(name = "shadowbid", version, about = "ShadowBid sealed-bid auctions from the command line")]
+struct Cli {
+    /// RPC URL of the cluster
+    #[arg(short, long, global = true, env = "SHADOWBID_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair that signs and pays for transactions [default: ~/.config/solana/id.json]
+    #[arg(short, long, global = true, env = "SHADOWBID_KEYPAIR")]
+    keypair: Option<String>,
+
+    /// Bid encryptor: `mock` for the mock Inco program, or `cmd:<program>`
+    #[arg(short, long, global = true, env = "SHADOWBID_ENCRYPTOR", default_value = "mock")]
+    encryptor: String,
+
+    /// Output format
+    #[arg(short, long, global = true, value_enum, default_value_t = Format::Text)]
+    output: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create an auction
+    Create(Box<CreateArgs>),
+    /// Create the global auction registry (once per deployment)
+    InitRegistry,
+    /// Create the global treasury for slashed bonds (once per deployment)
+    InitTreasury,
+    /// Migrate every auction and bid still in the pre-versioning layout,
+    /// paying their extra rent
+    Migrate,
+    /// List auctions
+    List {
+        /// Only auctions created by this seller
+        #[arg(long)]
+        seller: Option<Pubkey>,
+        /// Only auctions in this state (open, closed, revealing, ...);
+        /// open auctions are read from the registry pages
+        #[arg(long)]
+        state: Option<String>,
+    },
+    /// Show an auction and its bids
+    Show { auction: Pubkey },
+    /// Place or update a bid
+    Bid {
+        auction: Pubkey,
+        /// Bid amount in lamports
+        amount: u64,
+        /// Commit-reveal salt as 32 bytes of hex [default: random]
+        #[arg(long)]
+        salt: Option<String>,
+    },
+    /// Reveal a commit-reveal bid
+    Reveal {
+        auction: Pubkey,
+        /// The committed amount in lamports
+        amount: u64,
+        /// The salt printed by `bid`
+        #[arg(long)]
+        salt: String,
+    },
+    /// Close bidding, or the reveal window of a commit-reveal auction
+    Close { auction: Pubkey },
+    /// Process every unprocessed bid of a closed auction
+    Crank { auction: Pubkey },
+    /// Finalize the winner once every bid is processed
+    Finalize {
+        auction: Pubkey,
+        /// Also grant the seller decryption access to the winning bid
+        #[arg(long)]
+        reveal_to_seller: bool,
+    },
+    /// Settle the auction as its winner
+    Settle {
+        auction: Pubkey,
+        /// Leave out the auction's hook program, if its failures are ignored
+        #[arg(long)]
+        skip_hook: bool,
+    },
+    /// Settle the auction as its seller from the winner's bid deposit
+    SettleFromDeposit {
+        auction: Pubkey,
+        /// Leave out the auction's hook program, if its failures are ignored
+        #[arg(long)]
+        skip_hook: bool,
+    },
+    /// Reclaim your bid deposit once it no longer backs a settlement
+    ReclaimDeposit { auction: Pubkey },
+    /// Declare the winner in default once their settlement window has
+    /// ended without settlement
+    DeclareDefault { auction: Pubkey },
+    /// Cancel a sealed auction whose winning bid is below the reserve
+    /// price, as its seller or winner
+    CancelBelowReserve { auction: Pubkey },
+    /// Mark the winner's receipt NFT fulfilled as the auction's authority
+    Redeem { auction: Pubkey },
+    /// Confirm delivery as the winner, releasing the escrowed payment
+    ConfirmDelivery {
+        auction: Pubkey,
+        /// Leave out the auction's hook program, if its failures are ignored
+        #[arg(long)]
+        skip_hook: bool,
+    },
+    /// Release the escrowed payment to the beneficiary once the delivery
+    /// window has ended
+    ReleaseEscrow {
+        auction: Pubkey,
+        /// Leave out the auction's hook program, if its failures are ignored
+        #[arg(long)]
+        skip_hook: bool,
+    },
+    /// Dispute the delivery as the winner, freezing the escrowed payment
+    Dispute {
+        auction: Pubkey,
+        /// URI of your evidence (max 200 bytes)
+        #[arg(long, default_value = "")]
+        evidence: String,
+    },
+    /// Add evidence to an open dispute as the winner or the seller
+    Evidence {
+        auction: Pubkey,
+        /// URI of the evidence (max 200 bytes)
+        uri: String,
+    },
+    /// Resolve a dispute as the arbitrator
+    Resolve {
+        auction: Pubkey,
+        /// Share of the payment, after the fee, refunded to the winner in
+        /// basis points (10000 refunds everything)
+        #[arg(long)]
+        buyer_bps: u16,
+        /// Leave out the auction's hook program, if its failures are ignored
+        #[arg(long)]
+        skip_hook: bool,
+    },
+    /// Cancel an auction as its authority, or a delegate allowed to
+    Cancel {
+        auction: Pubkey,
+        /// Reason recorded in the cancellation event (max 128 bytes)
+        #[arg(long, default_value = "")]
+        reason: String,
+        /// Reason category counted in your seller profile
+        /// (item-unavailable, listing-error, low-demand, relisting, other)
+        #[arg(long, default_value = "other")]
+        category: String,
+        /// Leave out the auction's hook program, if its failures are ignored
+        #[arg(long)]
+        skip_hook: bool,
+    },
+    /// Claim your share of the bond of an auction its seller cancelled
+    Claim { auction: Pubkey },
+    /// Release your bond as the seller once the auction has settled, its
+    /// winner defaulted or it ended without a sale
+    ReleaseBond { auction: Pubkey },
+    /// Hand authority over the auction on as its authority, revoking
+    /// every delegate
+    TransferAuthority { auction: Pubkey, new_authority: Pubkey },
+    /// Grant a delegate permissions as the auction's authority; without
+    /// any, the delegate is revoked
+    Delegate {
+        auction: Pubkey,
+        delegate: Pubkey,
+        /// Allow cancelling the auction
+        #[arg(long)]
+        cancel: bool,
+        /// Allow creating and updating the auction's metadata
+        #[arg(long)]
+        metadata: bool,
+    },
+}
+
+#[derive(Args)]
+struct CreateArgs {
+    /// Auction title (max 64 bytes)
+    #[arg(long)]
+    title: String,
+    /// Auction description (max 256 bytes)
+    #[arg(long, default_value = "")]
+    description: String,
+    /// Reserve price in lamports
+    #[arg(long)]
+    reserve_price: u64,
+    /// Bidding duration in seconds
+    #[arg(long)]
+    duration: i64,
+    /// NFT mint being auctioned
+    #[arg(long)]
+    item_mint: Option<Pubkey>,
+    /// Run as a commit-reveal auction with this reveal window in seconds
+    #[arg(long, requires = "bid_collateral")]
+    reveal_duration: Option<i64>,
+    /// Commit-reveal collateral per bid in lamports
+    #[arg(long, requires = "reveal_duration")]
+    bid_collateral: Option<u64>,
+    /// Deposit per sealed bid in lamports, which you can settle from
+    /// without the winner
+    #[arg(long, conflicts_with = "reveal_duration")]
+    bid_deposit: Option<u64>,
+    /// Lock this good-faith bond in lamports until the auction
+    /// settles; it also allows cancelling after the first bid, which
+    /// splits it between the bidders
+    #[arg(long)]
+    seller_bond: Option<u64>,
+    /// Hold the winner's payment in escrow until they confirm
+    /// delivery, or for at most this many seconds
+    #[arg(long)]
+    delivery_timeout: Option<i64>,
+    /// Let this wallet or multisig resolve delivery disputes
+    #[arg(long, requires = "delivery_timeout")]
+    arbitrator: Option<Pubkey>,
+    /// Arbitrator's fee on a disputed payment, in basis points
+    #[arg(long, default_value_t = 0, requires = "arbitrator")]
+    arbitration_fee_bps: u16,
+    /// Call this program back with the settlement result (see
+    /// `shadowbid::callback`)
+    #[arg(long, conflicts_with_all = ["delivery_timeout", "hook_program"])]
+    settlement_callback: Option<Pubkey>,
+    /// Invoke this program when the auction completes or is cancelled
+    /// (see `shadowbid::hook`)
+    #[arg(long)]
+    hook_program: Option<Pubkey>,
+    /// Let completion and cancellation go ahead without the hook
+    /// program, so its failures can't block them
+    #[arg(long, requires = "hook_program")]
+    ignore_hook_failures: bool,
+    /// Mint a receipt NFT to the winner at settlement
+    #[arg(long)]
+    receipt: bool,
+    /// Reject bids from bidders who defaulted on more than this many
+    /// won auctions
+    #[arg(long)]
+    max_bidder_defaults: Option<u8>,
+    /// Let this wallet or multisig administer the auction instead of
+    /// you: cancel it, manage metadata and name delegates
+    #[arg(long)]
+    authority: Option<Pubkey>,
+    /// Pay the winning bid to this account instead of you
+    #[arg(long)]
+    beneficiary: Option<Pubkey>,
+}
+
+struct App {
+    rpc: RpcClient,
+    payer: Keypair,
+    encryptor: Box<dyn Encryptor>,
+    format: Format,
+}
+
+impl App {
+    fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+    }
+
+    fn report(&self, signature: Option<Signature>, auction: &Pubkey, extra: Value) -> Result<()> {
+        let state = accounts::fetch_auction(&self.rpc, auction)?.state()?;
+        let extra = match extra {
+            Value::Object(map) => map,
+            _ => Map::new(),
+        };
+        output::print_tx(
+            self.format,
+            &TxView {
+                signature: signature.map(|signature| signature.to_string()),
+                auction: auction.to_string(),
+                state: format!("{state:?}"),
+                extra,
+            },
+        );
+        Ok(())
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let keypair_path = match cli.keypair {
+        Some(path) => path,
+        None => format!(
+            "{}/.config/solana/id.json",
+            std::env::var("HOME").context("HOME is not set; pass --keypair")?
+        ),
+    };
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("failed to read keypair {keypair_path}: {err}"))?;
+
+    let ctx = App {
+        rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        payer,
+        encryptor: encryptor::from_arg(&cli.encryptor)?,
+        format: cli.output,
+    };
+
+    match cli.command {
+        Command::Create(args) => {
+            let CreateArgs {
+                title,
+                description,
+                reserve_price,
+                duration,
+                item_mint,
+                reveal_duration,
+                bid_collateral,
+                bid_deposit,
+                seller_bond,
+                delivery_timeout,
+                arbitrator,
+                arbitration_fee_bps,
+                settlement_callback,
+                hook_program,
+                ignore_hook_failures,
+                receipt,
+                max_bidder_defaults,
+                authority,
+                beneficiary,
+            } = *args;
+            let seller = ctx.payer.pubkey();
+            let commit_reveal = reveal_duration
+                .zip(bid_collateral)
+                .map(|(reveal_duration, bid_collateral)| CommitRevealParams {
+                    reveal_duration,
+                    bid_collateral,
+                });
+            let params = CreateAuctionParams {
+                title,
+                description,
+                reserve_price,
+                duration,
+                item_mint,
+                commit_reveal,
+                bid_deposit,
+                seller_bond,
+                delivery_timeout,
+                arbitration: arbitrator.map(|arbitrator| ArbitrationParams {
+                    arbitrator,
+                    fee_bps: arbitration_fee_bps,
+                }),
+                settlement_callback,
+                hook: hook_program.map(|program| HookParams {
+                    program,
+                    ignore_failures: ignore_hook_failures,
+                }),
+                issue_receipt: receipt,
+                max_bidder_defaults,
+                authority: (authority.is_some() || beneficiary.is_some()).then(|| AuthorityParams {
+                    authority: authority.unwrap_or(seller),
+                    beneficiary: beneficiary.unwrap_or(seller),
+                }),
+            };
+
+            let registry = accounts::fetch_registry(&ctx.rpc)
+                .context("registry not found; run `shadowbid init-registry` first")?;
+            let auction_id = registry.auction_count;
+            let (auction, _) = pda::auction_address(&seller, auction_id);
+            let signature = ctx.send(&[instructions::create_auction(&seller, &registry, params)])?;
+            ctx.report(Some(signature), &auction, json!({ "auction_id": auction_id }))
+        }
+
+        Command::InitRegistry => {
+            let signature = ctx.send(&[instructions::initialize_registry(&ctx.payer.pubkey())])?;
+            output::print_registry(
+                ctx.format,
+                &RegistryView {
+                    signature: signature.to_string(),
+                    registry: pda::registry_address().0.to_string(),
+                },
+            );
+            Ok(())
+        }
+
+        Command::InitTreasury => {
+            let signature = ctx.send(&[instructions::initialize_treasury(&ctx.payer.pubkey())])?;
+            output::print_treasury(
+                ctx.format,
+                &TreasuryView {
+                    signature: signature.to_string(),
+                    treasury: pda::treasury_address().0.to_string(),
+                },
+            );
+            Ok(())
+        }
+
+        Command::Migrate => {
+            let payer = ctx.payer.pubkey();
+            let mut migrations: Vec<_> = accounts::fetch_legacy_auctions(&ctx.rpc)?
+                .into_iter()
+                .map(|(auction, _)| (auction, instructions::migrate_auction(&payer, &auction)))
+                .collect();
+            migrations.extend(
+                accounts::fetch_legacy_bids(&ctx.rpc)?
+                    .into_iter()
+                    .map(|(bid, _)| (bid, instructions::migrate_bid(&payer, &bid))),
+            );
+
+            let mut view = MigrationView::default();
+            for batch in migrations.chunks(MIGRATIONS_PER_TRANSACTION) {
+                let ixs: Vec<_> = batch.iter().map(|(_, ix)| ix.clone()).collect();
+                view.signatures.push(ctx.send(&ixs)?.to_string());
+                view.accounts.extend(batch.iter().map(|(account, _)| account.to_string()));
+            }
+            output::print_migration(ctx.format, &view);
+            Ok(())
+        }
+
+        Command::List { seller, state } => {
+            let state = state.map(|state| parse_state(&state)).transpose()?;
+            let auctions = match (seller, state) {
+                (Some(seller), _) => accounts::fetch_auctions_by_seller(&ctx.rpc, &seller)?,
+                (None, Some(AuctionState::Open)) => accounts::fetch_open_auctions(&ctx.rpc)?,
+                (None, _) => accounts::fetch_auctions(&ctx.rpc)?,
+            };
+
+            let mut views: Vec<_> = auctions
+                .iter()
+                .filter(|(_, auction)| state.is_none_or(|state| auction.state().is_ok_and(|current| current == state)))
+                .map(|(address, auction)| AuctionView::new(address, auction))
+                .collect();
+            views.sort_by_key(|view| std::cmp::Reverse(view.start_time));
+            output::print_auctions(ctx.format, &views);
+            Ok(())
+        }
+
+        Command::Show { auction } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            let record = accounts::fetch_auction_authority(&ctx.rpc, &auction)?;
+            let bids = accounts::fetch_bids_for_auction(&ctx.rpc, &auction)?;
+            output::print_show(
+                ctx.format,
+                &ShowView {
+                    auction: AuctionView::new(&auction, &account),
+                    authority: record.as_ref().map(AuthorityView::new),
+                    bids: bids
+                        .iter()
+                        .map(|(address, bid)| BidView::new(address, bid))
+                        .collect(),
+                },
+            );
+            Ok(())
+        }
+
+        Command::Bid {
+            auction,
+            amount,
+            salt,
+        } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            let bidder = ctx.payer.pubkey();
+
+            if account.is_commit_reveal() {
+                let salt = match salt {
+                    Some(salt) => parse_salt(&salt)?,
+                    None => {
+                        let mut salt = [0u8; 32];
+                        rand::thread_rng().fill_bytes(&mut salt);
+                        salt
+                    }
+                };
+                let commitment = Bid::commitment_hash(amount, &salt, &bidder);
+                let signature =
+                    ctx.send(&[instructions::place_bid(&bidder, &auction, commitment.to_vec())])?;
+                // The salt is needed to reveal the bid and is never stored on-chain
+                ctx.report(Some(signature), &auction, json!({ "salt": hex::encode(salt) }))
+            } else {
+                let ciphertext = ctx.encryptor.encrypt(amount)?;
+                let signature = ctx.send(&[instructions::place_bid(&bidder, &auction, ciphertext)])?;
+                ctx.report(Some(signature), &auction, json!({}))
+            }
+        }
+
+        Command::Reveal {
+            auction,
+            amount,
+            salt,
+        } => {
+            let salt = parse_salt(&salt)?;
+            let ix = instructions::reveal_bid(&ctx.payer.pubkey(), &auction, amount, salt);
+            let signature = ctx.send(&[ix])?;
+            ctx.report(Some(signature), &auction, json!({ "amount": amount }))
+        }
+
+        Command::Close { auction } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            let caller = ctx.payer.pubkey();
+            let ix = match account.state()? {
+                AuctionState::Open => instructions::close_bidding(&caller, &auction, &account),
+                AuctionState::Revealing => instructions::close_reveal(&caller, &auction),
+                state => bail!("auction is {state:?}, nothing to close"),
+            };
+            let signature = ctx.send(&[ix])?;
+            ctx.report(Some(signature), &auction, json!({}))
+        }
+
+        Command::Crank { auction } => {
+            let processed = Lifecycle::new(&ctx.rpc, &ctx.payer).determine(&auction)?;
+            ctx.report(None, &auction, json!({ "bids_processed": processed }))
+        }
+
+        Command::Finalize {
+            auction,
+            reveal_to_seller,
+        } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            let ix = instructions::finalize_winner(
+                &ctx.payer.pubkey(),
+                &auction,
+                &account,
+                reveal_to_seller,
+            );
+            let signature = ctx.send(&[ix])?;
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            ctx.report(Some(signature), &auction, json!({ "winner": account.winner.to_string() }))
+        }
+
+        Command::Settle { auction, skip_hook } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            if account.winner != ctx.payer.pubkey() {
+                bail!("only the winner {} can settle", account.winner);
+            }
+            let beneficiary = accounts::fetch_beneficiary(&ctx.rpc, &auction, &account)?;
+
+            let mut ixs = Vec::new();
+            let (handle_bytes, plaintext_bytes) = if account.is_commit_reveal() {
+                (Vec::new(), Vec::new())
+            } else {
+                let decryption = ctx.encryptor.decrypt(account.highest_bid_handle, &ctx.payer)?;
+                ixs.extend(decryption.instructions);
+                (
+                    shadowbid::confidential::handle_to_bytes(account.highest_bid_handle),
+                    decryption.plaintext,
+                )
+            };
+            if account.has_settlement_callback() {
+                let callback = accounts::fetch_settlement_callback(&ctx.rpc, &auction)?
+                    .context("settlement callback account not found")?;
+                ixs.push(instructions::settle_auction_with_callback(
+                    &auction,
+                    &account,
+                    &beneficiary,
+                    handle_bytes,
+                    plaintext_bytes,
+                    &callback.program,
+                    Vec::new(),
+                ));
+            } else if let Some(hook_program) = hook_program(&ctx.rpc, &auction, &account, skip_hook)? {
+                ixs.push(instructions::settle_auction_with_hook(
+                    &auction,
+                    &account,
+                    &beneficiary,
+                    handle_bytes,
+                    plaintext_bytes,
+                    &hook_program,
+                    Vec::new(),
+                ));
+            } else {
+                ixs.push(instructions::settle_auction(
+                    &auction,
+                    &account,
+                    &beneficiary,
+                    handle_bytes,
+                    plaintext_bytes,
+                ));
+            }
+
+            let signature = ctx.send(&ixs)?;
+            let winning_amount = accounts::fetch_auction(&ctx.rpc, &auction)?.winning_amount;
+            ctx.report(
+                Some(signature),
+                &auction,
+                json!({ "winning_amount": winning_amount }),
+            )
+        }
+
+        Command::SettleFromDeposit { auction, skip_hook } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            if account.seller != ctx.payer.pubkey() {
+                bail!("only the seller {} can settle from the deposit", account.seller);
+            }
+            if !account.takes_bid_deposits() {
+                bail!("the auction takes no bid deposits");
+            }
+            let beneficiary = accounts::fetch_beneficiary(&ctx.rpc, &auction, &account)?;
+
+            // Needs the decryption access `finalize --reveal-to-seller` grants
+            let decryption = ctx.encryptor.decrypt(account.highest_bid_handle, &ctx.payer)?;
+            let handle_bytes = shadowbid::confidential::handle_to_bytes(account.highest_bid_handle);
+            let mut ixs = decryption.instructions;
+            if account.has_settlement_callback() {
+                let callback = accounts::fetch_settlement_callback(&ctx.rpc, &auction)?
+                    .context("settlement callback account not found")?;
+                ixs.push(instructions::settle_from_deposit_with_callback(
+                    &auction,
+                    &account,
+                    &beneficiary,
+                    handle_bytes,
+                    decryption.plaintext,
+                    &callback.program,
+                    Vec::new(),
+                ));
+            } else if let Some(hook_program) = hook_program(&ctx.rpc, &auction, &account, skip_hook)? {
+                ixs.push(instructions::settle_from_deposit_with_hook(
+                    &auction,
+                    &account,
+                    &beneficiary,
+                    handle_bytes,
+                    decryption.plaintext,
+                    &hook_program,
+                    Vec::new(),
+                ));
+            } else {
+                ixs.push(instructions::settle_from_deposit(
+                    &auction,
+                    &account,
+                    &beneficiary,
+                    handle_bytes,
+                    decryption.plaintext,
+                ));
+            }
+
+            let signature = ctx.send(&ixs)?;
+            let winning_amount = accounts::fetch_auction(&ctx.rpc, &auction)?.winning_amount;
+            ctx.report(
+                Some(signature),
+                &auction,
+                json!({ "winning_amount": winning_amount }),
+            )
+        }
+
+        Command::ReclaimDeposit { auction } => {
+            let bidder = ctx.payer.pubkey();
+            let bid = accounts::fetch_bid(&ctx.rpc, &pda::bid_address(&auction, &bidder).0)?;
+            let signature = ctx.send(&[instructions::reclaim_deposit(&bidder, &auction)])?;
+            ctx.report(Some(signature), &auction, json!({ "amount": bid.collateral }))
+        }
+
+        Command::DeclareDefault { auction } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            let ix = instructions::declare_default(&ctx.payer.pubkey(), &auction, &account);
+            let signature = ctx.send(&[ix])?;
+            ctx.report(Some(signature), &auction, json!({ "winner": account.winner.to_string() }))
+        }
+
+        Command::CancelBelowReserve { auction } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            let caller = ctx.payer.pubkey();
+            if caller != account.seller && caller != account.winner {
+                bail!("only the seller {} or the winner {} can cancel", account.seller, account.winner);
+            }
+            if account.is_commit_reveal() {
+                bail!("commit-reveal winners always meet the reserve price");
+            }
+
+            let decryption = ctx.encryptor.decrypt(account.highest_bid_handle, &ctx.payer)?;
+            let winning_amount = shadowbid::confidential::parse_plaintext_amount(&decryption.plaintext)?;
+            if winning_amount >= account.reserve_price {
+                bail!("the winning bid of {} lamports meets the reserve price", winning_amount);
+            }
+            let mut ixs = decryption.instructions;
+            ixs.push(instructions::cancel_below_reserve(
+                &caller,
+                &auction,
+                &account,
+                shadowbid::confidential::handle_to_bytes(account.highest_bid_handle),
+                decryption.plaintext,
+            ));
+
+            let signature = ctx.send(&ixs)?;
+            ctx.report(Some(signature), &auction, json!({ "winning_amount": winning_amount }))
+        }
+
+        Command::Redeem { auction } => {
+            let authority = authority_of(&ctx.rpc, &auction)?;
+            if authority != ctx.payer.pubkey() {
+                bail!("only the authority {} can redeem the receipt", authority);
+            }
+            let receipt = accounts::fetch_receipt(&ctx.rpc, &auction)?
+                .context("the auction has no receipt; it is minted at settlement")?;
+            let signature = ctx.send(&[instructions::redeem_receipt(&authority, &auction)])?;
+            ctx.report(Some(signature), &auction, json!({ "mint": receipt.mint.to_string() }))
+        }
+
+        Command::ConfirmDelivery { auction, skip_hook } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            if account.winner != ctx.payer.pubkey() {
+                bail!("only the winner {} can confirm delivery", account.winner);
+            }
+            let amount = account.winning_amount;
+            let beneficiary = accounts::fetch_beneficiary(&ctx.rpc, &auction, &account)?;
+            let ix = match hook_program(&ctx.rpc, &auction, &account, skip_hook)? {
+                Some(hook_program) => {
+                    instructions::confirm_delivery_with_hook(&auction, &account, &beneficiary, &hook_program, Vec::new())
+                }
+                None => instructions::confirm_delivery(&auction, &account, &beneficiary),
+            };
+            let signature = ctx.send(&[ix])?;
+            ctx.report(Some(signature), &auction, json!({ "amount": amount }))
+        }
+
+        Command::ReleaseEscrow { auction, skip_hook } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            let amount = account.winning_amount;
+            let beneficiary = accounts::fetch_beneficiary(&ctx.rpc, &auction, &account)?;
+            let caller = ctx.payer.pubkey();
+            let ix = match hook_program(&ctx.rpc, &auction, &account, skip_hook)? {
+                Some(hook_program) => instructions::release_escrow_with_hook(
+                    &caller,
+                    &auction,
+                    &account,
+                    &beneficiary,
+                    &hook_program,
+                    Vec::new(),
+                ),
+                None => instructions::release_escrow(&caller, &auction, &account, &beneficiary),
+            };
+            let signature = ctx.send(&[ix])?;
+            ctx.report(Some(signature), &auction, json!({ "amount": amount }))
+        }
+
+        Command::Dispute { auction, evidence } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            if account.winner != ctx.payer.pubkey() {
+                bail!("only the winner {} can dispute", account.winner);
+            }
+            let signature = ctx.send(&[instructions::dispute(&auction, &account, evidence)])?;
+            ctx.report(Some(signature), &auction, json!({}))
+        }
+
+        Command::Evidence { auction, uri } => {
+            let ix = instructions::submit_evidence(&ctx.payer.pubkey(), &auction, uri);
+            let signature = ctx.send(&[ix])?;
+            ctx.report(Some(signature), &auction, json!({}))
+        }
+
+        Command::Resolve {
+            auction,
+            buyer_bps,
+            skip_hook,
+        } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            if account.arbitrator != ctx.payer.pubkey() {
+                bail!("only the arbitrator {} can resolve", account.arbitrator);
+            }
+            let beneficiary = accounts::fetch_beneficiary(&ctx.rpc, &auction, &account)?;
+            let ix = match hook_program(&ctx.rpc, &auction, &account, skip_hook)? {
+                Some(hook_program) => instructions::resolve_dispute_with_hook(
+                    &auction,
+                    &account,
+                    &beneficiary,
+                    buyer_bps,
+                    &hook_program,
+                    Vec::new(),
+                ),
+                None => instructions::resolve_dispute(&auction, &account, &beneficiary, buyer_bps),
+            };
+            let signature = ctx.send(&[ix])?;
+            ctx.report(Some(signature), &auction, json!({ "buyer_bps": buyer_bps }))
+        }
+
+        Command::Cancel {
+            auction,
+            reason,
+            category,
+            skip_hook,
+        } => {
+            let category = parse_category(&category)?;
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            let authority = ctx.payer.pubkey();
+            let ix = match hook_program(&ctx.rpc, &auction, &account, skip_hook)? {
+                Some(hook_program) => instructions::cancel_auction_with_hook(
+                    &authority,
+                    &auction,
+                    &account,
+                    reason,
+                    category,
+                    &hook_program,
+                    Vec::new(),
+                ),
+                None => instructions::cancel_auction(&authority, &auction, &account, reason, category),
+            };
+            let signature = ctx.send(&[ix])?;
+            let compensation = accounts::fetch_seller_bond(&ctx.rpc, &auction)?
+                .map_or(0, |bond| bond.compensation_per_bid);
+            ctx.report(
+                Some(signature),
+                &auction,
+                json!({ "compensation_per_bid": compensation }),
+            )
+        }
+
+        Command::Claim { auction } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            let compensation = accounts::fetch_seller_bond(&ctx.rpc, &auction)?
+                .map_or(0, |bond| bond.compensation_per_bid);
+            let ix = instructions::claim_compensation(&ctx.payer.pubkey(), &auction, &account);
+            let signature = ctx.send(&[ix])?;
+            ctx.report(Some(signature), &auction, json!({ "amount": compensation }))
+        }
+
+        Command::ReleaseBond { auction } => {
+            let account = accounts::fetch_auction(&ctx.rpc, &auction)?;
+            let bond = account.seller_bond;
+            let ix = instructions::release_seller_bond(&auction, &account);
+            let signature = ctx.send(&[ix])?;
+            ctx.report(Some(signature), &auction, json!({ "amount": bond }))
+        }
+
+        Command::TransferAuthority { auction, new_authority } => {
+            let authority = authority_of(&ctx.rpc, &auction)?;
+            if authority != ctx.payer.pubkey() {
+                bail!("only the authority {} can transfer authority", authority);
+            }
+            let ix = instructions::transfer_authority(&authority, &auction, &new_authority);
+            let signature = ctx.send(&[ix])?;
+            ctx.report(
+                Some(signature),
+                &auction,
+                json!({ "authority": new_authority.to_string() }),
+            )
+        }
+
+        Command::Delegate {
+            auction,
+            delegate,
+            cancel,
+            metadata,
+        } => {
+            let authority = authority_of(&ctx.rpc, &auction)?;
+            if authority != ctx.payer.pubkey() {
+                bail!("only the authority {} can name delegates", authority);
+            }
+            let permissions = [(cancel, Permission::Cancel), (metadata, Permission::UpdateMetadata)]
+                .into_iter()
+                .filter(|(granted, _)| *granted)
+                .fold(0, |bits, (_, permission)| bits | permission.bit());
+            let ix = instructions::set_delegate(&authority, &auction, &delegate, permissions);
+            let signature = ctx.send(&[ix])?;
+            ctx.report(
+                Some(signature),
+                &auction,
+                json!({ "delegate": delegate.to_string(), "permissions": permissions }),
+            )
+        }
+    }
+}
+
+fn parse_state(state: &str) -> Result<AuctionState> {
+    Ok(match state.to_ascii_lowercase().replace(['_', '-'], "").as_str() {
+        "open" => AuctionState::Open,
+        "closed" => AuctionState::Closed,
+        "revealing" => AuctionState::Revealing,
+        "winnerdetermined" => AuctionState::WinnerDetermined,
+        "settled" => AuctionState::Settled,
+        "cancelled" => AuctionState::Cancelled,
+        "awaitingdelivery" => AuctionState::AwaitingDelivery,
+        "disputed" => AuctionState::Disputed,
+        "defaulted" => AuctionState::Defaulted,
+        _ => bail!("unknown auction state `{state}`"),
+    })
+}
+
+fn parse_category(category: &str) -> Result<CancellationReason> {
+    Ok(match category.to_ascii_lowercase().replace(['_', '-'], "").as_str() {
+        "itemunavailable" => CancellationReason::ItemUnavailable,
+        "listingerror" => CancellationReason::ListingError,
+        "lowdemand" => CancellationReason::LowDemand,
+        "relisting" => CancellationReason::Relisting,
+        "other" => CancellationReason::Other,
+        _ => bail!("unknown cancellation category `{category}`"),
+    })
+}
+
+fn parse_salt(salt: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(salt.trim_start_matches("0x")).context("salt must be hex")?;
+    <[u8; 32]>::try_from(bytes.as_slice()).map_err(|_| anyhow!("salt must be 32 bytes"))
+}
+
+/// The auction's authority: the one in its authority record, or the
+/// seller without one
+fn authority_of(rpc: &RpcClient, auction: &Pubkey) -> Result<Pubkey> {
+    Ok(match accounts::fetch_auction_authority(rpc, auction)? {
+        Some(record) => record.authority,
+        None => accounts::fetch_auction(rpc, auction)?.seller,
+    })
+}
+
+/// The hook program to pass to settlement or cancellation, if any
+fn hook_program(rpc: &RpcClient, auction: &Pubkey, account: &Auction, skip: bool) -> Result<Option<Pubkey>> {
+    match account.hook_mode()? {
+        HookMode::None => Ok(None),
+        HookMode::Ignore if skip => Ok(None),
+        HookMode::Abort if skip => bail!("the auction's hook can't be skipped"),
+        HookMode::Abort | HookMode::Ignore => {
+            let hook = accounts::fetch_auction_hook(rpc, auction)?.context("hook account not found")?;
+            Ok(Some(hook.program))
+        }
+    }
+}
@@ -0,0 +1,349 @@
+//! Text and JSON rendering of command results
+
+use serde::Serialize;
+use shadowbid_client::{Auction, AuctionAuthority, Bid, Permission};
+use solana_sdk::pubkey::Pubkey;
+
+/// Output format selected with `--output`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Serialize)]
+pub struct AuctionView {
+    pub address: String,
+    pub auction_id: u64,
+    pub seller: String,
+    pub title: String,
+    pub description: String,
+    pub state: String,
+    pub mode: String,
+    pub reserve_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub reveal_end_time: Option<i64>,
+    pub bid_collateral: Option<u64>,
+    pub bid_deposit: Option<u64>,
+    pub cancellation_policy: String,
+    pub seller_bond: Option<u64>,
+    pub delivery_timeout: Option<i64>,
+    pub delivery_deadline: Option<i64>,
+    pub arbitrator: Option<String>,
+    pub arbitration_fee_bps: Option<u16>,
+    pub settlement_callback: bool,
+    pub hook_mode: String,
+    pub issues_receipt: bool,
+    pub max_bidder_defaults: Option<u8>,
+    pub bid_count: u32,
+    pub bids_processed: u32,
+    pub current_leader: Option<String>,
+    pub winner: Option<String>,
+    pub winning_amount: Option<u64>,
+}
+
+impl AuctionView {
+    pub fn new(address: &Pubkey, auction: &Auction) -> Self {
+        let commit_reveal = auction.is_commit_reveal();
+        Self {
+            address: address.to_string(),
+            auction_id: auction.auction_id,
+            seller: auction.seller.to_string(),
+            title: auction.get_title(),
+            description: auction.get_description(),
+            state: variant_name(auction.state()),
+            mode: variant_name(auction.mode()),
+            reserve_price: auction.reserve_price,
+            start_time: auction.start_time,
+            end_time: auction.end_time,
+            reveal_end_time: commit_reveal.then_some(auction.reveal_end_time),
+            bid_collateral: commit_reveal.then_some(auction.bid_collateral),
+            bid_deposit: auction.takes_bid_deposits().then_some(auction.bid_collateral),
+            cancellation_policy: variant_name(auction.cancellation_policy()),
+            seller_bond: auction.is_bonded().then_some(auction.seller_bond),
+            delivery_timeout: auction.is_escrowed().then_some(auction.delivery_timeout),
+            delivery_deadline: (auction.delivery_deadline > 0).then_some(auction.delivery_deadline),
+            arbitrator: non_default(&auction.arbitrator),
+            arbitration_fee_bps: auction.has_arbitrator().then_some(auction.arbitration_fee_bps),
+            settlement_callback: auction.has_settlement_callback(),
+            hook_mode: variant_name(auction.hook_mode()),
+            issues_receipt: auction.issues_receipt(),
+            max_bidder_defaults: auction.max_bidder_defaults(),
+            bid_count: auction.bid_count,
+            bids_processed: auction.bids_processed,
+            current_leader: non_default(&auction.current_leader),
+            winner: non_default(&auction.winner),
+            winning_amount: (auction.winning_amount > 0).then_some(auction.winning_amount),
+        }
+    }
+
+    fn print(&self) {
+        println!("Auction:        {}", self.address);
+        println!("ID:             {}", self.auction_id);
+        println!("Title:          {}", self.title);
+        println!("Description:    {}", self.description);
+        println!("Seller:         {}", self.seller);
+        println!("State:          {}", self.state);
+        println!("Mode:           {}", self.mode);
+        println!("Reserve price:  {} lamports", self.reserve_price);
+        println!("Start time:     {}", self.start_time);
+        println!("End time:       {}", self.end_time);
+        if let Some(reveal_end_time) = self.reveal_end_time {
+            println!("Reveal ends:    {}", reveal_end_time);
+        }
+        if let Some(collateral) = self.bid_collateral {
+            println!("Bid collateral: {} lamports", collateral);
+        }
+        if let Some(deposit) = self.bid_deposit {
+            println!("Bid deposit:    {} lamports", deposit);
+        }
+        println!("Cancellation:   {}", self.cancellation_policy);
+        if let Some(bond) = self.seller_bond {
+            println!("Seller bond:    {} lamports", bond);
+        }
+        if let Some(timeout) = self.delivery_timeout {
+            println!("Escrow:         {}s to confirm delivery", timeout);
+        }
+        if let Some(deadline) = self.delivery_deadline {
+            println!("Delivery by:    {}", deadline);
+        }
+        if let (Some(arbitrator), Some(fee_bps)) = (&self.arbitrator, self.arbitration_fee_bps) {
+            println!("Arbitrator:     {} ({} bps fee)", arbitrator, fee_bps);
+        }
+        if self.settlement_callback {
+            println!("Settlement:     calls back the seller's program");
+        }
+        match self.hook_mode.as_str() {
+            "Abort" => println!("Hook:           invoked on completion, failures abort"),
+            "Ignore" => println!("Hook:           invoked on completion, failures ignored"),
+            _ => {}
+        }
+        if self.issues_receipt {
+            println!("Receipt:        NFT minted to the winner at settlement");
+        }
+        if let Some(max) = self.max_bidder_defaults {
+            println!("Bidders:        at most {} past defaults", max);
+        }
+        println!("Bids:           {} ({} processed)", self.bid_count, self.bids_processed);
+        if let Some(winner) = &self.winner {
+            println!("Winner:         {}", winner);
+        }
+        if let Some(amount) = self.winning_amount {
+            println!("Winning amount: {} lamports", amount);
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct BidView {
+    pub address: String,
+    pub bidder: String,
+    pub encrypted_amount: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub processed: bool,
+    pub revealed_amount: Option<u64>,
+}
+
+impl BidView {
+    pub fn new(address: &Pubkey, bid: &Bid) -> Self {
+        Self {
+            address: address.to_string(),
+            bidder: bid.bidder.to_string(),
+            encrypted_amount: format!("{:#034x}", bid.encrypted_amount),
+            created_at: bid.created_at,
+            updated_at: bid.updated_at,
+            processed: bid.processed,
+            revealed_amount: bid.revealed.then_some(bid.revealed_amount),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ShowView {
+    #[serde(flatten)]
+    pub auction: AuctionView,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authority: Option<AuthorityView>,
+    pub bids: Vec<BidView>,
+}
+
+/// An auction's authority record, for auctions that have one
+#[derive(Serialize)]
+pub struct AuthorityView {
+    pub authority: String,
+    pub beneficiary: String,
+    pub delegates: Vec<DelegateView>,
+}
+
+#[derive(Serialize)]
+pub struct DelegateView {
+    pub delegate: String,
+    pub permissions: Vec<String>,
+}
+
+impl AuthorityView {
+    pub fn new(record: &AuctionAuthority) -> Self {
+        Self {
+            authority: record.authority.to_string(),
+            beneficiary: record.beneficiary.to_string(),
+            delegates: record
+                .delegates
+                .iter()
+                .filter(|delegate| delegate.delegate != Pubkey::default())
+                .map(|delegate| DelegateView {
+                    delegate: delegate.delegate.to_string(),
+                    permissions: [Permission::Cancel, Permission::UpdateMetadata]
+                        .into_iter()
+                        .filter(|permission| delegate.has(*permission))
+                        .map(|permission| format!("{permission:?}"))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn print(&self) {
+        println!("Authority:      {}", self.authority);
+        println!("Beneficiary:    {}", self.beneficiary);
+        for delegate in &self.delegates {
+            println!("Delegate:       {} ({})", delegate.delegate, delegate.permissions.join(", "));
+        }
+    }
+}
+
+/// Result of a command that sent a transaction
+#[derive(Serialize)]
+pub struct TxView {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    pub auction: String,
+    pub state: String,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+fn non_default(key: &Pubkey) -> Option<String> {
+    (*key != Pubkey::default()).then(|| key.to_string())
+}
+
+/// Names a stored enum, which may hold a discriminant the CLI doesn't know
+fn variant_name<T: std::fmt::Debug, E>(value: std::result::Result<T, E>) -> String {
+    value.map_or_else(|_| "Unknown".to_string(), |value| format!("{value:?}"))
+}
+
+pub fn print_auctions(format: Format, auctions: &[AuctionView]) {
+    match format {
+        Format::Json => print_json(&auctions),
+        Format::Text => {
+            println!(
+                "{:<44}  {:<16}  {:>6}  {:>14}  TITLE",
+                "ADDRESS", "STATE", "BIDS", "RESERVE"
+            );
+            for auction in auctions {
+                println!(
+                    "{:<44}  {:<16}  {:>6}  {:>14}  {}",
+                    auction.address, auction.state, auction.bid_count, auction.reserve_price, auction.title
+                );
+            }
+        }
+    }
+}
+
+pub fn print_show(format: Format, view: &ShowView) {
+    match format {
+        Format::Json => print_json(view),
+        Format::Text => {
+            view.auction.print();
+            if let Some(authority) = &view.authority {
+                authority.print();
+            }
+            for bid in &view.bids {
+                let revealed = bid
+                    .revealed_amount
+                    .map(|amount| format!(", revealed {amount} lamports"))
+                    .unwrap_or_default();
+                println!(
+                    "  Bid {} by {} (processed: {}{})",
+                    bid.address, bid.bidder, bid.processed, revealed
+                );
+            }
+        }
+    }
+}
+
+pub fn print_tx(format: Format, view: &TxView) {
+    match format {
+        Format::Json => print_json(view),
+        Format::Text => {
+            if let Some(signature) = &view.signature {
+                println!("Signature: {}", signature);
+            }
+            println!("Auction:   {} ({})", view.auction, view.state);
+            for (key, value) in &view.extra {
+                match value {
+                    serde_json::Value::String(value) => println!("{key}: {value}"),
+                    value => println!("{key}: {value}"),
+                }
+            }
+        }
+    }
+}
+
+/// Result of `init-registry`
+#[derive(Serialize)]
+pub struct RegistryView {
+    pub signature: String,
+    pub registry: String,
+}
+
+pub fn print_registry(format: Format, view: &RegistryView) {
+    match format {
+        Format::Json => print_json(view),
+        Format::Text => {
+            println!("Signature: {}", view.signature);
+            println!("Registry:  {}", view.registry);
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TreasuryView {
+    pub signature: String,
+    pub treasury: String,
+}
+
+pub fn print_treasury(format: Format, view: &TreasuryView) {
+    match format {
+        Format::Json => print_json(view),
+        Format::Text => {
+            println!("Signature: {}", view.signature);
+            println!("Treasury:  {}", view.treasury);
+        }
+    }
+}
+
+/// Result of `migrate`
+#[derive(Serialize, Default)]
+pub struct MigrationView {
+    pub signatures: Vec<String>,
+    /// Migrated auction and bid addresses
+    pub accounts: Vec<String>,
+}
+
+pub fn print_migration(format: Format, view: &MigrationView) {
+    match format {
+        Format::Json => print_json(view),
+        Format::Text => {
+            for signature in &view.signatures {
+                println!("Signature: {}", signature);
+            }
+            println!("Migrated {} accounts", view.accounts.len());
+        }
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) {
+    println!("{}", serde_json::to_string_pretty(value).expect("views serialize"));
+}
@@ -0,0 +1,393 @@
+//! Typed account decoders and RPC fetchers
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_rpc_client_api::filter::{Memcmp, RpcFilterType};
+use shadowbid::state::{
+    Auction, AuctionAuthority, AuctionHook, AuctionMetadata, AuctionPage, Bid, BidderProfile, Escrow, Receipt, Registry,
+    SellerBond, SellerCounter, SellerProfile, SettlementCallback, Treasury, Versioned,
+};
+
+use crate::error::{ClientError, Result};
+use crate::pda::{
+    auction_authority_address, bidder_profile_address, escrow_address, hook_address, metadata_address, page_address, receipt_address,
+    registry_address, seller_bond_address, seller_counter_address, seller_profile_address,
+    settlement_callback_address, treasury_address,
+};
+
+/// Offset of `Bid::auction` (right after the discriminator)
+const BID_AUCTION_OFFSET: usize = 8;
+
+/// Offset of `Auction::seller` (right after the discriminator)
+const AUCTION_SELLER_OFFSET: usize = 8;
+
+/// Offset of `Bid::bidder`
+const BID_BIDDER_OFFSET: usize = 8 + 32;
+
+fn decode<T: AccountDeserialize>(kind: &'static str, data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..]).map_err(|err| ClientError::Decode {
+        kind,
+        reason: err.to_string(),
+    })
+}
+
+fn decode_versioned<T: Versioned>(kind: &'static str, data: &[u8]) -> Result<T> {
+    T::try_deserialize_versioned(data).map_err(|err| ClientError::Decode {
+        kind,
+        reason: err.to_string(),
+    })
+}
+
+/// Decodes `Auction` account data (including the discriminator), in the
+/// current or the pre-versioning layout (as version 0)
+pub fn decode_auction(data: &[u8]) -> Result<Auction> {
+    decode_versioned("Auction", data)
+}
+
+/// Decodes `Bid` account data (including the discriminator), in the
+/// current or the pre-versioning layout (as version 0)
+pub fn decode_bid(data: &[u8]) -> Result<Bid> {
+    decode_versioned("Bid", data)
+}
+
+/// Decodes `BidderProfile` account data (including the discriminator)
+pub fn decode_bidder_profile(data: &[u8]) -> Result<BidderProfile> {
+    decode("BidderProfile", data)
+}
+
+/// Decodes `AuctionMetadata` account data (including the discriminator)
+pub fn decode_auction_metadata(data: &[u8]) -> Result<AuctionMetadata> {
+    decode("AuctionMetadata", data)
+}
+
+/// Decodes `Registry` account data (including the discriminator)
+pub fn decode_registry(data: &[u8]) -> Result<Registry> {
+    decode("Registry", data)
+}
+
+/// Decodes `SellerCounter` account data (including the discriminator)
+pub fn decode_seller_counter(data: &[u8]) -> Result<SellerCounter> {
+    decode("SellerCounter", data)
+}
+
+/// Decodes `SellerProfile` account data (including the discriminator)
+pub fn decode_seller_profile(data: &[u8]) -> Result<SellerProfile> {
+    decode("SellerProfile", data)
+}
+
+/// Decodes `AuctionPage` account data (including the discriminator)
+pub fn decode_auction_page(data: &[u8]) -> Result<AuctionPage> {
+    decode("AuctionPage", data)
+}
+
+/// Decodes `SellerBond` account data (including the discriminator)
+pub fn decode_seller_bond(data: &[u8]) -> Result<SellerBond> {
+    decode("SellerBond", data)
+}
+
+/// Decodes `Escrow` account data (including the discriminator)
+pub fn decode_escrow(data: &[u8]) -> Result<Escrow> {
+    decode("Escrow", data)
+}
+
+/// Decodes `SettlementCallback` account data (including the discriminator)
+pub fn decode_settlement_callback(data: &[u8]) -> Result<SettlementCallback> {
+    decode("SettlementCallback", data)
+}
+
+/// Decodes `AuctionHook` account data (including the discriminator)
+pub fn decode_auction_hook(data: &[u8]) -> Result<AuctionHook> {
+    decode("AuctionHook", data)
+}
+
+/// Decodes `AuctionAuthority` account data (including the discriminator)
+pub fn decode_auction_authority(data: &[u8]) -> Result<AuctionAuthority> {
+    decode("AuctionAuthority", data)
+}
+
+/// Decodes `Receipt` account data (including the discriminator)
+pub fn decode_receipt(data: &[u8]) -> Result<Receipt> {
+    decode("Receipt", data)
+}
+
+/// Decodes `Treasury` account data (including the discriminator)
+pub fn decode_treasury(data: &[u8]) -> Result<Treasury> {
+    decode("Treasury", data)
+}
+
+/// Fetches and decodes an auction
+pub fn fetch_auction(rpc: &RpcClient, address: &Pubkey) -> Result<Auction> {
+    let account = rpc
+        .get_account_with_commitment(address, rpc.commitment())?
+        .value
+        .ok_or(ClientError::AccountNotFound(*address))?;
+    decode_auction(&account.data)
+}
+
+/// Fetches and decodes a bid
+pub fn fetch_bid(rpc: &RpcClient, address: &Pubkey) -> Result<Bid> {
+    let account = rpc
+        .get_account_with_commitment(address, rpc.commitment())?
+        .value
+        .ok_or(ClientError::AccountNotFound(*address))?;
+    decode_bid(&account.data)
+}
+
+/// Fetches and decodes an auction's metadata, if the seller created it
+pub fn fetch_auction_metadata(rpc: &RpcClient, auction: &Pubkey) -> Result<Option<AuctionMetadata>> {
+    let (address, _) = metadata_address(auction);
+    rpc.get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .map(|account| decode_auction_metadata(&account.data))
+        .transpose()
+}
+
+/// Fetches and decodes an auction's seller bond, while its vault is open
+pub fn fetch_seller_bond(rpc: &RpcClient, auction: &Pubkey) -> Result<Option<SellerBond>> {
+    let (address, _) = seller_bond_address(auction);
+    rpc.get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .map(|account| decode_seller_bond(&account.data))
+        .transpose()
+}
+
+/// Fetches and decodes an auction's escrow, while the payment is held
+pub fn fetch_escrow(rpc: &RpcClient, auction: &Pubkey) -> Result<Option<Escrow>> {
+    let (address, _) = escrow_address(auction);
+    rpc.get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .map(|account| decode_escrow(&account.data))
+        .transpose()
+}
+
+/// Fetches and decodes an auction's settlement callback, if it has one
+pub fn fetch_settlement_callback(rpc: &RpcClient, auction: &Pubkey) -> Result<Option<SettlementCallback>> {
+    let (address, _) = settlement_callback_address(auction);
+    rpc.get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .map(|account| decode_settlement_callback(&account.data))
+        .transpose()
+}
+
+/// Fetches and decodes an auction's hook, if it has one
+pub fn fetch_auction_hook(rpc: &RpcClient, auction: &Pubkey) -> Result<Option<AuctionHook>> {
+    let (address, _) = hook_address(auction);
+    rpc.get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .map(|account| decode_auction_hook(&account.data))
+        .transpose()
+}
+
+/// Fetches and decodes an auction's authority record, if it has one
+pub fn fetch_auction_authority(rpc: &RpcClient, auction: &Pubkey) -> Result<Option<AuctionAuthority>> {
+    let (address, _) = auction_authority_address(auction);
+    rpc.get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .map(|account| decode_auction_authority(&account.data))
+        .transpose()
+}
+
+/// Fetches who receives an auction's payment: the beneficiary in its
+/// authority record, or the seller without one
+pub fn fetch_beneficiary(rpc: &RpcClient, auction_address: &Pubkey, auction: &Auction) -> Result<Pubkey> {
+    Ok(fetch_auction_authority(rpc, auction_address)?
+        .map_or(auction.seller, |record| record.beneficiary))
+}
+
+/// Fetches and decodes an auction's receipt, once settlement minted it
+pub fn fetch_receipt(rpc: &RpcClient, auction: &Pubkey) -> Result<Option<Receipt>> {
+    let (address, _) = receipt_address(auction);
+    rpc.get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .map(|account| decode_receipt(&account.data))
+        .transpose()
+}
+
+/// Fetches and decodes the global treasury
+pub fn fetch_treasury(rpc: &RpcClient) -> Result<Treasury> {
+    let (address, _) = treasury_address();
+    let account = rpc
+        .get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .ok_or(ClientError::AccountNotFound(address))?;
+    decode_treasury(&account.data)
+}
+
+/// Fetches and decodes the global registry
+pub fn fetch_registry(rpc: &RpcClient) -> Result<Registry> {
+    let (address, _) = registry_address();
+    let account = rpc
+        .get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .ok_or(ClientError::AccountNotFound(address))?;
+    decode_registry(&account.data)
+}
+
+/// Number of auctions `seller` has created
+pub fn fetch_seller_auction_count(rpc: &RpcClient, seller: &Pubkey) -> Result<u64> {
+    let (address, _) = seller_counter_address(seller);
+    rpc.get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .map_or(Ok(0), |account| Ok(decode_seller_counter(&account.data)?.auction_count))
+}
+
+/// Fetches and decodes `seller`'s profile, once they have created an
+/// auction
+pub fn fetch_seller_profile(rpc: &RpcClient, seller: &Pubkey) -> Result<Option<SellerProfile>> {
+    let (address, _) = seller_profile_address(seller);
+    rpc.get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .map(|account| decode_seller_profile(&account.data))
+        .transpose()
+}
+
+/// Fetches and decodes `bidder`'s profile, once they have placed a bid
+pub fn fetch_bidder_profile(rpc: &RpcClient, bidder: &Pubkey) -> Result<Option<BidderProfile>> {
+    let (address, _) = bidder_profile_address(bidder);
+    rpc.get_account_with_commitment(&address, rpc.commitment())?
+        .value
+        .map(|account| decode_bidder_profile(&account.data))
+        .transpose()
+}
+
+/// Fetches the profiles of `sellers` that have one, in one
+/// `getMultipleAccounts` round per 100 sellers
+pub fn fetch_seller_profiles_for(rpc: &RpcClient, sellers: &[Pubkey]) -> Result<Vec<SellerProfile>> {
+    let addresses: Vec<Pubkey> = sellers
+        .iter()
+        .map(|seller| seller_profile_address(seller).0)
+        .collect();
+    Ok(fetch_multiple(rpc, &addresses, decode_seller_profile)?
+        .into_iter()
+        .map(|(_, profile)| profile)
+        .collect())
+}
+
+/// Every open auction, read from the registry's pages (without
+/// `getProgramAccounts`)
+pub fn fetch_open_auctions(rpc: &RpcClient) -> Result<Vec<(Pubkey, Auction)>> {
+    let pages: Vec<Pubkey> = (0..fetch_registry(rpc)?.page_count())
+        .map(|index| page_address(index).0)
+        .collect();
+    let mut open = Vec::new();
+    for (_, page) in fetch_multiple(rpc, &pages, decode_auction_page)? {
+        open.extend(page.open_auctions().copied());
+    }
+    fetch_multiple(rpc, &open, decode_auction)
+}
+
+/// Fetches and decodes the existing accounts among `addresses`
+fn fetch_multiple<T>(
+    rpc: &RpcClient,
+    addresses: &[Pubkey],
+    decode: fn(&[u8]) -> Result<T>,
+) -> Result<Vec<(Pubkey, T)>> {
+    // getMultipleAccounts takes at most 100 addresses
+    let mut decoded = Vec::new();
+    for chunk in addresses.chunks(100) {
+        let accounts = rpc
+            .get_multiple_accounts_with_commitment(chunk, rpc.commitment())?
+            .value;
+        for (address, account) in chunk.iter().zip(accounts) {
+            if let Some(account) = account {
+                decoded.push((*address, decode(&account.data)?));
+            }
+        }
+    }
+    Ok(decoded)
+}
+
+/// Program accounts starting with `discriminator`, of any size unless
+/// `space` is given
+fn fetch_all<T>(
+    rpc: &RpcClient,
+    discriminator: &[u8],
+    space: Option<usize>,
+    memcmp: Option<(usize, &Pubkey)>,
+    decode: fn(&[u8]) -> Result<T>,
+) -> Result<Vec<(Pubkey, T)>> {
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+        0,
+        discriminator.to_vec(),
+    ))];
+    if let Some(space) = space {
+        filters.push(RpcFilterType::DataSize(space as u64));
+    }
+    if let Some((offset, key)) = memcmp {
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            offset,
+            key.to_bytes().to_vec(),
+        )));
+    }
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    rpc.get_program_accounts_with_config(&shadowbid::ID, config)?
+        .into_iter()
+        .map(|(address, account)| Ok((address, decode(&account.data)?)))
+        .collect()
+}
+
+/// Fetches every auction, in either layout
+pub fn fetch_auctions(rpc: &RpcClient) -> Result<Vec<(Pubkey, Auction)>> {
+    fetch_all(rpc, Auction::DISCRIMINATOR, None, None, decode_auction)
+}
+
+/// Fetches the auctions created by `seller`
+pub fn fetch_auctions_by_seller(rpc: &RpcClient, seller: &Pubkey) -> Result<Vec<(Pubkey, Auction)>> {
+    let seller = Some((AUCTION_SELLER_OFFSET, seller));
+    fetch_all(rpc, Auction::DISCRIMINATOR, None, seller, decode_auction)
+}
+
+/// Fetches every bid, in either layout
+pub fn fetch_bids(rpc: &RpcClient) -> Result<Vec<(Pubkey, Bid)>> {
+    fetch_all(rpc, Bid::DISCRIMINATOR, None, None, decode_bid)
+}
+
+/// Fetches every bid placed on `auction`
+pub fn fetch_bids_for_auction(rpc: &RpcClient, auction: &Pubkey) -> Result<Vec<(Pubkey, Bid)>> {
+    let auction = Some((BID_AUCTION_OFFSET, auction));
+    fetch_all(rpc, Bid::DISCRIMINATOR, None, auction, decode_bid)
+}
+
+/// Fetches every bid placed by `bidder`
+pub fn fetch_bids_by_bidder(rpc: &RpcClient, bidder: &Pubkey) -> Result<Vec<(Pubkey, Bid)>> {
+    let bidder = Some((BID_BIDDER_OFFSET, bidder));
+    fetch_all(rpc, Bid::DISCRIMINATOR, None, bidder, decode_bid)
+}
+
+/// Fetches every seller profile, ranked by `SellerProfile::reliability_bps`
+/// then by volume, best first
+pub fn fetch_seller_profiles(rpc: &RpcClient) -> Result<Vec<(Pubkey, SellerProfile)>> {
+    let space = Some(SellerProfile::SPACE);
+    let mut profiles = fetch_all(rpc, SellerProfile::DISCRIMINATOR, space, None, decode_seller_profile)?;
+    profiles.sort_by_key(|(_, profile)| {
+        std::cmp::Reverse((profile.reliability_bps(), profile.total_volume))
+    });
+    Ok(profiles)
+}
+
+/// Fetches the auctions still in the pre-versioning layout, which
+/// `migrate_auction` upgrades
+pub fn fetch_legacy_auctions(rpc: &RpcClient) -> Result<Vec<(Pubkey, Auction)>> {
+    let space = Some(Auction::LEGACY_SPACE);
+    fetch_all(rpc, Auction::DISCRIMINATOR, space, None, decode_auction)
+}
+
+/// Fetches the bids still in the pre-versioning layout, which
+/// `migrate_bid` upgrades
+pub fn fetch_legacy_bids(rpc: &RpcClient) -> Result<Vec<(Pubkey, Bid)>> {
+    let space = Some(Bid::LEGACY_SPACE);
+    fetch_all(rpc, Bid::DISCRIMINATOR, space, None, decode_bid)
+}
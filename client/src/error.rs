@@ -0,0 +1,45 @@
+use anchor_lang::prelude::Pubkey;
+use shadowbid::state::AuctionState;
+use thiserror::Error;
+
+/// Errors returned by the ShadowBid client
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The RPC request or transaction failed
+    #[error("RPC error: {0}")]
+    Rpc(Box<solana_rpc_client_api::client_error::Error>),
+
+    /// Account data could not be decoded as the expected type
+    #[error("Failed to decode {kind} account: {reason}")]
+    Decode { kind: &'static str, reason: String },
+
+    /// The account does not exist
+    #[error("Account {0} not found")]
+    AccountNotFound(Pubkey),
+
+    /// The auction is not in a state the operation can start from
+    #[error("Auction {auction} is in state {state:?}")]
+    UnexpectedState {
+        auction: Pubkey,
+        state: AuctionState,
+    },
+
+    /// An account field holds a value the program doesn't define
+    #[error("Invalid account data: {0}")]
+    InvalidData(anchor_lang::error::Error),
+}
+
+impl From<anchor_lang::error::Error> for ClientError {
+    fn from(err: anchor_lang::error::Error) -> Self {
+        Self::InvalidData(err)
+    }
+}
+
+impl From<solana_rpc_client_api::client_error::Error> for ClientError {
+    fn from(err: solana_rpc_client_api::client_error::Error) -> Self {
+        Self::Rpc(Box::new(err))
+    }
+}
+
+/// Result type for the ShadowBid client
+pub type Result<T> = std::result::Result<T, ClientError>;
@@ -0,0 +1,162 @@
+//! Event parsing from transaction logs
+//!
+//! Anchor's `emit!` writes each event as a `Program data: <base64>` log
+//! line holding the event discriminator followed by its Borsh encoding.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use shadowbid::events::*;
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+macro_rules! shadowbid_events {
+    ($($event:ident),* $(,)?) => {
+        /// Any event emitted by the ShadowBid program
+        #[derive(Debug, Clone)]
+        pub enum ShadowBidEvent {
+            $($event($event),)*
+        }
+
+        impl ShadowBidEvent {
+            /// Decodes an event from its discriminator-prefixed data
+            ///
+            /// Returns `None` for data that isn't a ShadowBid event.
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                $(
+                    if let Some(mut payload) = data.strip_prefix($event::DISCRIMINATOR) {
+                        return $event::deserialize(&mut payload).ok().map(Self::$event);
+                    }
+                )*
+                None
+            }
+
+            /// The event's name, as in `events.rs`
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$event(_) => stringify!($event),)*
+                }
+            }
+        }
+    };
+}
+
+shadowbid_events!(
+    AuctionCreated,
+    BidPlaced,
+    BidUpdated,
+    BiddingClosed,
+    BidProcessed,
+    WinnerDetermined,
+    AuctionSettled,
+    AuctionCancelled,
+    AuctionStatsRevealed,
+    BidRevealed,
+    RevealClosed,
+    CollateralForfeited,
+    DepositReclaimed,
+    MetadataUpdated,
+    MetadataClosed,
+    AccountMigrated,
+    CompensationClaimed,
+    BondReleased,
+    BondSlashed,
+    PaymentEscrowed,
+    EscrowReleased,
+    DisputeOpened,
+    EvidenceSubmitted,
+    DisputeResolved,
+    SettlementCallbackInvoked,
+    HookInvoked,
+    HookSkipped,
+    ReceiptMinted,
+    ReceiptRedeemed,
+    WinnerDefaulted,
+    AuthorityTransferred,
+    DelegateUpdated,
+);
+
+impl ShadowBidEvent {
+    /// The auction the event belongs to
+    pub fn auction(&self) -> Pubkey {
+        match self {
+            Self::AuctionCreated(e) => e.auction,
+            Self::BidPlaced(e) => e.auction,
+            Self::BidUpdated(e) => e.auction,
+            Self::BiddingClosed(e) => e.auction,
+            Self::BidProcessed(e) => e.auction,
+            Self::WinnerDetermined(e) => e.auction,
+            Self::AuctionSettled(e) => e.auction,
+            Self::AuctionCancelled(e) => e.auction,
+            Self::AuctionStatsRevealed(e) => e.auction,
+            Self::BidRevealed(e) => e.auction,
+            Self::RevealClosed(e) => e.auction,
+            Self::CollateralForfeited(e) => e.auction,
+            Self::DepositReclaimed(e) => e.auction,
+            Self::MetadataUpdated(e) => e.auction,
+            Self::MetadataClosed(e) => e.auction,
+            Self::AccountMigrated(e) => e.auction,
+            Self::CompensationClaimed(e) => e.auction,
+            Self::BondReleased(e) => e.auction,
+            Self::BondSlashed(e) => e.auction,
+            Self::PaymentEscrowed(e) => e.auction,
+            Self::EscrowReleased(e) => e.auction,
+            Self::DisputeOpened(e) => e.auction,
+            Self::EvidenceSubmitted(e) => e.auction,
+            Self::DisputeResolved(e) => e.auction,
+            Self::SettlementCallbackInvoked(e) => e.auction,
+            Self::HookInvoked(e) => e.auction,
+            Self::HookSkipped(e) => e.auction,
+            Self::ReceiptMinted(e) => e.auction,
+            Self::ReceiptRedeemed(e) => e.auction,
+            Self::WinnerDefaulted(e) => e.auction,
+            Self::AuthorityTransferred(e) => e.auction,
+            Self::DelegateUpdated(e) => e.auction,
+        }
+    }
+}
+
+/// Parses every ShadowBid event from a transaction's log messages
+///
+/// Only `Program data:` lines logged while ShadowBid is the executing
+/// program are considered, so events from CPI'd programs (such as
+/// Inco Lightning) are never misattributed.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<ShadowBidEvent> {
+    let program_id = shadowbid::ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        let line = line.as_ref();
+        if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(id), Some("invoke")) => {
+                    stack.push(id);
+                    continue;
+                }
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    stack.pop();
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        if stack.last() != Some(&program_id.as_str()) {
+            continue;
+        }
+        if let Some(encoded) = line.strip_prefix(PROGRAM_DATA) {
+            if let Some(event) = STANDARD
+                .decode(encoded.trim())
+                .ok()
+                .and_then(|data| ShadowBidEvent::decode(&data))
+            {
+                events.push(event);
+            }
+        }
+    }
+
+    events
+}
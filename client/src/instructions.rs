@@ -0,0 +1,922 @@
+//! Instruction builders, one per program instruction
+//!
+//! Builders that depend on auction state (handles, winner, seller) take
+//! the decoded `Auction` so callers don't have to derive the Inco
+//! allowance accounts themselves. Builders that pay the seller's side
+//! take the beneficiary too: the seller, unless the auction's authority
+//! record names another (see `accounts::fetch_beneficiary`).
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use shadowbid::constants::INCO_LIGHTNING_PROGRAM_ID;
+use shadowbid::instructions::{AuctionMetadataParams, CreateAuctionParams};
+use shadowbid::state::{Auction, CancellationReason, Registry};
+
+use crate::pda::{
+    allowance_address, auction_address, auction_authority_address, auction_page_address, bid_address, bidder_profile_address, escrow_address,
+    metadata_address,
+    hook_address, receipt_address, receipt_authority_address, receipt_mint_address,
+    receipt_token_address, registry_address, seller_bond_address, seller_counter_address,
+    seller_profile_address, settlement_callback_address, treasury_address,
+};
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: shadowbid::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds `initialize_registry`
+pub fn initialize_registry(payer: &Pubkey) -> Instruction {
+    instruction(
+        shadowbid::accounts::InitializeRegistry {
+            payer: *payer,
+            registry: registry_address().0,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::InitializeRegistry {},
+    )
+}
+
+/// Builds `initialize_treasury`
+pub fn initialize_treasury(payer: &Pubkey) -> Instruction {
+    instruction(
+        shadowbid::accounts::InitializeTreasury {
+            payer: *payer,
+            treasury: treasury_address().0,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::InitializeTreasury {},
+    )
+}
+
+/// Builds `create_auction` for the registry's next auction ID; the
+/// auction address is `auction_address(seller, registry.auction_count)`
+///
+/// The addresses depend on `registry.auction_count`, so the transaction
+/// fails if another auction is created first; refetch the registry and
+/// rebuild to retry. With `params.seller_bond` the SellerBond vault is
+/// created as well, with `params.settlement_callback` the
+/// SettlementCallback, with `params.hook` the AuctionHook and with
+/// `params.authority` the AuctionAuthority. The seller pays for
+/// everything.
+pub fn create_auction(seller: &Pubkey, registry: &Registry, params: CreateAuctionParams) -> Instruction {
+    create_auction_with_payer(seller, seller, registry, params)
+}
+
+/// Builds `create_auction` with `payer` funding the accounts and the
+/// seller bond, e.g. for a PDA seller that signs through its program
+pub fn create_auction_with_payer(
+    payer: &Pubkey,
+    seller: &Pubkey,
+    registry: &Registry,
+    params: CreateAuctionParams,
+) -> Instruction {
+    let auction_id = registry.auction_count;
+    let (auction, _) = auction_address(seller, auction_id);
+    instruction(
+        shadowbid::accounts::CreateAuction {
+            payer: *payer,
+            seller: *seller,
+            registry: registry_address().0,
+            seller_counter: seller_counter_address(seller).0,
+            seller_profile: seller_profile_address(seller).0,
+            page: auction_page_address(auction_id).0,
+            auction,
+            seller_bond: params.seller_bond.map(|_| seller_bond_address(&auction).0),
+            settlement_callback: params
+                .settlement_callback
+                .map(|_| settlement_callback_address(&auction).0),
+            hook: params.hook.as_ref().map(|_| hook_address(&auction).0),
+            hook_program: params.hook.as_ref().map(|hook| hook.program),
+            auction_authority: params.authority.as_ref().map(|_| auction_authority_address(&auction).0),
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::CreateAuction { params },
+    )
+}
+
+/// Builds `create_auction_metadata` for the auction's authority (its
+/// seller, without an authority record) or a delegate allowed to update
+/// metadata, who pays for the account
+pub fn create_auction_metadata(authority: &Pubkey, auction: &Pubkey, params: AuctionMetadataParams) -> Instruction {
+    instruction(
+        shadowbid::accounts::CreateAuctionMetadata {
+            authority: *authority,
+            auction: *auction,
+            auction_authority: auction_authority_address(auction).0,
+            metadata: metadata_address(auction).0,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::CreateAuctionMetadata { params },
+    )
+}
+
+/// Builds `update_auction_metadata` for the auction's authority or a
+/// delegate allowed to update metadata, who pays for growth; `seller`
+/// gets the rent a shrink frees
+pub fn update_auction_metadata(
+    authority: &Pubkey,
+    auction: &Pubkey,
+    seller: &Pubkey,
+    params: AuctionMetadataParams,
+) -> Instruction {
+    instruction(
+        shadowbid::accounts::UpdateAuctionMetadata {
+            authority: *authority,
+            auction: *auction,
+            auction_authority: auction_authority_address(auction).0,
+            seller: *seller,
+            metadata: metadata_address(auction).0,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::UpdateAuctionMetadata { params },
+    )
+}
+
+/// Builds `close_auction_metadata` for the auction's authority or a
+/// delegate allowed to update metadata; `seller` gets the rent back
+pub fn close_auction_metadata(authority: &Pubkey, auction: &Pubkey, seller: &Pubkey) -> Instruction {
+    instruction(
+        shadowbid::accounts::CloseAuctionMetadata {
+            authority: *authority,
+            auction: *auction,
+            auction_authority: auction_authority_address(auction).0,
+            seller: *seller,
+            metadata: metadata_address(auction).0,
+        },
+        shadowbid::instruction::CloseAuctionMetadata {},
+    )
+}
+
+/// Builds `place_bid`
+///
+/// `ciphertext` is the Inco-encrypted amount, or the 32-byte commitment
+/// (`Bid::commitment_hash`) for commit-reveal auctions.
+pub fn place_bid(bidder: &Pubkey, auction: &Pubkey, ciphertext: Vec<u8>) -> Instruction {
+    let (bid, _) = bid_address(auction, bidder);
+    instruction(
+        shadowbid::accounts::PlaceBid {
+            bidder: *bidder,
+            auction: *auction,
+            bid,
+            bidder_profile: bidder_profile_address(bidder).0,
+            inco_lightning_program: INCO_LIGHTNING_PROGRAM_ID,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::PlaceBid { ciphertext },
+    )
+}
+
+/// Builds `close_bidding`
+pub fn close_bidding(caller: &Pubkey, auction_address: &Pubkey, auction: &Auction) -> Instruction {
+    instruction(
+        shadowbid::accounts::CloseBidding {
+            caller: *caller,
+            auction: *auction_address,
+            page: auction_page_address(auction.auction_id).0,
+        },
+        shadowbid::instruction::CloseBidding {},
+    )
+}
+
+/// Builds `reveal_bid` for a commit-reveal auction
+pub fn reveal_bid(bidder: &Pubkey, auction: &Pubkey, amount: u64, salt: [u8; 32]) -> Instruction {
+    let (bid, _) = bid_address(auction, bidder);
+    instruction(
+        shadowbid::accounts::RevealBid {
+            bidder: *bidder,
+            auction: *auction,
+            bid,
+        },
+        shadowbid::instruction::RevealBid { amount, salt },
+    )
+}
+
+/// Builds `close_reveal` for a commit-reveal auction
+pub fn close_reveal(caller: &Pubkey, auction: &Pubkey) -> Instruction {
+    instruction(
+        shadowbid::accounts::CloseReveal {
+            caller: *caller,
+            auction: *auction,
+        },
+        shadowbid::instruction::CloseReveal {},
+    )
+}
+
+/// Builds `determine_winner` for `bidder`'s bid
+///
+/// Commit-reveal auctions pass the seller as a remaining account so an
+/// unrevealed bid's collateral can be forfeited to them.
+pub fn determine_winner(
+    caller: &Pubkey,
+    auction_address: &Pubkey,
+    auction: &Auction,
+    bidder: &Pubkey,
+) -> Instruction {
+    let (bid, _) = bid_address(auction_address, bidder);
+    let mut ix = instruction(
+        shadowbid::accounts::DetermineWinner {
+            caller: *caller,
+            auction: *auction_address,
+            bid,
+            inco_lightning_program: INCO_LIGHTNING_PROGRAM_ID,
+        },
+        shadowbid::instruction::DetermineWinner {},
+    );
+    if auction.is_commit_reveal() {
+        ix.accounts.push(AccountMeta::new(auction.seller, false));
+    }
+    ix
+}
+
+/// Builds `finalize_winner`, granting the current leader decryption
+/// access to the winning bid
+///
+/// With `reveal_to_seller`, the seller is granted access as well. The
+/// leader's bid and profile record the win.
+pub fn finalize_winner(
+    caller: &Pubkey,
+    auction_address: &Pubkey,
+    auction: &Auction,
+    reveal_to_seller: bool,
+) -> Instruction {
+    let leader = &auction.current_leader;
+    let (allowance_account, _) = allowance_address(auction.highest_bid_handle, leader);
+    let mut ix = instruction(
+        shadowbid::accounts::FinalizeWinner {
+            caller: *caller,
+            auction: *auction_address,
+            allowance_account,
+            winner_address: *leader,
+            winner_bid: (*leader != Pubkey::default()).then(|| bid_address(auction_address, leader).0),
+            winner_profile: bidder_profile_address(leader).0,
+            inco_lightning_program: INCO_LIGHTNING_PROGRAM_ID,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::FinalizeWinner {},
+    );
+    if reveal_to_seller {
+        let (seller_allowance, _) = allowance_address(auction.highest_bid_handle, &auction.seller);
+        ix.accounts.push(AccountMeta::new(seller_allowance, false));
+        ix.accounts.push(AccountMeta::new_readonly(auction.seller, false));
+    }
+    ix
+}
+
+/// Builds `settle_auction` for the auction's winner, paying `beneficiary`
+///
+/// The Inco Ed25519 signature verification instructions for the
+/// decryption must precede this instruction in the transaction.
+/// Commit-reveal auctions ignore `handle_bytes` and `plaintext_bytes`.
+/// Auctions issuing receipts get the receipt accounts, with the winner
+/// paying for the mint. Auctions with a settlement callback need
+/// `settle_auction_with_callback`, and auctions with a hook
+/// `settle_auction_with_hook`, unless its failures are ignored.
+pub fn settle_auction(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+) -> Instruction {
+    settle(auction_address, auction, beneficiary, handle_bytes, plaintext_bytes, None, None)
+}
+
+/// Builds `settle_auction` for an auction with a settlement callback to
+/// `callback_program`, passing it `callback_accounts` after its fixed
+/// accounts (see `shadowbid::callback`)
+pub fn settle_auction_with_callback(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+    callback_program: &Pubkey,
+    callback_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut ix = settle(
+        auction_address,
+        auction,
+        beneficiary,
+        handle_bytes,
+        plaintext_bytes,
+        Some(*callback_program),
+        None,
+    );
+    ix.accounts.extend(callback_accounts);
+    ix
+}
+
+/// Builds `settle_auction` for an auction with a hook to `hook_program`,
+/// passing it `hook_accounts` after its fixed accounts (see
+/// `shadowbid::hook`)
+pub fn settle_auction_with_hook(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+    hook_program: &Pubkey,
+    hook_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut ix = settle(
+        auction_address,
+        auction,
+        beneficiary,
+        handle_bytes,
+        plaintext_bytes,
+        None,
+        Some(*hook_program),
+    );
+    ix.accounts.extend(hook_accounts);
+    ix
+}
+
+fn settle(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+    callback_program: Option<Pubkey>,
+    hook_program: Option<Pubkey>,
+) -> Instruction {
+    let receipt = auction.issues_receipt();
+    instruction(
+        shadowbid::accounts::SettleAuction {
+            winner: auction.winner,
+            auction: *auction_address,
+            seller: auction.seller,
+            seller_profile: seller_profile_address(&auction.seller).0,
+            winner_profile: bidder_profile_address(&auction.winner).0,
+            auction_authority: auction_authority_address(auction_address).0,
+            beneficiary: *beneficiary,
+            instructions: sysvar::instructions::ID,
+            inco_lightning_program: INCO_LIGHTNING_PROGRAM_ID,
+            escrow: auction
+                .is_escrowed()
+                .then(|| escrow_address(auction_address).0),
+            settlement_callback: callback_program
+                .map(|_| settlement_callback_address(auction_address).0),
+            callback_program,
+            hook: hook_program.map(|_| hook_address(auction_address).0),
+            hook_program,
+            receipt: receipt.then(|| receipt_address(auction_address).0),
+            receipt_mint: receipt.then(|| receipt_mint_address(auction_address).0),
+            receipt_token: receipt.then(|| receipt_token_address(auction_address, &auction.winner)),
+            receipt_authority: receipt.then(|| receipt_authority_address().0),
+            token_program: receipt.then_some(anchor_spl::token_2022::ID),
+            associated_token_program: receipt.then_some(anchor_spl::associated_token::ID),
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::SettleAuction {
+            handle_bytes,
+            plaintext_bytes,
+        },
+    )
+}
+
+/// Builds `settle_from_deposit` for the auction's seller, paying
+/// `beneficiary` from the winner's bid deposit
+///
+/// The Inco Ed25519 signature verification instructions for the seller's
+/// decryption must precede this instruction in the transaction. Auctions
+/// with a settlement callback need `settle_from_deposit_with_callback`,
+/// and auctions with a hook `settle_from_deposit_with_hook`, unless its
+/// failures are ignored.
+pub fn settle_from_deposit(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+) -> Instruction {
+    settle_deposit(auction_address, auction, beneficiary, handle_bytes, plaintext_bytes, None, None)
+}
+
+/// Builds `settle_from_deposit` for an auction with a settlement callback
+/// to `callback_program`, passing it `callback_accounts` after its fixed
+/// accounts (see `shadowbid::callback`)
+pub fn settle_from_deposit_with_callback(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+    callback_program: &Pubkey,
+    callback_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut ix = settle_deposit(
+        auction_address,
+        auction,
+        beneficiary,
+        handle_bytes,
+        plaintext_bytes,
+        Some(*callback_program),
+        None,
+    );
+    ix.accounts.extend(callback_accounts);
+    ix
+}
+
+/// Builds `settle_from_deposit` for an auction with a hook to
+/// `hook_program`, passing it `hook_accounts` after its fixed accounts
+/// (see `shadowbid::hook`)
+pub fn settle_from_deposit_with_hook(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+    hook_program: &Pubkey,
+    hook_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut ix = settle_deposit(
+        auction_address,
+        auction,
+        beneficiary,
+        handle_bytes,
+        plaintext_bytes,
+        None,
+        Some(*hook_program),
+    );
+    ix.accounts.extend(hook_accounts);
+    ix
+}
+
+fn settle_deposit(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+    callback_program: Option<Pubkey>,
+    hook_program: Option<Pubkey>,
+) -> Instruction {
+    instruction(
+        shadowbid::accounts::SettleFromDeposit {
+            seller: auction.seller,
+            auction: *auction_address,
+            winner: auction.winner,
+            winner_bid: bid_address(auction_address, &auction.winner).0,
+            seller_profile: seller_profile_address(&auction.seller).0,
+            winner_profile: bidder_profile_address(&auction.winner).0,
+            auction_authority: auction_authority_address(auction_address).0,
+            beneficiary: *beneficiary,
+            instructions: sysvar::instructions::ID,
+            inco_lightning_program: INCO_LIGHTNING_PROGRAM_ID,
+            settlement_callback: callback_program
+                .map(|_| settlement_callback_address(auction_address).0),
+            callback_program,
+            hook: hook_program.map(|_| hook_address(auction_address).0),
+            hook_program,
+        },
+        shadowbid::instruction::SettleFromDeposit {
+            handle_bytes,
+            plaintext_bytes,
+        },
+    )
+}
+
+/// Builds `reclaim_deposit` for `bidder`'s bid on a sealed auction taking
+/// bid deposits
+pub fn reclaim_deposit(bidder: &Pubkey, auction: &Pubkey) -> Instruction {
+    let (bid, _) = bid_address(auction, bidder);
+    instruction(
+        shadowbid::accounts::ReclaimDeposit {
+            bidder: *bidder,
+            auction: *auction,
+            bid,
+        },
+        shadowbid::instruction::ReclaimDeposit {},
+    )
+}
+
+/// Builds `redeem_receipt` for the auction's authority (its seller,
+/// without an authority record)
+pub fn redeem_receipt(authority: &Pubkey, auction_address: &Pubkey) -> Instruction {
+    instruction(
+        shadowbid::accounts::RedeemReceipt {
+            authority: *authority,
+            auction: *auction_address,
+            auction_authority: auction_authority_address(auction_address).0,
+            receipt: receipt_address(auction_address).0,
+            receipt_mint: receipt_mint_address(auction_address).0,
+            receipt_authority: receipt_authority_address().0,
+            token_program: anchor_spl::token_2022::ID,
+        },
+        shadowbid::instruction::RedeemReceipt {},
+    )
+}
+
+/// Builds `confirm_delivery` for the auction's winner, releasing the
+/// payment to `beneficiary`
+///
+/// Auctions with a hook need `confirm_delivery_with_hook`, unless its
+/// failures are ignored.
+pub fn confirm_delivery(auction_address: &Pubkey, auction: &Auction, beneficiary: &Pubkey) -> Instruction {
+    confirm(auction_address, auction, beneficiary, None)
+}
+
+/// Builds `confirm_delivery` for an auction with a hook to `hook_program`,
+/// passing it `hook_accounts` after its fixed accounts (see
+/// `shadowbid::hook`)
+pub fn confirm_delivery_with_hook(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    hook_program: &Pubkey,
+    hook_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut ix = confirm(auction_address, auction, beneficiary, Some(*hook_program));
+    ix.accounts.extend(hook_accounts);
+    ix
+}
+
+fn confirm(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    hook_program: Option<Pubkey>,
+) -> Instruction {
+    instruction(
+        shadowbid::accounts::ConfirmDelivery {
+            winner: auction.winner,
+            auction: *auction_address,
+            seller: auction.seller,
+            beneficiary: *beneficiary,
+            escrow: escrow_address(auction_address).0,
+            hook: hook_program.map(|_| hook_address(auction_address).0),
+            hook_program,
+        },
+        shadowbid::instruction::ConfirmDelivery {},
+    )
+}
+
+/// Builds `release_escrow`, releasing the payment to `beneficiary`;
+/// anyone can release once the delivery window ends
+///
+/// Auctions with a hook need `release_escrow_with_hook`, unless its
+/// failures are ignored.
+pub fn release_escrow(
+    caller: &Pubkey,
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+) -> Instruction {
+    release(caller, auction_address, auction, beneficiary, None)
+}
+
+/// Builds `release_escrow` for an auction with a hook to `hook_program`,
+/// passing it `hook_accounts` after its fixed accounts (see
+/// `shadowbid::hook`)
+pub fn release_escrow_with_hook(
+    caller: &Pubkey,
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    hook_program: &Pubkey,
+    hook_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut ix = release(caller, auction_address, auction, beneficiary, Some(*hook_program));
+    ix.accounts.extend(hook_accounts);
+    ix
+}
+
+fn release(
+    caller: &Pubkey,
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    hook_program: Option<Pubkey>,
+) -> Instruction {
+    instruction(
+        shadowbid::accounts::ReleaseEscrow {
+            caller: *caller,
+            auction: *auction_address,
+            winner: auction.winner,
+            seller: auction.seller,
+            beneficiary: *beneficiary,
+            escrow: escrow_address(auction_address).0,
+            hook: hook_program.map(|_| hook_address(auction_address).0),
+            hook_program,
+        },
+        shadowbid::instruction::ReleaseEscrow {},
+    )
+}
+
+/// Builds `declare_default`; anyone can declare the winner in default
+/// once their settlement window ends
+pub fn declare_default(caller: &Pubkey, auction_address: &Pubkey, auction: &Auction) -> Instruction {
+    instruction(
+        shadowbid::accounts::DeclareDefault {
+            caller: *caller,
+            auction: *auction_address,
+            winner: auction.winner,
+            winner_bid: bid_address(auction_address, &auction.winner).0,
+            winner_profile: bidder_profile_address(&auction.winner).0,
+        },
+        shadowbid::instruction::DeclareDefault {},
+    )
+}
+
+/// Builds `cancel_below_reserve` for the auction's seller or winner,
+/// whichever `caller` is
+///
+/// The Inco Ed25519 signature verification instructions for the
+/// decryption must precede this instruction in the transaction.
+pub fn cancel_below_reserve(
+    caller: &Pubkey,
+    auction_address: &Pubkey,
+    auction: &Auction,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+) -> Instruction {
+    instruction(
+        shadowbid::accounts::CancelBelowReserve {
+            caller: *caller,
+            auction: *auction_address,
+            winner_profile: bidder_profile_address(&auction.winner).0,
+            instructions: sysvar::instructions::ID,
+            inco_lightning_program: INCO_LIGHTNING_PROGRAM_ID,
+        },
+        shadowbid::instruction::CancelBelowReserve {
+            handle_bytes,
+            plaintext_bytes,
+        },
+    )
+}
+
+/// Builds `dispute` for the auction's winner
+pub fn dispute(auction_address: &Pubkey, auction: &Auction, evidence_uri: String) -> Instruction {
+    instruction(
+        shadowbid::accounts::Dispute {
+            winner: auction.winner,
+            auction: *auction_address,
+            escrow: escrow_address(auction_address).0,
+        },
+        shadowbid::instruction::Dispute { evidence_uri },
+    )
+}
+
+/// Builds `submit_evidence` for the winner or the seller of a disputed auction
+pub fn submit_evidence(submitter: &Pubkey, auction_address: &Pubkey, evidence_uri: String) -> Instruction {
+    instruction(
+        shadowbid::accounts::SubmitEvidence {
+            submitter: *submitter,
+            auction: *auction_address,
+        },
+        shadowbid::instruction::SubmitEvidence { evidence_uri },
+    )
+}
+
+/// Builds `resolve_dispute` for the auction's arbitrator, paying the
+/// seller's share to `beneficiary`, with the SellerBond vault for bonded
+/// auctions
+///
+/// Auctions with a hook need `resolve_dispute_with_hook`, unless its
+/// failures are ignored.
+pub fn resolve_dispute(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    buyer_bps: u16,
+) -> Instruction {
+    resolve(auction_address, auction, beneficiary, buyer_bps, None)
+}
+
+/// Builds `resolve_dispute` for an auction with a hook to `hook_program`,
+/// passing it `hook_accounts` after its fixed accounts (see
+/// `shadowbid::hook`)
+pub fn resolve_dispute_with_hook(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    buyer_bps: u16,
+    hook_program: &Pubkey,
+    hook_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut ix = resolve(auction_address, auction, beneficiary, buyer_bps, Some(*hook_program));
+    ix.accounts.extend(hook_accounts);
+    ix
+}
+
+fn resolve(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    beneficiary: &Pubkey,
+    buyer_bps: u16,
+    hook_program: Option<Pubkey>,
+) -> Instruction {
+    instruction(
+        shadowbid::accounts::ResolveDispute {
+            arbitrator: auction.arbitrator,
+            auction: *auction_address,
+            winner: auction.winner,
+            seller: auction.seller,
+            seller_profile: seller_profile_address(&auction.seller).0,
+            beneficiary: *beneficiary,
+            escrow: escrow_address(auction_address).0,
+            seller_bond: bonded_vault(auction_address, auction),
+            hook: hook_program.map(|_| hook_address(auction_address).0),
+            hook_program,
+        },
+        shadowbid::instruction::ResolveDispute { buyer_bps },
+    )
+}
+
+fn bonded_vault(auction_address: &Pubkey, auction: &Auction) -> Option<Pubkey> {
+    auction
+        .is_bonded()
+        .then(|| seller_bond_address(auction_address).0)
+}
+
+/// Builds `cancel_auction` for the auction's authority (its seller,
+/// without an authority record) or a delegate allowed to cancel, with
+/// the SellerBond vault for bonded auctions
+///
+/// `category` is counted in the seller's profile. Auctions with a hook
+/// need `cancel_auction_with_hook`, unless its failures are ignored.
+pub fn cancel_auction(
+    authority: &Pubkey,
+    auction_address: &Pubkey,
+    auction: &Auction,
+    reason: String,
+    category: CancellationReason,
+) -> Instruction {
+    cancel(authority, auction_address, auction, reason, category, None)
+}
+
+/// Builds `cancel_auction` for an auction with a hook to `hook_program`,
+/// passing it `hook_accounts` after its fixed accounts (see
+/// `shadowbid::hook`)
+pub fn cancel_auction_with_hook(
+    authority: &Pubkey,
+    auction_address: &Pubkey,
+    auction: &Auction,
+    reason: String,
+    category: CancellationReason,
+    hook_program: &Pubkey,
+    hook_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut ix = cancel(authority, auction_address, auction, reason, category, Some(*hook_program));
+    ix.accounts.extend(hook_accounts);
+    ix
+}
+
+fn cancel(
+    authority: &Pubkey,
+    auction_address: &Pubkey,
+    auction: &Auction,
+    reason: String,
+    category: CancellationReason,
+    hook_program: Option<Pubkey>,
+) -> Instruction {
+    instruction(
+        shadowbid::accounts::CancelAuction {
+            authority: *authority,
+            seller: auction.seller,
+            auction: *auction_address,
+            page: auction_page_address(auction.auction_id).0,
+            seller_profile: seller_profile_address(&auction.seller).0,
+            auction_authority: auction_authority_address(auction_address).0,
+            seller_bond: bonded_vault(auction_address, auction),
+            treasury: treasury_address().0,
+            hook: hook_program.map(|_| hook_address(auction_address).0),
+            hook_program,
+            system_program: hook_program.map(|_| system_program::ID),
+        },
+        shadowbid::instruction::CancelAuction { reason, category },
+    )
+}
+
+/// Builds `claim_compensation` for `bidder`'s share of a slashed seller bond
+pub fn claim_compensation(bidder: &Pubkey, auction_address: &Pubkey, auction: &Auction) -> Instruction {
+    let (bid, _) = bid_address(auction_address, bidder);
+    instruction(
+        shadowbid::accounts::ClaimCompensation {
+            bidder: *bidder,
+            auction: *auction_address,
+            bid,
+            seller_bond: seller_bond_address(auction_address).0,
+            seller: auction.seller,
+        },
+        shadowbid::instruction::ClaimCompensation {},
+    )
+}
+
+/// Builds `release_seller_bond` for the auction's seller
+pub fn release_seller_bond(auction_address: &Pubkey, auction: &Auction) -> Instruction {
+    instruction(
+        shadowbid::accounts::ReleaseSellerBond {
+            seller: auction.seller,
+            auction: *auction_address,
+            seller_bond: seller_bond_address(auction_address).0,
+        },
+        shadowbid::instruction::ReleaseSellerBond {},
+    )
+}
+
+/// Builds `transfer_authority` for the auction's authority (its seller,
+/// without an authority record, who then pays for the record)
+pub fn transfer_authority(authority: &Pubkey, auction_address: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    instruction(
+        shadowbid::accounts::TransferAuthority {
+            authority: *authority,
+            auction: *auction_address,
+            auction_authority: auction_authority_address(auction_address).0,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::TransferAuthority {
+            new_authority: *new_authority,
+        },
+    )
+}
+
+/// Builds `set_delegate` for the auction's authority, granting
+/// `delegate` exactly `permissions` (`Permission` bits; 0 revokes it)
+pub fn set_delegate(authority: &Pubkey, auction_address: &Pubkey, delegate: &Pubkey, permissions: u8) -> Instruction {
+    instruction(
+        shadowbid::accounts::SetDelegate {
+            authority: *authority,
+            auction: *auction_address,
+            auction_authority: auction_authority_address(auction_address).0,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::SetDelegate {
+            delegate: *delegate,
+            permissions,
+        },
+    )
+}
+
+/// Builds `reveal_auction_stats` for the auction's seller
+pub fn reveal_auction_stats(auction_address: &Pubkey, auction: &Auction) -> Instruction {
+    let seller = &auction.seller;
+    instruction(
+        shadowbid::accounts::RevealAuctionStats {
+            seller: *seller,
+            auction: *auction_address,
+            sum_allowance_account: allowance_address(auction.bid_sum_handle, seller).0,
+            lowest_allowance_account: allowance_address(auction.lowest_bid_handle, seller).0,
+            count_allowance_account: allowance_address(auction.above_reserve_count_handle, seller)
+                .0,
+            inco_lightning_program: INCO_LIGHTNING_PROGRAM_ID,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::RevealAuctionStats {},
+    )
+}
+
+/// Builds `publish_auction_stats` for the auction's seller
+///
+/// `plaintext_bytes` are the decrypted bid sum, lowest bid and
+/// above-reserve count, in that order. The Inco Ed25519 signature
+/// verification instructions must precede this instruction.
+pub fn publish_auction_stats(
+    auction_address: &Pubkey,
+    auction: &Auction,
+    plaintext_bytes: Vec<Vec<u8>>,
+) -> Instruction {
+    instruction(
+        shadowbid::accounts::PublishAuctionStats {
+            seller: auction.seller,
+            auction: *auction_address,
+            instructions: sysvar::instructions::ID,
+            inco_lightning_program: INCO_LIGHTNING_PROGRAM_ID,
+        },
+        shadowbid::instruction::PublishAuctionStats { plaintext_bytes },
+    )
+}
+
+/// Builds `migrate_auction` for an auction in the pre-versioning layout,
+/// with `payer` covering the extra rent
+pub fn migrate_auction(payer: &Pubkey, auction: &Pubkey) -> Instruction {
+    instruction(
+        shadowbid::accounts::MigrateAuction {
+            payer: *payer,
+            auction: *auction,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::MigrateAuction {},
+    )
+}
+
+/// Builds `migrate_bid` for a bid in the pre-versioning layout, with
+/// `payer` covering the extra rent
+pub fn migrate_bid(payer: &Pubkey, bid: &Pubkey) -> Instruction {
+    instruction(
+        shadowbid::accounts::MigrateBid {
+            payer: *payer,
+            bid: *bid,
+            system_program: system_program::ID,
+        },
+        shadowbid::instruction::MigrateBid {},
+    )
+}
@@ -0,0 +1,32 @@
+//! Rust client SDK for the ShadowBid program
+//!
+//! - `pda`: Auction, Bid, metadata, registry, seller and bidder profile, bond,
+//!   settlement callback, hook, receipt, treasury and Inco allowance addresses
+//! - `instructions`: instruction builders for every program instruction
+//! - `accounts`: typed account decoders and RPC fetchers, including open
+//!   auctions from the registry pages and seller profiles ranked for
+//!   marketplaces
+//! - `events`: event parsing from transaction logs
+//! - `lifecycle`: drives an auction through close → determine → finalize
+
+pub mod accounts;
+pub mod error;
+pub mod events;
+pub mod instructions;
+pub mod lifecycle;
+pub mod pda;
+
+pub use error::{ClientError, Result};
+pub use events::{parse_logs, ShadowBidEvent};
+pub use lifecycle::Lifecycle;
+pub use shadowbid::callback::SettlementResult;
+pub use shadowbid::hook::{AuctionCompletion, AuctionOutcome};
+pub use shadowbid::instructions::{
+    ArbitrationParams, AuthorityParams, CommitRevealParams, CreateAuctionParams, HookParams,
+};
+pub use shadowbid::state::{
+    Auction, AuctionAuthority, AuctionHook, AuctionMode, AuctionPage, AuctionState, Bid, BidderProfile,
+    CancellationPolicy, CancellationReason, Delegate, Escrow, HookMode, Permission, Receipt, Registry, SellerBond,
+    SellerProfile, SettlementCallback, Treasury,
+};
+pub use shadowbid::ID as PROGRAM_ID;
@@ -0,0 +1,129 @@
+//! Drives an auction through close → determine → finalize
+
+use anchor_lang::prelude::Pubkey;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use shadowbid::state::{Auction, AuctionState};
+
+use crate::accounts::{fetch_auction, fetch_bids_for_auction};
+use crate::error::{ClientError, Result};
+use crate::instructions;
+
+/// Sends the permissionless post-bidding instructions for an auction
+///
+/// Every step is idempotent with respect to auction state, so `run`
+/// can be called repeatedly (e.g. by a crank) and resumes wherever the
+/// auction currently is.
+pub struct Lifecycle<'a> {
+    rpc: &'a RpcClient,
+    payer: &'a Keypair,
+    reveal_to_seller: bool,
+}
+
+impl<'a> Lifecycle<'a> {
+    /// `payer` signs and pays for every transaction as the caller
+    pub fn new(rpc: &'a RpcClient, payer: &'a Keypair) -> Self {
+        Self {
+            rpc,
+            payer,
+            reveal_to_seller: false,
+        }
+    }
+
+    /// Also grant the seller decryption access when finalizing
+    pub fn reveal_to_seller(mut self, reveal_to_seller: bool) -> Self {
+        self.reveal_to_seller = reveal_to_seller;
+        self
+    }
+
+    /// Signs and sends a single instruction
+    pub fn send(&self, ix: Instruction) -> Result<Signature> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.payer.pubkey()),
+            &[self.payer],
+            blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+    }
+
+    /// Closes bidding (and the reveal window of commit-reveal auctions)
+    ///
+    /// The end time, and reveal end time, must have passed.
+    pub fn close(&self, address: &Pubkey) -> Result<Auction> {
+        let mut auction = fetch_auction(self.rpc, address)?;
+        if auction.state()? == AuctionState::Open {
+            self.send(instructions::close_bidding(&self.payer.pubkey(), address, &auction))?;
+            auction = fetch_auction(self.rpc, address)?;
+        }
+        if auction.state()? == AuctionState::Revealing {
+            self.send(instructions::close_reveal(&self.payer.pubkey(), address))?;
+            auction = fetch_auction(self.rpc, address)?;
+        }
+        Ok(auction)
+    }
+
+    /// Processes every unprocessed bid of a closed auction
+    ///
+    /// Returns the number of bids processed.
+    pub fn determine(&self, address: &Pubkey) -> Result<u32> {
+        let auction = self.expect_state(address, AuctionState::Closed)?;
+
+        let mut processed = 0;
+        for (_, bid) in fetch_bids_for_auction(self.rpc, address)? {
+            if bid.is_processed() {
+                continue;
+            }
+            self.send(instructions::determine_winner(
+                &self.payer.pubkey(),
+                address,
+                &auction,
+                &bid.bidder,
+            ))?;
+            processed += 1;
+        }
+        Ok(processed)
+    }
+
+    /// Finalizes the winner once every bid is processed
+    pub fn finalize(&self, address: &Pubkey) -> Result<Auction> {
+        let auction = self.expect_state(address, AuctionState::Closed)?;
+        self.send(instructions::finalize_winner(
+            &self.payer.pubkey(),
+            address,
+            &auction,
+            self.reveal_to_seller,
+        ))?;
+        fetch_auction(self.rpc, address)
+    }
+
+    /// Runs close → determine → finalize from the auction's current state
+    ///
+    /// Returns the auction after the last step; auctions without bids
+    /// end up `Cancelled` at the close step.
+    pub fn run(&self, address: &Pubkey) -> Result<Auction> {
+        let auction = self.close(address)?;
+        if auction.state()? != AuctionState::Closed {
+            return Ok(auction);
+        }
+
+        if !auction.all_bids_processed() {
+            self.determine(address)?;
+        }
+        self.finalize(address)
+    }
+
+    fn expect_state(&self, address: &Pubkey, state: AuctionState) -> Result<Auction> {
+        let auction = fetch_auction(self.rpc, address)?;
+        if auction.state()? != state {
+            return Err(ClientError::UnexpectedState {
+                auction: *address,
+                state: auction.state()?,
+            });
+        }
+        Ok(auction)
+    }
+}
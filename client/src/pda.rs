@@ -0,0 +1,144 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use shadowbid::state::AuctionPage;
+use shadowbid::constants::{
+    AUCTION_AUTHORITY_SEED, AUCTION_PAGE_SEED, AUCTION_SEED, BID_SEED, BIDDER_PROFILE_SEED, ESCROW_SEED, HOOK_SEED, INCO_LIGHTNING_PROGRAM_ID, METADATA_SEED,
+    RECEIPT_AUTHORITY_SEED, RECEIPT_MINT_SEED, RECEIPT_SEED, REGISTRY_SEED, SELLER_BOND_SEED,
+    SELLER_COUNTER_SEED, SELLER_PROFILE_SEED, SETTLEMENT_CALLBACK_SEED, TREASURY_SEED,
+};
+
+/// Derives the Auction PDA
+/// Seeds: ["auction", seller, auction_id (u64 LE)]
+pub fn auction_address(seller: &Pubkey, auction_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[AUCTION_SEED, seller.as_ref(), &auction_id.to_le_bytes()],
+        &shadowbid::ID,
+    )
+}
+
+/// Derives the global Registry PDA
+/// Seeds: ["registry"]
+pub fn registry_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTRY_SEED], &shadowbid::ID)
+}
+
+/// Derives the SellerCounter PDA
+/// Seeds: ["seller_counter", seller]
+pub fn seller_counter_address(seller: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SELLER_COUNTER_SEED, seller.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the SellerProfile PDA
+/// Seeds: ["seller_profile", seller]
+pub fn seller_profile_address(seller: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SELLER_PROFILE_SEED, seller.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the AuctionPage PDA listing auction `auction_id`
+/// Seeds: ["auction_page", page index (u64 LE)]
+pub fn auction_page_address(auction_id: u64) -> (Pubkey, u8) {
+    page_address(AuctionPage::index_of(auction_id))
+}
+
+/// Derives the AuctionPage PDA at `index`
+pub fn page_address(index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_PAGE_SEED, &index.to_le_bytes()], &shadowbid::ID)
+}
+
+/// Derives the Bid PDA
+/// Seeds: ["bid", auction, bidder]
+pub fn bid_address(auction: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BID_SEED, auction.as_ref(), bidder.as_ref()],
+        &shadowbid::ID,
+    )
+}
+
+/// Derives the BidderProfile PDA
+/// Seeds: ["bidder_profile", bidder]
+pub fn bidder_profile_address(bidder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BIDDER_PROFILE_SEED, bidder.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the AuctionMetadata PDA
+/// Seeds: ["metadata", auction]
+pub fn metadata_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[METADATA_SEED, auction.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the SellerBond vault PDA
+/// Seeds: ["seller_bond", auction]
+pub fn seller_bond_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SELLER_BOND_SEED, auction.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the Escrow PDA holding an escrowed auction's payment
+/// Seeds: ["escrow", auction]
+pub fn escrow_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_SEED, auction.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the SettlementCallback PDA naming the program an auction's
+/// settlement calls back
+/// Seeds: ["settlement_callback", auction]
+pub fn settlement_callback_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SETTLEMENT_CALLBACK_SEED, auction.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the AuctionHook PDA naming the program an auction invokes
+/// when it completes or is cancelled
+/// Seeds: ["hook", auction]
+pub fn hook_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HOOK_SEED, auction.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the AuctionAuthority PDA naming an auction's authority,
+/// beneficiary and delegates
+/// Seeds: ["auction_authority", auction]
+pub fn auction_authority_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_AUTHORITY_SEED, auction.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the Receipt PDA recording an auction's receipt NFT
+/// Seeds: ["receipt", auction]
+pub fn receipt_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_SEED, auction.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the mint PDA of an auction's receipt NFT
+/// Seeds: ["receipt_mint", auction]
+pub fn receipt_mint_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_MINT_SEED, auction.as_ref()], &shadowbid::ID)
+}
+
+/// Derives the global PDA holding the mint and metadata authority of
+/// every receipt NFT
+/// Seeds: ["receipt_authority"]
+pub fn receipt_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_AUTHORITY_SEED], &shadowbid::ID)
+}
+
+/// Derives the winner's Token-2022 account holding an auction's receipt
+pub fn receipt_token_address(auction: &Pubkey, owner: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(
+        owner,
+        &receipt_mint_address(auction).0,
+        &anchor_spl::token_2022::ID,
+    )
+}
+
+/// Derives the global Treasury PDA
+/// Seeds: ["treasury"]
+pub fn treasury_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], &shadowbid::ID)
+}
+
+/// Derives the Inco Lightning allowance PDA granting `allowed_address`
+/// decryption access to `handle`
+/// Seeds: [handle (u128 LE), allowed_address]
+pub fn allowance_address(handle: u128, allowed_address: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[&handle.to_le_bytes(), allowed_address.as_ref()],
+        &INCO_LIGHTNING_PROGRAM_ID,
+    )
+}
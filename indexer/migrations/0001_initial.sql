@@ -0,0 +1,56 @@
+-- Auctions, kept current from events and reconciled against account state
+CREATE TABLE auctions (
+    address         TEXT PRIMARY KEY,
+    seller          TEXT NOT NULL,
+    auction_id      INTEGER,
+    title           TEXT NOT NULL,
+    description     TEXT,
+    state           TEXT NOT NULL,
+    mode            TEXT,
+    reserve_price   INTEGER NOT NULL,
+    start_time      INTEGER NOT NULL,
+    end_time        INTEGER NOT NULL,
+    bid_count       INTEGER NOT NULL DEFAULT 0,
+    bids_processed  INTEGER NOT NULL DEFAULT 0,
+    winner          TEXT,
+    winning_amount  INTEGER,
+    cancel_reason   TEXT,
+    reconciled_at   INTEGER
+);
+
+CREATE INDEX auctions_seller ON auctions (seller);
+CREATE INDEX auctions_state ON auctions (state);
+
+-- One row per bidder per auction, like the Bid PDA
+CREATE TABLE bids (
+    auction          TEXT NOT NULL,
+    bidder           TEXT NOT NULL,
+    placed_at        INTEGER,
+    updated_at       INTEGER,
+    processed        INTEGER NOT NULL DEFAULT 0,
+    revealed_amount  INTEGER,
+    PRIMARY KEY (auction, bidder)
+);
+
+CREATE INDEX bids_bidder ON bids (bidder);
+
+-- Every decoded event, in transaction order
+CREATE TABLE events (
+    signature   TEXT NOT NULL,
+    idx         INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    block_time  INTEGER,
+    name        TEXT NOT NULL,
+    auction     TEXT NOT NULL,
+    data        TEXT NOT NULL,
+    PRIMARY KEY (signature, idx)
+);
+
+CREATE INDEX events_auction ON events (auction);
+CREATE INDEX events_slot ON events (slot);
+
+-- Indexer cursor and bookkeeping
+CREATE TABLE sync_state (
+    key    TEXT PRIMARY KEY,
+    value  TEXT NOT NULL
+);
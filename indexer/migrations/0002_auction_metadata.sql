@@ -0,0 +1,4 @@
+-- Structured metadata from MetadataUpdated events
+ALTER TABLE auctions ADD COLUMN metadata_uri TEXT;
+ALTER TABLE auctions ADD COLUMN content_hash TEXT;
+ALTER TABLE auctions ADD COLUMN tags TEXT;
@@ -0,0 +1,2 @@
+-- Cancellation reason categories from AuctionCancelled events
+ALTER TABLE auctions ADD COLUMN cancel_category TEXT;
@@ -0,0 +1,121 @@
+//! Local HTTP/JSON query API
+//!
+//! - `GET /health`: cursor and row counts
+//! - `GET /auctions?seller=&state=&limit=&offset=`: auction listing
+//! - `GET /auctions/<address>`: one auction with its bids and events
+//! - `GET /sellers/<seller>/auctions?state=`: a seller's auctions
+//! - `GET /bidders/<bidder>/bids`: a bidder's bids
+//! - `GET /events?auction=&limit=`: decoded events
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::db::{AuctionFilter, Db};
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1000;
+
+/// Serves requests until the process exits
+pub fn serve(addr: &str, db: Db) -> Result<()> {
+    let server = Server::http(addr).map_err(|err| anyhow!("failed to listen on {addr}: {err}"))?;
+    log::info!("query API listening on http://{addr}");
+
+    for request in server.incoming_requests() {
+        let (status, body) = match handle(&db, &request) {
+            Ok(Some(body)) => (200, body),
+            Ok(None) => (404, json!({ "error": "not found" })),
+            Err(err) => (400, json!({ "error": format!("{err:#}") })),
+        };
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(Header::from_bytes("Content-Type", "application/json").expect("static header"));
+        if let Err(err) = request.respond(response) {
+            log::warn!("failed to respond: {err}");
+        }
+    }
+    Ok(())
+}
+
+fn handle(db: &Db, request: &Request) -> Result<Option<Value>> {
+    if request.method() != &Method::Get {
+        return Err(anyhow!("only GET is supported"));
+    }
+
+    let (path, query) = match request.url().split_once('?') {
+        Some((path, query)) => (path, parse_query(query)),
+        None => (request.url(), HashMap::new()),
+    };
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let limit = match query.get("limit") {
+        Some(limit) => limit.parse::<u32>()?.min(MAX_LIMIT),
+        None => DEFAULT_LIMIT,
+    };
+    let offset = match query.get("offset") {
+        Some(offset) => offset.parse::<u32>()?,
+        None => 0,
+    };
+
+    Ok(match segments.as_slice() {
+        ["health"] => Some(db.status()?),
+        ["auctions"] => Some(Value::Array(db.auctions(&AuctionFilter {
+            seller: query.get("seller").cloned(),
+            state: query.get("state").cloned(),
+            tag: query.get("tag").cloned(),
+            limit,
+            offset,
+        })?)),
+        ["auctions", address] => db.auction(address)?,
+        ["sellers", seller, "auctions"] => Some(Value::Array(db.auctions(&AuctionFilter {
+            seller: Some(seller.to_string()),
+            state: query.get("state").cloned(),
+            tag: query.get("tag").cloned(),
+            limit,
+            offset,
+        })?)),
+        ["bidders", bidder, "bids"] => Some(Value::Array(db.bids_by_bidder(bidder)?)),
+        ["events"] => Some(Value::Array(
+            db.events(query.get("auction").map(String::as_str), limit)?,
+        )),
+        _ => None,
+    })
+}
+
+/// Parses `a=1&b=2`, percent-decoding values (tags are free text)
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), percent_decode(value)))
+        .collect()
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (escaped, bytes[i]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (None, b'+') => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (None, byte) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,484 @@
+//! SQLite storage: migrations, writes from events and accounts, and queries
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+use rusqlite::types::ValueRef;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use serde_json::{Map, Value};
+use shadowbid_client::{Auction, AuctionMode, AuctionState, Bid, ShadowBidEvent};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::events;
+
+/// Schema migrations, applied in order and tracked with `PRAGMA user_version`
+const MIGRATIONS: &[&str] = &[
+    include_str!("../migrations/0001_initial.sql"),
+    include_str!("../migrations/0002_auction_metadata.sql"),
+    include_str!("../migrations/0003_cancel_category.sql"),
+];
+
+/// `sync_state` key of the newest fully ingested transaction
+const CURSOR_KEY: &str = "last_signature";
+
+pub struct Db {
+    conn: Connection,
+}
+
+/// Where an event was found
+pub struct EventSource<'a> {
+    pub signature: &'a str,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+}
+
+/// Filters for `Db::auctions`
+#[derive(Default)]
+pub struct AuctionFilter {
+    pub seller: Option<String>,
+    pub state: Option<String>,
+    /// Category tag from the auction's metadata
+    pub tag: Option<String>,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl Db {
+    /// Opens (or creates) the database and applies pending migrations
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+
+        let mut db = Self { conn };
+        db.migrate()?;
+        Ok(db)
+    }
+
+    fn migrate(&mut self) -> Result<()> {
+        let version: usize = self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = self.conn.transaction()?;
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", index + 1)?;
+            tx.commit()?;
+            log::info!("applied migration {}", index + 1);
+        }
+        Ok(())
+    }
+
+    pub fn cursor(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = ?1",
+                [CURSOR_KEY],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Stores the events of one transaction and advances the cursor to it
+    pub fn ingest(&mut self, source: &EventSource, events: &[ShadowBidEvent]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for (idx, event) in events.iter().enumerate() {
+            apply_event(&tx, source, idx, event)?;
+        }
+        tx.execute(
+            "INSERT INTO sync_state (key, value) VALUES (?1, ?2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            params![CURSOR_KEY, source.signature],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Overwrites auction and bid rows with on-chain account state
+    pub fn reconcile(
+        &mut self,
+        auctions: &[(Pubkey, Auction)],
+        bids: &[(Pubkey, Bid)],
+        now: i64,
+    ) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for (address, auction) in auctions {
+            upsert_auction(&tx, address, auction, now)?;
+        }
+        for (_, bid) in bids {
+            upsert_bid(&tx, bid)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn auctions(&self, filter: &AuctionFilter) -> Result<Vec<Value>> {
+        let mut sql = String::from("SELECT * FROM auctions WHERE 1 = 1");
+        let mut args: Vec<String> = Vec::new();
+        if let Some(seller) = &filter.seller {
+            args.push(seller.clone());
+            sql.push_str(&format!(" AND seller = ?{}", args.len()));
+        }
+        if let Some(state) = &filter.state {
+            args.push(state.clone());
+            sql.push_str(&format!(" AND lower(state) = lower(?{})", args.len()));
+        }
+        if let Some(tag) = &filter.tag {
+            args.push(tag.clone());
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM json_each(auctions.tags) WHERE value = ?{})",
+                args.len()
+            ));
+        }
+        sql.push_str(&format!(
+            " ORDER BY start_time DESC LIMIT {} OFFSET {}",
+            filter.limit, filter.offset
+        ));
+        self.query(&sql, &args)
+    }
+
+    /// The auction with its bids and events, if indexed
+    pub fn auction(&self, address: &str) -> Result<Option<Value>> {
+        let Some(Value::Object(mut auction)) = self
+            .query("SELECT * FROM auctions WHERE address = ?1", &[address])?
+            .into_iter()
+            .next()
+        else {
+            return Ok(None);
+        };
+
+        auction.insert(
+            "bids".into(),
+            Value::Array(self.query(
+                "SELECT * FROM bids WHERE auction = ?1 ORDER BY placed_at",
+                &[address],
+            )?),
+        );
+        auction.insert("events".into(), Value::Array(self.events(Some(address), 1000)?));
+        Ok(Some(Value::Object(auction)))
+    }
+
+    /// A bidder's bids, with the title and state of each auction
+    pub fn bids_by_bidder(&self, bidder: &str) -> Result<Vec<Value>> {
+        self.query(
+            "SELECT bids.*, auctions.title, auctions.state, auctions.winner
+             FROM bids LEFT JOIN auctions ON auctions.address = bids.auction
+             WHERE bids.bidder = ?1 ORDER BY bids.placed_at DESC",
+            &[bidder],
+        )
+    }
+
+    pub fn events(&self, auction: Option<&str>, limit: u32) -> Result<Vec<Value>> {
+        let mut events = match auction {
+            Some(auction) => self.query(
+                &format!("SELECT * FROM events WHERE auction = ?1 ORDER BY slot, signature, idx LIMIT {limit}"),
+                &[auction],
+            )?,
+            None => self.query::<&str>(
+                &format!("SELECT * FROM events ORDER BY slot DESC, signature, idx LIMIT {limit}"),
+                &[],
+            )?,
+        };
+
+        // Stored as JSON text; return it as an object
+        for event in &mut events {
+            if let Some(Value::String(data)) = event.get("data") {
+                let data = serde_json::from_str(data).unwrap_or(Value::Null);
+                event["data"] = data;
+            }
+        }
+        Ok(events)
+    }
+
+    /// Counts and cursor for `/health`
+    pub fn status(&self) -> Result<Value> {
+        let count = |table: &str| -> Result<i64> {
+            Ok(self
+                .conn
+                .query_row(&format!("SELECT count(*) FROM {table}"), [], |row| row.get(0))?)
+        };
+        Ok(serde_json::json!({
+            "last_signature": self.cursor()?,
+            "auctions": count("auctions")?,
+            "bids": count("bids")?,
+            "events": count("events")?,
+        }))
+    }
+
+    /// Runs a query and returns each row as a JSON object keyed by column
+    fn query<P: rusqlite::ToSql>(&self, sql: &str, args: &[P]) -> Result<Vec<Value>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let rows = stmt.query_map(params_from_iter(args), |row| {
+            let mut object = Map::new();
+            for (index, column) in columns.iter().enumerate() {
+                let value = match row.get_ref(index)? {
+                    ValueRef::Null => Value::Null,
+                    ValueRef::Integer(value) => value.into(),
+                    ValueRef::Real(value) => value.into(),
+                    ValueRef::Text(value) => String::from_utf8_lossy(value).into(),
+                    ValueRef::Blob(value) => Value::Array(value.iter().map(|b| (*b).into()).collect()),
+                };
+                object.insert(column.clone(), value);
+            }
+            Ok(Value::Object(object))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+fn state_name(state: AuctionState) -> String {
+    format!("{state:?}")
+}
+
+/// Stores one event and applies it to the auction and bid rows
+///
+/// Events already stored (same signature and index) are skipped, so
+/// re-ingesting a transaction is harmless.
+fn apply_event(tx: &Transaction, source: &EventSource, idx: usize, event: &ShadowBidEvent) -> Result<()> {
+    let auction = event.auction().to_string();
+    let inserted = tx.execute(
+        "INSERT OR IGNORE INTO events (signature, idx, slot, block_time, name, auction, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            source.signature,
+            idx as i64,
+            source.slot as i64,
+            source.block_time,
+            event.name(),
+            auction,
+            events::to_json(event).to_string(),
+        ],
+    )?;
+    if inserted == 0 {
+        return Ok(());
+    }
+
+    match event {
+        ShadowBidEvent::AuctionCreated(e) => {
+            tx.execute(
+                "INSERT OR IGNORE INTO auctions (address, seller, title, state, reserve_price, start_time, end_time)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    auction,
+                    e.seller.to_string(),
+                    e.title,
+                    state_name(AuctionState::Open),
+                    e.reserve_price as i64,
+                    e.start_time,
+                    e.end_time,
+                ],
+            )?;
+        }
+        ShadowBidEvent::BidPlaced(e) => {
+            tx.execute(
+                "INSERT INTO bids (auction, bidder, placed_at, updated_at) VALUES (?1, ?2, ?3, ?3)
+                 ON CONFLICT (auction, bidder) DO UPDATE SET placed_at = excluded.placed_at",
+                params![auction, e.bidder.to_string(), e.timestamp],
+            )?;
+            tx.execute(
+                "UPDATE auctions SET bid_count = max(bid_count, ?2) WHERE address = ?1",
+                params![auction, e.bid_number],
+            )?;
+        }
+        ShadowBidEvent::BidUpdated(e) => {
+            tx.execute(
+                "UPDATE bids SET updated_at = ?3 WHERE auction = ?1 AND bidder = ?2",
+                params![auction, e.bidder.to_string(), e.timestamp],
+            )?;
+        }
+        ShadowBidEvent::BiddingClosed(e) => {
+            // Commit-reveal auctions move to Revealing; the mode is only
+            // known once the auction has been reconciled
+            tx.execute(
+                "UPDATE auctions SET bid_count = ?2, state = CASE
+                     WHEN ?2 = 0 THEN ?3
+                     WHEN mode = ?4 THEN ?5
+                     ELSE ?6 END
+                 WHERE address = ?1",
+                params![
+                    auction,
+                    e.total_bids,
+                    state_name(AuctionState::Cancelled),
+                    format!("{:?}", AuctionMode::CommitReveal),
+                    state_name(AuctionState::Revealing),
+                    state_name(AuctionState::Closed),
+                ],
+            )?;
+        }
+        ShadowBidEvent::BidRevealed(e) => {
+            tx.execute(
+                "UPDATE bids SET revealed_amount = ?3 WHERE auction = ?1 AND bidder = ?2",
+                params![auction, e.bidder.to_string(), e.amount as i64],
+            )?;
+        }
+        ShadowBidEvent::RevealClosed(_) => {
+            tx.execute(
+                "UPDATE auctions SET state = ?2 WHERE address = ?1",
+                params![auction, state_name(AuctionState::Closed)],
+            )?;
+        }
+        ShadowBidEvent::BidProcessed(e) => {
+            tx.execute(
+                "UPDATE bids SET processed = 1 WHERE auction = ?1 AND bidder = ?2",
+                params![auction, e.bidder.to_string()],
+            )?;
+            tx.execute(
+                "UPDATE auctions SET bids_processed = max(bids_processed, ?2) WHERE address = ?1",
+                params![auction, e.bids_processed],
+            )?;
+        }
+        ShadowBidEvent::WinnerDetermined(e) => {
+            tx.execute(
+                "UPDATE auctions SET state = ?2, winner = ?3 WHERE address = ?1",
+                params![auction, state_name(AuctionState::WinnerDetermined), e.winner.to_string()],
+            )?;
+        }
+        ShadowBidEvent::AuctionSettled(e) => {
+            tx.execute(
+                "UPDATE auctions SET state = ?2, winner = ?3, winning_amount = ?4 WHERE address = ?1",
+                params![
+                    auction,
+                    state_name(AuctionState::Settled),
+                    e.winner.to_string(),
+                    e.winning_amount as i64,
+                ],
+            )?;
+        }
+        ShadowBidEvent::PaymentEscrowed(e) => {
+            tx.execute(
+                "UPDATE auctions SET state = ?2, winner = ?3, winning_amount = ?4 WHERE address = ?1",
+                params![
+                    auction,
+                    state_name(AuctionState::AwaitingDelivery),
+                    e.winner.to_string(),
+                    e.amount as i64,
+                ],
+            )?;
+        }
+        ShadowBidEvent::WinnerDefaulted(_) => {
+            tx.execute(
+                "UPDATE auctions SET state = ?2 WHERE address = ?1",
+                params![auction, state_name(AuctionState::Defaulted)],
+            )?;
+        }
+        ShadowBidEvent::DisputeOpened(_) => {
+            tx.execute(
+                "UPDATE auctions SET state = ?2 WHERE address = ?1",
+                params![auction, state_name(AuctionState::Disputed)],
+            )?;
+        }
+        ShadowBidEvent::AuctionCancelled(e) => {
+            tx.execute(
+                "UPDATE auctions SET state = ?2, cancel_reason = ?3, cancel_category = ?4 WHERE address = ?1",
+                params![
+                    auction,
+                    state_name(AuctionState::Cancelled),
+                    e.reason,
+                    format!("{:?}", e.category),
+                ],
+            )?;
+        }
+        ShadowBidEvent::MetadataUpdated(e) => {
+            tx.execute(
+                "UPDATE auctions SET metadata_uri = ?2, content_hash = ?3, tags = ?4 WHERE address = ?1",
+                params![
+                    auction,
+                    e.uri,
+                    events::hex(&e.content_hash),
+                    serde_json::to_string(&e.tags)?,
+                ],
+            )?;
+        }
+        ShadowBidEvent::MetadataClosed(_) => {
+            tx.execute(
+                "UPDATE auctions SET metadata_uri = NULL, content_hash = NULL, tags = NULL WHERE address = ?1",
+                params![auction],
+            )?;
+        }
+        // History only
+        ShadowBidEvent::AuctionStatsRevealed(_)
+        | ShadowBidEvent::CollateralForfeited(_)
+        | ShadowBidEvent::DepositReclaimed(_)
+        | ShadowBidEvent::AccountMigrated(_)
+        | ShadowBidEvent::CompensationClaimed(_)
+        | ShadowBidEvent::BondReleased(_)
+        | ShadowBidEvent::BondSlashed(_)
+        | ShadowBidEvent::EscrowReleased(_)
+        | ShadowBidEvent::EvidenceSubmitted(_)
+        | ShadowBidEvent::DisputeResolved(_)
+        | ShadowBidEvent::SettlementCallbackInvoked(_)
+        | ShadowBidEvent::HookInvoked(_)
+        | ShadowBidEvent::HookSkipped(_)
+        | ShadowBidEvent::ReceiptMinted(_)
+        | ShadowBidEvent::ReceiptRedeemed(_)
+        | ShadowBidEvent::AuthorityTransferred(_)
+        | ShadowBidEvent::DelegateUpdated(_) => {}
+    }
+
+    Ok(())
+}
+
+fn upsert_auction(tx: &Transaction, address: &Pubkey, auction: &Auction, now: i64) -> Result<()> {
+    let winner = (auction.winner != Pubkey::default()).then(|| auction.winner.to_string());
+    let winning_amount = (auction.winning_amount > 0).then_some(auction.winning_amount as i64);
+    tx.execute(
+        "INSERT INTO auctions (address, seller, auction_id, title, description, state, mode,
+             reserve_price, start_time, end_time, bid_count, bids_processed, winner,
+             winning_amount, reconciled_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+         ON CONFLICT (address) DO UPDATE SET
+             auction_id = excluded.auction_id,
+             title = excluded.title,
+             description = excluded.description,
+             state = excluded.state,
+             mode = excluded.mode,
+             bid_count = excluded.bid_count,
+             bids_processed = excluded.bids_processed,
+             winner = excluded.winner,
+             winning_amount = excluded.winning_amount,
+             reconciled_at = excluded.reconciled_at",
+        params![
+            address.to_string(),
+            auction.seller.to_string(),
+            auction.auction_id as i64,
+            auction.get_title(),
+            auction.get_description(),
+            state_name(auction.state()?),
+            format!("{:?}", auction.mode()?),
+            auction.reserve_price as i64,
+            { auction.start_time },
+            { auction.end_time },
+            { auction.bid_count },
+            { auction.bids_processed },
+            winner,
+            winning_amount,
+            now,
+        ],
+    )?;
+    Ok(())
+}
+
+fn upsert_bid(tx: &Transaction, bid: &Bid) -> Result<()> {
+    tx.execute(
+        "INSERT INTO bids (auction, bidder, placed_at, updated_at, processed, revealed_amount)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT (auction, bidder) DO UPDATE SET
+             placed_at = excluded.placed_at,
+             updated_at = excluded.updated_at,
+             processed = excluded.processed,
+             revealed_amount = excluded.revealed_amount",
+        params![
+            bid.auction.to_string(),
+            bid.bidder.to_string(),
+            bid.created_at,
+            bid.updated_at,
+            bid.processed,
+            bid.revealed.then_some(bid.revealed_amount as i64),
+        ],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,209 @@
+//! JSON encoding of decoded events
+
+use serde_json::{json, Value};
+use shadowbid_client::ShadowBidEvent;
+
+/// The event's fields as a JSON object (public keys as base58 strings)
+pub fn to_json(event: &ShadowBidEvent) -> Value {
+    match event {
+        ShadowBidEvent::AuctionCreated(e) => json!({
+            "auction": e.auction.to_string(),
+            "seller": e.seller.to_string(),
+            "title": e.title,
+            "reserve_price": e.reserve_price,
+            "start_time": e.start_time,
+            "end_time": e.end_time,
+        }),
+        ShadowBidEvent::BidPlaced(e) => json!({
+            "auction": e.auction.to_string(),
+            "bidder": e.bidder.to_string(),
+            "bid_number": e.bid_number,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::BidUpdated(e) => json!({
+            "auction": e.auction.to_string(),
+            "bidder": e.bidder.to_string(),
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::BiddingClosed(e) => json!({
+            "auction": e.auction.to_string(),
+            "total_bids": e.total_bids,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::BidProcessed(e) => json!({
+            "auction": e.auction.to_string(),
+            "bidder": e.bidder.to_string(),
+            "bids_processed": e.bids_processed,
+        }),
+        ShadowBidEvent::WinnerDetermined(e) => json!({
+            "auction": e.auction.to_string(),
+            "winner": e.winner.to_string(),
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::AuctionSettled(e) => json!({
+            "auction": e.auction.to_string(),
+            "winner": e.winner.to_string(),
+            "winning_amount": e.winning_amount,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::AuctionCancelled(e) => json!({
+            "auction": e.auction.to_string(),
+            "seller": e.seller.to_string(),
+            "reason": e.reason,
+            "category": format!("{:?}", e.category),
+            "compensation_per_bid": e.compensation_per_bid,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::AuctionStatsRevealed(e) => json!({
+            "auction": e.auction.to_string(),
+            "bid_count": e.bid_count,
+            "total_amount": e.total_amount,
+            "average_amount": e.average_amount,
+            "lowest_amount": e.lowest_amount,
+            "spread": e.spread,
+            "bids_above_reserve": e.bids_above_reserve,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::BidRevealed(e) => json!({
+            "auction": e.auction.to_string(),
+            "bidder": e.bidder.to_string(),
+            "amount": e.amount,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::RevealClosed(e) => json!({
+            "auction": e.auction.to_string(),
+            "bids_revealed": e.bids_revealed,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::CollateralForfeited(e) => json!({
+            "auction": e.auction.to_string(),
+            "bidder": e.bidder.to_string(),
+            "amount": e.amount,
+        }),
+        ShadowBidEvent::DepositReclaimed(e) => json!({
+            "auction": e.auction.to_string(),
+            "bidder": e.bidder.to_string(),
+            "amount": e.amount,
+        }),
+        ShadowBidEvent::MetadataUpdated(e) => json!({
+            "auction": e.auction.to_string(),
+            "uri": e.uri,
+            "content_hash": hex(&e.content_hash),
+            "tags": e.tags,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::MetadataClosed(e) => json!({
+            "auction": e.auction.to_string(),
+            "seller": e.seller.to_string(),
+            "refunded": e.refunded,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::AccountMigrated(e) => json!({
+            "auction": e.auction.to_string(),
+            "account": e.account.to_string(),
+            "version": e.version,
+            "payer": e.payer.to_string(),
+        }),
+        ShadowBidEvent::CompensationClaimed(e) => json!({
+            "auction": e.auction.to_string(),
+            "bidder": e.bidder.to_string(),
+            "amount": e.amount,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::BondReleased(e) => json!({
+            "auction": e.auction.to_string(),
+            "seller": e.seller.to_string(),
+            "amount": e.amount,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::BondSlashed(e) => json!({
+            "auction": e.auction.to_string(),
+            "seller": e.seller.to_string(),
+            "to_bidders": e.to_bidders,
+            "to_treasury": e.to_treasury,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::PaymentEscrowed(e) => json!({
+            "auction": e.auction.to_string(),
+            "winner": e.winner.to_string(),
+            "amount": e.amount,
+            "delivery_deadline": e.delivery_deadline,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::EscrowReleased(e) => json!({
+            "auction": e.auction.to_string(),
+            "seller": e.seller.to_string(),
+            "amount": e.amount,
+            "confirmed": e.confirmed,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::DisputeOpened(e) => json!({
+            "auction": e.auction.to_string(),
+            "winner": e.winner.to_string(),
+            "amount": e.amount,
+            "evidence_uri": e.evidence_uri,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::EvidenceSubmitted(e) => json!({
+            "auction": e.auction.to_string(),
+            "submitter": e.submitter.to_string(),
+            "evidence_uri": e.evidence_uri,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::DisputeResolved(e) => json!({
+            "auction": e.auction.to_string(),
+            "arbitrator": e.arbitrator.to_string(),
+            "to_buyer": e.to_buyer,
+            "to_seller": e.to_seller,
+            "fee": e.fee,
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::SettlementCallbackInvoked(e) => json!({
+            "auction": e.auction.to_string(),
+            "program": e.program.to_string(),
+            "amount": e.amount,
+        }),
+        ShadowBidEvent::HookInvoked(e) => json!({
+            "auction": e.auction.to_string(),
+            "program": e.program.to_string(),
+            "outcome": format!("{:?}", e.outcome),
+        }),
+        ShadowBidEvent::HookSkipped(e) => json!({
+            "auction": e.auction.to_string(),
+            "outcome": format!("{:?}", e.outcome),
+        }),
+        ShadowBidEvent::ReceiptMinted(e) => json!({
+            "auction": e.auction.to_string(),
+            "winner": e.winner.to_string(),
+            "mint": e.mint.to_string(),
+            "amount": e.amount,
+        }),
+        ShadowBidEvent::ReceiptRedeemed(e) => json!({
+            "auction": e.auction.to_string(),
+            "mint": e.mint.to_string(),
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::WinnerDefaulted(e) => json!({
+            "auction": e.auction.to_string(),
+            "winner": e.winner.to_string(),
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::AuthorityTransferred(e) => json!({
+            "auction": e.auction.to_string(),
+            "previous": e.previous.to_string(),
+            "authority": e.authority.to_string(),
+            "timestamp": e.timestamp,
+        }),
+        ShadowBidEvent::DelegateUpdated(e) => json!({
+            "auction": e.auction.to_string(),
+            "delegate": e.delegate.to_string(),
+            "permissions": e.permissions,
+            "timestamp": e.timestamp,
+        }),
+    }
+}
+
+/// Lowercase hex encoding of a hash
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,101 @@
+//! Pulls ShadowBid transactions and account state from RPC into the database
+
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use shadowbid_client::{accounts, parse_logs};
+use solana_rpc_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+
+use crate::db::{Db, EventSource};
+
+/// Signatures requested per `getSignaturesForAddress` page
+const PAGE_SIZE: usize = 1000;
+
+pub struct Ingestor {
+    rpc: RpcClient,
+}
+
+impl Ingestor {
+    pub fn new(rpc: RpcClient) -> Self {
+        Self { rpc }
+    }
+
+    /// Ingests every program transaction newer than the stored cursor,
+    /// oldest first, returning the number of transactions ingested
+    pub fn sync(&self, db: &mut Db) -> Result<usize> {
+        let until = db.cursor()?.map(|sig| Signature::from_str(&sig)).transpose()?;
+
+        // Newest first, paged backwards until the cursor
+        let mut statuses = Vec::new();
+        let mut before = None;
+        loop {
+            let page = self.rpc.get_signatures_for_address_with_config(
+                &shadowbid::ID,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(PAGE_SIZE),
+                    commitment: Some(self.rpc.commitment()),
+                },
+            )?;
+            let full = page.len() == PAGE_SIZE;
+            before = page
+                .last()
+                .map(|status| Signature::from_str(&status.signature))
+                .transpose()?;
+            statuses.extend(page);
+            if !full {
+                break;
+            }
+        }
+
+        let count = statuses.len();
+        for status in statuses.into_iter().rev() {
+            // Failed transactions emit no events but still advance the cursor
+            let events = if status.err.is_some() {
+                Vec::new()
+            } else {
+                let tx = self.rpc.get_transaction_with_config(
+                    &Signature::from_str(&status.signature)?,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Json),
+                        commitment: Some(self.rpc.commitment()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )?;
+                match tx.transaction.meta.map(|meta| meta.log_messages) {
+                    Some(OptionSerializer::Some(logs)) => parse_logs(&logs),
+                    _ => Vec::new(),
+                }
+            };
+
+            db.ingest(
+                &EventSource {
+                    signature: &status.signature,
+                    slot: status.slot,
+                    block_time: status.block_time,
+                },
+                &events,
+            )?;
+        }
+
+        Ok(count)
+    }
+
+    /// Overwrites every indexed auction and bid with its account state
+    ///
+    /// Account state is authoritative: this fills in fields events don't
+    /// carry (auction ID, mode, description) and repairs anything missed.
+    pub fn reconcile(&self, db: &mut Db) -> Result<usize> {
+        let auctions = accounts::fetch_auctions(&self.rpc)?;
+        let bids = accounts::fetch_bids(&self.rpc)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        db.reconcile(&auctions, &bids, now)?;
+        Ok(auctions.len())
+    }
+}
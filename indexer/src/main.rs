@@ -0,0 +1,93 @@
+//! `shadowbid-indexer`: indexes ShadowBid events and account state into
+//! SQLite and serves a local query API
+
+mod api;
+mod db;
+mod events;
+mod ingest;
+
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use clap::Parser;
+use log::{info, warn};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::db::Db;
+use crate::ingest::Ingestor;
+
+#[derive(Parser)]
+#[command(name = "shadowbid-indexer", version, about = "Indexes ShadowBid auctions into SQLite")]
+struct Args {
+    /// RPC URL of the cluster
+    #[arg(short, long, env = "SHADOWBID_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// SQLite database path
+    #[arg(long, env = "SHADOWBID_INDEXER_DB", default_value = "shadowbid-indexer.db")]
+    db: PathBuf,
+
+    /// Address of the query API
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: String,
+
+    /// Seconds between transaction scans
+    #[arg(long, default_value_t = 5)]
+    interval: u64,
+
+    /// Seconds between reconciliations against account state
+    #[arg(long, default_value_t = 60)]
+    reconcile_interval: u64,
+
+    /// Don't serve the query API
+    #[arg(long)]
+    no_api: bool,
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+
+    // Migrate before the API opens its own connection
+    let mut db = Db::open(&args.db)?;
+    if !args.no_api {
+        let api_db = Db::open(&args.db)?;
+        let listen = args.listen.clone();
+        thread::spawn(move || {
+            if let Err(err) = api::serve(&listen, api_db) {
+                warn!("query API stopped: {err:#}");
+            }
+        });
+    }
+
+    let ingestor = Ingestor::new(RpcClient::new_with_commitment(
+        args.url.clone(),
+        CommitmentConfig::confirmed(),
+    ));
+    info!("indexing {} from {} into {}", shadowbid::ID, args.url, args.db.display());
+
+    let interval = Duration::from_secs(args.interval);
+    let reconcile_interval = Duration::from_secs(args.reconcile_interval);
+    let mut last_reconcile: Option<Instant> = None;
+
+    loop {
+        match ingestor.sync(&mut db) {
+            Ok(0) => {}
+            Ok(count) => info!("ingested {count} transactions"),
+            Err(err) => warn!("sync failed: {err:#}"),
+        }
+
+        if last_reconcile.is_none_or(|at| at.elapsed() >= reconcile_interval) {
+            match ingestor.reconcile(&mut db) {
+                Ok(count) => info!("reconciled {count} auctions"),
+                Err(err) => warn!("reconcile failed: {err:#}"),
+            }
+            last_reconcile = Some(Instant::now());
+        }
+
+        thread::sleep(interval);
+    }
+}
@@ -0,0 +1,267 @@
+//! Keeper that drives ShadowBid auctions through their permissionless steps
+//!
+//! Each tick scans every `Auction` account with `getProgramAccounts` and,
+//! using the cluster clock:
+//! 1. Closes bidding on open auctions past `end_time`
+//! 2. Closes the reveal window of commit-reveal auctions past `reveal_end_time`
+//! 3. Submits `determine_winner` for every unprocessed bid of closed auctions
+//! 4. Finalizes closed auctions once `all_bids_processed()` is true
+//! 5. Releases escrowed payments to the beneficiary once the delivery window ends
+//!
+//! Transactions carry a compute unit price and are retried with a fresh
+//! blockhash on transport errors. Program errors are not retried: they
+//! usually mean another keeper got there first, and the next tick
+//! re-reads the auction.
+
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use shadowbid_client::{accounts, instructions, Auction, AuctionState};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::clock::Clock;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::sysvar;
+use solana_sdk::transaction::Transaction;
+
+/// Keeper settings
+#[derive(Clone, Debug)]
+pub struct KeeperConfig {
+    /// Compute unit price in micro-lamports (0 disables the priority fee)
+    pub priority_fee: u64,
+    /// Compute unit limit per transaction (0 keeps the runtime default)
+    pub compute_unit_limit: u32,
+    /// Attempts per transaction before giving up until the next tick
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on each further attempt
+    pub retry_delay: Duration,
+    /// Also grant sellers decryption access when finalizing
+    pub reveal_to_seller: bool,
+}
+
+impl Default for KeeperConfig {
+    fn default() -> Self {
+        Self {
+            priority_fee: 0,
+            compute_unit_limit: 0,
+            max_attempts: 3,
+            retry_delay: Duration::from_millis(500),
+            reveal_to_seller: false,
+        }
+    }
+}
+
+/// The next permissionless step for an auction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    CloseBidding,
+    CloseReveal,
+    DetermineWinner,
+    FinalizeWinner,
+    ReleaseEscrow,
+}
+
+impl Action {
+    /// Picks the next step for `auction` at cluster time `now`, if any
+    ///
+    /// Auctions in the pre-versioning layout are skipped until someone
+    /// migrates them, since the program can't load them, and so are
+    /// auctions whose state byte isn't a known `AuctionState`.
+    pub fn next(auction: &Auction, now: i64) -> Option<Self> {
+        if auction.version < Auction::VERSION {
+            return None;
+        }
+        match auction.state().ok()? {
+            AuctionState::Open if auction.is_bidding_ended(now) => Some(Self::CloseBidding),
+            AuctionState::Revealing if now >= auction.reveal_end_time => Some(Self::CloseReveal),
+            AuctionState::Closed if !auction.all_bids_processed() => Some(Self::DetermineWinner),
+            AuctionState::Closed => Some(Self::FinalizeWinner),
+            AuctionState::AwaitingDelivery if auction.is_delivery_window_ended(now) => {
+                Some(Self::ReleaseEscrow)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// What one tick did
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TickReport {
+    /// Auctions that needed at least one step
+    pub auctions: usize,
+    /// Transactions confirmed
+    pub transactions: usize,
+    /// Auctions left unfinished because a transaction failed
+    pub failures: usize,
+}
+
+pub struct Keeper {
+    rpc: RpcClient,
+    payer: Keypair,
+    config: KeeperConfig,
+}
+
+impl Keeper {
+    /// `payer` signs and pays for every crank transaction
+    pub fn new(rpc: RpcClient, payer: Keypair, config: KeeperConfig) -> Self {
+        Self { rpc, payer, config }
+    }
+
+    /// Runs ticks forever, `interval` apart
+    pub fn run(&self, interval: Duration) -> ! {
+        loop {
+            match self.tick() {
+                Ok(report) if report.auctions > 0 => info!(
+                    "tick: {} auctions, {} transactions, {} failures",
+                    report.auctions, report.transactions, report.failures
+                ),
+                Ok(_) => {}
+                Err(err) => warn!("tick failed: {err:#}"),
+            }
+            thread::sleep(interval);
+        }
+    }
+
+    /// Scans every auction once and drives each as far as it can go
+    pub fn tick(&self) -> Result<TickReport> {
+        let now = self.cluster_time()?;
+        let mut report = TickReport::default();
+
+        for (address, auction) in accounts::fetch_auctions(&self.rpc)? {
+            if Action::next(&auction, now).is_none() {
+                continue;
+            }
+            report.auctions += 1;
+
+            match self.advance(&address, auction, now) {
+                Ok(sent) => report.transactions += sent,
+                Err(err) => {
+                    report.failures += 1;
+                    warn!("auction {address}: {err:#}");
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Applies steps to one auction until it needs none, returning the
+    /// number of transactions sent
+    pub fn advance(&self, address: &Pubkey, mut auction: Auction, now: i64) -> Result<usize> {
+        let caller = self.payer.pubkey();
+        let mut sent = 0;
+
+        while let Some(action) = Action::next(&auction, now) {
+            match action {
+                Action::CloseBidding => {
+                    self.send(instructions::close_bidding(&caller, address, &auction))?;
+                    sent += 1;
+                }
+                Action::CloseReveal => {
+                    self.send(instructions::close_reveal(&caller, address))?;
+                    sent += 1;
+                }
+                Action::DetermineWinner => {
+                    let pending: Vec<_> = accounts::fetch_bids_for_auction(&self.rpc, address)?
+                        .into_iter()
+                        .filter(|(_, bid)| !bid.is_processed())
+                        .collect();
+                    if pending.is_empty() {
+                        return Err(anyhow!(
+                            "{} of {} bids processed but no unprocessed bid found",
+                            { auction.bids_processed },
+                            { auction.bid_count }
+                        ));
+                    }
+                    for (_, bid) in pending {
+                        self.send(instructions::determine_winner(
+                            &caller,
+                            address,
+                            &auction,
+                            &bid.bidder,
+                        ))?;
+                        sent += 1;
+                    }
+                }
+                Action::FinalizeWinner => {
+                    self.send(instructions::finalize_winner(
+                        &caller,
+                        address,
+                        &auction,
+                        self.config.reveal_to_seller,
+                    ))?;
+                    sent += 1;
+                }
+                Action::ReleaseEscrow => {
+                    let beneficiary = accounts::fetch_beneficiary(&self.rpc, address, &auction)?;
+                    self.send(instructions::release_escrow(&caller, address, &auction, &beneficiary))?;
+                    sent += 1;
+                }
+            }
+
+            info!("auction {address}: {action:?}");
+            auction = accounts::fetch_auction(&self.rpc, address)?;
+        }
+
+        Ok(sent)
+    }
+
+    /// The cluster's current Unix timestamp, as seen by the program
+    pub fn cluster_time(&self) -> Result<i64> {
+        let account = self.rpc.get_account(&sysvar::clock::ID)?;
+        let clock: Clock = solana_sdk::account::from_account(&account)
+            .ok_or_else(|| anyhow!("failed to decode the clock sysvar"))?;
+        Ok(clock.unix_timestamp)
+    }
+
+    /// Sends `ix` with the configured compute budget, retrying transport
+    /// errors with a fresh blockhash
+    pub fn send(&self, ix: Instruction) -> Result<Signature> {
+        let mut ixs = Vec::with_capacity(3);
+        if self.config.compute_unit_limit > 0 {
+            ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(
+                self.config.compute_unit_limit,
+            ));
+        }
+        if self.config.priority_fee > 0 {
+            ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
+                self.config.priority_fee,
+            ));
+        }
+        ixs.push(ix);
+
+        let mut delay = self.config.retry_delay;
+        let mut attempt = 1;
+        loop {
+            let result = match self.rpc.get_latest_blockhash() {
+                Ok(blockhash) => {
+                    let tx = Transaction::new_signed_with_payer(
+                        &ixs,
+                        Some(&self.payer.pubkey()),
+                        &[&self.payer],
+                        blockhash,
+                    );
+                    self.rpc.send_and_confirm_transaction(&tx)
+                }
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(signature) => return Ok(signature),
+                // The program rejected it; resending the same instruction won't help
+                Err(err) if err.get_transaction_error().is_some() => return Err(err.into()),
+                Err(err) if attempt >= self.config.max_attempts.max(1) => return Err(err.into()),
+                Err(err) => {
+                    warn!("attempt {attempt} failed, retrying in {delay:?}: {err}");
+                    thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
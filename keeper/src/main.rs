@@ -0,0 +1,87 @@
+//! `shadowbid-keeper`: cranks ShadowBid auctions through close →
+//! determine → finalize
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use log::info;
+use shadowbid_keeper::{Keeper, KeeperConfig};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{read_keypair_file, Signer};
+
+#[derive(Parser)]
+#[command(name = "shadowbid-keeper", version, about = "Cranks ShadowBid auctions through their lifecycle")]
+struct Args {
+    /// RPC URL of the cluster
+    #[arg(short, long, env = "SHADOWBID_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair that pays for crank transactions [default: ~/.config/solana/id.json]
+    #[arg(short, long, env = "SHADOWBID_KEYPAIR")]
+    keypair: Option<String>,
+
+    /// Seconds between scans
+    #[arg(long, default_value_t = 10)]
+    interval: u64,
+
+    /// Compute unit price in micro-lamports
+    #[arg(long, default_value_t = 0)]
+    priority_fee: u64,
+
+    /// Compute unit limit per transaction (0 keeps the runtime default)
+    #[arg(long, default_value_t = 0)]
+    compute_unit_limit: u32,
+
+    /// Attempts per transaction
+    #[arg(long, default_value_t = 3)]
+    max_attempts: u32,
+
+    /// Also grant sellers decryption access when finalizing
+    #[arg(long)]
+    reveal_to_seller: bool,
+
+    /// Run a single scan and exit
+    #[arg(long)]
+    once: bool,
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+
+    let keypair_path = match args.keypair {
+        Some(path) => path,
+        None => format!(
+            "{}/.config/solana/id.json",
+            std::env::var("HOME").context("HOME is not set; pass --keypair")?
+        ),
+    };
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("failed to read keypair {keypair_path}: {err}"))?;
+    info!("keeper {} watching {}", payer.pubkey(), args.url);
+
+    let keeper = Keeper::new(
+        RpcClient::new_with_commitment(args.url, CommitmentConfig::confirmed()),
+        payer,
+        KeeperConfig {
+            priority_fee: args.priority_fee,
+            compute_unit_limit: args.compute_unit_limit,
+            max_attempts: args.max_attempts,
+            reveal_to_seller: args.reveal_to_seller,
+            ..KeeperConfig::default()
+        },
+    );
+
+    if args.once {
+        let report = keeper.tick()?;
+        info!(
+            "{} auctions, {} transactions, {} failures",
+            report.auctions, report.transactions, report.failures
+        );
+        return Ok(());
+    }
+
+    keeper.run(Duration::from_secs(args.interval))
+}
@@ -0,0 +1,124 @@
+//! Runs the keeper against a local validator
+//!
+//! Needs `solana-test-validator` with ShadowBid deployed and the mock
+//! Inco program at the Inco address (see "Running Without Inco" in the
+//! README), then:
+//!
+//! ```bash
+//! cargo test -p shadowbid-keeper -- --ignored
+//! ```
+//!
+//! Auctions last at least `MIN_AUCTION_DURATION`, so this takes a few
+//! minutes.
+
+use std::thread;
+use std::time::Duration;
+
+use mock_inco::codec as mock;
+use shadowbid::constants::MIN_AUCTION_DURATION;
+use shadowbid_client::{accounts, instructions, pda, AuctionState, CreateAuctionParams};
+use shadowbid_keeper::{Action, Keeper, KeeperConfig};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+fn rpc() -> RpcClient {
+    let url = std::env::var("SHADOWBID_RPC_URL").unwrap_or_else(|_| "http://127.0.0.1:8899".into());
+    RpcClient::new_with_commitment(url, CommitmentConfig::confirmed())
+}
+
+fn funded_keypair(rpc: &RpcClient) -> Keypair {
+    let keypair = Keypair::new();
+    let signature = rpc
+        .request_airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL)
+        .expect("airdrop");
+    while !rpc.confirm_transaction(&signature).expect("confirm airdrop") {
+        thread::sleep(Duration::from_millis(200));
+    }
+    keypair
+}
+
+fn send(rpc: &RpcClient, ix: Instruction, signer: &Keypair) {
+    let blockhash = rpc.get_latest_blockhash().expect("blockhash");
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&signer.pubkey()), &[signer], blockhash);
+    rpc.send_and_confirm_transaction(&tx).expect("transaction");
+}
+
+#[test]
+#[ignore = "needs a local validator with ShadowBid and mock-inco deployed"]
+fn keeper_closes_determines_and_finalizes() {
+    let rpc = rpc();
+    let seller = funded_keypair(&rpc);
+    let bidders = [funded_keypair(&rpc), funded_keypair(&rpc), funded_keypair(&rpc)];
+    let amounts = [3 * LAMPORTS_PER_SOL, 5 * LAMPORTS_PER_SOL, 4 * LAMPORTS_PER_SOL];
+
+    if accounts::fetch_registry(&rpc).is_err() {
+        send(&rpc, instructions::initialize_registry(&seller.pubkey()), &seller);
+    }
+    let registry = accounts::fetch_registry(&rpc).unwrap();
+    let (auction, _) = pda::auction_address(&seller.pubkey(), registry.auction_count);
+    send(
+        &rpc,
+        instructions::create_auction(
+            &seller.pubkey(),
+            &registry,
+            CreateAuctionParams {
+                title: "Keeper test".into(),
+                description: String::new(),
+                reserve_price: LAMPORTS_PER_SOL,
+                duration: MIN_AUCTION_DURATION,
+                item_mint: None,
+                commit_reveal: None,
+                bid_deposit: None,
+                seller_bond: None,
+                delivery_timeout: None,
+                arbitration: None,
+                settlement_callback: None,
+                hook: None,
+                issue_receipt: false,
+                max_bidder_defaults: None,
+                authority: None,
+            },
+        ),
+        &seller,
+    );
+    for (bidder, amount) in bidders.iter().zip(amounts) {
+        send(
+            &rpc,
+            instructions::place_bid(&bidder.pubkey(), &auction, mock::encrypt(amount)),
+            bidder,
+        );
+    }
+
+    let keeper = Keeper::new(
+        self::rpc(),
+        funded_keypair(&rpc),
+        KeeperConfig {
+            priority_fee: 1_000,
+            ..KeeperConfig::default()
+        },
+    );
+
+    // Nothing to do while bidding is open
+    let account = accounts::fetch_auction(&rpc, &auction).unwrap();
+    assert_eq!(Action::next(&account, keeper.cluster_time().unwrap()), None);
+
+    while keeper.cluster_time().unwrap() < account.end_time {
+        thread::sleep(Duration::from_secs(2));
+    }
+    let report = keeper.tick().unwrap();
+    assert_eq!(report.failures, 0);
+
+    let account = accounts::fetch_auction(&rpc, &auction).unwrap();
+    assert_eq!(account.state().unwrap(), AuctionState::WinnerDetermined);
+    assert_eq!({ account.bids_processed }, 3);
+    assert_eq!(account.winner, bidders[1].pubkey());
+    assert_eq!(mock::decrypt(account.highest_bid_handle), Some(amounts[1]));
+
+    // A second scan finds nothing left to do for this auction
+    let now = keeper.cluster_time().unwrap();
+    assert_eq!(Action::next(&account, now), None);
+}
@@ -0,0 +1,61 @@
+use anchor_lang::solana_program::hash::hashv;
+
+/// Tag stored in the high 64 bits of every encrypted boolean handle
+pub const EBOOL_TAG: u64 = 0xB001_B001_B001_B001;
+
+/// Encodes an amount as a mock ciphertext (16 bytes, little-endian)
+///
+/// This is what a client passes to `place_bid` instead of an Inco
+/// SDK ciphertext when running against the mock.
+pub fn encrypt(amount: u64) -> Vec<u8> {
+    (amount as u128).to_le_bytes().to_vec()
+}
+
+/// Decodes a mock ciphertext back into its amount
+pub fn decode_ciphertext(ciphertext: &[u8]) -> Option<u64> {
+    match ciphertext.len() {
+        8 => Some(u64::from_le_bytes(ciphertext.try_into().ok()?)),
+        16 => u64::try_from(u128::from_le_bytes(ciphertext.try_into().ok()?)).ok(),
+        _ => None,
+    }
+}
+
+/// Builds a handle carrying `value`
+///
+/// The high 64 bits are a non-zero tag derived from `seeds`, so two
+/// bids for the same amount still get distinct handles. The low 64
+/// bits hold the plaintext.
+pub fn make_handle(seeds: &[&[u8]], value: u64) -> u128 {
+    let hash = hashv(seeds).to_bytes();
+    let mut tag = [0u8; 8];
+    tag.copy_from_slice(&hash[..8]);
+    let tag = u64::from_le_bytes(tag) | 1;
+    ((tag as u128) << 64) | value as u128
+}
+
+/// Builds an encrypted boolean handle
+pub fn make_bool(value: bool) -> u128 {
+    ((EBOOL_TAG as u128) << 64) | value as u128
+}
+
+/// Returns the plaintext of a handle, or None for an uninitialized handle
+pub fn decrypt(handle: u128) -> Option<u64> {
+    if handle >> 64 == 0 {
+        return None;
+    }
+    Some(handle as u64)
+}
+
+/// Returns the plaintext of an encrypted boolean handle
+pub fn decrypt_bool(handle: u128) -> Option<bool> {
+    if (handle >> 64) as u64 != EBOOL_TAG {
+        return None;
+    }
+    Some(handle & 1 == 1)
+}
+
+/// Encodes a decrypted amount the way attested plaintexts are passed
+/// to `settle_auction` (16 bytes, little-endian)
+pub fn plaintext_bytes(value: u64) -> Vec<u8> {
+    (value as u128).to_le_bytes().to_vec()
+}
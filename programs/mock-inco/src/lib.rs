@@ -0,0 +1,225 @@
+// The IDL instructions generated by `#[program]` still call `AccountInfo::realloc`
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use inco_lightning::types::{Ebool, Euint128, SignatureVerificationResult};
+
+pub mod codec;
+
+// Impersonates Inco Lightning: it declares the real Inco program ID so
+// ShadowBid's address constraints accept it unchanged. Only load it into
+// `solana-program-test` or a throwaway `solana-test-validator`; never
+// deploy it to a cluster where the real Inco program is expected
+declare_id!("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj");
+
+/// Marks the right-hand operand as a plaintext scalar
+const SCALAR: u8 = 1;
+
+/// Mock Inco Lightning: a plaintext stand-in for offline testing
+///
+/// Implements the subset of the Inco Lightning interface used by
+/// ShadowBid with the same instruction encoding, so the Inco CPI
+/// helpers work against it unchanged. Handles carry their plaintext
+/// (see `codec`), so nothing here is confidential. Never deploy this
+/// anywhere but a test validator.
+#[program]
+pub mod mock_inco {
+    use super::*;
+
+    /// Creates a handle from a mock ciphertext (see `codec::encrypt`)
+    pub fn new_euint128(
+        ctx: Context<Operation>,
+        ciphertext: Vec<u8>,
+        _input_type: u8,
+    ) -> Result<Euint128> {
+        let value = codec::decode_ciphertext(&ciphertext).ok_or(MockIncoError::InvalidCiphertext)?;
+        let signer = ctx.accounts.signer.key();
+        Ok(Euint128(codec::make_handle(
+            &[b"new_euint128", signer.as_ref(), &ciphertext],
+            value,
+        )))
+    }
+
+    /// Creates a handle from a public value
+    pub fn as_euint128(ctx: Context<Operation>, value: u128) -> Result<Euint128> {
+        let value = u64::try_from(value).map_err(|_| MockIncoError::Overflow)?;
+        let signer = ctx.accounts.signer.key();
+        Ok(Euint128(codec::make_handle(
+            &[b"as_euint128", signer.as_ref(), &value.to_le_bytes()],
+            value,
+        )))
+    }
+
+    pub fn e_add(
+        _ctx: Context<Operation>,
+        lhs: Euint128,
+        rhs: Euint128,
+        scalar_byte: u8,
+    ) -> Result<Euint128> {
+        let (a, b) = operands(lhs, rhs, scalar_byte)?;
+        let sum = a.checked_add(b).ok_or(MockIncoError::Overflow)?;
+        Ok(Euint128(codec::make_handle(
+            &[b"e_add", &lhs.0.to_le_bytes(), &rhs.0.to_le_bytes()],
+            sum,
+        )))
+    }
+
+    pub fn e_ge(
+        _ctx: Context<Operation>,
+        lhs: Euint128,
+        rhs: Euint128,
+        scalar_byte: u8,
+    ) -> Result<Ebool> {
+        let (a, b) = operands(lhs, rhs, scalar_byte)?;
+        Ok(Ebool(codec::make_bool(a >= b)))
+    }
+
+    pub fn e_select(
+        _ctx: Context<Operation>,
+        condition: Ebool,
+        if_true: Euint128,
+        if_false: Euint128,
+        _scalar_byte: u8,
+    ) -> Result<Euint128> {
+        let condition = codec::decrypt_bool(condition.0).ok_or(MockIncoError::HandleNotInitialized)?;
+        Ok(if condition { if_true } else { if_false })
+    }
+
+    /// Records a decryption permission in an allowance PDA
+    /// Seeds: [handle (16 bytes LE), allowed_address]
+    pub fn allow(
+        ctx: Context<Allow>,
+        handle: u128,
+        value: bool,
+        allowed_address: Pubkey,
+    ) -> Result<()> {
+        require_keys_eq!(
+            allowed_address,
+            ctx.accounts.allowed_address.key(),
+            MockIncoError::AllowedAddressMismatch
+        );
+
+        let allowance = &mut ctx.accounts.allowance_account;
+        allowance.handle = handle;
+        allowance.allowed_address = allowed_address;
+        allowance.allowed = value;
+
+        Ok(())
+    }
+
+    /// Checks that each plaintext is the value carried by its handle
+    ///
+    /// Stands in for the Ed25519 attestation check, so no signature
+    /// instructions are needed in the transaction.
+    pub fn is_validsignature(
+        _ctx: Context<VerifySignature>,
+        expected_signature_count: u8,
+        handles: Option<Vec<Vec<u8>>>,
+        plaintext_values: Option<Vec<Vec<u8>>>,
+    ) -> Result<Vec<SignatureVerificationResult>> {
+        let handles = handles.unwrap_or_default();
+        let plaintext_values = plaintext_values.unwrap_or_default();
+        require!(
+            handles.len() == expected_signature_count as usize
+                && plaintext_values.len() == handles.len(),
+            MockIncoError::InvalidSignature
+        );
+
+        let mut results = Vec::with_capacity(handles.len());
+        for (handle, plaintext) in handles.iter().zip(plaintext_values.iter()) {
+            let handle: [u8; 16] = handle
+                .as_slice()
+                .try_into()
+                .map_err(|_| MockIncoError::InvalidSignature)?;
+            let value = codec::decrypt(u128::from_le_bytes(handle))
+                .ok_or(MockIncoError::HandleNotInitialized)?;
+            let claimed = codec::decode_ciphertext(plaintext).ok_or(MockIncoError::InvalidSignature)?;
+            require!(value == claimed, MockIncoError::InvalidSignature);
+
+            results.push(SignatureVerificationResult {
+                public_key: [0u8; 32],
+                signature: [0u8; 64],
+                message: [handle.as_slice(), plaintext.as_slice()].concat(),
+            });
+        }
+
+        Ok(results)
+    }
+}
+
+/// Resolves the plaintext operands of a binary operation
+fn operands(lhs: Euint128, rhs: Euint128, scalar_byte: u8) -> Result<(u64, u64)> {
+    let a = codec::decrypt(lhs.0).ok_or(MockIncoError::HandleNotInitialized)?;
+    let b = if scalar_byte == SCALAR {
+        u64::try_from(rhs.0).map_err(|_| MockIncoError::Overflow)?
+    } else {
+        codec::decrypt(rhs.0).ok_or(MockIncoError::HandleNotInitialized)?
+    };
+    Ok((a, b))
+}
+
+#[derive(Accounts)]
+pub struct Operation<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(handle: u128)]
+pub struct Allow<'info> {
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = Allowance::SPACE,
+        seeds = [&handle.to_le_bytes(), allowed_address.key().as_ref()],
+        bump
+    )]
+    pub allowance_account: Account<'info, Allowance>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: Only its address is recorded
+    pub allowed_address: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifySignature<'info> {
+    /// CHECK: Not inspected by the mock
+    pub instructions: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+/// Decryption permission for one handle and address
+#[account]
+pub struct Allowance {
+    pub handle: u128,
+    pub allowed_address: Pubkey,
+    pub allowed: bool,
+}
+
+impl Allowance {
+    pub const SPACE: usize = 8 + 16 + 32 + 1;
+}
+
+#[error_code]
+pub enum MockIncoError {
+    #[msg("Mock ciphertext must be an 8 or 16 byte little-endian amount")]
+    InvalidCiphertext,
+
+    #[msg("Handle is not initialized")]
+    HandleNotInitialized,
+
+    #[msg("Mock arithmetic overflowed 64 bits")]
+    Overflow,
+
+    #[msg("Plaintext does not match the handle")]
+    InvalidSignature,
+
+    #[msg("Allowed address does not match the instruction argument")]
+    AllowedAddressMismatch,
+}
@@ -0,0 +1,185 @@
+//! Settlement callback to the programs that sell through ShadowBid
+//!
+//! An auction created with `settlement_callback` calls that program back
+//! from `settle_auction`, after the payment reached the seller. The callee
+//! implements an instruction named `on_auction_settled` (Anchor
+//! discriminator `ON_AUCTION_SETTLED_DISCRIMINATOR`) taking a
+//! `SettlementResult`, with the accounts:
+//!
+//! 0. `auction` - signer: the Auction PDA, signing through ShadowBid, which
+//!    proves the call comes from a real settlement. Check its owner is
+//!    `shadowbid::ID`.
+//! 1. `winner` - the winning bidder
+//! 2. `seller` - writable: the seller that was paid
+//! 3. ... - the accounts the winner passed after `settle_auction`'s own,
+//!    with their writability but never as signers
+//!
+//! A failing callback fails the settlement, so collateral release and
+//! payment happen atomically or not at all. Callbacks only hear of direct
+//! settlements; programs that also need escrowed payouts, refunds or
+//! cancellations use a hook instead (see `crate::hook`).
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+
+use crate::constants::AUCTION_SEED;
+use crate::errors::ShadowBidError;
+use crate::events::SettlementCallbackInvoked;
+use crate::hook::AuctionCompletion;
+use crate::state::SettlementCallback;
+
+/// First 8 bytes of `sha256("global:on_auction_settled")`, the Anchor
+/// discriminator of the callback instruction
+pub const ON_AUCTION_SETTLED_DISCRIMINATOR: [u8; 8] = [205, 222, 170, 186, 75, 79, 3, 30];
+
+/// Argument of the `on_auction_settled` callback
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SettlementResult {
+    /// The settled auction
+    pub auction: Pubkey,
+    /// Registry ID of the auction
+    pub auction_id: u64,
+    /// The seller that was paid
+    pub seller: Pubkey,
+    /// The winning bidder
+    pub winner: Pubkey,
+    /// Lamports paid to the seller
+    pub amount: u64,
+}
+
+impl From<&AuctionCompletion> for SettlementResult {
+    fn from(completion: &AuctionCompletion) -> Self {
+        Self {
+            auction: completion.auction,
+            auction_id: completion.auction_id,
+            seller: completion.seller,
+            winner: completion.winner,
+            amount: completion.amount,
+        }
+    }
+}
+
+impl SettlementResult {
+    /// Instruction data of the callback: discriminator then Borsh arguments
+    pub fn instruction_data(&self) -> Result<Vec<u8>> {
+        let mut data = ON_AUCTION_SETTLED_DISCRIMINATOR.to_vec();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+
+/// Checks the callback accounts a settlement was given against the auction
+///
+/// An auction with a callback requires it, or the seller's program would
+/// never learn it was paid; the program passed must be the one it names.
+/// Returns the program to call back, if any.
+pub fn check_settlement_callback<'a, 'info>(
+    has_callback: bool,
+    callback: Option<&SettlementCallback>,
+    program: Option<&'a AccountInfo<'info>>,
+) -> Result<Option<&'a AccountInfo<'info>>> {
+    match (has_callback, callback, program) {
+        (false, None, _) => Ok(None),
+        (true, Some(callback), Some(program)) if program.key() == callback.program => Ok(Some(program)),
+        _ => err!(ShadowBidError::SettlementCallbackMismatch),
+    }
+}
+
+/// Calls back `program` with `result`, signed by the auction PDA with
+/// `bump`, and records the call
+///
+/// The callee receives the auction account, so the caller must release
+/// its borrow of the auction first.
+pub(crate) fn run_settlement_callback<'info>(
+    program: &AccountInfo<'info>,
+    auction: &AccountInfo<'info>,
+    winner: &AccountInfo<'info>,
+    seller: &AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    result: &SettlementResult,
+    bump: u8,
+) -> Result<()> {
+    let auction_id = result.auction_id.to_le_bytes();
+    invoke_settlement_callback(
+        program,
+        auction,
+        winner,
+        seller,
+        extra_accounts,
+        result,
+        &[AUCTION_SEED, result.seller.as_ref(), &auction_id, &[bump]],
+    )?;
+
+    emit!(SettlementCallbackInvoked {
+        auction: result.auction,
+        program: program.key(),
+        amount: result.amount,
+    });
+    msg!("Settlement callback invoked: {}", program.key());
+    Ok(())
+}
+
+/// Invokes `on_auction_settled` on `program`, signed by the auction PDA
+///
+/// Signatures of the transaction are never forwarded: a callback program
+/// is chosen by the seller, and must not act for the winner.
+pub fn invoke_settlement_callback<'info>(
+    program: &AccountInfo<'info>,
+    auction: &AccountInfo<'info>,
+    winner: &AccountInfo<'info>,
+    seller: &AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    result: &SettlementResult,
+    auction_seeds: &[&[u8]],
+) -> Result<()> {
+    invoke_as_auction(
+        program,
+        auction,
+        winner,
+        seller,
+        extra_accounts,
+        result.instruction_data()?,
+        auction_seeds,
+    )
+}
+
+/// Invokes `program` with `data` and the accounts shared by callbacks and
+/// hooks: the auction PDA as signer, the winner, the writable seller, then
+/// `extra_accounts` with their writability but never as signers
+pub(crate) fn invoke_as_auction<'info>(
+    program: &AccountInfo<'info>,
+    auction: &AccountInfo<'info>,
+    winner: &AccountInfo<'info>,
+    seller: &AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+    auction_seeds: &[&[u8]],
+) -> Result<()> {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(auction.key(), true),
+        AccountMeta::new_readonly(winner.key(), false),
+        AccountMeta::new(seller.key(), false),
+    ];
+    accounts.extend(extra_accounts.iter().map(|account| AccountMeta {
+        pubkey: account.key(),
+        is_signer: false,
+        is_writable: account.is_writable,
+    }));
+
+    let mut account_infos = vec![auction.clone(), winner.clone(), seller.clone()];
+    account_infos.extend_from_slice(extra_accounts);
+    account_infos.push(program.clone());
+
+    invoke_signed(
+        &Instruction {
+            program_id: program.key(),
+            accounts,
+            data,
+        },
+        &account_infos,
+        &[auction_seeds],
+    )?;
+
+    Ok(())
+}
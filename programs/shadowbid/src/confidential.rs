@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::{Allow, Operation, VerifySignature};
+use inco_lightning::types::{Ebool, Euint128};
+
+use crate::errors::ShadowBidError;
+
+/// Marks the right-hand operand as a plaintext scalar
+const SCALAR: u8 = 1;
+
+/// Marks both operands as encrypted handles
+const ENCRYPTED: u8 = 0;
+
+/// Confidential compute operations used by the auction
+///
+/// Every instruction goes through this trait instead of calling the
+/// Inco Lightning CPI helpers directly, so the encrypted operations
+/// live in one place.
+pub trait ConfidentialBackend<'info> {
+    /// Creates an encrypted handle from a client-side ciphertext
+    fn new_euint128(&self, ciphertext: Vec<u8>) -> Result<Euint128>;
+
+    /// Creates an encrypted handle from a public value
+    fn as_euint128(&self, value: u128) -> Result<Euint128>;
+
+    /// Encrypted addition of two handles
+    fn e_add(&self, lhs: Euint128, rhs: Euint128) -> Result<Euint128>;
+
+    /// Encrypted addition of a handle and a plaintext scalar
+    fn e_add_scalar(&self, lhs: Euint128, rhs: u128) -> Result<Euint128>;
+
+    /// Encrypted `lhs >= rhs` of two handles
+    fn e_ge(&self, lhs: Euint128, rhs: Euint128) -> Result<Ebool>;
+
+    /// Encrypted `lhs >= rhs` of a handle and a plaintext scalar
+    fn e_ge_scalar(&self, lhs: Euint128, rhs: u128) -> Result<Ebool>;
+
+    /// Encrypted `condition ? if_true : if_false`
+    fn e_select(&self, condition: Ebool, if_true: Euint128, if_false: Euint128) -> Result<Euint128>;
+
+    /// Grants `allowed_address` permission to decrypt `handle`
+    fn allow(
+        &self,
+        allowance_account: &AccountInfo<'info>,
+        allowed_address: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        handle: u128,
+    ) -> Result<()>;
+
+    /// Verifies attested decryptions of `handles` to `plaintexts`
+    ///
+    /// The transaction must carry the Ed25519 signature verification
+    /// instructions for every handle.
+    fn verify_decryption(
+        &self,
+        instructions: &AccountInfo<'info>,
+        handles: Vec<Vec<u8>>,
+        plaintexts: Vec<Vec<u8>>,
+    ) -> Result<()>;
+}
+
+/// Backend that CPIs into the Inco Lightning program
+pub struct IncoBackend<'info> {
+    /// The Inco Lightning program (or the mock deployed at its address)
+    program: AccountInfo<'info>,
+    /// The account signing the encrypted operations
+    signer: AccountInfo<'info>,
+}
+
+impl<'info> IncoBackend<'info> {
+    pub fn new(program: &AccountInfo<'info>, signer: &AccountInfo<'info>) -> Self {
+        Self {
+            program: program.clone(),
+            signer: signer.clone(),
+        }
+    }
+
+    fn operation(&self) -> CpiContext<'_, '_, '_, 'info, Operation<'info>> {
+        CpiContext::new(
+            self.program.clone(),
+            Operation {
+                signer: self.signer.clone(),
+            },
+        )
+    }
+}
+
+impl<'info> ConfidentialBackend<'info> for IncoBackend<'info> {
+    fn new_euint128(&self, ciphertext: Vec<u8>) -> Result<Euint128> {
+        inco_lightning::cpi::new_euint128(self.operation(), ciphertext, 0)
+    }
+
+    fn as_euint128(&self, value: u128) -> Result<Euint128> {
+        inco_lightning::cpi::as_euint128(self.operation(), value)
+    }
+
+    fn e_add(&self, lhs: Euint128, rhs: Euint128) -> Result<Euint128> {
+        inco_lightning::cpi::e_add(self.operation(), lhs, rhs, ENCRYPTED)
+    }
+
+    fn e_add_scalar(&self, lhs: Euint128, rhs: u128) -> Result<Euint128> {
+        inco_lightning::cpi::e_add(self.operation(), lhs, Euint128(rhs), SCALAR)
+    }
+
+    fn e_ge(&self, lhs: Euint128, rhs: Euint128) -> Result<Ebool> {
+        inco_lightning::cpi::e_ge(self.operation(), lhs, rhs, ENCRYPTED)
+    }
+
+    fn e_ge_scalar(&self, lhs: Euint128, rhs: u128) -> Result<Ebool> {
+        inco_lightning::cpi::e_ge(self.operation(), lhs, Euint128(rhs), SCALAR)
+    }
+
+    fn e_select(&self, condition: Ebool, if_true: Euint128, if_false: Euint128) -> Result<Euint128> {
+        inco_lightning::cpi::e_select(self.operation(), condition, if_true, if_false, ENCRYPTED)
+    }
+
+    fn allow(
+        &self,
+        allowance_account: &AccountInfo<'info>,
+        allowed_address: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        handle: u128,
+    ) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            self.program.clone(),
+            Allow {
+                allowance_account: allowance_account.clone(),
+                signer: self.signer.clone(),
+                allowed_address: allowed_address.clone(),
+                system_program: system_program.clone(),
+            },
+        );
+
+        inco_lightning::cpi::allow(cpi_ctx, handle, true, allowed_address.key())
+    }
+
+    fn verify_decryption(
+        &self,
+        instructions: &AccountInfo<'info>,
+        handles: Vec<Vec<u8>>,
+        plaintexts: Vec<Vec<u8>>,
+    ) -> Result<()> {
+        require!(
+            !handles.is_empty() && handles.len() == plaintexts.len(),
+            ShadowBidError::InvalidDecryptionProof
+        );
+        let expected_signature_count =
+            u8::try_from(handles.len()).map_err(|_| ShadowBidError::InvalidDecryptionProof)?;
+
+        let cpi_ctx = CpiContext::new(
+            self.program.clone(),
+            VerifySignature {
+                instructions: instructions.clone(),
+                signer: self.signer.clone(),
+            },
+        );
+
+        // This will fail if the Ed25519 instructions aren't in the transaction
+        inco_lightning::cpi::is_validsignature(
+            cpi_ctx,
+            expected_signature_count,
+            Some(handles),
+            Some(plaintexts),
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Encodes a handle the way the Inco SDK does (16 bytes, little-endian)
+pub fn handle_to_bytes(handle: u128) -> Vec<u8> {
+    handle.to_le_bytes().to_vec()
+}
+
+/// Parses a decrypted amount from attested plaintext bytes
+///
+/// The plaintext is a u128 in little-endian format; an 8-byte u64 is
+/// also accepted. Amounts past `u64::MAX` are rejected rather than
+/// truncated, which would let a huge bid win and pay almost nothing.
+pub fn parse_plaintext_amount(plaintext_bytes: &[u8]) -> Result<u64> {
+    if plaintext_bytes.len() >= 16 {
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&plaintext_bytes[..16]);
+        u64::try_from(u128::from_le_bytes(bytes)).map_err(|_| ShadowBidError::AmountOverflow.into())
+    } else if plaintext_bytes.len() >= 8 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&plaintext_bytes[..8]);
+        Ok(u64::from_le_bytes(bytes))
+    } else {
+        Err(ShadowBidError::InvalidDecryptionProof.into())
+    }
+}
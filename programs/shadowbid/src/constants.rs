@@ -6,18 +6,119 @@ pub const AUCTION_SEED: &[u8] = b"auction";
 /// Seed for deriving Bid PDA
 pub const BID_SEED: &[u8] = b"bid";
 
+/// Seed for deriving AuctionMetadata PDA
+pub const METADATA_SEED: &[u8] = b"metadata";
+
+/// Seed for deriving the global Registry PDA
+pub const REGISTRY_SEED: &[u8] = b"registry";
+
+/// Seed for deriving SellerCounter PDA
+pub const SELLER_COUNTER_SEED: &[u8] = b"seller_counter";
+
+/// Seed for deriving SellerProfile PDA
+pub const SELLER_PROFILE_SEED: &[u8] = b"seller_profile";
+
+/// Seed for deriving BidderProfile PDA
+pub const BIDDER_PROFILE_SEED: &[u8] = b"bidder_profile";
+
+/// Seed for deriving AuctionPage PDA
+pub const AUCTION_PAGE_SEED: &[u8] = b"auction_page";
+
+/// Seed for deriving SellerBond PDA
+pub const SELLER_BOND_SEED: &[u8] = b"seller_bond";
+
+/// Seed for deriving the global Treasury PDA
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+/// Seed for deriving Escrow PDA
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+/// Seed for deriving SettlementCallback PDA
+pub const SETTLEMENT_CALLBACK_SEED: &[u8] = b"settlement_callback";
+
+/// Seed for deriving AuctionHook PDA
+pub const HOOK_SEED: &[u8] = b"hook";
+
+/// Seed for deriving AuctionAuthority PDA
+pub const AUCTION_AUTHORITY_SEED: &[u8] = b"auction_authority";
+
+/// Seed for deriving Receipt PDA
+pub const RECEIPT_SEED: &[u8] = b"receipt";
+
+/// Seed for deriving the receipt NFT mint PDA
+pub const RECEIPT_MINT_SEED: &[u8] = b"receipt_mint";
+
+/// Seed for deriving the global PDA holding the receipt mint and metadata
+/// authority
+pub const RECEIPT_AUTHORITY_SEED: &[u8] = b"receipt_authority";
+
+/// Symbol of receipt NFTs
+pub const RECEIPT_SYMBOL: &str = "SBRCPT";
+
+/// Auction slots per AuctionPage
+pub const AUCTIONS_PER_PAGE: usize = 32;
+
 /// Minimum auction duration (2 minutes for testing)
 pub const MIN_AUCTION_DURATION: i64 = 120;
 
 /// Maximum auction duration (7 days in seconds)
 pub const MAX_AUCTION_DURATION: i64 = 604800;
 
+/// Minimum reveal window for commit-reveal auctions (1 minute)
+pub const MIN_REVEAL_DURATION: i64 = 60;
+
+/// Maximum reveal window for commit-reveal auctions (1 day in seconds)
+pub const MAX_REVEAL_DURATION: i64 = 86400;
+
+/// Time the winner has to settle once determined, before anyone can
+/// declare them in default (3 days in seconds)
+pub const SETTLEMENT_WINDOW: i64 = 259_200;
+
+/// Minimum delivery window for escrowed auctions (1 hour)
+pub const MIN_DELIVERY_TIMEOUT: i64 = 3600;
+
+/// Maximum delivery window for escrowed auctions (30 days in seconds)
+pub const MAX_DELIVERY_TIMEOUT: i64 = 2_592_000;
+
+/// Maximum arbitration fee in basis points (10%)
+pub const MAX_ARBITRATION_FEE_BPS: u16 = 1_000;
+
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
 /// Maximum title length in bytes
 pub const MAX_TITLE_LENGTH: usize = 64;
 
 /// Maximum description length in bytes
 pub const MAX_DESCRIPTION_LENGTH: usize = 256;
 
+/// Maximum cancellation reason length in bytes
+pub const MAX_CANCEL_REASON_LENGTH: usize = 128;
+
+// Metadata limits keep a maximal create or update within one
+// 1232-byte transaction; longer content belongs in the URI's document
+
+/// Maximum metadata title length in bytes
+pub const MAX_METADATA_TITLE_LENGTH: usize = 100;
+
+/// Maximum metadata description length in bytes
+pub const MAX_METADATA_DESCRIPTION_LENGTH: usize = 300;
+
+/// Maximum metadata URI length in bytes
+pub const MAX_METADATA_URI_LENGTH: usize = 200;
+
+/// Maximum dispute evidence URI length in bytes
+pub const MAX_EVIDENCE_URI_LENGTH: usize = 200;
+
+/// Maximum number of category tags per auction
+pub const MAX_TAGS: usize = 8;
+
+/// Maximum category tag length in bytes
+pub const MAX_TAG_LENGTH: usize = 16;
+
+/// Maximum number of delegates per auction authority
+pub const MAX_DELEGATES: usize = 4;
+
 /// Inco Lightning Program ID
 pub const INCO_LIGHTNING_PROGRAM_ID: Pubkey = 
     pubkey!("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj");
@@ -87,4 +87,212 @@ pub enum ShadowBidError {
 
     #[msg("Insufficient remaining accounts for allowance")]
     InsufficientRemainingAccounts,
+
+    // Statistics Errors
+    #[msg("Auction statistics have not been accumulated")]
+    StatsNotAvailable,
+
+    // Commit-Reveal Errors
+    #[msg("Operation is not supported in this auction mode")]
+    WrongAuctionMode,
+
+    #[msg("Reveal duration is out of range (1 minute to 1 day)")]
+    InvalidRevealDuration,
+
+    #[msg("Auction is not in the reveal period")]
+    AuctionNotRevealing,
+
+    #[msg("Reveal period has ended")]
+    RevealEnded,
+
+    #[msg("Reveal period has not ended yet")]
+    RevealNotEnded,
+
+    #[msg("Revealed amount and salt do not match the commitment")]
+    InvalidCommitment,
+
+    #[msg("Bid has already been revealed")]
+    BidAlreadyRevealed,
+
+    #[msg("Commit-reveal auctions with bids cannot be cancelled")]
+    CollateralLocked,
+
+    // Metadata Errors
+    #[msg("Metadata title, description or URI is too long")]
+    MetadataTooLong,
+
+    #[msg("Too many category tags (maximum 8)")]
+    TooManyTags,
+
+    #[msg("Category tags must be 1 to 16 bytes")]
+    InvalidTag,
+
+    #[msg("Metadata cannot change once the auction has bids")]
+    MetadataLocked,
+
+    // Versioning Errors
+    #[msg("Account already uses the current layout")]
+    AccountAlreadyMigrated,
+
+    // Cancellation Errors
+    #[msg("Auction has bids and its seller posted no bond")]
+    CancellationNotAllowed,
+
+    #[msg("Cancellation reason is too long (maximum 128 bytes)")]
+    CancelReasonTooLong,
+
+    #[msg("Seller bond must be greater than zero")]
+    InvalidSellerBond,
+
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+
+    #[msg("Compensation has already been claimed")]
+    CompensationAlreadyClaimed,
+
+    #[msg("Seller bond is held until the auction is over without the seller backing out")]
+    BondLocked,
+
+    #[msg("Seller bond account missing or unexpected")]
+    SellerBondMismatch,
+
+    // Escrow Errors
+    #[msg("Delivery timeout must be between 1 hour and 30 days")]
+    InvalidDeliveryTimeout,
+
+    #[msg("Escrow account missing or unexpected")]
+    EscrowMismatch,
+
+    #[msg("Auction is not awaiting delivery")]
+    NotAwaitingDelivery,
+
+    #[msg("Delivery window has not ended")]
+    DeliveryWindowOpen,
+
+    #[msg("Delivery window has ended")]
+    DeliveryWindowEnded,
+
+    // Arbitration Errors
+    #[msg("Arbitrator must be set and differ from the seller")]
+    InvalidArbitrator,
+
+    #[msg("Arbitration fee exceeds 1000 basis points")]
+    InvalidArbitrationFee,
+
+    #[msg("Arbitration requires an escrowed auction")]
+    ArbitrationRequiresEscrow,
+
+    #[msg("Auction has no arbitrator to resolve disputes")]
+    NoArbitrator,
+
+    #[msg("Only the arbitrator can resolve this dispute")]
+    NotArbitrator,
+
+    #[msg("Auction is not disputed")]
+    NotDisputed,
+
+    #[msg("Buyer share exceeds 10000 basis points")]
+    InvalidBuyerShare,
+
+    #[msg("Evidence URI exceeds maximum length (200)")]
+    EvidenceUriTooLong,
+
+    #[msg("Only the winner or the seller can submit evidence")]
+    NotDisputeParty,
+
+    #[msg("Arbitrator cannot bid on the auction")]
+    ArbitratorCannotBid,
+
+    // Settlement Callback Errors
+    #[msg("Settlement callback must be another program")]
+    InvalidSettlementCallback,
+
+    #[msg("Settlement callbacks require direct payment (no escrow)")]
+    CallbackRequiresDirectPayment,
+
+    #[msg("Settlement callback account or program missing or unexpected")]
+    SettlementCallbackMismatch,
+
+    // Receipt Errors
+    #[msg("Receipt accounts missing or unexpected")]
+    ReceiptMismatch,
+
+    #[msg("Receipt already redeemed")]
+    ReceiptAlreadyRedeemed,
+
+    #[msg("Receipt can't be redeemed while the payment is disputed")]
+    ReceiptDisputed,
+
+    // Bidder Profile Errors
+    #[msg("Bidder default limit must be below 255")]
+    InvalidBidderDefaultLimit,
+
+    #[msg("Bidder has defaulted on too many auctions")]
+    TooManyDefaults,
+
+    #[msg("Winner's bid account missing or unexpected")]
+    WinnerBidMismatch,
+
+    #[msg("Winner can still settle")]
+    SettlementWindowOpen,
+
+    // Authority Errors
+    #[msg("Caller is not the auction authority or a delegate allowed to do this")]
+    NotAuthority,
+
+    #[msg("Authority, beneficiary and delegates can't be the default pubkey")]
+    InvalidAuthority,
+
+    #[msg("Auction authority account missing or unexpected")]
+    AuthorityMismatch,
+
+    #[msg("Payment recipient is not the auction's beneficiary")]
+    NotBeneficiary,
+
+    #[msg("Unknown delegate permissions")]
+    InvalidPermissions,
+
+    #[msg("Auction already has the maximum number of delegates")]
+    TooManyDelegates,
+
+    // Decryption Errors
+    #[msg("Decrypted amount does not fit in a u64")]
+    AmountOverflow,
+
+    // Encoding Errors
+    #[msg("Account holds an unknown enum discriminant")]
+    InvalidDiscriminant,
+
+    // Reserve Errors
+    #[msg("Winning bid meets the reserve price")]
+    ReserveMet,
+
+    #[msg("Only the seller or the winner can do this")]
+    NotAuctionParty,
+
+    // Hook Errors
+    #[msg("Hook must be another executable program")]
+    InvalidHookProgram,
+
+    #[msg("Hook account or program missing or unexpected")]
+    HookMismatch,
+
+    #[msg("Auctions take a settlement callback or a hook, not both")]
+    CallbackWithHook,
+
+    // Bid Deposit Errors
+    #[msg("Bid deposits must be positive and are for sealed auctions")]
+    InvalidBidDeposit,
+
+    #[msg("The winner's bid holds no deposit")]
+    NoBidDeposit,
+
+    #[msg("Winning amount exceeds the winner's deposit")]
+    DepositTooSmall,
+
+    #[msg("Escrowed auctions and auctions issuing receipts are settled by the winner")]
+    WinnerMustSettle,
+
+    #[msg("Deposits stay locked until the winner is determined, and the winner's until settlement")]
+    DepositLocked,
 }
@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
+use crate::hook::AuctionOutcome;
+use crate::state::CancellationReason;
 
 /// Emitted when a new auction is created
 #[event]
+#[derive(Debug, Clone)]
 pub struct AuctionCreated {
     /// The auction's public key
     pub auction: Pubkey,
@@ -19,6 +22,7 @@ pub struct AuctionCreated {
 
 /// Emitted when a bid is placed
 #[event]
+#[derive(Debug, Clone)]
 pub struct BidPlaced {
     /// The auction's public key
     pub auction: Pubkey,
@@ -32,6 +36,7 @@ pub struct BidPlaced {
 
 /// Emitted when a bid is updated
 #[event]
+#[derive(Debug, Clone)]
 pub struct BidUpdated {
     /// The auction's public key
     pub auction: Pubkey,
@@ -43,6 +48,7 @@ pub struct BidUpdated {
 
 /// Emitted when bidding period closes
 #[event]
+#[derive(Debug, Clone)]
 pub struct BiddingClosed {
     /// The auction's public key
     pub auction: Pubkey,
@@ -54,6 +60,7 @@ pub struct BiddingClosed {
 
 /// Emitted when a bid is processed during winner determination
 #[event]
+#[derive(Debug, Clone)]
 pub struct BidProcessed {
     /// The auction's public key
     pub auction: Pubkey,
@@ -65,6 +72,7 @@ pub struct BidProcessed {
 
 /// Emitted when winner is determined
 #[event]
+#[derive(Debug, Clone)]
 pub struct WinnerDetermined {
     /// The auction's public key
     pub auction: Pubkey,
@@ -76,6 +84,7 @@ pub struct WinnerDetermined {
 
 /// Emitted when auction is settled
 #[event]
+#[derive(Debug, Clone)]
 pub struct AuctionSettled {
     /// The auction's public key
     pub auction: Pubkey,
@@ -89,6 +98,7 @@ pub struct AuctionSettled {
 
 /// Emitted when auction is cancelled
 #[event]
+#[derive(Debug, Clone)]
 pub struct AuctionCancelled {
     /// The auction's public key
     pub auction: Pubkey,
@@ -96,6 +106,355 @@ pub struct AuctionCancelled {
     pub seller: Pubkey,
     /// Reason for cancellation
     pub reason: String,
+    /// Category of the reason
+    pub category: CancellationReason,
+    /// Bond share each bidder can claim (0 without bids or bond)
+    pub compensation_per_bid: u64,
     /// Unix timestamp when auction was cancelled
     pub timestamp: i64,
 }
+
+/// Emitted when the seller publishes the decrypted auction statistics
+#[event]
+#[derive(Debug, Clone)]
+pub struct AuctionStatsRevealed {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// Total number of bids
+    pub bid_count: u32,
+    /// Sum of all bid amounts
+    pub total_amount: u64,
+    /// Average bid amount (total_amount / bid_count)
+    pub average_amount: u64,
+    /// Lowest bid amount
+    pub lowest_amount: u64,
+    /// Spread between the winning and lowest bid amounts
+    pub spread: u64,
+    /// Number of bids at or above the reserve price
+    pub bids_above_reserve: u32,
+    /// Unix timestamp when the statistics were published
+    pub timestamp: i64,
+}
+
+/// Emitted when a commit-reveal bid is revealed
+#[event]
+#[derive(Debug, Clone)]
+pub struct BidRevealed {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The bidder's public key
+    pub bidder: Pubkey,
+    /// The revealed bid amount
+    pub amount: u64,
+    /// Unix timestamp when the bid was revealed
+    pub timestamp: i64,
+}
+
+/// Emitted when the reveal window of a commit-reveal auction closes
+#[event]
+#[derive(Debug, Clone)]
+pub struct RevealClosed {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// Number of bids revealed
+    pub bids_revealed: u32,
+    /// Unix timestamp when the reveal window closed
+    pub timestamp: i64,
+}
+
+/// Emitted when an unrevealed bid's collateral is forfeited to the seller
+#[event]
+#[derive(Debug, Clone)]
+pub struct CollateralForfeited {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The bidder who failed to reveal
+    pub bidder: Pubkey,
+    /// Collateral forfeited in lamports
+    pub amount: u64,
+}
+
+/// Emitted when a bidder took back their sealed bid's deposit
+#[event]
+#[derive(Debug, Clone)]
+pub struct DepositReclaimed {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The bidder
+    pub bidder: Pubkey,
+    /// Deposit returned in lamports
+    pub amount: u64,
+}
+
+/// Emitted when an auction's metadata is created or updated
+#[event]
+#[derive(Debug, Clone)]
+pub struct MetadataUpdated {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// Off-chain metadata document URI
+    pub uri: String,
+    /// SHA-256 of the document at `uri`
+    pub content_hash: [u8; 32],
+    /// Category tags
+    pub tags: Vec<String>,
+    /// Unix timestamp of the update
+    pub timestamp: i64,
+}
+
+/// Emitted when an auction's metadata is closed and its rent returned
+/// to the seller
+#[event]
+#[derive(Debug, Clone)]
+pub struct MetadataClosed {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The seller who got the rent back
+    pub seller: Pubkey,
+    /// Lamports returned to the seller
+    pub refunded: u64,
+    /// Unix timestamp of the closure
+    pub timestamp: i64,
+}
+
+/// Emitted when a legacy Auction or Bid account is migrated to the
+/// current layout
+#[event]
+#[derive(Debug, Clone)]
+pub struct AccountMigrated {
+    /// The auction, or the bid's auction
+    pub auction: Pubkey,
+    /// The migrated account
+    pub account: Pubkey,
+    /// Layout version after migration
+    pub version: u8,
+    /// Who paid the extra rent
+    pub payer: Pubkey,
+}
+
+/// Emitted when a bidder claims their share of a slashed seller bond
+#[event]
+#[derive(Debug, Clone)]
+pub struct CompensationClaimed {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The compensated bidder
+    pub bidder: Pubkey,
+    /// Compensation paid in lamports
+    pub amount: u64,
+    /// Unix timestamp of the claim
+    pub timestamp: i64,
+}
+
+/// Emitted when a seller bond goes back to the seller
+#[event]
+#[derive(Debug, Clone)]
+pub struct BondReleased {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The seller's public key
+    pub seller: Pubkey,
+    /// Bond returned in lamports
+    pub amount: u64,
+    /// Unix timestamp of the release
+    pub timestamp: i64,
+}
+
+/// Emitted when a seller bond is slashed
+#[event]
+#[derive(Debug, Clone)]
+pub struct BondSlashed {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The seller's public key
+    pub seller: Pubkey,
+    /// Lamports set aside for the bidders to claim
+    pub to_bidders: u64,
+    /// Lamports paid to the treasury
+    pub to_treasury: u64,
+    /// Unix timestamp of the slash
+    pub timestamp: i64,
+}
+
+/// Emitted when an escrowed auction's payment moves into its escrow
+#[event]
+#[derive(Debug, Clone)]
+pub struct PaymentEscrowed {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The winner who paid
+    pub winner: Pubkey,
+    /// Escrowed amount in lamports
+    pub amount: u64,
+    /// Unix timestamp after which the payment can be released without
+    /// the winner's confirmation
+    pub delivery_deadline: i64,
+    /// Unix timestamp of the settlement
+    pub timestamp: i64,
+}
+
+/// Emitted when an escrowed payment is released to the seller
+#[event]
+#[derive(Debug, Clone)]
+pub struct EscrowReleased {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The seller's public key
+    pub seller: Pubkey,
+    /// Released amount in lamports
+    pub amount: u64,
+    /// Whether the winner confirmed delivery (false after a timeout)
+    pub confirmed: bool,
+    /// Unix timestamp of the release
+    pub timestamp: i64,
+}
+
+/// Emitted when the winner disputes the delivery of an escrowed auction
+#[event]
+#[derive(Debug, Clone)]
+pub struct DisputeOpened {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The winner disputing the delivery
+    pub winner: Pubkey,
+    /// Frozen amount in lamports
+    pub amount: u64,
+    /// URI of the winner's evidence
+    pub evidence_uri: String,
+    /// Unix timestamp of the dispute
+    pub timestamp: i64,
+}
+
+/// Emitted when the winner or the seller adds evidence to a dispute
+#[event]
+#[derive(Debug, Clone)]
+pub struct EvidenceSubmitted {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The winner or seller submitting it
+    pub submitter: Pubkey,
+    /// URI of the evidence
+    pub evidence_uri: String,
+    /// Unix timestamp of the submission
+    pub timestamp: i64,
+}
+
+/// Emitted when the arbitrator splits a disputed payment
+#[event]
+#[derive(Debug, Clone)]
+pub struct DisputeResolved {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The arbitrator who ruled
+    pub arbitrator: Pubkey,
+    /// Lamports refunded to the winner
+    pub to_buyer: u64,
+    /// Lamports paid to the seller
+    pub to_seller: u64,
+    /// Lamports taken as the arbitration fee
+    pub fee: u64,
+    /// Unix timestamp of the ruling
+    pub timestamp: i64,
+}
+
+/// Emitted when settlement called back into the seller's program
+#[event]
+#[derive(Debug, Clone)]
+pub struct SettlementCallbackInvoked {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The program that was called back
+    pub program: Pubkey,
+    /// Lamports reported as paid to the seller
+    pub amount: u64,
+}
+
+/// Emitted when the auction's completion invoked its hook
+#[event]
+#[derive(Debug, Clone)]
+pub struct HookInvoked {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The hook program that was invoked
+    pub program: Pubkey,
+    /// How the auction completed
+    pub outcome: AuctionOutcome,
+}
+
+/// Emitted when an auction whose hook failures are ignored completed
+/// without invoking it
+#[event]
+#[derive(Debug, Clone)]
+pub struct HookSkipped {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// How the auction completed
+    pub outcome: AuctionOutcome,
+}
+
+/// Emitted when settlement minted a receipt NFT to the winner
+#[event]
+#[derive(Debug, Clone)]
+pub struct ReceiptMinted {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The winner the receipt was minted to
+    pub winner: Pubkey,
+    /// The receipt NFT mint
+    pub mint: Pubkey,
+    /// Winning amount in lamports
+    pub amount: u64,
+}
+
+/// Emitted when the seller marked a receipt fulfilled
+#[event]
+#[derive(Debug, Clone)]
+pub struct ReceiptRedeemed {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The receipt NFT mint
+    pub mint: Pubkey,
+    /// Unix timestamp of the redemption
+    pub timestamp: i64,
+}
+
+/// Emitted when a winner who didn't settle in time is declared in default
+#[event]
+#[derive(Debug, Clone)]
+pub struct WinnerDefaulted {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The winner who didn't settle
+    pub winner: Pubkey,
+    /// Unix timestamp of the declaration
+    pub timestamp: i64,
+}
+
+/// Emitted when an auction's authority was handed on
+#[event]
+#[derive(Debug, Clone)]
+pub struct AuthorityTransferred {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The previous authority
+    pub previous: Pubkey,
+    /// The new authority
+    pub authority: Pubkey,
+    /// Unix timestamp of the transfer
+    pub timestamp: i64,
+}
+
+/// Emitted when the authority granted, changed or revoked a delegate's
+/// permissions
+#[event]
+#[derive(Debug, Clone)]
+pub struct DelegateUpdated {
+    /// The auction's public key
+    pub auction: Pubkey,
+    /// The delegate
+    pub delegate: Pubkey,
+    /// `Permission` bits now granted (0 when revoked)
+    pub permissions: u8,
+    /// Unix timestamp of the update
+    pub timestamp: i64,
+}
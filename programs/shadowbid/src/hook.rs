@@ -0,0 +1,190 @@
+//! Interface between ShadowBid and the programs that build on it
+//!
+//! Programs create and cancel auctions through the `cpi` module that
+//! `#[program]` generates under the `cpi` feature (`shadowbid::cpi::create_auction`
+//! and friends, with `shadowbid::cpi::accounts::*`). The seller may be a PDA
+//! signing with `invoke_signed`; a separate `payer` funds the accounts, since
+//! a PDA holding data can't pay for them.
+//!
+//! An auction created with a `hook` calls that program back when it
+//! completes: from `settle_auction` once the payment reached the seller,
+//! from `confirm_delivery`, `release_escrow` or `resolve_dispute` as an
+//! escrowed payment is paid out (the hook runs first, in the same
+//! instruction), and from `cancel_auction`. The hook program implements
+//! an instruction named `on_auction_completed` (Anchor discriminator
+//! `ON_AUCTION_COMPLETED_DISCRIMINATOR`) taking an `AuctionCompletion`,
+//! with the accounts:
+//!
+//! 0. `auction` - signer: the Auction PDA, signing through ShadowBid, which
+//!    proves the call comes from a real completion. Check its owner is
+//!    `shadowbid::ID`.
+//! 1. `winner` - the winning bidder (the default pubkey on cancellation)
+//! 2. `seller` - writable: the auction's seller
+//! 3. ... - the accounts the caller passed after the instruction's own,
+//!    with their writability but never as signers
+//!
+//! Solana can't catch a failed CPI, so a failing hook fails the
+//! transaction. With `HookMode::Abort` the hook must be invoked, so
+//! payment and hook happen atomically or not at all; with
+//! `HookMode::Ignore` the caller may leave the hook out, e.g. after it
+//! failed, and the auction completes without it.
+//!
+//! Solana rejects reentrant calls, so a seller program that cancels its
+//! auction over CPI can't also be the auction's hook.
+//!
+//! Hooks generalize the settlement callback (see `crate::callback`), which
+//! only hears of direct settlements. An auction has one or the other.
+
+use anchor_lang::prelude::*;
+
+use crate::callback::invoke_as_auction;
+use crate::constants::AUCTION_SEED;
+use crate::errors::ShadowBidError;
+use crate::events::{HookInvoked, HookSkipped};
+use crate::state::{AuctionHook, HookMode};
+
+/// First 8 bytes of `sha256("global:on_auction_completed")`, the Anchor
+/// discriminator of the hook instruction
+pub const ON_AUCTION_COMPLETED_DISCRIMINATOR: [u8; 8] = [158, 30, 164, 124, 248, 167, 216, 220];
+
+/// How the auction completed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuctionOutcome {
+    /// The winner paid the seller
+    Settled,
+    /// The seller cancelled the auction
+    Cancelled,
+    /// The arbitrator refunded the whole escrowed payment to the winner
+    Refunded,
+}
+
+/// Argument of the `on_auction_completed` hook
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuctionCompletion {
+    /// The completed auction
+    pub auction: Pubkey,
+    /// Registry ID of the auction
+    pub auction_id: u64,
+    /// The auction's seller
+    pub seller: Pubkey,
+    /// The winning bidder (default pubkey when cancelled)
+    pub winner: Pubkey,
+    /// Lamports paid to the seller (0 when cancelled or refunded)
+    pub amount: u64,
+    /// How the auction completed
+    pub outcome: AuctionOutcome,
+}
+
+impl AuctionCompletion {
+    /// Instruction data of the hook: discriminator then Borsh arguments
+    pub fn instruction_data(&self) -> Result<Vec<u8>> {
+        let mut data = ON_AUCTION_COMPLETED_DISCRIMINATOR.to_vec();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+
+/// What an instruction completing an auction does about its hook
+pub enum HookCall<'a, 'info> {
+    /// The auction has no hook
+    None,
+    /// The hook's failures are ignored and the caller left it out
+    Skipped,
+    /// The hook program to invoke
+    Invoke(&'a AccountInfo<'info>),
+}
+
+/// Checks the hook accounts an instruction was given against the auction's
+/// `HookMode`
+///
+/// `HookMode::Abort` requires the hook, so nobody can complete the auction
+/// without it; `HookMode::Ignore` lets the caller leave out both accounts.
+/// A hook that is passed must name the program that is passed with it.
+pub fn check_hook<'a, 'info>(
+    mode: HookMode,
+    hook: Option<&AuctionHook>,
+    program: Option<&'a AccountInfo<'info>>,
+) -> Result<HookCall<'a, 'info>> {
+    match (mode, hook, program) {
+        (HookMode::None, None, None) => Ok(HookCall::None),
+        (HookMode::Ignore, None, None) => Ok(HookCall::Skipped),
+        (HookMode::Abort | HookMode::Ignore, Some(hook), Some(program))
+            if program.key() == hook.program =>
+        {
+            Ok(HookCall::Invoke(program))
+        }
+        _ => err!(ShadowBidError::HookMismatch),
+    }
+}
+
+impl<'info> HookCall<'_, 'info> {
+    /// Invokes the hook with `completion`, signed by the auction PDA with
+    /// `bump`, or records that the caller left it out
+    ///
+    /// The hook receives the auction account, so the caller must release
+    /// its borrow of the auction first.
+    pub fn run(
+        &self,
+        auction: &AccountInfo<'info>,
+        winner: &AccountInfo<'info>,
+        seller: &AccountInfo<'info>,
+        extra_accounts: &[AccountInfo<'info>],
+        completion: &AuctionCompletion,
+        bump: u8,
+    ) -> Result<()> {
+        match self {
+            HookCall::None => {}
+            HookCall::Skipped => {
+                emit!(HookSkipped {
+                    auction: completion.auction,
+                    outcome: completion.outcome,
+                });
+                msg!("Hook skipped");
+            }
+            HookCall::Invoke(program) => {
+                let auction_id = completion.auction_id.to_le_bytes();
+                invoke_hook(
+                    program,
+                    auction,
+                    winner,
+                    seller,
+                    extra_accounts,
+                    completion,
+                    &[AUCTION_SEED, completion.seller.as_ref(), &auction_id, &[bump]],
+                )?;
+
+                emit!(HookInvoked {
+                    auction: completion.auction,
+                    program: program.key(),
+                    outcome: completion.outcome,
+                });
+                msg!("Hook invoked: {}", program.key());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Invokes `on_auction_completed` on `program`, signed by the auction PDA
+///
+/// Signatures of the transaction are never forwarded: a hook program is
+/// chosen by the seller, and must not act for the winner or anyone else.
+pub fn invoke_hook<'info>(
+    program: &AccountInfo<'info>,
+    auction: &AccountInfo<'info>,
+    winner: &AccountInfo<'info>,
+    seller: &AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    completion: &AuctionCompletion,
+    auction_seeds: &[&[u8]],
+) -> Result<()> {
+    invoke_as_auction(
+        program,
+        auction,
+        winner,
+        seller,
+        extra_accounts,
+        completion.instruction_data()?,
+        auction_seeds,
+    )
+}
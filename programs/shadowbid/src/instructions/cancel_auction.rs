@@ -1,33 +1,132 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::{AUCTION_AUTHORITY_SEED, AUCTION_PAGE_SEED, HOOK_SEED, MAX_CANCEL_REASON_LENGTH, SELLER_BOND_SEED, SELLER_PROFILE_SEED, TREASURY_SEED};
 use crate::errors::ShadowBidError;
-use crate::events::AuctionCancelled;
-use crate::state::{Auction, AuctionState};
+use crate::events::{AuctionCancelled, BondReleased, BondSlashed};
+use crate::hook::{check_hook, AuctionCompletion, AuctionOutcome, HookCall};
+use crate::state::{Auction, AuctionAuthority, AuctionHook, AuctionPage, AuctionState, CancellationPolicy, CancellationReason, LoadVersioned, Permission, SellerBond, SellerProfile, Treasury};
 
 #[derive(Accounts)]
 pub struct CancelAuction<'info> {
-    /// The seller cancelling the auction
+    /// The authority cancelling the auction, or a delegate allowed to;
+    /// checked by the handler
+    pub authority: Signer<'info>,
+
+    /// CHECK: The seller, refunded a released bond; validated against
+    /// auction.seller
     #[account(
         mut,
-        constraint = seller.key() == auction.seller @ ShadowBidError::NotSeller,
+        constraint = seller.key() == auction.load_versioned()?.seller @ ShadowBidError::NotSeller,
     )]
-    pub seller: Signer<'info>,
+    pub seller: UncheckedAccount<'info>,
 
     /// The auction to cancel
     #[account(
         mut,
-        constraint = auction.state == AuctionState::Open || auction.state == AuctionState::Closed @ ShadowBidError::AuctionAlreadySettled,
+        constraint = matches!(auction.load_versioned()?.state()?, AuctionState::Open | AuctionState::Closed) @ ShadowBidError::AuctionAlreadySettled,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The page listing the auction while it is open. Seeds are
+    /// checked; auctions created before the registry have no page and
+    /// `AuctionPage::unlist_from` skips it
+    #[account(
+        mut,
+        seeds = [AUCTION_PAGE_SEED, &AuctionPage::index_of(auction.load_versioned()?.auction_id).to_le_bytes()],
+        bump
+    )]
+    pub page: UncheckedAccount<'info>,
+
+    /// CHECK: The seller's profile, recording the cancellation. Seeds are
+    /// checked; sellers without one are skipped by
+    /// `SellerProfile::update_from`
+    #[account(
+        mut,
+        seeds = [SELLER_PROFILE_SEED, seller.key().as_ref()],
+        bump
+    )]
+    pub seller_profile: UncheckedAccount<'info>,
+
+    /// CHECK: The auction's authority record. Seeds are checked; without
+    /// one, `AuctionAuthority::check` requires the seller
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+
+    /// The auction's seller bond vault, required for bonded auctions
+    #[account(
+        mut,
+        seeds = [SELLER_BOND_SEED, auction.key().as_ref()],
+        bump = seller_bond.bump,
+    )]
+    pub seller_bond: Option<Account<'info, SellerBond>>,
+
+    /// The treasury, paid what a slashed bond can't split between bidders
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The auction's hook, passed only for auctions created with one
+    /// (and optional when its failures are ignored)
+    #[account(
+        seeds = [HOOK_SEED, auction.key().as_ref()],
+        bump = hook.bump,
     )]
-    pub auction: Account<'info, Auction>,
+    pub hook: Option<Account<'info, AuctionHook>>,
+
+    /// CHECK: The hook program, checked against `hook.program` by the
+    /// handler
+    pub hook_program: Option<UncheckedAccount<'info>>,
+
+    /// Passed with the hook as its winner account: a cancelled auction
+    /// has no winner, and the default pubkey is the system program's
+    pub system_program: Option<Program<'info, System>>,
 }
 
-pub fn handler(ctx: Context<CancelAuction>, reason: String) -> Result<()> {
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelAuction<'info>>,
+    reason: String,
+    category: CancellationReason,
+) -> Result<()> {
+    require!(
+        reason.len() <= MAX_CANCEL_REASON_LENGTH,
+        ShadowBidError::CancelReasonTooLong
+    );
+
     let clock = Clock::get()?;
-    let auction = &mut ctx.accounts.auction;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+
+    AuctionAuthority::check(
+        &ctx.accounts.auction_authority,
+        &auction.seller,
+        ctx.accounts.authority.key,
+        Some(Permission::Cancel),
+    )?;
+
+    // Commit-reveal bids hold collateral that only reveal_bid and
+    // determine_winner release, so those auctions must run to completion
+    require!(
+        !(auction.is_commit_reveal() && auction.bid_count > 0),
+        ShadowBidError::CollateralLocked
+    );
+
+    // Once bidders have committed, backing out costs the seller their bond
+    if auction.bid_count > 0 {
+        require!(
+            auction.cancellation_policy()? == CancellationPolicy::Bonded,
+            ShadowBidError::CancellationNotAllowed
+        );
+    }
 
     // Can only cancel if no bids have been placed, or if auction is in a state that allows cancellation
     // If bids exist and winner was determined, cannot cancel
-    if auction.state == AuctionState::Closed && auction.bid_count > 0 {
+    if auction.state()? == AuctionState::Closed && auction.bid_count > 0 {
         // If bidding closed with bids, can only cancel if winner determination hasn't started
         require!(
             auction.bids_processed == 0,
@@ -36,7 +135,7 @@ pub fn handler(ctx: Context<CancelAuction>, reason: String) -> Result<()> {
     }
 
     // For open auctions, seller can cancel anytime before end if no bids
-    if auction.state == AuctionState::Open && auction.bid_count > 0 {
+    if auction.state()? == AuctionState::Open && auction.bid_count > 0 {
         // Has bids - check if we're past end time
         require!(
             clock.unix_timestamp >= auction.end_time,
@@ -44,17 +143,106 @@ pub fn handler(ctx: Context<CancelAuction>, reason: String) -> Result<()> {
         );
     }
 
-    auction.state = AuctionState::Cancelled;
+    require!(
+        ctx.accounts.seller_bond.is_some()
+            == (auction.cancellation_policy()? == CancellationPolicy::Bonded),
+        ShadowBidError::SellerBondMismatch
+    );
 
-    emit!(AuctionCancelled {
-        auction: auction.key(),
+    // Unless its failures are ignored, the seller can't skip the hook
+    let hook = check_hook(
+        auction.hook_mode()?,
+        ctx.accounts.hook.as_deref(),
+        ctx.accounts.hook_program.as_deref(),
+    )?;
+
+    auction.set_state(AuctionState::Cancelled);
+    AuctionPage::unlist_from(&ctx.accounts.page, auction.auction_id, &auction_key)?;
+    SellerProfile::update_from(&ctx.accounts.seller_profile, |profile| {
+        profile.record_cancellation(category, auction.bid_count)
+    })?;
+
+    let completion = AuctionCompletion {
+        auction: auction_key,
+        auction_id: auction.auction_id,
         seller: auction.seller,
+        winner: Pubkey::default(),
+        amount: 0,
+        outcome: AuctionOutcome::Cancelled,
+    };
+    let bid_count = auction.bid_count;
+    let bump = auction.bump;
+
+    // The hook receives the auction account, which can't be borrowed
+    // across the CPI. It runs before the bond moves: the runtime rejects
+    // a CPI after lamports moved between accounts it isn't given.
+    drop(auction);
+    // The hook's winner account is the system program, whose address is
+    // the default pubkey; only an invoked hook reads it
+    let no_winner = match (&hook, &ctx.accounts.system_program) {
+        (_, Some(program)) => program.to_account_info(),
+        (HookCall::Invoke(_), None) => return err!(ShadowBidError::HookMismatch),
+        (_, None) => ctx.accounts.seller.to_account_info(),
+    };
+    hook.run(
+        &ctx.accounts.auction.to_account_info(),
+        &no_winner,
+        &ctx.accounts.seller.to_account_info(),
+        ctx.remaining_accounts,
+        &completion,
+        bump,
+    )?;
+
+    let mut compensation_per_bid = 0;
+    if let Some(vault) = &mut ctx.accounts.seller_bond {
+        let seller = ctx.accounts.seller.to_account_info();
+        if bid_count == 0 {
+            // A clean cancellation returns the whole bond with the rent
+            emit!(BondReleased {
+                auction: auction_key,
+                seller: completion.seller,
+                amount: vault.amount,
+                timestamp: clock.unix_timestamp,
+            });
+            msg!("Seller bond released: {} lamports", vault.amount);
+            vault.close(seller)?;
+        } else {
+            // Each bidder claims an equal share, since the amounts are
+            // sealed; the treasury takes the remainder
+            let to_treasury = vault.slash(bid_count);
+            compensation_per_bid = vault.compensation_per_bid;
+            if to_treasury > 0 {
+                **vault.to_account_info().try_borrow_mut_lamports()? -= to_treasury;
+                **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += to_treasury;
+                ctx.accounts.treasury.total_slashed += to_treasury;
+            }
+
+            emit!(BondSlashed {
+                auction: auction_key,
+                seller: completion.seller,
+                to_bidders: vault.amount,
+                to_treasury,
+                timestamp: clock.unix_timestamp,
+            });
+            msg!("Seller bond slashed: {} lamports per bid, {} to the treasury", compensation_per_bid, to_treasury);
+
+            if vault.claims_remaining == 0 {
+                vault.close(seller)?;
+            }
+        }
+    }
+
+    emit!(AuctionCancelled {
+        auction: auction_key,
+        seller: completion.seller,
         reason: reason.clone(),
+        category,
+        compensation_per_bid,
         timestamp: clock.unix_timestamp,
     });
 
-    msg!("Auction cancelled: {}", auction.key());
-    msg!("Reason: {}", reason);
+    msg!("Auction cancelled: {}", auction_key);
+    msg!("Reason: {} ({:?})", reason, category);
 
     Ok(())
 }
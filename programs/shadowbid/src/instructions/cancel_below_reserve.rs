@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+
+use crate::confidential::{handle_to_bytes, parse_plaintext_amount, ConfidentialBackend, IncoBackend};
+use crate::constants::BIDDER_PROFILE_SEED;
+use crate::errors::ShadowBidError;
+use crate::events::AuctionCancelled;
+use crate::state::{Auction, AuctionState, BidderProfile, CancellationReason, LoadVersioned};
+
+/// This instruction:
+/// 1. Verifies the attested decryption of the winning handle from Inco
+/// 2. Checks the winning bid is below the reserve price, so the winner
+///    could never settle
+/// 3. Marks the auction as cancelled, freeing the seller's bond
+/// 4. Withdraws the default if the winner was already declared in one
+///
+/// A default leaves a bid deposit where it was, so the winner reclaims
+/// it with `reclaim_deposit` either way.
+///
+/// Sealed auctions only pick their winner by encrypted comparison, so a
+/// winner below the reserve is only found out at decryption. The seller
+/// or the winner, whoever holds decryption permission, signs.
+///
+/// The transaction must include Ed25519 signature verification instructions
+/// from the Inco SDK before calling this instruction.
+#[derive(Accounts)]
+pub struct CancelBelowReserve<'info> {
+    /// The seller or the winner, allowed to decrypt the winning handle
+    #[account(
+        mut,
+        constraint = caller.key() == auction.load_versioned()?.seller
+            || caller.key() == auction.load_versioned()?.winner @ ShadowBidError::NotAuctionParty,
+    )]
+    pub caller: Signer<'info>,
+
+    /// The sealed auction whose winner can't settle
+    #[account(
+        mut,
+        constraint = matches!(
+            auction.load_versioned()?.state()?,
+            AuctionState::WinnerDetermined | AuctionState::Defaulted
+        ) @ ShadowBidError::WinnerNotDetermined,
+        constraint = !auction.load_versioned()?.is_commit_reveal() @ ShadowBidError::WrongAuctionMode,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The winner's profile. Seeds are checked; winners without
+    /// one are skipped by `BidderProfile::update_from`
+    #[account(
+        mut,
+        seeds = [BIDDER_PROFILE_SEED, auction.load_versioned()?.winner.as_ref()],
+        bump
+    )]
+    pub winner_profile: UncheckedAccount<'info>,
+
+    /// Instructions sysvar for Ed25519 signature verification
+    /// CHECK: Validated by address constraint
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// Inco Lightning program for attestation verification
+    /// CHECK: Validated by address constraint
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+pub fn handler(
+    ctx: Context<CancelBelowReserve>,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+
+    require!(
+        handle_bytes == handle_to_bytes(auction.highest_bid_handle),
+        ShadowBidError::InvalidDecryptionProof
+    );
+    let winning_amount = parse_plaintext_amount(&plaintext_bytes)?;
+    require!(
+        winning_amount < auction.reserve_price,
+        ShadowBidError::ReserveMet
+    );
+
+    let backend = IncoBackend::new(
+        &ctx.accounts.inco_lightning_program,
+        &ctx.accounts.caller.to_account_info(),
+    );
+    backend.verify_decryption(
+        &ctx.accounts.instructions,
+        vec![handle_bytes],
+        vec![plaintext_bytes],
+    )?;
+
+    // Settlement was never possible, so it wasn't the winner's default
+    if auction.state()? == AuctionState::Defaulted {
+        BidderProfile::update_from(&ctx.accounts.winner_profile, |profile| {
+            profile.auctions_defaulted = profile.auctions_defaulted.saturating_sub(1)
+        })?;
+    }
+    auction.set_state(AuctionState::Cancelled);
+
+    emit!(AuctionCancelled {
+        auction: auction_key,
+        seller: auction.seller,
+        reason: "Winning bid is below the reserve price".to_string(),
+        category: CancellationReason::LowDemand,
+        compensation_per_bid: 0,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Auction cancelled - winning bid below the reserve price");
+    msg!("Winning bid: {} lamports, reserve: {} lamports", winning_amount, { auction.reserve_price });
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{BID_SEED, SELLER_BOND_SEED};
+use crate::errors::ShadowBidError;
+use crate::events::CompensationClaimed;
+use crate::state::{Auction, AuctionState, Bid, LoadVersioned, SellerBond};
+
+/// This instruction:
+/// 1. Checks the seller cancelled the auction after bids came in
+/// 2. Pays the bidder their share of the slashed seller bond
+/// 3. Marks the bid as compensated, closing the vault after the last claim
+#[derive(Accounts)]
+pub struct ClaimCompensation<'info> {
+    /// The bidder claiming their share
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// The cancelled auction
+    #[account(
+        constraint = auction.load_versioned()?.state()? == AuctionState::Cancelled @ ShadowBidError::NothingToClaim,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// The bidder's bid account
+    #[account(
+        mut,
+        seeds = [
+            BID_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref()
+        ],
+        bump = bid.bump,
+        constraint = bid.auction == auction.key() @ ShadowBidError::BidAuctionMismatch,
+        constraint = !bid.compensated @ ShadowBidError::CompensationAlreadyClaimed,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    /// The vault holding the slashed bond
+    #[account(
+        mut,
+        seeds = [SELLER_BOND_SEED, auction.key().as_ref()],
+        bump = seller_bond.bump,
+        constraint = seller_bond.compensation_per_bid > 0 @ ShadowBidError::NothingToClaim,
+    )]
+    pub seller_bond: Account<'info, SellerBond>,
+
+    /// CHECK: The seller, who gets the vault's rent back after the last
+    /// claim; must match the bond
+    #[account(mut, address = seller_bond.seller @ ShadowBidError::NotSeller)]
+    pub seller: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<ClaimCompensation>) -> Result<()> {
+    let clock = Clock::get()?;
+    let vault = &mut ctx.accounts.seller_bond;
+    let bid = &mut ctx.accounts.bid;
+    let amount = vault.compensation_per_bid;
+
+    bid.compensated = true;
+    vault.amount -= amount;
+    vault.claims_remaining -= 1;
+    **vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.bidder.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    emit!(CompensationClaimed {
+        auction: ctx.accounts.auction.key(),
+        bidder: bid.bidder,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Compensation claimed by {}: {} lamports", bid.bidder, amount);
+
+    if vault.claims_remaining == 0 {
+        vault.close(ctx.accounts.seller.to_account_info())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{AUCTION_AUTHORITY_SEED, METADATA_SEED};
+use crate::errors::ShadowBidError;
+use crate::events::MetadataClosed;
+use crate::state::{Auction, AuctionAuthority, AuctionMetadata, AuctionState, LoadVersioned, Permission};
+
+/// This instruction closes an auction's metadata and returns its rent to
+/// the seller. Bidders bid on what the metadata describes, so it can only
+/// go before the first bid or once the auction is over
+#[derive(Accounts)]
+pub struct CloseAuctionMetadata<'info> {
+    /// The authority closing the auction's metadata, or a delegate
+    /// allowed to update it; checked by the handler
+    pub authority: Signer<'info>,
+
+    /// The described auction: open without bids, or finished
+    #[account(
+        constraint = {
+            let auction = auction.load_versioned()?;
+            match auction.state()? {
+                AuctionState::Open => auction.bid_count == 0,
+                AuctionState::Settled | AuctionState::Cancelled | AuctionState::Defaulted => true,
+                _ => false,
+            }
+        } @ ShadowBidError::MetadataLocked,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The auction's authority record. Seeds are checked; without
+    /// one, `AuctionAuthority::check` requires the seller
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The auction's seller, who gets the metadata's rent back
+    #[account(
+        mut,
+        constraint = seller.key() == auction.load_versioned()?.seller @ ShadowBidError::NotSeller,
+    )]
+    pub seller: UncheckedAccount<'info>,
+
+    /// The metadata account, closed to the seller
+    #[account(
+        mut,
+        close = seller,
+        seeds = [METADATA_SEED, auction.key().as_ref()],
+        bump = metadata.bump,
+    )]
+    pub metadata: Account<'info, AuctionMetadata>,
+}
+
+pub fn handler(ctx: Context<CloseAuctionMetadata>) -> Result<()> {
+    AuctionAuthority::check(
+        &ctx.accounts.auction_authority,
+        &ctx.accounts.auction.load_versioned()?.seller,
+        ctx.accounts.authority.key,
+        Some(Permission::UpdateMetadata),
+    )?;
+
+    let clock = Clock::get()?;
+    let refunded = ctx.accounts.metadata.get_lamports();
+
+    emit!(MetadataClosed {
+        auction: ctx.accounts.auction.key(),
+        seller: ctx.accounts.seller.key(),
+        refunded,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Metadata closed for auction {}", ctx.accounts.auction.key());
+
+    Ok(())
+}
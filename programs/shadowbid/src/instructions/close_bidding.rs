@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::AUCTION_PAGE_SEED;
 use crate::errors::ShadowBidError;
 use crate::events::BiddingClosed;
-use crate::state::{Auction, AuctionState};
+use crate::state::{Auction, AuctionPage, AuctionState, LoadVersioned};
 
 #[derive(Accounts)]
 pub struct CloseBidding<'info> {
@@ -13,14 +14,25 @@ pub struct CloseBidding<'info> {
     /// The auction to close
     #[account(
         mut,
-        constraint = auction.state == AuctionState::Open @ ShadowBidError::AuctionNotOpen,
+        constraint = auction.load_versioned()?.state()? == AuctionState::Open @ ShadowBidError::AuctionNotOpen,
     )]
-    pub auction: Account<'info, Auction>,
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The page listing the auction, which it leaves once bidding
+    /// closes. Seeds are checked; auctions created before the registry
+    /// have no page and `AuctionPage::unlist_from` skips it
+    #[account(
+        mut,
+        seeds = [AUCTION_PAGE_SEED, &AuctionPage::index_of(auction.load_versioned()?.auction_id).to_le_bytes()],
+        bump
+    )]
+    pub page: UncheckedAccount<'info>,
 }
 
 pub fn handler(ctx: Context<CloseBidding>) -> Result<()> {
     let clock = Clock::get()?;
-    let auction = &mut ctx.accounts.auction;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
 
     // Ensure bidding period has ended
     require!(
@@ -31,16 +43,22 @@ pub fn handler(ctx: Context<CloseBidding>) -> Result<()> {
     // Check if there are any bids
     if auction.bid_count == 0 {
         // No bids - cancel the auction
-        auction.state = AuctionState::Cancelled;
+        auction.set_state(AuctionState::Cancelled);
         msg!("Auction cancelled - no bids received");
+    } else if auction.is_commit_reveal() {
+        // Commit-reveal bids must be revealed before they can be processed
+        auction.set_state(AuctionState::Revealing);
+        msg!("Bidding closed - {} bids to reveal by {}", { auction.bid_count }, { auction.reveal_end_time });
     } else {
         // Transition to Closed state
-        auction.state = AuctionState::Closed;
-        msg!("Bidding closed - {} bids to process", auction.bid_count);
+        auction.set_state(AuctionState::Closed);
+        msg!("Bidding closed - {} bids to process", { auction.bid_count });
     }
 
+    AuctionPage::unlist_from(&ctx.accounts.page, auction.auction_id, &auction_key)?;
+
     emit!(BiddingClosed {
-        auction: auction.key(),
+        auction: auction_key,
         total_bids: auction.bid_count,
         timestamp: clock.unix_timestamp,
     });
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ShadowBidError;
+use crate::events::RevealClosed;
+use crate::state::{Auction, AuctionState, LoadVersioned};
+
+#[derive(Accounts)]
+pub struct CloseReveal<'info> {
+    /// Anyone can close the reveal period after it ends (permissionless)
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// The commit-reveal auction to close
+    #[account(
+        mut,
+        constraint = auction.load_versioned()?.state()? == AuctionState::Revealing @ ShadowBidError::AuctionNotRevealing,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+}
+
+pub fn handler(ctx: Context<CloseReveal>) -> Result<()> {
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+
+    // Ensure the reveal period has ended
+    require!(
+        clock.unix_timestamp >= auction.reveal_end_time,
+        ShadowBidError::RevealNotEnded
+    );
+
+    // Every bid still goes through determine_winner, which forfeits
+    // the collateral of bids that were never revealed
+    auction.set_state(AuctionState::Closed);
+    msg!(
+        "Reveal closed - {}/{} bids revealed",
+        { auction.bids_revealed },
+        { auction.bid_count }
+    );
+
+    emit!(RevealClosed {
+        auction: auction_key,
+        bids_revealed: auction.bids_revealed,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
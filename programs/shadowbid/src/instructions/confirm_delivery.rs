@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{ESCROW_SEED, HOOK_SEED};
+use crate::errors::ShadowBidError;
+use crate::events::{AuctionSettled, EscrowReleased};
+use crate::hook::{check_hook, AuctionCompletion, AuctionOutcome};
+use crate::state::{Auction, AuctionHook, AuctionState, Escrow, LoadVersioned};
+
+/// This instruction:
+/// 1. Lets the winner confirm they received the item
+/// 2. Releases the escrowed payment to the beneficiary
+/// 3. Marks auction as settled and returns the escrow's rent to the winner
+/// 4. Invokes the auction's hook program, if it has one, before the
+///    payment moves
+#[derive(Accounts)]
+pub struct ConfirmDelivery<'info> {
+    /// The winner confirming delivery
+    #[account(
+        mut,
+        constraint = winner.key() == auction.load_versioned()?.winner @ ShadowBidError::NotWinner,
+    )]
+    pub winner: Signer<'info>,
+
+    /// The auction awaiting delivery
+    #[account(
+        mut,
+        constraint = auction.load_versioned()?.state()? == AuctionState::AwaitingDelivery @ ShadowBidError::NotAwaitingDelivery,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The seller, passed to the hook; validated against
+    /// auction.seller
+    #[account(
+        mut,
+        constraint = seller.key() == auction.load_versioned()?.seller @ ShadowBidError::NotSeller,
+    )]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: The beneficiary receiving payment; validated against
+    /// escrow.beneficiary
+    #[account(mut, address = escrow.beneficiary @ ShadowBidError::NotBeneficiary)]
+    pub beneficiary: UncheckedAccount<'info>,
+
+    /// The escrow holding the payment, closed to the winner
+    #[account(
+        mut,
+        close = winner,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// The auction's hook, passed only for auctions created with one
+    /// (and optional when its failures are ignored)
+    #[account(
+        seeds = [HOOK_SEED, auction.key().as_ref()],
+        bump = hook.bump,
+    )]
+    pub hook: Option<Account<'info, AuctionHook>>,
+
+    /// CHECK: The hook program, checked against `hook.program` by the
+    /// handler
+    pub hook_program: Option<UncheckedAccount<'info>>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ConfirmDelivery<'info>>) -> Result<()> {
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+    let amount = ctx.accounts.escrow.amount;
+
+    // Unless its failures are ignored, the winner can't skip the hook
+    let hook = check_hook(
+        auction.hook_mode()?,
+        ctx.accounts.hook.as_deref(),
+        ctx.accounts.hook_program.as_deref(),
+    )?;
+
+    auction.set_state(AuctionState::Settled);
+
+    let completion = AuctionCompletion {
+        auction: auction_key,
+        auction_id: auction.auction_id,
+        seller: auction.seller,
+        winner: auction.winner,
+        amount,
+        outcome: AuctionOutcome::Settled,
+    };
+    let bump = auction.bump;
+
+    // The hook receives the auction account, which can't be borrowed
+    // across the CPI. It runs before the escrow pays out: the runtime
+    // rejects a CPI after lamports moved between accounts it isn't given.
+    drop(auction);
+    hook.run(
+        &ctx.accounts.auction.to_account_info(),
+        &ctx.accounts.winner.to_account_info(),
+        &ctx.accounts.seller.to_account_info(),
+        ctx.remaining_accounts,
+        &completion,
+        bump,
+    )?;
+
+    **ctx.accounts.escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.beneficiary.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    emit!(EscrowReleased {
+        auction: auction_key,
+        seller: completion.seller,
+        amount,
+        confirmed: true,
+        timestamp: clock.unix_timestamp,
+    });
+    emit!(AuctionSettled {
+        auction: auction_key,
+        winner: completion.winner,
+        winning_amount: amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Delivery confirmed by {}", completion.winner);
+    msg!("Payment released to {}: {} lamports", ctx.accounts.escrow.beneficiary, amount);
+
+    Ok(())
+}
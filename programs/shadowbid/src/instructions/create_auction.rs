@@ -1,14 +1,53 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 
-use crate::constants::{AUCTION_SEED, MIN_AUCTION_DURATION, MAX_AUCTION_DURATION, MAX_TITLE_LENGTH, MAX_DESCRIPTION_LENGTH};
+use crate::constants::{AUCTION_SEED, AUCTION_AUTHORITY_SEED, AUCTION_PAGE_SEED, REGISTRY_SEED, SELLER_BOND_SEED, SELLER_COUNTER_SEED, SELLER_PROFILE_SEED, SETTLEMENT_CALLBACK_SEED, HOOK_SEED, MIN_AUCTION_DURATION, MAX_AUCTION_DURATION, MAX_TITLE_LENGTH, MAX_DESCRIPTION_LENGTH, MIN_REVEAL_DURATION, MAX_REVEAL_DURATION, MIN_DELIVERY_TIMEOUT, MAX_DELIVERY_TIMEOUT, MAX_ARBITRATION_FEE_BPS};
 use crate::errors::ShadowBidError;
 use crate::events::AuctionCreated;
-use crate::state::{Auction, AuctionState};
+use crate::state::{Auction, AuctionAuthority, AuctionMode, AuctionPage, AuctionState, CancellationPolicy, Registry, SellerBond, SellerCounter, SellerProfile, SettlementCallback, AuctionHook, HookMode};
+
+/// Settings for a commit-reveal auction (no Inco Lightning required)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CommitRevealParams {
+    /// Length of the reveal window after end_time, in seconds
+    pub reveal_duration: i64,
+    /// Collateral each bidder deposits with their commitment, in lamports
+    pub bid_collateral: u64,
+}
+
+/// Dispute resolution for an escrowed auction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ArbitrationParams {
+    /// Who resolves disputes: a wallet, or a multisig that signs through
+    /// its program
+    pub arbitrator: Pubkey,
+    /// Share of a disputed payment the arbitrator takes, in basis points
+    pub fee_bps: u16,
+}
+
+/// Program invoked when the auction completes or is cancelled
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct HookParams {
+    /// The hook program (see `crate::hook` for its interface)
+    pub program: Pubkey,
+    /// Let completion and cancellation go ahead without the hook, so its
+    /// failure can't block them; otherwise a failing hook aborts them
+    pub ignore_failures: bool,
+}
+
+/// Who administers the auction and who is paid for it, instead of the
+/// seller
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AuthorityParams {
+    /// Takes administrative actions and names delegates: a wallet, or a
+    /// multisig that signs through its program
+    pub authority: Pubkey,
+    /// Receives the winner's payment
+    pub beneficiary: Pubkey,
+}
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct CreateAuctionParams {
-    /// Unique identifier for this auction
-    pub auction_id: u64,
     /// Auction title (max 64 bytes)
     pub title: String,
     /// Auction description (max 256 bytes)
@@ -19,28 +58,154 @@ pub struct CreateAuctionParams {
     pub duration: i64,
     /// Optional: NFT mint address being auctioned
     pub item_mint: Option<Pubkey>,
+    /// Optional: run as a commit-reveal auction instead of an Inco sealed-bid auction
+    pub commit_reveal: Option<CommitRevealParams>,
+    /// Optional: lamports each bidder of a sealed auction deposits with
+    /// their first bid. The seller can settle from the winner's deposit
+    /// without the winner (see `settle_from_deposit`); other deposits are
+    /// reclaimed once the winner is determined
+    pub bid_deposit: Option<u64>,
+    /// Optional: good-faith bond in lamports, locked in the SellerBond
+    /// vault until the auction settles. It also lets the seller cancel
+    /// after the first bid, at the cost of the bond; without it the first
+    /// bid rules out cancellation
+    pub seller_bond: Option<u64>,
+    /// Optional: hold the winner's payment in escrow until they confirm
+    /// delivery, or for this many seconds after settlement
+    pub delivery_timeout: Option<i64>,
+    /// Optional: arbitrator who resolves delivery disputes (escrowed
+    /// auctions only); without one the winner can't dispute
+    pub arbitration: Option<ArbitrationParams>,
+    /// Optional: program called back by `settle_auction` with the
+    /// settlement result (see `crate::callback`); not for escrowed
+    /// auctions, nor together with `hook`
+    pub settlement_callback: Option<Pubkey>,
+    /// Optional: program invoked with the outcome when the auction
+    /// completes, by `settle_auction`, `cancel_auction` or, for escrowed
+    /// auctions, whichever instruction pays out the escrow
+    pub hook: Option<HookParams>,
+    /// Mint a receipt NFT to the winner at settlement, which the seller
+    /// redeems once the purchase is fulfilled
+    pub issue_receipt: bool,
+    /// Optional: reject bids from bidders whose profile shows more than
+    /// this many defaults (below 255)
+    pub max_bidder_defaults: Option<u8>,
+    /// Optional: separate authority and beneficiary, recorded in the
+    /// AuctionAuthority account; without it the seller is both
+    pub authority: Option<AuthorityParams>,
 }
 
 #[derive(Accounts)]
-#[instruction(params: CreateAuctionParams)]
 pub struct CreateAuction<'info> {
-    /// The seller creating this auction
+    /// Funds the new accounts and the seller bond; a wallet seller
+    /// passes itself
     #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The seller creating this auction; a program signs for its PDA
+    /// with `invoke_signed`
     pub seller: Signer<'info>,
 
-    /// The auction account to be created
+    /// The global registry, which assigns the auction ID
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// The seller's auction counter, created with their first auction
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = SellerCounter::SPACE,
+        seeds = [SELLER_COUNTER_SEED, seller.key().as_ref()],
+        bump
+    )]
+    pub seller_counter: Account<'info, SellerCounter>,
+
+    /// The seller's track record, created with their first auction
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = SellerProfile::SPACE,
+        seeds = [SELLER_PROFILE_SEED, seller.key().as_ref()],
+        bump
+    )]
+    pub seller_profile: Box<Account<'info, SellerProfile>>,
+
+    /// The page listing this auction, created by the first auction on it
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = AuctionPage::SPACE,
+        seeds = [
+            AUCTION_PAGE_SEED,
+            &AuctionPage::index_of(registry.auction_count).to_le_bytes()
+        ],
+        bump
+    )]
+    pub page: Box<Account<'info, AuctionPage>>,
+
+    /// The auction account to be created, at the registry's next ID
     #[account(
         init,
-        payer = seller,
+        payer = payer,
         space = Auction::SPACE,
         seeds = [
             AUCTION_SEED,
             seller.key().as_ref(),
-            &params.auction_id.to_le_bytes()
+            &registry.auction_count.to_le_bytes()
         ],
         bump
     )]
-    pub auction: Account<'info, Auction>,
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// The vault holding the seller bond, passed only with `seller_bond`
+    #[account(
+        init,
+        payer = payer,
+        space = SellerBond::SPACE,
+        seeds = [SELLER_BOND_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub seller_bond: Option<Account<'info, SellerBond>>,
+
+    /// The program to call back on settlement, passed only with
+    /// `settlement_callback`
+    #[account(
+        init,
+        payer = payer,
+        space = SettlementCallback::SPACE,
+        seeds = [SETTLEMENT_CALLBACK_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub settlement_callback: Option<Account<'info, SettlementCallback>>,
+
+    /// Names the hook program, passed only with `hook`
+    #[account(
+        init,
+        payer = payer,
+        space = AuctionHook::SPACE,
+        seeds = [HOOK_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub hook: Option<Account<'info, AuctionHook>>,
+
+    /// CHECK: The hook program itself, passed only with `hook`; checked
+    /// against `hook.program` by the handler
+    #[account(executable)]
+    pub hook_program: Option<UncheckedAccount<'info>>,
+
+    /// Names the authority and beneficiary, passed only with `authority`
+    #[account(
+        init,
+        payer = payer,
+        space = AuctionAuthority::SPACE,
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_authority: Option<Box<Account<'info, AuctionAuthority>>>,
 
     /// System program for account creation
     pub system_program: Program<'info, System>,
@@ -68,9 +233,153 @@ pub fn handler(ctx: Context<CreateAuction>, params: CreateAuctionParams) -> Resu
         params.reserve_price > 0,
         ShadowBidError::InvalidReservePrice
     );
+    if let Some(commit_reveal) = &params.commit_reveal {
+        require!(
+            commit_reveal.reveal_duration >= MIN_REVEAL_DURATION
+                && commit_reveal.reveal_duration <= MAX_REVEAL_DURATION,
+            ShadowBidError::InvalidRevealDuration
+        );
+    }
+    if let Some(deposit) = params.bid_deposit {
+        require!(
+            deposit > 0 && params.commit_reveal.is_none(),
+            ShadowBidError::InvalidBidDeposit
+        );
+    }
+    if let Some(bond) = params.seller_bond {
+        require!(bond > 0, ShadowBidError::InvalidSellerBond);
+    }
+    if let Some(timeout) = params.delivery_timeout {
+        require!(
+            (MIN_DELIVERY_TIMEOUT..=MAX_DELIVERY_TIMEOUT).contains(&timeout),
+            ShadowBidError::InvalidDeliveryTimeout
+        );
+    }
+    if let Some(arbitration) = &params.arbitration {
+        require!(
+            params.delivery_timeout.is_some(),
+            ShadowBidError::ArbitrationRequiresEscrow
+        );
+        require!(
+            arbitration.arbitrator != Pubkey::default()
+                && arbitration.arbitrator != ctx.accounts.seller.key()
+                && params.authority.as_ref().is_none_or(|authority| arbitration.arbitrator != authority.beneficiary),
+            ShadowBidError::InvalidArbitrator
+        );
+        require!(
+            arbitration.fee_bps <= MAX_ARBITRATION_FEE_BPS,
+            ShadowBidError::InvalidArbitrationFee
+        );
+    }
+    if let Some(program) = params.settlement_callback {
+        require!(
+            program != Pubkey::default() && program != crate::ID,
+            ShadowBidError::InvalidSettlementCallback
+        );
+        require!(
+            params.delivery_timeout.is_none(),
+            ShadowBidError::CallbackRequiresDirectPayment
+        );
+        require!(params.hook.is_none(), ShadowBidError::CallbackWithHook);
+    }
+    if let Some(hook) = &params.hook {
+        require!(
+            hook.program != Pubkey::default()
+                && hook.program != crate::ID
+                && ctx.accounts.hook_program.as_ref().map(|program| program.key()) == Some(hook.program),
+            ShadowBidError::InvalidHookProgram
+        );
+    }
+    require!(
+        params.max_bidder_defaults != Some(u8::MAX),
+        ShadowBidError::InvalidBidderDefaultLimit
+    );
+    if let Some(authority) = &params.authority {
+        require!(
+            authority.authority != Pubkey::default() && authority.beneficiary != Pubkey::default(),
+            ShadowBidError::InvalidAuthority
+        );
+    }
+    require!(
+        params.seller_bond.is_some() == ctx.accounts.seller_bond.is_some(),
+        ShadowBidError::SellerBondMismatch
+    );
+    require!(
+        params.settlement_callback.is_some() == ctx.accounts.settlement_callback.is_some(),
+        ShadowBidError::SettlementCallbackMismatch
+    );
+    require!(
+        params.hook.is_some() == ctx.accounts.hook.is_some(),
+        ShadowBidError::HookMismatch
+    );
+    require!(
+        params.authority.is_some() == ctx.accounts.auction_authority.is_some(),
+        ShadowBidError::AuthorityMismatch
+    );
 
     let clock = Clock::get()?;
-    let auction = &mut ctx.accounts.auction;
+    let auction_id = ctx.accounts.registry.auction_count;
+    ctx.accounts.registry.auction_count += 1;
+
+    let seller_counter = &mut ctx.accounts.seller_counter;
+    seller_counter.seller = ctx.accounts.seller.key();
+    seller_counter.auction_count += 1;
+    seller_counter.bump = ctx.bumps.seller_counter;
+
+    let profile = &mut ctx.accounts.seller_profile;
+    profile.seller = ctx.accounts.seller.key();
+    profile.auctions_created += 1;
+    profile.bump = ctx.bumps.seller_profile;
+
+    let page = &mut ctx.accounts.page;
+    page.index = AuctionPage::index_of(auction_id);
+    page.bump = ctx.bumps.page;
+    page.list(auction_id, ctx.accounts.auction.key());
+
+    // The bond sits in its own vault, apart from the auction's rent and
+    // any commit-reveal collateral, until it is released or slashed
+    let seller_bond = params.seller_bond.unwrap_or(0);
+    if let Some(vault) = &mut ctx.accounts.seller_bond {
+        vault.auction = ctx.accounts.auction.key();
+        vault.seller = ctx.accounts.seller.key();
+        vault.amount = seller_bond;
+        vault.compensation_per_bid = 0;
+        vault.claims_remaining = 0;
+        vault.bump = ctx.bumps.seller_bond.unwrap_or_default();
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: vault.to_account_info(),
+                },
+            ),
+            seller_bond,
+        )?;
+    }
+
+    if let Some(callback) = &mut ctx.accounts.settlement_callback {
+        callback.auction = ctx.accounts.auction.key();
+        callback.program = params.settlement_callback.unwrap_or_default();
+        callback.bump = ctx.bumps.settlement_callback.unwrap_or_default();
+    }
+
+    if let (Some(hook), Some(params)) = (&mut ctx.accounts.hook, &params.hook) {
+        hook.auction = ctx.accounts.auction.key();
+        hook.program = params.program;
+        hook.bump = ctx.bumps.hook.unwrap_or_default();
+    }
+
+    if let (Some(record), Some(params)) = (&mut ctx.accounts.auction_authority, &params.authority) {
+        record.auction = ctx.accounts.auction.key();
+        record.authority = params.authority;
+        record.beneficiary = params.beneficiary;
+        record.bump = ctx.bumps.auction_authority.unwrap_or_default();
+    }
+
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_init()?;
 
     // Initialize title (pad with zeros)
     let mut title_bytes = [0u8; MAX_TITLE_LENGTH];
@@ -84,25 +393,70 @@ pub fn handler(ctx: Context<CreateAuction>, params: CreateAuctionParams) -> Resu
 
     // Set auction fields
     auction.seller = ctx.accounts.seller.key();
-    auction.item_mint = params.item_mint.unwrap_or(Pubkey::default());
+    auction.item_mint = params.item_mint.unwrap_or_default();
     auction.title = title_bytes;
     auction.description = description_bytes;
     auction.reserve_price = params.reserve_price;
     auction.start_time = clock.unix_timestamp;
     auction.end_time = clock.unix_timestamp + params.duration;
-    auction.state = AuctionState::Open;
+    auction.set_state(AuctionState::Open);
     auction.bid_count = 0;
     auction.bids_processed = 0;
     auction.highest_bid_handle = 0;
     auction.current_leader = Pubkey::default();
     auction.winner = Pubkey::default();
     auction.winning_amount = 0;
-    auction.auction_id = params.auction_id;
+    auction.bid_sum_handle = 0;
+    auction.lowest_bid_handle = 0;
+    auction.above_reserve_count_handle = 0;
+    match &params.commit_reveal {
+        Some(commit_reveal) => {
+            auction.set_mode(AuctionMode::CommitReveal);
+            auction.reveal_end_time = auction.end_time + commit_reveal.reveal_duration;
+            auction.bid_collateral = commit_reveal.bid_collateral;
+        }
+        None => {
+            auction.set_mode(AuctionMode::Sealed);
+            auction.reveal_end_time = 0;
+            auction.bid_collateral = params.bid_deposit.unwrap_or_default();
+        }
+    }
+    auction.highest_revealed_amount = 0;
+    auction.bids_revealed = 0;
+    auction.forfeited_collateral = 0;
+    auction.auction_id = auction_id;
     auction.bump = ctx.bumps.auction;
+    auction.version = Auction::VERSION;
+    auction.set_cancellation_policy(if seller_bond > 0 {
+        CancellationPolicy::Bonded
+    } else {
+        CancellationPolicy::NoCancelAfterBid
+    });
+    auction.seller_bond = seller_bond;
+    auction.delivery_timeout = params.delivery_timeout.unwrap_or(0);
+    auction.delivery_deadline = 0;
+    match &params.arbitration {
+        Some(arbitration) => {
+            auction.arbitrator = arbitration.arbitrator;
+            auction.arbitration_fee_bps = arbitration.fee_bps;
+        }
+        None => {
+            auction.arbitrator = Pubkey::default();
+            auction.arbitration_fee_bps = 0;
+        }
+    }
+    auction.set_settlement_callback(params.settlement_callback.is_some());
+    auction.set_hook_mode(match &params.hook {
+        Some(hook) if hook.ignore_failures => HookMode::Ignore,
+        Some(_) => HookMode::Abort,
+        None => HookMode::None,
+    });
+    auction.set_issues_receipt(params.issue_receipt);
+    auction.set_max_bidder_defaults(params.max_bidder_defaults);
 
     // Emit event
     emit!(AuctionCreated {
-        auction: auction.key(),
+        auction: auction_key,
         seller: auction.seller,
         title: params.title,
         reserve_price: params.reserve_price,
@@ -110,10 +464,28 @@ pub fn handler(ctx: Context<CreateAuction>, params: CreateAuctionParams) -> Resu
         end_time: auction.end_time,
     });
 
-    msg!("Auction created: {}", auction.key());
+    msg!("Auction created: {} (ID {})", auction_key, auction_id);
     msg!("Title: {}", auction.get_title());
-    msg!("Reserve price: {} lamports", auction.reserve_price);
-    msg!("Ends at: {}", auction.end_time);
+    msg!("Reserve price: {} lamports", { auction.reserve_price });
+    msg!("Ends at: {}", { auction.end_time });
+    if seller_bond > 0 {
+        msg!("Seller bond: {} lamports", seller_bond);
+    }
+    if let Some(deposit) = params.bid_deposit {
+        msg!("Bid deposit: {} lamports", deposit);
+    }
+    if let Some(program) = params.settlement_callback {
+        msg!("Settlement callback: {}", program);
+    }
+    if let Some(hook) = &params.hook {
+        msg!("Hook: {}", hook.program);
+    }
+    if let Some(max) = params.max_bidder_defaults {
+        msg!("Max bidder defaults: {}", max);
+    }
+    if let Some(authority) = &params.authority {
+        msg!("Authority: {}, beneficiary: {}", authority.authority, authority.beneficiary);
+    }
 
     Ok(())
 }
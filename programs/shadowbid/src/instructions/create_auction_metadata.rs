@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{AUCTION_AUTHORITY_SEED, METADATA_SEED, MAX_METADATA_TITLE_LENGTH, MAX_METADATA_DESCRIPTION_LENGTH, MAX_METADATA_URI_LENGTH, MAX_TAGS, MAX_TAG_LENGTH};
+use crate::errors::ShadowBidError;
+use crate::events::MetadataUpdated;
+use crate::state::{Auction, AuctionAuthority, AuctionMetadata, AuctionState, LoadVersioned, Permission};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AuctionMetadataParams {
+    /// Full auction title (max 100 bytes)
+    pub title: String,
+    /// Full auction description (max 300 bytes)
+    pub description: String,
+    /// Off-chain metadata document URI (max 200 bytes)
+    pub uri: String,
+    /// SHA-256 of the document at `uri`
+    pub content_hash: [u8; 32],
+    /// Category tags (max 8, each 1 to 16 bytes)
+    pub tags: Vec<String>,
+}
+
+impl AuctionMetadataParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.title.len() <= MAX_METADATA_TITLE_LENGTH
+                && self.description.len() <= MAX_METADATA_DESCRIPTION_LENGTH
+                && self.uri.len() <= MAX_METADATA_URI_LENGTH,
+            ShadowBidError::MetadataTooLong
+        );
+        require!(self.tags.len() <= MAX_TAGS, ShadowBidError::TooManyTags);
+        require!(
+            self.tags.iter().all(|tag| !tag.is_empty() && tag.len() <= MAX_TAG_LENGTH),
+            ShadowBidError::InvalidTag
+        );
+        Ok(())
+    }
+
+    /// Space of an AuctionMetadata account holding these params
+    pub fn space(&self) -> usize {
+        AuctionMetadata::space(&self.title, &self.description, &self.uri, &self.tags)
+    }
+
+    /// Writes the params into `metadata` and emits `MetadataUpdated`
+    pub(crate) fn apply(self, metadata: &mut AuctionMetadata, timestamp: i64) {
+        metadata.title = self.title;
+        metadata.description = self.description;
+        metadata.uri = self.uri;
+        metadata.content_hash = self.content_hash;
+        metadata.tags = self.tags;
+        metadata.updated_at = timestamp;
+
+        emit!(MetadataUpdated {
+            auction: metadata.auction,
+            uri: metadata.uri.clone(),
+            content_hash: metadata.content_hash,
+            tags: metadata.tags.clone(),
+            timestamp,
+        });
+
+        msg!("Metadata updated for auction {}", metadata.auction);
+        msg!("URI: {}", metadata.uri);
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(params: AuctionMetadataParams)]
+pub struct CreateAuctionMetadata<'info> {
+    /// The authority describing the auction, or a delegate allowed to;
+    /// checked by the handler
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The auction being described; bidders must see the final metadata
+    #[account(
+        constraint = auction.load_versioned()?.state()? == AuctionState::Open @ ShadowBidError::AuctionNotOpen,
+        constraint = auction.load_versioned()?.bid_count == 0 @ ShadowBidError::MetadataLocked,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The auction's authority record. Seeds are checked; without
+    /// one, `AuctionAuthority::check` requires the seller
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+
+    /// The metadata account to be created
+    #[account(
+        init,
+        payer = authority,
+        space = params.space(),
+        seeds = [METADATA_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub metadata: Account<'info, AuctionMetadata>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateAuctionMetadata>, params: AuctionMetadataParams) -> Result<()> {
+    AuctionAuthority::check(
+        &ctx.accounts.auction_authority,
+        &ctx.accounts.auction.load_versioned()?.seller,
+        ctx.accounts.authority.key,
+        Some(Permission::UpdateMetadata),
+    )?;
+    params.validate()?;
+
+    let clock = Clock::get()?;
+    let metadata = &mut ctx.accounts.metadata;
+    metadata.auction = ctx.accounts.auction.key();
+    metadata.bump = ctx.bumps.metadata;
+    params.apply(metadata, clock.unix_timestamp);
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{BID_SEED, BIDDER_PROFILE_SEED};
+use crate::errors::ShadowBidError;
+use crate::events::WinnerDefaulted;
+use crate::state::{Auction, AuctionState, Bid, BidderProfile, LoadVersioned};
+
+/// This instruction:
+/// 1. Checks the winner's settlement window has ended without settlement
+/// 2. Marks the auction as defaulted, freeing the seller's bond
+/// 3. Counts the default in the winner's profile
+///
+/// Permissionless: the seller, or anyone, can call it. Until it is called
+/// the winner can still settle late. A sealed winner below the reserve
+/// couldn't settle at all, and `cancel_below_reserve` withdraws the
+/// default.
+#[derive(Accounts)]
+pub struct DeclareDefault<'info> {
+    /// Anyone can declare an expired winner in default
+    pub caller: Signer<'info>,
+
+    /// The auction waiting for settlement
+    #[account(
+        mut,
+        constraint = auction.load_versioned()?.state()? == AuctionState::WinnerDetermined @ ShadowBidError::WinnerNotDetermined,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The winner who didn't settle; validated against auction.winner
+    #[account(
+        constraint = winner.key() == auction.load_versioned()?.winner @ ShadowBidError::NotWinner,
+    )]
+    pub winner: UncheckedAccount<'info>,
+
+    /// The winning bid, recording when it won
+    #[account(
+        seeds = [BID_SEED, auction.key().as_ref(), winner.key().as_ref()],
+        bump = winner_bid.bump,
+    )]
+    pub winner_bid: Account<'info, Bid>,
+
+    /// CHECK: The winner's profile. Seeds are checked; winners without
+    /// one are skipped by `BidderProfile::update_from`
+    #[account(
+        mut,
+        seeds = [BIDDER_PROFILE_SEED, winner.key().as_ref()],
+        bump
+    )]
+    pub winner_profile: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<DeclareDefault>) -> Result<()> {
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+
+    require!(
+        ctx.accounts.winner_bid.is_settlement_window_ended(clock.unix_timestamp),
+        ShadowBidError::SettlementWindowOpen
+    );
+
+    auction.set_state(AuctionState::Defaulted);
+    BidderProfile::update_from(&ctx.accounts.winner_profile, |profile| {
+        profile.auctions_defaulted += 1
+    })?;
+
+    emit!(WinnerDefaulted {
+        auction: auction_key,
+        winner: auction.winner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Winner defaulted: {}", auction.winner);
+
+    Ok(())
+}
@@ -1,22 +1,27 @@
 use anchor_lang::prelude::*;
-use inco_lightning::cpi::accounts::Operation;
-use inco_lightning::cpi::{e_ge, e_select};
 use inco_lightning::types::{Euint128, Ebool};
 use inco_lightning::ID as INCO_LIGHTNING_ID;
 
+use crate::confidential::{ConfidentialBackend, IncoBackend};
 use crate::constants::BID_SEED;
 use crate::errors::ShadowBidError;
-use crate::events::BidProcessed;
-use crate::state::{Auction, AuctionState, Bid};
+use crate::events::{BidProcessed, CollateralForfeited};
+use crate::state::{Auction, AuctionState, Bid, LoadVersioned};
 
 /// The process:
 /// 1. Load the bid's encrypted amount
 /// 2. Compare against current highest using e_ge (encrypted greater-than-or-equal)
 /// 3. Use e_select to conditionally update highest_bid_handle
 /// 4. Track the leader's pubkey alongside the handle
+/// 5. Accumulate the encrypted statistics (sum, lowest bid and the
+///    number of bids at or above the reserve price)
 /// 
 /// This must be called once for each bid (except the first one which
 /// is automatically set as leader during place_bid).
+///
+/// Commit-reveal auctions compare the plaintext revealed amounts instead,
+/// and forfeit the collateral of unrevealed bids to the seller, who must
+/// be passed as the first remaining account.
 #[derive(Accounts)]
 pub struct DetermineWinner<'info> {
     /// Anyone can call this to help process bids (permissionless)
@@ -26,9 +31,9 @@ pub struct DetermineWinner<'info> {
     /// The auction being processed
     #[account(
         mut,
-        constraint = auction.state == AuctionState::Closed @ ShadowBidError::AuctionNotClosed,
+        constraint = auction.load_versioned()?.state()? == AuctionState::Closed @ ShadowBidError::AuctionNotClosed,
     )]
-    pub auction: Account<'info, Auction>,
+    pub auction: AccountLoader<'info, Auction>,
 
     /// The bid to compare against current highest
     #[account(
@@ -50,16 +55,25 @@ pub struct DetermineWinner<'info> {
     pub inco_lightning_program: AccountInfo<'info>,
 }
 
-pub fn handler(ctx: Context<DetermineWinner>) -> Result<()> {
-    let auction = &mut ctx.accounts.auction;
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, DetermineWinner<'info>>) -> Result<()> {
+    if ctx.accounts.auction.load_versioned()?.is_commit_reveal() {
+        return process_revealed_bid(ctx);
+    }
+
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
     let bid = &mut ctx.accounts.bid;
-    let inco = ctx.accounts.inco_lightning_program.to_account_info();
-    let signer = ctx.accounts.caller.to_account_info();
+    let backend = IncoBackend::new(
+        &ctx.accounts.inco_lightning_program,
+        &ctx.accounts.caller.to_account_info(),
+    );
 
     // Get handles for comparison
     let current_highest = Euint128(auction.highest_bid_handle);
     let this_bid = Euint128(bid.encrypted_amount);
 
+    accumulate_stats(&backend, &mut auction, this_bid)?;
+
     // If this is the first bid being processed (current_highest is 0),
     // just set it as the leader without comparison
     if auction.highest_bid_handle == 0 {
@@ -69,7 +83,7 @@ pub fn handler(ctx: Context<DetermineWinner>) -> Result<()> {
         auction.bids_processed = auction.bids_processed.checked_add(1).unwrap();
 
         emit!(BidProcessed {
-            auction: auction.key(),
+            auction: auction_key,
             bidder: bid.bidder,
             bids_processed: auction.bids_processed,
         });
@@ -80,25 +94,11 @@ pub fn handler(ctx: Context<DetermineWinner>) -> Result<()> {
 
     // Compare this bid against current highest using encrypted comparison
     // e_ge returns Ebool: true if this_bid >= current_highest
-    let cpi_ctx = CpiContext::new(
-        inco.clone(),
-        Operation { signer: signer.clone() },
-    );
-    let is_higher_or_equal: Ebool = e_ge(cpi_ctx, this_bid, current_highest, 0)?;
+    let is_higher_or_equal: Ebool = backend.e_ge(this_bid, current_highest)?;
 
     // Use e_select to conditionally choose the new highest bid
     // If this_bid >= current_highest, select this_bid; otherwise keep current_highest
-    let cpi_ctx = CpiContext::new(
-        inco.clone(),
-        Operation { signer: signer.clone() },
-    );
-    let new_highest: Euint128 = e_select(
-        cpi_ctx,
-        is_higher_or_equal,
-        this_bid,
-        current_highest,
-        0,
-    )?;
+    let new_highest: Euint128 = backend.e_select(is_higher_or_equal, this_bid, current_highest)?;
 
     // Check if the handle changed (indicating this bid is higher)
     // Note: In a production system, you might want to handle ties differently
@@ -119,13 +119,108 @@ pub fn handler(ctx: Context<DetermineWinner>) -> Result<()> {
     auction.bids_processed = auction.bids_processed.checked_add(1).unwrap();
 
     emit!(BidProcessed {
-        auction: auction.key(),
+        auction: auction_key,
+        bidder: bid.bidder,
+        bids_processed: auction.bids_processed,
+    });
+
+    msg!("Bid processed: {}", bid.bidder);
+    msg!("Bids processed: {}/{}", { auction.bids_processed }, { auction.bid_count });
+
+    Ok(())
+}
+
+/// Folds one bid into the encrypted auction statistics
+///
+/// None of these values are revealed here; the seller can only decrypt
+/// them after settlement through reveal_auction_stats.
+fn accumulate_stats<'info>(
+    backend: &impl ConfidentialBackend<'info>,
+    auction: &mut Auction,
+    this_bid: Euint128,
+) -> Result<()> {
+    if auction.bids_processed == 0 {
+        // First processed bid seeds the sum and lowest bid
+        auction.bid_sum_handle = this_bid.0;
+        auction.lowest_bid_handle = this_bid.0;
+        auction.above_reserve_count_handle = backend.as_euint128(0)?.0;
+    } else {
+        // sum += this_bid
+        let sum = backend.e_add(Euint128(auction.bid_sum_handle), this_bid)?;
+        auction.bid_sum_handle = sum.0;
+
+        // lowest = this_bid >= lowest ? lowest : this_bid
+        let lowest = Euint128(auction.lowest_bid_handle);
+        let is_not_lower: Ebool = backend.e_ge(this_bid, lowest)?;
+        let new_lowest = backend.e_select(is_not_lower, lowest, this_bid)?;
+        auction.lowest_bid_handle = new_lowest.0;
+    }
+
+    // count += (this_bid >= reserve_price) ? 1 : 0
+    let count = Euint128(auction.above_reserve_count_handle);
+    let meets_reserve: Ebool = backend.e_ge_scalar(this_bid, auction.reserve_price as u128)?;
+    let incremented = backend.e_add_scalar(count, 1)?;
+    let new_count = backend.e_select(meets_reserve, incremented, count)?;
+    auction.above_reserve_count_handle = new_count.0;
+
+    Ok(())
+}
+
+/// Commit-reveal winner determination on plaintext revealed amounts
+///
+/// Revealed bids at or above the reserve price compete; ties go to the
+/// later processed bid, as with e_ge in the sealed path. Unrevealed bids
+/// lose their collateral to the seller.
+fn process_revealed_bid<'info>(ctx: Context<'_, '_, '_, 'info, DetermineWinner<'info>>) -> Result<()> {
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+    let bid = &mut ctx.accounts.bid;
+
+    if bid.revealed {
+        if bid.revealed_amount >= auction.reserve_price
+            && bid.revealed_amount >= auction.highest_revealed_amount
+        {
+            auction.highest_revealed_amount = bid.revealed_amount;
+            auction.current_leader = bid.bidder;
+            msg!("New leader: {}", bid.bidder);
+        }
+    } else if bid.collateral > 0 {
+        let seller = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(ShadowBidError::InsufficientRemainingAccounts)?;
+        require!(
+            seller.key() == auction.seller,
+            ShadowBidError::NotSeller
+        );
+
+        let collateral = bid.collateral;
+        bid.collateral = 0;
+        **bid.to_account_info().try_borrow_mut_lamports()? -= collateral;
+        **seller.try_borrow_mut_lamports()? += collateral;
+        auction.forfeited_collateral = auction.forfeited_collateral.checked_add(collateral).unwrap();
+
+        emit!(CollateralForfeited {
+            auction: auction_key,
+            bidder: bid.bidder,
+            amount: collateral,
+        });
+
+        msg!("Unrevealed bid by {} forfeits {} lamports", bid.bidder, collateral);
+    }
+
+    // Mark bid as processed
+    bid.processed = true;
+    auction.bids_processed = auction.bids_processed.checked_add(1).unwrap();
+
+    emit!(BidProcessed {
+        auction: auction_key,
         bidder: bid.bidder,
         bids_processed: auction.bids_processed,
     });
 
     msg!("Bid processed: {}", bid.bidder);
-    msg!("Bids processed: {}/{}", auction.bids_processed, auction.bid_count);
+    msg!("Bids processed: {}/{}", { auction.bids_processed }, { auction.bid_count });
 
     Ok(())
 }
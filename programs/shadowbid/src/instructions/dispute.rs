@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{ESCROW_SEED, MAX_EVIDENCE_URI_LENGTH};
+use crate::errors::ShadowBidError;
+use crate::events::DisputeOpened;
+use crate::state::{Auction, AuctionState, Escrow, LoadVersioned};
+
+/// This instruction lets the winner dispute the delivery of an escrowed
+/// auction before the delivery window ends, freezing the payment until
+/// the arbitrator resolves it
+#[derive(Accounts)]
+pub struct Dispute<'info> {
+    /// The winner disputing the delivery
+    #[account(
+        constraint = winner.key() == auction.load_versioned()?.winner @ ShadowBidError::NotWinner,
+    )]
+    pub winner: Signer<'info>,
+
+    /// The auction awaiting delivery
+    #[account(
+        mut,
+        constraint = auction.load_versioned()?.state()? == AuctionState::AwaitingDelivery @ ShadowBidError::NotAwaitingDelivery,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// The escrow holding the payment
+    #[account(
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+}
+
+pub fn handler(ctx: Context<Dispute>, evidence_uri: String) -> Result<()> {
+    require!(
+        evidence_uri.len() <= MAX_EVIDENCE_URI_LENGTH,
+        ShadowBidError::EvidenceUriTooLong
+    );
+
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+
+    // Without an arbitrator nobody could ever unfreeze the payment
+    require!(auction.has_arbitrator(), ShadowBidError::NoArbitrator);
+
+    // Past the deadline anyone can release the payment, so a dispute
+    // must come in while the seller is still expected to deliver
+    require!(
+        !auction.is_delivery_window_ended(clock.unix_timestamp),
+        ShadowBidError::DeliveryWindowEnded
+    );
+
+    auction.set_state(AuctionState::Disputed);
+
+    emit!(DisputeOpened {
+        auction: auction_key,
+        winner: auction.winner,
+        amount: ctx.accounts.escrow.amount,
+        evidence_uri,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Delivery disputed: {}", auction_key);
+    msg!("Frozen payment: {} lamports", ctx.accounts.escrow.amount);
+
+    Ok(())
+}
@@ -1,17 +1,26 @@
 use anchor_lang::prelude::*;
-use inco_lightning::cpi::accounts::Allow;
-use inco_lightning::cpi::allow;
 use inco_lightning::ID as INCO_LIGHTNING_ID;
 
+use crate::confidential::{ConfidentialBackend, IncoBackend};
+use crate::constants::{BID_SEED, BIDDER_PROFILE_SEED};
 use crate::errors::ShadowBidError;
-use crate::events::WinnerDetermined;
-use crate::state::{Auction, AuctionState};
+use crate::events::{AuctionCancelled, WinnerDetermined};
+use crate::state::{Auction, AuctionState, Bid, BidderProfile, CancellationReason, LoadVersioned};
 
 /// This instruction:
 /// 1. Verifies all bids have been processed
 /// 2. Sets the winner from current_leader
 /// 3. Grants decryption permission to the winner via Inco's allow()
-/// 4. Transitions auction to WinnerDetermined state
+/// 4. Optionally grants the seller decryption permission as well
+/// 5. Transitions auction to WinnerDetermined state, starting the
+///    winner's settlement window and counting the win in their profile
+///
+/// To also reveal the clearing price to the seller, pass the seller's
+/// allowance account and the seller's address as remaining accounts:
+/// `[seller_allowance_account (mut), seller]`
+///
+/// Commit-reveal auctions skip the allow() calls since the winning amount
+/// is already public, and are cancelled if no revealed bid met the reserve.
 #[derive(Accounts)]
 pub struct FinalizeWinner<'info> {
     /// Anyone can finalize after all bids processed (permissionless)
@@ -21,10 +30,10 @@ pub struct FinalizeWinner<'info> {
     /// The auction to finalize
     #[account(
         mut,
-        constraint = auction.state == AuctionState::Closed @ ShadowBidError::AuctionNotClosed,
-        constraint = auction.all_bids_processed() @ ShadowBidError::NoBidsPlaced,
+        constraint = auction.load_versioned()?.state()? == AuctionState::Closed @ ShadowBidError::AuctionNotClosed,
+        constraint = auction.load_versioned()?.all_bids_processed() @ ShadowBidError::NoBidsPlaced,
     )]
-    pub auction: Account<'info, Auction>,
+    pub auction: AccountLoader<'info, Auction>,
 
     /// CHECK: Allowance account PDA for granting decrypt permission
     /// Seeds: [handle_bytes, winner_address]
@@ -32,8 +41,29 @@ pub struct FinalizeWinner<'info> {
     pub allowance_account: AccountInfo<'info>,
 
     /// CHECK: The winner's address (validated against auction.current_leader)
+    #[account(
+        constraint = winner_address.key() == auction.load_versioned()?.current_leader @ ShadowBidError::NotWinner,
+    )]
     pub winner_address: AccountInfo<'info>,
 
+    /// The winner's bid, stamped with the time it won; passed whenever
+    /// there is a winner
+    #[account(
+        mut,
+        seeds = [BID_SEED, auction.key().as_ref(), winner_address.key().as_ref()],
+        bump = winner_bid.bump,
+    )]
+    pub winner_bid: Option<Account<'info, Bid>>,
+
+    /// CHECK: The winner's profile. Seeds are checked; winners without
+    /// one are skipped by `BidderProfile::update_from`
+    #[account(
+        mut,
+        seeds = [BIDDER_PROFILE_SEED, winner_address.key().as_ref()],
+        bump
+    )]
+    pub winner_profile: UncheckedAccount<'info>,
+
     /// Inco Lightning program for allowance
     /// CHECK: Validated by address constraint
     #[account(address = INCO_LIGHTNING_ID)]
@@ -43,15 +73,15 @@ pub struct FinalizeWinner<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<FinalizeWinner>) -> Result<()> {
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, FinalizeWinner<'info>>) -> Result<()> {
     let clock = Clock::get()?;
-    let auction = &mut ctx.accounts.auction;
 
-    // Verify the winner address matches the current leader
-    require!(
-        ctx.accounts.winner_address.key() == auction.current_leader,
-        ShadowBidError::NotWinner
-    );
+    if ctx.accounts.auction.load_versioned()?.is_commit_reveal() {
+        return finalize_revealed(ctx, &clock);
+    }
+
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
 
     // Ensure there's actually a winner
     require!(
@@ -59,38 +89,105 @@ pub fn handler(ctx: Context<FinalizeWinner>) -> Result<()> {
         ShadowBidError::WinnerNotSet
     );
 
-    // Grant decryption access to the winner for the winning bid handle
-    let cpi_ctx = CpiContext::new(
-        ctx.accounts.inco_lightning_program.to_account_info(),
-        Allow {
-            allowance_account: ctx.accounts.allowance_account.to_account_info(),
-            signer: ctx.accounts.caller.to_account_info(),
-            allowed_address: ctx.accounts.winner_address.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-        },
+    let backend = IncoBackend::new(
+        &ctx.accounts.inco_lightning_program,
+        &ctx.accounts.caller.to_account_info(),
     );
+    let system_program = ctx.accounts.system_program.to_account_info();
 
-    // Grant permission: allow(handle, grant=true, address)
-    allow(
-        cpi_ctx,
+    // Grant decryption access to the winner for the winning bid handle
+    backend.allow(
+        &ctx.accounts.allowance_account,
+        &ctx.accounts.winner_address,
+        &system_program,
         auction.highest_bid_handle,
-        true,
-        auction.current_leader,
     )?;
 
+    // Optionally grant decryption access to the seller as well
+    let seller_allowed = match ctx.remaining_accounts {
+        [] => false,
+        [seller_allowance_account, seller_address, ..] => {
+            require!(
+                seller_address.key() == auction.seller,
+                ShadowBidError::NotSeller
+            );
+
+            backend.allow(
+                seller_allowance_account,
+                seller_address,
+                &system_program,
+                auction.highest_bid_handle,
+            )?;
+
+            true
+        }
+        _ => return Err(ShadowBidError::InsufficientRemainingAccounts.into()),
+    };
+
     // Set final winner
     auction.winner = auction.current_leader;
-    auction.state = AuctionState::WinnerDetermined;
+    auction.set_state(AuctionState::WinnerDetermined);
+    record_win(&mut ctx.accounts.winner_bid, &ctx.accounts.winner_profile, &clock)?;
 
     emit!(WinnerDetermined {
-        auction: auction.key(),
+        auction: auction_key,
         winner: auction.winner,
         timestamp: clock.unix_timestamp,
     });
 
     msg!("Winner determined: {}", auction.winner);
-    msg!("Decryption permission granted for handle: {}", auction.highest_bid_handle);
+    msg!("Decryption permission granted for handle: {}", { auction.highest_bid_handle });
+    if seller_allowed {
+        msg!("Decryption permission also granted to seller: {}", auction.seller);
+    }
     msg!("Winner can now reveal their winning bid");
 
     Ok(())
 }
+
+/// Commit-reveal finalization: no decryption permission is needed
+fn finalize_revealed(ctx: Context<FinalizeWinner>, clock: &Clock) -> Result<()> {
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+
+    // No revealed bid met the reserve price - cancel the auction
+    if auction.current_leader == Pubkey::default() {
+        auction.set_state(AuctionState::Cancelled);
+
+        emit!(AuctionCancelled {
+            auction: auction_key,
+            seller: auction.seller,
+            reason: "No revealed bid met the reserve price".to_string(),
+            category: CancellationReason::LowDemand,
+            compensation_per_bid: 0,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Auction cancelled - no valid revealed bids");
+        return Ok(());
+    }
+
+    // Set final winner
+    auction.winner = auction.current_leader;
+    auction.set_state(AuctionState::WinnerDetermined);
+    record_win(&mut ctx.accounts.winner_bid, &ctx.accounts.winner_profile, clock)?;
+
+    emit!(WinnerDetermined {
+        auction: auction_key,
+        winner: auction.winner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Winner determined: {}", auction.winner);
+    msg!("Winning amount: {} lamports", { auction.highest_revealed_amount });
+
+    Ok(())
+}
+
+/// Starts the winner's settlement window on their bid and counts the win
+/// in their profile
+fn record_win(winner_bid: &mut Option<Account<Bid>>, winner_profile: &AccountInfo, clock: &Clock) -> Result<()> {
+    let bid = winner_bid.as_mut().ok_or(ShadowBidError::WinnerBidMismatch)?;
+    bid.won_at = clock.unix_timestamp;
+    BidderProfile::update_from(winner_profile, |profile| profile.auctions_won += 1)
+}
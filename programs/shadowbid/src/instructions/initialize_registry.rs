@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::REGISTRY_SEED;
+use crate::state::Registry;
+
+#[derive(Accounts)]
+pub struct InitializeRegistry<'info> {
+    /// Anyone can create the registry once (permissionless)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The global registry to be created
+    #[account(
+        init,
+        payer = payer,
+        space = Registry::SPACE,
+        seeds = [REGISTRY_SEED],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeRegistry>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.auction_count = 0;
+    registry.bump = ctx.bumps.registry;
+
+    msg!("Registry initialized: {}", registry.key());
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::TREASURY_SEED;
+use crate::state::Treasury;
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    /// Anyone can create the treasury once (permissionless)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The global treasury to be created
+    #[account(
+        init,
+        payer = payer,
+        space = Treasury::SPACE,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeTreasury>) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_slashed = 0;
+    treasury.bump = ctx.bumps.treasury;
+
+    msg!("Treasury initialized: {}", treasury.key());
+
+    Ok(())
+}
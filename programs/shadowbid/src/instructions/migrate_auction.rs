@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::events::AccountMigrated;
+use crate::state::{migrate, Auction};
+
+#[derive(Accounts)]
+pub struct MigrateAuction<'info> {
+    /// Anyone can migrate an auction, paying its extra rent (permissionless)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: An Auction in the legacy layout, which doesn't deserialize
+    /// as `Account<Auction>`. Ownership is checked here, the
+    /// discriminator and size by `migrate`
+    #[account(mut, owner = crate::ID)]
+    pub auction: UncheckedAccount<'info>,
+
+    /// System program for the rent top-up
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateAuction>) -> Result<()> {
+    migrate::<Auction>(
+        &ctx.accounts.auction,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+
+    emit!(AccountMigrated {
+        auction: ctx.accounts.auction.key(),
+        account: ctx.accounts.auction.key(),
+        version: Auction::VERSION,
+        payer: ctx.accounts.payer.key(),
+    });
+
+    msg!("Auction {} migrated to version {}", ctx.accounts.auction.key(), Auction::VERSION);
+
+    Ok(())
+}
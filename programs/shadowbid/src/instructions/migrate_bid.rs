@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::events::AccountMigrated;
+use crate::state::{migrate, Bid};
+
+#[derive(Accounts)]
+pub struct MigrateBid<'info> {
+    /// Anyone can migrate a bid, paying its extra rent (permissionless)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: A Bid in the legacy layout, which doesn't deserialize as
+    /// `Account<Bid>`. Ownership is checked here, the discriminator and
+    /// size by `migrate`
+    #[account(mut, owner = crate::ID)]
+    pub bid: UncheckedAccount<'info>,
+
+    /// System program for the rent top-up
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateBid>) -> Result<()> {
+    migrate::<Bid>(
+        &ctx.accounts.bid,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+    let bid = Bid::try_deserialize(&mut &ctx.accounts.bid.try_borrow_data()?[..])?;
+
+    emit!(AccountMigrated {
+        auction: bid.auction,
+        account: ctx.accounts.bid.key(),
+        version: Bid::VERSION,
+        payer: ctx.accounts.payer.key(),
+    });
+
+    msg!("Bid {} migrated to version {}", ctx.accounts.bid.key(), Bid::VERSION);
+
+    Ok(())
+}
@@ -1,15 +1,67 @@
+#![allow(ambiguous_glob_reexports)]
+
 pub mod cancel_auction;
+pub mod cancel_below_reserve;
+pub mod claim_compensation;
+pub mod confirm_delivery;
+pub mod close_auction_metadata;
 pub mod close_bidding;
+pub mod close_reveal;
 pub mod create_auction;
+pub mod create_auction_metadata;
+pub mod declare_default;
 pub mod determine_winner;
+pub mod dispute;
 pub mod finalize_winner;
+pub mod initialize_registry;
+pub mod initialize_treasury;
+pub mod migrate_auction;
+pub mod migrate_bid;
 pub mod place_bid;
+pub mod reclaim_deposit;
+pub mod publish_auction_stats;
+pub mod redeem_receipt;
+pub mod release_escrow;
+pub mod release_seller_bond;
+pub mod reveal_auction_stats;
+pub mod resolve_dispute;
+pub mod reveal_bid;
+pub mod set_delegate;
 pub mod settle_auction;
+pub mod settle_from_deposit;
+pub mod submit_evidence;
+pub mod transfer_authority;
+pub mod update_auction_metadata;
 
 pub use cancel_auction::*;
+pub use cancel_below_reserve::*;
+pub use claim_compensation::*;
+pub use confirm_delivery::*;
+pub use close_auction_metadata::*;
 pub use close_bidding::*;
+pub use close_reveal::*;
 pub use create_auction::*;
+pub use create_auction_metadata::*;
+pub use declare_default::*;
 pub use determine_winner::*;
+pub use dispute::*;
 pub use finalize_winner::*;
+pub use initialize_registry::*;
+pub use initialize_treasury::*;
+pub use migrate_auction::*;
+pub use migrate_bid::*;
 pub use place_bid::*;
+pub use reclaim_deposit::*;
+pub use publish_auction_stats::*;
+pub use redeem_receipt::*;
+pub use release_escrow::*;
+pub use release_seller_bond::*;
+pub use reveal_auction_stats::*;
+pub use resolve_dispute::*;
+pub use reveal_bid::*;
+pub use set_delegate::*;
 pub use settle_auction::*;
+pub use settle_from_deposit::*;
+pub use submit_evidence::*;
+pub use transfer_authority::*;
+pub use update_auction_metadata::*;
@@ -1,13 +1,12 @@
 use anchor_lang::prelude::*;
-use inco_lightning::cpi::accounts::Operation;
-use inco_lightning::cpi::new_euint128;
 use inco_lightning::types::Euint128;
 use inco_lightning::ID as INCO_LIGHTNING_ID;
 
-use crate::constants::BID_SEED;
+use crate::confidential::{ConfidentialBackend, IncoBackend};
+use crate::constants::{BID_SEED, BIDDER_PROFILE_SEED};
 use crate::errors::ShadowBidError;
 use crate::events::{BidPlaced, BidUpdated};
-use crate::state::{Auction, AuctionState, Bid};
+use crate::state::{Auction, AuctionState, Bid, BidderProfile, LoadVersioned};
 
 #[derive(Accounts)]
 pub struct PlaceBid<'info> {
@@ -18,10 +17,11 @@ pub struct PlaceBid<'info> {
     /// The auction to bid on
     #[account(
         mut,
-        constraint = auction.state == AuctionState::Open @ ShadowBidError::AuctionNotOpen,
-        constraint = auction.seller != bidder.key() @ ShadowBidError::SellerCannotBid,
+        constraint = auction.load_versioned()?.state()? == AuctionState::Open @ ShadowBidError::AuctionNotOpen,
+        constraint = auction.load_versioned()?.seller != bidder.key() @ ShadowBidError::SellerCannotBid,
+        constraint = auction.load_versioned()?.arbitrator != bidder.key() @ ShadowBidError::ArbitratorCannotBid,
     )]
-    pub auction: Account<'info, Auction>,
+    pub auction: AccountLoader<'info, Auction>,
 
     /// The bid account (created or updated)
     #[account(
@@ -37,6 +37,16 @@ pub struct PlaceBid<'info> {
     )]
     pub bid: Account<'info, Bid>,
 
+    /// The bidder's history, created with their first bid
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = BidderProfile::SPACE,
+        seeds = [BIDDER_PROFILE_SEED, bidder.key().as_ref()],
+        bump
+    )]
+    pub bidder_profile: Box<Account<'info, BidderProfile>>,
+
     /// Inco Lightning program for encrypted operations
     /// CHECK: Validated by address constraint
     #[account(address = INCO_LIGHTNING_ID)]
@@ -47,33 +57,48 @@ pub struct PlaceBid<'info> {
 }
 
 pub fn handler(ctx: Context<PlaceBid>, ciphertext: Vec<u8>) -> Result<()> {
+    // Check timing constraints
     let clock = Clock::get()?;
-    let auction = &mut ctx.accounts.auction;
-    let bid = &mut ctx.accounts.bid;
+    let is_commit_reveal = {
+        let auction = ctx.accounts.auction.load_versioned()?;
+        require!(
+            auction.is_bidding_open(clock.unix_timestamp),
+            ShadowBidError::BiddingEnded
+        );
+        require!(
+            ctx.accounts.bidder_profile.is_eligible(auction.max_bidder_defaults()),
+            ShadowBidError::TooManyDefaults
+        );
+        auction.is_commit_reveal()
+    };
+
+    let profile = &mut ctx.accounts.bidder_profile;
+    profile.bidder = ctx.accounts.bidder.key();
+    profile.bump = ctx.bumps.bidder_profile;
+
+    if is_commit_reveal {
+        return place_commitment(ctx, ciphertext, &clock);
+    }
 
-    // Check timing constraints
-    require!(
-        auction.is_bidding_open(clock.unix_timestamp),
-        ShadowBidError::BiddingEnded
-    );
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+    let bid = &mut ctx.accounts.bid;
 
     // Check if this is a new bid or an update
     let is_new_bid = bid.encrypted_amount == 0;
 
     // Create encrypted handle from ciphertext via Inco CPI
-    let cpi_ctx = CpiContext::new(
-        ctx.accounts.inco_lightning_program.to_account_info(),
-        Operation {
-            signer: ctx.accounts.bidder.to_account_info(),
-        },
+    let backend = IncoBackend::new(
+        &ctx.accounts.inco_lightning_program,
+        &ctx.accounts.bidder.to_account_info(),
     );
 
     // new_euint128 creates an encrypted handle from the ciphertext
     // The ciphertext was encrypted client-side using Inco's public key
-    let encrypted_amount: Euint128 = new_euint128(cpi_ctx, ciphertext, 0)?;
+    let encrypted_amount: Euint128 = backend.new_euint128(ciphertext)?;
 
     // Store bid data
-    bid.auction = auction.key();
+    bid.auction = auction_key;
     bid.bidder = ctx.accounts.bidder.key();
     bid.encrypted_amount = encrypted_amount.0;
     bid.updated_at = clock.unix_timestamp;
@@ -82,6 +107,11 @@ pub fn handler(ctx: Context<PlaceBid>, ciphertext: Vec<u8>) -> Result<()> {
     if is_new_bid {
         bid.created_at = clock.unix_timestamp;
         bid.bump = ctx.bumps.bid;
+        bid.version = Bid::VERSION;
+        ctx.accounts.bidder_profile.bids_placed += 1;
+
+        // Lock the deposit the seller can settle from if this bid wins
+        lock_collateral(&ctx.accounts.bidder, bid, &ctx.accounts.system_program, auction.bid_collateral)?;
         
         // Increment bid count
         auction.bid_count = auction.bid_count.checked_add(1).unwrap();
@@ -93,22 +123,31 @@ pub fn handler(ctx: Context<PlaceBid>, ciphertext: Vec<u8>) -> Result<()> {
         }
 
         emit!(BidPlaced {
-            auction: auction.key(),
+            auction: auction_key,
             bidder: bid.bidder,
             bid_number: auction.bid_count,
             timestamp: clock.unix_timestamp,
         });
 
-        msg!("New bid placed on auction {}", auction.key());
-        msg!("Bid #{} by {}", auction.bid_count, bid.bidder);
+        msg!("New bid placed on auction {}", auction_key);
+        msg!("Bid #{} by {}", { auction.bid_count }, bid.bidder);
+        if bid.collateral > 0 {
+            msg!("Deposit locked: {} lamports", bid.collateral);
+        }
     } else {
+        // The first bid seeded the leader's handle, which must follow
+        // that bidder's updates or a withdrawn amount could still win
+        if auction.current_leader == bid.bidder {
+            auction.highest_bid_handle = encrypted_amount.0;
+        }
+
         emit!(BidUpdated {
-            auction: auction.key(),
+            auction: auction_key,
             bidder: bid.bidder,
             timestamp: clock.unix_timestamp,
         });
 
-        msg!("Bid updated on auction {}", auction.key());
+        msg!("Bid updated on auction {}", auction_key);
         msg!("Bidder: {}", bid.bidder);
     }
 
@@ -116,3 +155,89 @@ pub fn handler(ctx: Context<PlaceBid>, ciphertext: Vec<u8>) -> Result<()> {
 
     Ok(())
 }
+
+/// Commit-reveal bidding: `commitment` is the 32-byte
+/// `Bid::commitment_hash(amount, salt, bidder)`. New bids also deposit
+/// the auction's bid collateral into the bid account.
+fn place_commitment(ctx: Context<PlaceBid>, commitment: Vec<u8>, clock: &Clock) -> Result<()> {
+    let commitment: [u8; 32] = commitment
+        .try_into()
+        .map_err(|_| ShadowBidError::InvalidCommitment)?;
+    // Zero is what a fresh bid account holds, not a hash anyone can open
+    require!(commitment != [0u8; 32], ShadowBidError::InvalidCommitment);
+
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+    let bid = &mut ctx.accounts.bid;
+
+    let is_new_bid = bid.created_at == 0;
+
+    bid.auction = auction_key;
+    bid.bidder = ctx.accounts.bidder.key();
+    bid.commitment = commitment;
+    bid.updated_at = clock.unix_timestamp;
+    bid.processed = false;
+
+    if is_new_bid {
+        bid.created_at = clock.unix_timestamp;
+        bid.bump = ctx.bumps.bid;
+        bid.version = Bid::VERSION;
+
+        // Lock the collateral in the bid account until the bid is revealed
+        lock_collateral(&ctx.accounts.bidder, bid, &ctx.accounts.system_program, auction.bid_collateral)?;
+        ctx.accounts.bidder_profile.bids_placed += 1;
+
+        // Increment bid count
+        auction.bid_count = auction.bid_count.checked_add(1).unwrap();
+
+        emit!(BidPlaced {
+            auction: auction_key,
+            bidder: bid.bidder,
+            bid_number: auction.bid_count,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("New commitment placed on auction {}", auction_key);
+        msg!("Bid #{} by {}", { auction.bid_count }, bid.bidder);
+        msg!("Collateral locked: {} lamports", bid.collateral);
+    } else {
+        emit!(BidUpdated {
+            auction: auction_key,
+            bidder: bid.bidder,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Commitment updated on auction {}", auction_key);
+        msg!("Bidder: {}", bid.bidder);
+    }
+
+    Ok(())
+}
+
+/// Moves `amount` from the bidder into their bid account, as the
+/// commit-reveal collateral or the sealed bid's deposit
+fn lock_collateral<'info>(
+    bidder: &Signer<'info>,
+    bid: &mut Account<'info, Bid>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    if amount > 0 {
+        let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+            bidder.key,
+            &bid.key(),
+            amount,
+        );
+
+        anchor_lang::solana_program::program::invoke(
+            &transfer_ix,
+            &[
+                bidder.to_account_info(),
+                bid.to_account_info(),
+                system_program.to_account_info(),
+            ],
+        )?;
+    }
+    bid.collateral = amount;
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+
+use crate::confidential::{handle_to_bytes, parse_plaintext_amount, ConfidentialBackend, IncoBackend};
+use crate::errors::ShadowBidError;
+use crate::events::AuctionStatsRevealed;
+use crate::state::{Auction, AuctionState, LoadVersioned};
+
+/// This instruction:
+/// 1. Verifies the attested decryption of the three statistics handles
+/// 2. Emits the plaintext aggregates through AuctionStatsRevealed
+///
+/// The handles are read from the auction account, so only the
+/// aggregates accumulated during determine_winner can be published.
+/// The transaction must include the Ed25519 signature verification
+/// instructions from the Inco SDK, in the order sum, lowest, count.
+#[derive(Accounts)]
+pub struct PublishAuctionStats<'info> {
+    /// The seller publishing the statistics
+    #[account(
+        mut,
+        constraint = seller.key() == auction.load_versioned()?.seller @ ShadowBidError::NotSeller,
+    )]
+    pub seller: Signer<'info>,
+
+    /// The settled auction
+    #[account(
+        constraint = auction.load_versioned()?.state()? == AuctionState::Settled @ ShadowBidError::WinnerNotDetermined,
+        constraint = auction.load_versioned()?.has_stats() @ ShadowBidError::StatsNotAvailable,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// Instructions sysvar for Ed25519 signature verification
+    /// CHECK: Validated by address constraint
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// Inco Lightning program for attestation verification
+    /// CHECK: Validated by address constraint
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<PublishAuctionStats>, plaintext_bytes: Vec<Vec<u8>>) -> Result<()> {
+    let clock = Clock::get()?;
+    let auction = ctx.accounts.auction.load_versioned()?;
+
+    require!(
+        plaintext_bytes.len() == 3,
+        ShadowBidError::InvalidDecryptionProof
+    );
+
+    let handles = vec![
+        handle_to_bytes(auction.bid_sum_handle),
+        handle_to_bytes(auction.lowest_bid_handle),
+        handle_to_bytes(auction.above_reserve_count_handle),
+    ];
+
+    // Verify the Ed25519 attestation signatures from Inco
+    let backend = IncoBackend::new(
+        &ctx.accounts.inco_lightning_program,
+        &ctx.accounts.seller.to_account_info(),
+    );
+    backend.verify_decryption(&ctx.accounts.instructions, handles, plaintext_bytes.clone())?;
+
+    let total_amount = parse_plaintext_amount(&plaintext_bytes[0])?;
+    let lowest_amount = parse_plaintext_amount(&plaintext_bytes[1])?;
+    let bids_above_reserve = u32::try_from(parse_plaintext_amount(&plaintext_bytes[2])?)
+        .map_err(|_| ShadowBidError::InvalidDecryptionProof)?;
+
+    let average_amount = total_amount
+        .checked_div(auction.bid_count as u64)
+        .ok_or(ShadowBidError::NoBidsPlaced)?;
+    let spread = auction.winning_amount.saturating_sub(lowest_amount);
+
+    emit!(AuctionStatsRevealed {
+        auction: ctx.accounts.auction.key(),
+        bid_count: auction.bid_count,
+        total_amount,
+        average_amount,
+        lowest_amount,
+        spread,
+        bids_above_reserve,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Auction statistics published for {}", ctx.accounts.auction.key());
+    msg!("Average bid: {} lamports", average_amount);
+    msg!("Spread: {} lamports", spread);
+
+    Ok(())
+}
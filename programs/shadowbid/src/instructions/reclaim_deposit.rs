@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::BID_SEED;
+use crate::errors::ShadowBidError;
+use crate::events::DepositReclaimed;
+use crate::state::{Auction, AuctionState, Bid, LoadVersioned};
+
+/// This instruction (sealed auctions with bid deposits only):
+/// 1. Checks the bid's deposit no longer backs a settlement
+/// 2. Returns the deposit to the bidder
+///
+/// Losing bidders reclaim once the winner is determined. The winner's
+/// deposit stays locked for the seller to settle from until the auction
+/// is settled by the winner, cancelled or declared in default.
+#[derive(Accounts)]
+pub struct ReclaimDeposit<'info> {
+    /// The bidder reclaiming their deposit
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// The auction the bid was placed on
+    #[account(
+        constraint = auction.load_versioned()?.takes_bid_deposits() @ ShadowBidError::NoBidDeposit,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// The bidder's bid account, holding the deposit
+    #[account(
+        mut,
+        seeds = [
+            BID_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref()
+        ],
+        bump = bid.bump,
+        constraint = bid.auction == auction.key() @ ShadowBidError::BidAuctionMismatch,
+        constraint = bid.collateral > 0 @ ShadowBidError::NothingToClaim,
+    )]
+    pub bid: Account<'info, Bid>,
+}
+
+pub fn handler(ctx: Context<ReclaimDeposit>) -> Result<()> {
+    let auction_key = ctx.accounts.auction.key();
+    let auction = ctx.accounts.auction.load_versioned()?;
+    let bid = &mut ctx.accounts.bid;
+
+    match auction.state()? {
+        AuctionState::Open | AuctionState::Closed => {
+            return err!(ShadowBidError::DepositLocked);
+        }
+        AuctionState::WinnerDetermined => {
+            require!(bid.bidder != auction.winner, ShadowBidError::DepositLocked);
+        }
+        _ => {}
+    }
+
+    let amount = bid.collateral;
+    bid.collateral = 0;
+    **bid.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.bidder.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    emit!(DepositReclaimed {
+        auction: auction_key,
+        bidder: bid.bidder,
+        amount,
+    });
+
+    msg!("Deposit returned to {}: {} lamports", bid.bidder, amount);
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::Field;
+use anchor_spl::token_interface::{token_metadata_update_field, TokenMetadataUpdateField};
+
+use crate::constants::{AUCTION_AUTHORITY_SEED, RECEIPT_AUTHORITY_SEED, RECEIPT_SEED};
+use crate::errors::ShadowBidError;
+use crate::events::ReceiptRedeemed;
+use crate::state::{Auction, AuctionAuthority, AuctionState, LoadVersioned, Receipt};
+
+/// This instruction:
+/// 1. Lets the auction's authority mark its receipt fulfilled
+/// 2. Records the redemption in the Receipt and the NFT's metadata
+#[derive(Accounts)]
+pub struct RedeemReceipt<'info> {
+    /// The authority who fulfilled the purchase; checked by the handler
+    pub authority: Signer<'info>,
+
+    /// The auction the receipt proves
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The auction's authority record. Seeds are checked; without
+    /// one, `AuctionAuthority::check` requires the seller
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+
+    /// The receipt record
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, auction.key().as_ref()],
+        bump = receipt.bump,
+    )]
+    pub receipt: Account<'info, Receipt>,
+
+    /// CHECK: The receipt NFT mint holding the metadata; validated against
+    /// receipt.mint
+    #[account(mut, address = receipt.mint)]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    /// CHECK: PDA holding the metadata authority of every receipt
+    #[account(seeds = [RECEIPT_AUTHORITY_SEED], bump)]
+    pub receipt_authority: UncheckedAccount<'info>,
+
+    /// Token-2022, owner of the receipt mint
+    pub token_program: Program<'info, Token2022>,
+}
+
+pub fn handler(ctx: Context<RedeemReceipt>) -> Result<()> {
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+
+    AuctionAuthority::check(
+        &ctx.accounts.auction_authority,
+        &ctx.accounts.auction.load_versioned()?.seller,
+        ctx.accounts.authority.key,
+        None,
+    )?;
+
+    // A disputed payment may still be refunded, so the purchase isn't
+    // settled yet
+    require!(
+        ctx.accounts.auction.load_versioned()?.state()? != AuctionState::Disputed,
+        ShadowBidError::ReceiptDisputed
+    );
+    require!(
+        !ctx.accounts.receipt.is_redeemed(),
+        ShadowBidError::ReceiptAlreadyRedeemed
+    );
+
+    token_metadata_update_field(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TokenMetadataUpdateField {
+                program_id: ctx.accounts.token_program.to_account_info(),
+                metadata: ctx.accounts.receipt_mint.to_account_info(),
+                update_authority: ctx.accounts.receipt_authority.to_account_info(),
+            },
+            &[&[RECEIPT_AUTHORITY_SEED, &[ctx.bumps.receipt_authority]]],
+        ),
+        Field::Key(Receipt::FULFILLED_FIELD.to_string()),
+        true.to_string(),
+    )?;
+
+    let receipt = &mut ctx.accounts.receipt;
+    receipt.redeemed_at = clock.unix_timestamp;
+
+    emit!(ReceiptRedeemed {
+        auction: auction_key,
+        mint: receipt.mint,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Receipt redeemed: {}", receipt.mint);
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::SELLER_BOND_SEED;
+use crate::errors::ShadowBidError;
+use crate::events::BondReleased;
+use crate::state::{Auction, AuctionState, LoadVersioned, SellerBond};
+
+/// This instruction returns the seller bond, with the vault's rent, once
+/// the auction has settled or its winner defaulted, or after it cancelled
+/// itself because bidding or reveals ended without a sale. A seller's own
+/// cancellation releases or slashes the bond in `cancel_auction`
+#[derive(Accounts)]
+pub struct ReleaseSellerBond<'info> {
+    /// The seller whose bond is released
+    #[account(
+        mut,
+        constraint = seller.key() == auction.load_versioned()?.seller @ ShadowBidError::NotSeller,
+    )]
+    pub seller: Signer<'info>,
+
+    /// The settled, defaulted or cancelled auction
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// The vault holding the bond, closed to the seller. A vault left on
+    /// a cancelled auction was never slashed unless bidders still have
+    /// shares to claim
+    #[account(
+        mut,
+        close = seller,
+        constraint = match auction.load_versioned()?.state()? {
+            AuctionState::Settled | AuctionState::Defaulted => true,
+            AuctionState::Cancelled => seller_bond.compensation_per_bid == 0,
+            _ => false,
+        } @ ShadowBidError::BondLocked,
+        seeds = [SELLER_BOND_SEED, auction.key().as_ref()],
+        bump = seller_bond.bump,
+    )]
+    pub seller_bond: Account<'info, SellerBond>,
+}
+
+pub fn handler(ctx: Context<ReleaseSellerBond>) -> Result<()> {
+    let clock = Clock::get()?;
+    let amount = ctx.accounts.seller_bond.amount;
+
+    emit!(BondReleased {
+        auction: ctx.accounts.auction.key(),
+        seller: ctx.accounts.seller.key(),
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Seller bond released: {} lamports", amount);
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{BPS_DENOMINATOR, ESCROW_SEED, HOOK_SEED, SELLER_BOND_SEED, SELLER_PROFILE_SEED};
+use crate::errors::ShadowBidError;
+use crate::events::{AuctionSettled, BondReleased, BondSlashed, DisputeResolved};
+use crate::hook::{check_hook, AuctionCompletion, AuctionOutcome};
+use crate::state::{Auction, AuctionHook, AuctionState, Escrow, LoadVersioned, SellerBond, SellerProfile};
+
+/// This instruction:
+/// 1. Lets the arbitrator rule on a disputed escrow
+/// 2. Pays the arbitration fee, then splits the rest between the winner
+///    and the beneficiary by `buyer_bps`
+/// 3. Marks auction as settled and returns the escrow's rent to the winner
+/// 4. Counts a ruling for the winner as a default in the seller's profile
+/// 5. Pays the winner the same share of a seller bond, returning the rest
+///    of it with the vault's rent to the seller
+/// 6. Invokes the auction's hook program, if it has one, before any
+///    payment moves: a full refund is reported as `AuctionOutcome::Refunded`
+///
+/// The arbitrator signs directly, or through its multisig program
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    /// The arbitrator named at creation, receives the fee
+    #[account(
+        mut,
+        constraint = arbitrator.key() == auction.load_versioned()?.arbitrator @ ShadowBidError::NotArbitrator,
+    )]
+    pub arbitrator: Signer<'info>,
+
+    /// The disputed auction
+    #[account(
+        mut,
+        constraint = auction.load_versioned()?.state()? == AuctionState::Disputed @ ShadowBidError::NotDisputed,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The winner, refunded their share and the escrow's rent;
+    /// validated against auction.winner
+    #[account(
+        mut,
+        constraint = winner.key() == auction.load_versioned()?.winner @ ShadowBidError::NotWinner,
+    )]
+    pub winner: UncheckedAccount<'info>,
+
+    /// CHECK: The seller, whose profile records the ruling and who gets
+    /// back what the ruling leaves of a bond, passed to the hook;
+    /// validated against auction.seller
+    #[account(
+        mut,
+        constraint = seller.key() == auction.load_versioned()?.seller @ ShadowBidError::NotSeller,
+    )]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: The seller's profile. Seeds are checked; sellers without
+    /// one are skipped by `SellerProfile::update_from`
+    #[account(
+        mut,
+        seeds = [SELLER_PROFILE_SEED, seller.key().as_ref()],
+        bump
+    )]
+    pub seller_profile: UncheckedAccount<'info>,
+
+    /// CHECK: The beneficiary receiving the seller's share; validated
+    /// against escrow.beneficiary
+    #[account(mut, address = escrow.beneficiary @ ShadowBidError::NotBeneficiary)]
+    pub beneficiary: UncheckedAccount<'info>,
+
+    /// The escrow holding the payment, closed to the winner
+    #[account(
+        mut,
+        close = winner,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// The auction's seller bond vault, required for bonded auctions
+    #[account(
+        mut,
+        seeds = [SELLER_BOND_SEED, auction.key().as_ref()],
+        bump = seller_bond.bump,
+    )]
+    pub seller_bond: Option<Account<'info, SellerBond>>,
+
+    /// The auction's hook, passed only for auctions created with one
+    /// (and optional when its failures are ignored)
+    #[account(
+        seeds = [HOOK_SEED, auction.key().as_ref()],
+        bump = hook.bump,
+    )]
+    pub hook: Option<Account<'info, AuctionHook>>,
+
+    /// CHECK: The hook program, checked against `hook.program` by the
+    /// handler
+    pub hook_program: Option<UncheckedAccount<'info>>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, ResolveDispute<'info>>,
+    buyer_bps: u16,
+) -> Result<()> {
+    require!(buyer_bps <= BPS_DENOMINATOR, ShadowBidError::InvalidBuyerShare);
+
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+    require!(
+        ctx.accounts.seller_bond.is_some() == auction.is_bonded(),
+        ShadowBidError::SellerBondMismatch
+    );
+
+    // Unless its failures are ignored, the arbitrator can't skip the hook
+    let hook = check_hook(
+        auction.hook_mode()?,
+        ctx.accounts.hook.as_deref(),
+        ctx.accounts.hook_program.as_deref(),
+    )?;
+
+    let amount = ctx.accounts.escrow.amount;
+    let fee = bps_of(amount, auction.arbitration_fee_bps);
+    let to_buyer = bps_of(amount - fee, buyer_bps);
+    let to_seller = amount - fee - to_buyer;
+    auction.set_state(AuctionState::Settled);
+
+    let completion = AuctionCompletion {
+        auction: auction_key,
+        auction_id: auction.auction_id,
+        seller: auction.seller,
+        winner: auction.winner,
+        amount: to_seller,
+        outcome: if to_seller > 0 { AuctionOutcome::Settled } else { AuctionOutcome::Refunded },
+    };
+    let arbitrator = auction.arbitrator;
+    let bump = auction.bump;
+
+    // The hook receives the auction account, which can't be borrowed
+    // across the CPI, and runs before the escrow pays out (see
+    // `confirm_delivery`)
+    drop(auction);
+    hook.run(
+        &ctx.accounts.auction.to_account_info(),
+        &ctx.accounts.winner.to_account_info(),
+        &ctx.accounts.seller.to_account_info(),
+        ctx.remaining_accounts,
+        &completion,
+        bump,
+    )?;
+
+    **ctx.accounts.escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.arbitrator.to_account_info().try_borrow_mut_lamports()? += fee;
+    **ctx.accounts.winner.to_account_info().try_borrow_mut_lamports()? += to_buyer;
+    **ctx.accounts.beneficiary.to_account_info().try_borrow_mut_lamports()? += to_seller;
+
+    // Any refund means the seller didn't deliver what was sold
+    if to_buyer > 0 {
+        SellerProfile::update_from(&ctx.accounts.seller_profile, |profile| {
+            profile.record_default(to_buyer)
+        })?;
+    }
+
+    // The bond backs the seller's delivery too, so the winner takes the
+    // share of it the ruling refunds
+    if let Some(vault) = &mut ctx.accounts.seller_bond {
+        let slashed = bps_of(vault.amount, buyer_bps);
+        if slashed > 0 {
+            vault.sub_lamports(slashed)?;
+            ctx.accounts.winner.add_lamports(slashed)?;
+            vault.amount -= slashed;
+
+            emit!(BondSlashed {
+                auction: auction_key,
+                seller: completion.seller,
+                to_bidders: slashed,
+                to_treasury: 0,
+                timestamp: clock.unix_timestamp,
+            });
+            msg!("Seller bond slashed: {} lamports to the winner", slashed);
+        }
+        if vault.amount > 0 {
+            emit!(BondReleased {
+                auction: auction_key,
+                seller: completion.seller,
+                amount: vault.amount,
+                timestamp: clock.unix_timestamp,
+            });
+            msg!("Seller bond released: {} lamports", vault.amount);
+        }
+        vault.close(ctx.accounts.seller.to_account_info())?;
+    }
+
+    emit!(DisputeResolved {
+        auction: auction_key,
+        arbitrator,
+        to_buyer,
+        to_seller,
+        fee,
+        timestamp: clock.unix_timestamp,
+    });
+    emit!(AuctionSettled {
+        auction: auction_key,
+        winner: completion.winner,
+        winning_amount: amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Dispute resolved: {}", auction_key);
+    msg!("Buyer: {} lamports, seller: {} lamports, fee: {} lamports", to_buyer, to_seller, fee);
+
+    Ok(())
+}
+
+/// `bps` basis points of `amount`, rounded down
+fn bps_of(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
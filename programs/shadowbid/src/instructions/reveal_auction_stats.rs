@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+
+use crate::confidential::{ConfidentialBackend, IncoBackend};
+use crate::errors::ShadowBidError;
+use crate::state::{Auction, AuctionState, LoadVersioned};
+
+/// This instruction:
+/// 1. Verifies the auction has been settled
+/// 2. Grants the seller decryption permission on the encrypted
+///    statistics (bid sum, lowest bid, bids at or above reserve)
+///
+/// Individual bid handles are never allowed, so the seller only
+/// learns the aggregates.
+#[derive(Accounts)]
+pub struct RevealAuctionStats<'info> {
+    /// The seller requesting the statistics
+    #[account(
+        mut,
+        constraint = seller.key() == auction.load_versioned()?.seller @ ShadowBidError::NotSeller,
+    )]
+    pub seller: Signer<'info>,
+
+    /// The settled auction
+    #[account(
+        constraint = auction.load_versioned()?.state()? == AuctionState::Settled @ ShadowBidError::WinnerNotDetermined,
+        constraint = auction.load_versioned()?.has_stats() @ ShadowBidError::StatsNotAvailable,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: Allowance account PDA for the bid sum handle
+    /// Seeds: [bid_sum_handle, seller]
+    #[account(mut)]
+    pub sum_allowance_account: AccountInfo<'info>,
+
+    /// CHECK: Allowance account PDA for the lowest bid handle
+    /// Seeds: [lowest_bid_handle, seller]
+    #[account(mut)]
+    pub lowest_allowance_account: AccountInfo<'info>,
+
+    /// CHECK: Allowance account PDA for the above-reserve count handle
+    /// Seeds: [above_reserve_count_handle, seller]
+    #[account(mut)]
+    pub count_allowance_account: AccountInfo<'info>,
+
+    /// Inco Lightning program for allowance
+    /// CHECK: Validated by address constraint
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RevealAuctionStats>) -> Result<()> {
+    let auction = ctx.accounts.auction.load_versioned()?;
+    let seller = ctx.accounts.seller.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let backend = IncoBackend::new(&ctx.accounts.inco_lightning_program, &seller);
+
+    let grants = [
+        (&ctx.accounts.sum_allowance_account, auction.bid_sum_handle),
+        (&ctx.accounts.lowest_allowance_account, auction.lowest_bid_handle),
+        (&ctx.accounts.count_allowance_account, auction.above_reserve_count_handle),
+    ];
+
+    for (allowance_account, handle) in grants {
+        backend.allow(allowance_account, &seller, &system_program, handle)?;
+    }
+
+    msg!("Auction statistics revealed to seller: {}", auction.seller);
+
+    Ok(())
+}
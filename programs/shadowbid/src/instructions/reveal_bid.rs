@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::BID_SEED;
+use crate::errors::ShadowBidError;
+use crate::events::BidRevealed;
+use crate::state::{Auction, AuctionState, Bid, LoadVersioned};
+
+/// This instruction (commit-reveal auctions only):
+/// 1. Checks the revealed amount and salt against the bid's commitment
+/// 2. Records the plaintext amount for winner determination
+/// 3. Returns the bidder's collateral
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    /// The bidder revealing their bid
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// The auction in its reveal period
+    #[account(
+        mut,
+        constraint = auction.load_versioned()?.is_commit_reveal() @ ShadowBidError::WrongAuctionMode,
+        constraint = auction.load_versioned()?.state()? == AuctionState::Revealing @ ShadowBidError::AuctionNotRevealing,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// The bidder's bid account
+    #[account(
+        mut,
+        seeds = [
+            BID_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref()
+        ],
+        bump = bid.bump,
+        constraint = bid.auction == auction.key() @ ShadowBidError::BidAuctionMismatch,
+        constraint = !bid.revealed @ ShadowBidError::BidAlreadyRevealed,
+    )]
+    pub bid: Account<'info, Bid>,
+}
+
+pub fn handler(ctx: Context<RevealBid>, amount: u64, salt: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+    let bid = &mut ctx.accounts.bid;
+
+    require!(
+        auction.is_reveal_open(clock.unix_timestamp),
+        ShadowBidError::RevealEnded
+    );
+
+    require!(
+        Bid::commitment_hash(amount, &salt, &bid.bidder) == bid.commitment,
+        ShadowBidError::InvalidCommitment
+    );
+
+    bid.revealed_amount = amount;
+    bid.revealed = true;
+    auction.bids_revealed = auction.bids_revealed.checked_add(1).unwrap();
+
+    // Return the collateral now that the bid is revealed
+    let collateral = bid.collateral;
+    if collateral > 0 {
+        bid.collateral = 0;
+        **bid.to_account_info().try_borrow_mut_lamports()? -= collateral;
+        **ctx.accounts.bidder.to_account_info().try_borrow_mut_lamports()? += collateral;
+    }
+
+    emit!(BidRevealed {
+        auction: auction_key,
+        bidder: bid.bidder,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Bid revealed by {}: {} lamports", bid.bidder, amount);
+    msg!("Collateral returned: {} lamports", collateral);
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::AUCTION_AUTHORITY_SEED;
+use crate::events::DelegateUpdated;
+use crate::state::{Auction, AuctionAuthority, LoadVersioned};
+
+/// This instruction grants a delegate exactly the given `Permission`
+/// bits, or revokes it with none (authority only; the seller, for an
+/// auction without a record)
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    /// The authority, paying for the record if the auction has none yet
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The auction the delegate acts on
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// The auction's authority record, created with the seller in both
+    /// roles if the auction has none yet
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = AuctionAuthority::SPACE,
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_authority: Account<'info, AuctionAuthority>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetDelegate>, delegate: Pubkey, permissions: u8) -> Result<()> {
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+    let seller = ctx.accounts.auction.load_versioned()?.seller;
+    let record = &mut ctx.accounts.auction_authority;
+    record.claim(auction_key, seller, ctx.bumps.auction_authority, ctx.accounts.authority.key)?;
+    record.set_delegate(delegate, permissions)?;
+
+    emit!(DelegateUpdated {
+        auction: auction_key,
+        delegate,
+        permissions,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Delegate {} permissions: {:#04b}", delegate, permissions);
+
+    Ok(())
+}
@@ -1,43 +1,92 @@
 use anchor_lang::prelude::*;
-use inco_lightning::cpi::accounts::VerifySignature;
-use inco_lightning::cpi::is_validsignature;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::{Field, TokenMetadata};
+use anchor_spl::token_interface::{
+    mint_to, token_metadata_initialize, token_metadata_update_field, Mint, MintTo, TokenAccount,
+    TokenMetadataInitialize, TokenMetadataUpdateField,
+};
 use inco_lightning::ID as INCO_LIGHTNING_ID;
 
+use crate::callback::{check_settlement_callback, run_settlement_callback, SettlementResult};
+use crate::confidential::{handle_to_bytes, parse_plaintext_amount, ConfidentialBackend, IncoBackend};
+use crate::constants::{AUCTION_AUTHORITY_SEED, ESCROW_SEED, SETTLEMENT_CALLBACK_SEED, HOOK_SEED, RECEIPT_AUTHORITY_SEED, RECEIPT_MINT_SEED, RECEIPT_SEED, RECEIPT_SYMBOL, SELLER_PROFILE_SEED, BIDDER_PROFILE_SEED};
 use crate::errors::ShadowBidError;
-use crate::events::AuctionSettled;
-use crate::state::{Auction, AuctionState};
+use crate::events::{AuctionSettled, PaymentEscrowed, ReceiptMinted};
+use crate::hook::{check_hook, AuctionCompletion, AuctionOutcome, HookCall};
+use crate::state::{Auction, AuctionAuthority, AuctionHook, AuctionState, BidderProfile, Escrow, LoadVersioned, Receipt, SellerProfile, SettlementCallback};
  
 /// This instruction:
 /// 1. Verifies the attested decryption proof from Inco
-/// 2. Transfers payment from winner to seller
-/// 3. Marks auction as settled
+/// 2. Transfers payment from winner to the beneficiary, or into escrow
+/// 3. Mints the receipt NFT to the winner, if the auction issues one
+/// 4. Marks auction as settled, or awaiting delivery
+/// 5. Calls back the seller's program, if the auction has a settlement
+///    callback
+/// 6. Invokes the auction's hook program, if it has one and was paid
+///    directly
 /// 
 /// The transaction must include Ed25519 signature verification instructions
-/// from the Inco SDK before calling this instruction.
+/// from the Inco SDK before calling this instruction. Commit-reveal auctions
+/// settle at the revealed amount and ignore the handle and plaintext.
 #[derive(Accounts)]
 pub struct SettleAuction<'info> {
     /// The winner settling the auction
     #[account(
         mut,
-        constraint = winner.key() == auction.winner @ ShadowBidError::NotWinner,
+        constraint = winner.key() == auction.load_versioned()?.winner @ ShadowBidError::NotWinner,
     )]
     pub winner: Signer<'info>,
 
     /// The auction to settle
     #[account(
         mut,
-        constraint = auction.state == AuctionState::WinnerDetermined @ ShadowBidError::WinnerNotDetermined,
+        constraint = auction.load_versioned()?.state()? == AuctionState::WinnerDetermined @ ShadowBidError::WinnerNotDetermined,
     )]
-    pub auction: Account<'info, Auction>,
+    pub auction: AccountLoader<'info, Auction>,
 
-    /// The seller receiving payment
+    /// The seller, credited with the sale and passed to the hook
     /// CHECK: Validated against auction.seller
     #[account(
         mut,
-        constraint = seller.key() == auction.seller @ ShadowBidError::NotSeller,
+        constraint = seller.key() == auction.load_versioned()?.seller @ ShadowBidError::NotSeller,
     )]
     pub seller: AccountInfo<'info>,
 
+    /// CHECK: The seller's profile, credited with the sale. Seeds are
+    /// checked; sellers without one are skipped by
+    /// `SellerProfile::update_from`
+    #[account(
+        mut,
+        seeds = [SELLER_PROFILE_SEED, seller.key().as_ref()],
+        bump
+    )]
+    pub seller_profile: UncheckedAccount<'info>,
+
+    /// CHECK: The winner's profile, credited with the settlement. Seeds
+    /// are checked; winners without one are skipped by
+    /// `BidderProfile::update_from`
+    #[account(
+        mut,
+        seeds = [BIDDER_PROFILE_SEED, winner.key().as_ref()],
+        bump
+    )]
+    pub winner_profile: UncheckedAccount<'info>,
+
+    /// CHECK: The auction's authority record. Seeds are checked; without
+    /// one, the seller is the beneficiary
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The beneficiary receiving payment (or named in the escrow);
+    /// checked against the authority record by the handler
+    #[account(mut)]
+    pub beneficiary: UncheckedAccount<'info>,
+
     /// Instructions sysvar for Ed25519 signature verification
     /// CHECK: Validated by address constraint
     #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
@@ -48,49 +97,126 @@ pub struct SettleAuction<'info> {
     #[account(address = INCO_LIGHTNING_ID)]
     pub inco_lightning_program: AccountInfo<'info>,
 
+    /// The escrow holding the payment, passed only for escrowed auctions
+    #[account(
+        init,
+        payer = winner,
+        space = Escrow::SPACE,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub escrow: Option<Account<'info, Escrow>>,
+
+    /// The auction's settlement callback, passed only for auctions
+    /// created with one
+    #[account(
+        seeds = [SETTLEMENT_CALLBACK_SEED, auction.key().as_ref()],
+        bump = settlement_callback.bump,
+    )]
+    pub settlement_callback: Option<Account<'info, SettlementCallback>>,
+
+    /// CHECK: The program called back, checked against
+    /// `settlement_callback.program` by the handler
+    pub callback_program: Option<UncheckedAccount<'info>>,
+
+    /// The auction's hook, passed only for auctions created with one
+    /// (and optional when its failures are ignored)
+    #[account(
+        seeds = [HOOK_SEED, auction.key().as_ref()],
+        bump = hook.bump,
+    )]
+    pub hook: Option<Account<'info, AuctionHook>>,
+
+    /// CHECK: The hook program, checked against `hook.program` by the
+    /// handler
+    pub hook_program: Option<UncheckedAccount<'info>>,
+
+    /// The receipt record, passed only for auctions issuing receipts
+    #[account(
+        init,
+        payer = winner,
+        space = Receipt::SPACE,
+        seeds = [RECEIPT_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub receipt: Option<Account<'info, Receipt>>,
+
+    /// The receipt NFT mint, carrying its own metadata, passed with
+    /// `receipt`
+    #[account(
+        init,
+        payer = winner,
+        seeds = [RECEIPT_MINT_SEED, auction.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = receipt_authority,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = receipt_authority,
+        extensions::metadata_pointer::metadata_address = receipt_mint,
+    )]
+    pub receipt_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// The winner's token account for the receipt, passed with `receipt`
+    #[account(
+        init,
+        payer = winner,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = winner,
+        associated_token::token_program = token_program,
+    )]
+    pub receipt_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: PDA holding the mint and metadata authority of every
+    /// receipt, passed with `receipt`
+    #[account(seeds = [RECEIPT_AUTHORITY_SEED], bump)]
+    pub receipt_authority: Option<UncheckedAccount<'info>>,
+
+    /// Token-2022, for the receipt mint's metadata extension
+    pub token_program: Option<Program<'info, Token2022>>,
+
+    /// Creates the winner's receipt token account
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
     /// System program for transfers
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<SettleAuction>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleAuction<'info>>,
     handle_bytes: Vec<u8>,
     plaintext_bytes: Vec<u8>,
 ) -> Result<()> {
     let clock = Clock::get()?;
-    let auction = &mut ctx.accounts.auction;
-
-    // Verify the Ed25519 attestation signature from Inco
-    // This proves the plaintext_bytes is the correct decryption of handle_bytes
-    let cpi_ctx = CpiContext::new(
-        ctx.accounts.inco_lightning_program.to_account_info(),
-        VerifySignature {
-            instructions: ctx.accounts.instructions.to_account_info(),
-            signer: ctx.accounts.winner.to_account_info(),
-        },
-    );
-
-    // Verify the signature
-    // This will fail if the Ed25519 instruction isn't in the transaction
-    let _results = is_validsignature(
-        cpi_ctx,
-        1,                           // expected signature count
-        Some(vec![handle_bytes]),    // handles being verified
-        Some(vec![plaintext_bytes.clone()]), // claimed plaintext values
-    )?;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
 
-    // Parse the winning amount from plaintext bytes
-    // The plaintext is a u128 in little-endian format
-    let winning_amount = if plaintext_bytes.len() >= 16 {
-        let mut bytes = [0u8; 16];
-        bytes.copy_from_slice(&plaintext_bytes[..16]);
-        u128::from_le_bytes(bytes) as u64
-    } else if plaintext_bytes.len() >= 8 {
-        let mut bytes = [0u8; 8];
-        bytes.copy_from_slice(&plaintext_bytes[..8]);
-        u64::from_le_bytes(bytes)
+    let winning_amount = if auction.is_commit_reveal() {
+        // The winning amount was revealed in plaintext, no proof needed
+        auction.highest_revealed_amount
     } else {
-        return Err(ShadowBidError::InvalidDecryptionProof.into());
+        // Only the winning handle settles the auction; any other attested
+        // handle would let the winner pay someone else's (or no) bid
+        require!(
+            handle_bytes == handle_to_bytes(auction.highest_bid_handle),
+            ShadowBidError::InvalidDecryptionProof
+        );
+
+        // Parse the winning amount from plaintext bytes
+        let winning_amount = parse_plaintext_amount(&plaintext_bytes)?;
+
+        // Verify the Ed25519 attestation signature from Inco
+        // This proves the plaintext_bytes is the correct decryption of handle_bytes
+        let backend = IncoBackend::new(
+            &ctx.accounts.inco_lightning_program,
+            &ctx.accounts.winner.to_account_info(),
+        );
+        backend.verify_decryption(
+            &ctx.accounts.instructions,
+            vec![handle_bytes],
+            vec![plaintext_bytes],
+        )?;
+
+        winning_amount
     };
 
     // Ensure winning amount meets reserve price
@@ -99,10 +225,51 @@ pub fn handler(
         ShadowBidError::InvalidDecryptionProof
     );
 
-    // Transfer payment from winner to seller
+    require!(
+        auction.is_escrowed() == ctx.accounts.escrow.is_some(),
+        ShadowBidError::EscrowMismatch
+    );
+
+    require!(
+        auction.issues_receipt() == ctx.accounts.receipt.is_some(),
+        ShadowBidError::ReceiptMismatch
+    );
+
+    require_keys_eq!(
+        ctx.accounts.beneficiary.key(),
+        AuctionAuthority::beneficiary_from(&ctx.accounts.auction_authority, &auction.seller)?,
+        ShadowBidError::NotBeneficiary
+    );
+
+    // The winner can't skip the callback, or the seller's program would
+    // never learn it was paid
+    let callback = check_settlement_callback(
+        auction.has_settlement_callback(),
+        ctx.accounts.settlement_callback.as_deref(),
+        ctx.accounts.callback_program.as_deref(),
+    )?;
+
+    // Unless its failures are ignored, the winner can't skip the hook, or
+    // the hook program would never learn the auction was paid. Escrowed
+    // auctions invoke it once the escrow pays out instead
+    let hook = if auction.is_escrowed() {
+        HookCall::None
+    } else {
+        check_hook(
+            auction.hook_mode()?,
+            ctx.accounts.hook.as_deref(),
+            ctx.accounts.hook_program.as_deref(),
+        )?
+    };
+
+    // Transfer payment from winner to the beneficiary, or into escrow
+    let recipient = match &ctx.accounts.escrow {
+        Some(escrow) => escrow.to_account_info(),
+        None => ctx.accounts.beneficiary.to_account_info(),
+    };
     let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
         ctx.accounts.winner.key,
-        ctx.accounts.seller.key,
+        recipient.key,
         winning_amount,
     );
 
@@ -110,17 +277,66 @@ pub fn handler(
         &transfer_ix,
         &[
             ctx.accounts.winner.to_account_info(),
-            ctx.accounts.seller.to_account_info(),
+            recipient,
             ctx.accounts.system_program.to_account_info(),
         ],
     )?;
 
-    // Update auction state
     auction.winning_amount = winning_amount;
-    auction.state = AuctionState::Settled;
+    SellerProfile::update_from(&ctx.accounts.seller_profile, |profile| {
+        profile.record_settlement(winning_amount)
+    })?;
+    BidderProfile::update_from(&ctx.accounts.winner_profile, |profile| {
+        profile.auctions_settled += 1
+    })?;
+
+    if let Some(mint) = mint_receipt(ctx.accounts, &ctx.bumps, auction_key, auction.get_title(), winning_amount)? {
+        if let Some(receipt) = &mut ctx.accounts.receipt {
+            receipt.auction = auction_key;
+            receipt.mint = mint;
+            receipt.winner = auction.winner;
+            receipt.amount = winning_amount;
+            receipt.redeemed_at = 0;
+            receipt.bump = ctx.bumps.receipt.unwrap_or_default();
+        }
+
+        emit!(ReceiptMinted {
+            auction: auction_key,
+            winner: auction.winner,
+            mint,
+            amount: winning_amount,
+        });
+        msg!("Receipt minted: {}", mint);
+    }
+
+    if let Some(escrow) = &mut ctx.accounts.escrow {
+        escrow.auction = auction_key;
+        escrow.buyer = auction.winner;
+        escrow.beneficiary = ctx.accounts.beneficiary.key();
+        escrow.amount = winning_amount;
+        escrow.bump = ctx.bumps.escrow.unwrap_or_default();
+
+        auction.delivery_deadline = clock.unix_timestamp + auction.delivery_timeout;
+        auction.set_state(AuctionState::AwaitingDelivery);
+
+        emit!(PaymentEscrowed {
+            auction: auction_key,
+            winner: auction.winner,
+            amount: winning_amount,
+            delivery_deadline: auction.delivery_deadline,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Payment escrowed: {} lamports", winning_amount);
+        msg!("Delivery deadline: {}", { auction.delivery_deadline });
+        return Ok(());
+    }
+
+    // Update auction state
+    auction.set_state(AuctionState::Settled);
 
     emit!(AuctionSettled {
-        auction: auction.key(),
+        auction: auction_key,
         winner: auction.winner,
         winning_amount,
         timestamp: clock.unix_timestamp,
@@ -129,7 +345,142 @@ pub fn handler(
     msg!("Auction settled!");
     msg!("Winner: {}", auction.winner);
     msg!("Winning amount: {} lamports", winning_amount);
-    msg!("Payment transferred to seller: {}", auction.seller);
+    msg!("Payment transferred to beneficiary: {}", ctx.accounts.beneficiary.key());
+
+    let completion = AuctionCompletion {
+        auction: auction_key,
+        auction_id: auction.auction_id,
+        seller: auction.seller,
+        winner: auction.winner,
+        amount: winning_amount,
+        outcome: AuctionOutcome::Settled,
+    };
+    let bump = auction.bump;
+
+    // The callback and hook receive the auction account, which can't be
+    // borrowed across the CPI. An auction has at most one of them
+    drop(auction);
+    if let Some(program) = callback {
+        run_settlement_callback(
+            program,
+            &ctx.accounts.auction.to_account_info(),
+            &ctx.accounts.winner.to_account_info(),
+            &ctx.accounts.seller,
+            ctx.remaining_accounts,
+            &SettlementResult::from(&completion),
+            bump,
+        )?;
+    }
+    hook.run(
+        &ctx.accounts.auction.to_account_info(),
+        &ctx.accounts.winner.to_account_info(),
+        &ctx.accounts.seller,
+        ctx.remaining_accounts,
+        &completion,
+        bump,
+    )
+}
+
+/// Mints the receipt NFT to the winner, if the receipt accounts were
+/// passed: a Token-2022 mint whose metadata, stored in the mint itself,
+/// names the auction, its title and the winning amount
+fn mint_receipt(
+    accounts: &SettleAuction,
+    bumps: &SettleAuctionBumps,
+    auction_key: Pubkey,
+    title: String,
+    amount: u64,
+) -> Result<Option<Pubkey>> {
+    if accounts.receipt.is_none() {
+        return Ok(None);
+    }
+    let (Some(mint), Some(token), Some(authority), Some(token_program), Some(_)) = (
+        &accounts.receipt_mint,
+        &accounts.receipt_token,
+        &accounts.receipt_authority,
+        &accounts.token_program,
+        &accounts.associated_token_program,
+    ) else {
+        return err!(ShadowBidError::ReceiptMismatch);
+    };
+    let authority_bump = [bumps.receipt_authority.unwrap_or_default()];
+    let signer: &[&[&[u8]]] = &[&[RECEIPT_AUTHORITY_SEED, &authority_bump]];
+
+    let metadata = TokenMetadata {
+        name: title,
+        symbol: RECEIPT_SYMBOL.to_string(),
+        uri: String::new(),
+        additional_metadata: vec![
+            (Receipt::AUCTION_FIELD.to_string(), auction_key.to_string()),
+            (Receipt::AMOUNT_FIELD.to_string(), amount.to_string()),
+            (Receipt::FULFILLED_FIELD.to_string(), false.to_string()),
+        ],
+        ..Default::default()
+    };
+
+    // Token-2022 grows the mint for the metadata but doesn't fund it
+    let mint_info = mint.to_account_info();
+    let space = mint_info.data_len() + metadata.tlv_size_of()?;
+    let top_up = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(mint_info.lamports());
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                Transfer {
+                    from: accounts.winner.to_account_info(),
+                    to: mint_info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TokenMetadataInitialize {
+                program_id: token_program.to_account_info(),
+                mint: mint_info.clone(),
+                metadata: mint_info.clone(),
+                mint_authority: authority.to_account_info(),
+                update_authority: authority.to_account_info(),
+            },
+            signer,
+        ),
+        metadata.name,
+        metadata.symbol,
+        metadata.uri,
+    )?;
+    for (field, value) in metadata.additional_metadata {
+        token_metadata_update_field(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TokenMetadataUpdateField {
+                    program_id: token_program.to_account_info(),
+                    metadata: mint_info.clone(),
+                    update_authority: authority.to_account_info(),
+                },
+                signer,
+            ),
+            Field::Key(field),
+            value,
+        )?;
+    }
+
+    mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: mint_info.clone(),
+                to: token.to_account_info(),
+                authority: authority.to_account_info(),
+            },
+            signer,
+        ),
+        1,
+    )?;
 
-    Ok(())
+    Ok(Some(mint.key()))
 }
@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+
+use crate::callback::{check_settlement_callback, run_settlement_callback, SettlementResult};
+use crate::confidential::{handle_to_bytes, parse_plaintext_amount, ConfidentialBackend, IncoBackend};
+use crate::constants::{AUCTION_AUTHORITY_SEED, BID_SEED, BIDDER_PROFILE_SEED, HOOK_SEED, SELLER_PROFILE_SEED, SETTLEMENT_CALLBACK_SEED};
+use crate::errors::ShadowBidError;
+use crate::events::AuctionSettled;
+use crate::hook::{check_hook, AuctionCompletion, AuctionOutcome};
+use crate::state::{Auction, AuctionAuthority, AuctionHook, AuctionState, Bid, BidderProfile, LoadVersioned, SellerProfile, SettlementCallback};
+
+/// This instruction (sealed auctions with bid deposits only):
+/// 1. Verifies the seller's attested decryption of the winning handle
+/// 2. Checks the winner's deposit covers the winning amount
+/// 3. Marks auction as settled
+/// 4. Calls back the seller's program or invokes the hook, if the auction
+///    has one
+/// 5. Pays the winning amount from the deposit to the beneficiary and
+///    returns the rest to the winner
+///
+/// The seller decrypts with the permission `finalize_winner` granted them,
+/// so the winner doesn't sign. The transaction must include Ed25519
+/// signature verification instructions from the Inco SDK before calling
+/// this instruction.
+#[derive(Accounts)]
+pub struct SettleFromDeposit<'info> {
+    /// The seller settling the auction, passed to the callback or hook
+    #[account(
+        mut,
+        constraint = seller.key() == auction.load_versioned()?.seller @ ShadowBidError::NotSeller,
+    )]
+    pub seller: Signer<'info>,
+
+    /// The auction to settle
+    #[account(
+        mut,
+        constraint = auction.load_versioned()?.state()? == AuctionState::WinnerDetermined @ ShadowBidError::WinnerNotDetermined,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The winner, who gets back what the deposit holds beyond the
+    /// winning amount; validated against auction.winner
+    #[account(
+        mut,
+        constraint = winner.key() == auction.load_versioned()?.winner @ ShadowBidError::NotWinner,
+    )]
+    pub winner: UncheckedAccount<'info>,
+
+    /// The winning bid, holding the deposit
+    #[account(
+        mut,
+        seeds = [BID_SEED, auction.key().as_ref(), winner.key().as_ref()],
+        bump = winner_bid.bump,
+    )]
+    pub winner_bid: Account<'info, Bid>,
+
+    /// CHECK: The seller's profile, credited with the sale. Seeds are
+    /// checked; sellers without one are skipped by
+    /// `SellerProfile::update_from`
+    #[account(
+        mut,
+        seeds = [SELLER_PROFILE_SEED, seller.key().as_ref()],
+        bump
+    )]
+    pub seller_profile: UncheckedAccount<'info>,
+
+    /// CHECK: The winner's profile, credited with the settlement. Seeds
+    /// are checked; winners without one are skipped by
+    /// `BidderProfile::update_from`
+    #[account(
+        mut,
+        seeds = [BIDDER_PROFILE_SEED, winner.key().as_ref()],
+        bump
+    )]
+    pub winner_profile: UncheckedAccount<'info>,
+
+    /// CHECK: The auction's authority record. Seeds are checked; without
+    /// one, the seller is the beneficiary
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The beneficiary receiving payment; checked against the
+    /// authority record by the handler
+    #[account(mut)]
+    pub beneficiary: UncheckedAccount<'info>,
+
+    /// Instructions sysvar for Ed25519 signature verification
+    /// CHECK: Validated by address constraint
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// Inco Lightning program for attestation verification
+    /// CHECK: Validated by address constraint
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// The auction's settlement callback, passed only for auctions
+    /// created with one
+    #[account(
+        seeds = [SETTLEMENT_CALLBACK_SEED, auction.key().as_ref()],
+        bump = settlement_callback.bump,
+    )]
+    pub settlement_callback: Option<Account<'info, SettlementCallback>>,
+
+    /// CHECK: The program called back, checked against
+    /// `settlement_callback.program` by the handler
+    pub callback_program: Option<UncheckedAccount<'info>>,
+
+    /// The auction's hook, passed only for auctions created with one
+    /// (and optional when its failures are ignored)
+    #[account(
+        seeds = [HOOK_SEED, auction.key().as_ref()],
+        bump = hook.bump,
+    )]
+    pub hook: Option<Account<'info, AuctionHook>>,
+
+    /// CHECK: The hook program, checked against `hook.program` by the
+    /// handler
+    pub hook_program: Option<UncheckedAccount<'info>>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleFromDeposit<'info>>,
+    handle_bytes: Vec<u8>,
+    plaintext_bytes: Vec<u8>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+    let mut auction = ctx.accounts.auction.load_versioned_mut()?;
+    let deposit = ctx.accounts.winner_bid.collateral;
+
+    require!(
+        auction.takes_bid_deposits() && deposit > 0,
+        ShadowBidError::NoBidDeposit
+    );
+
+    // Escrow and receipt accounts are paid for by the winner
+    require!(
+        !auction.is_escrowed() && !auction.issues_receipt(),
+        ShadowBidError::WinnerMustSettle
+    );
+
+    // Only the winning handle settles the auction
+    require!(
+        handle_bytes == handle_to_bytes(auction.highest_bid_handle),
+        ShadowBidError::InvalidDecryptionProof
+    );
+    let winning_amount = parse_plaintext_amount(&plaintext_bytes)?;
+
+    // The seller was allowed on the handle by finalize_winner
+    let backend = IncoBackend::new(
+        &ctx.accounts.inco_lightning_program,
+        &ctx.accounts.seller.to_account_info(),
+    );
+    backend.verify_decryption(
+        &ctx.accounts.instructions,
+        vec![handle_bytes],
+        vec![plaintext_bytes],
+    )?;
+
+    require!(
+        winning_amount >= auction.reserve_price,
+        ShadowBidError::InvalidDecryptionProof
+    );
+    require!(winning_amount <= deposit, ShadowBidError::DepositTooSmall);
+
+    require_keys_eq!(
+        ctx.accounts.beneficiary.key(),
+        AuctionAuthority::beneficiary_from(&ctx.accounts.auction_authority, &auction.seller)?,
+        ShadowBidError::NotBeneficiary
+    );
+
+    // As in settle_auction, the seller can't skip the callback or an
+    // aborting hook
+    let callback = check_settlement_callback(
+        auction.has_settlement_callback(),
+        ctx.accounts.settlement_callback.as_deref(),
+        ctx.accounts.callback_program.as_deref(),
+    )?;
+    let hook = check_hook(
+        auction.hook_mode()?,
+        ctx.accounts.hook.as_deref(),
+        ctx.accounts.hook_program.as_deref(),
+    )?;
+
+    auction.winning_amount = winning_amount;
+    auction.set_state(AuctionState::Settled);
+    SellerProfile::update_from(&ctx.accounts.seller_profile, |profile| {
+        profile.record_settlement(winning_amount)
+    })?;
+    BidderProfile::update_from(&ctx.accounts.winner_profile, |profile| {
+        profile.auctions_settled += 1
+    })?;
+
+    emit!(AuctionSettled {
+        auction: auction_key,
+        winner: auction.winner,
+        winning_amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Auction settled from the winner's deposit");
+    msg!("Winner: {}", auction.winner);
+    msg!("Winning amount: {} lamports", winning_amount);
+    msg!("Payment transferred to beneficiary: {}", ctx.accounts.beneficiary.key());
+
+    let completion = AuctionCompletion {
+        auction: auction_key,
+        auction_id: auction.auction_id,
+        seller: auction.seller,
+        winner: auction.winner,
+        amount: winning_amount,
+        outcome: AuctionOutcome::Settled,
+    };
+    let bump = auction.bump;
+
+    // The callback and hook receive the auction account, which can't be
+    // borrowed across the CPI. They run before the deposit is paid out:
+    // the runtime rejects a CPI after lamports moved between accounts the
+    // callee isn't given
+    drop(auction);
+    if let Some(program) = callback {
+        run_settlement_callback(
+            program,
+            &ctx.accounts.auction.to_account_info(),
+            &ctx.accounts.winner,
+            &ctx.accounts.seller,
+            ctx.remaining_accounts,
+            &SettlementResult::from(&completion),
+            bump,
+        )?;
+    }
+    hook.run(
+        &ctx.accounts.auction.to_account_info(),
+        &ctx.accounts.winner,
+        &ctx.accounts.seller,
+        ctx.remaining_accounts,
+        &completion,
+        bump,
+    )?;
+
+    let refund = deposit - winning_amount;
+    ctx.accounts.winner_bid.collateral = 0;
+    **ctx.accounts.winner_bid.to_account_info().try_borrow_mut_lamports()? -= deposit;
+    **ctx.accounts.beneficiary.try_borrow_mut_lamports()? += winning_amount;
+    **ctx.accounts.winner.try_borrow_mut_lamports()? += refund;
+
+    msg!("Deposit returned to winner: {} lamports", refund);
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_EVIDENCE_URI_LENGTH;
+use crate::errors::ShadowBidError;
+use crate::events::EvidenceSubmitted;
+use crate::state::{Auction, AuctionState, LoadVersioned};
+
+/// This instruction lets either side of a dispute point the arbitrator
+/// at more evidence while the dispute is open
+#[derive(Accounts)]
+pub struct SubmitEvidence<'info> {
+    /// The winner or the seller
+    #[account(
+        constraint = {
+            let auction = auction.load_versioned()?;
+            submitter.key() == auction.winner || submitter.key() == auction.seller
+        } @ ShadowBidError::NotDisputeParty,
+    )]
+    pub submitter: Signer<'info>,
+
+    /// The disputed auction
+    #[account(
+        constraint = auction.load_versioned()?.state()? == AuctionState::Disputed @ ShadowBidError::NotDisputed,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+}
+
+pub fn handler(ctx: Context<SubmitEvidence>, evidence_uri: String) -> Result<()> {
+    require!(
+        evidence_uri.len() <= MAX_EVIDENCE_URI_LENGTH,
+        ShadowBidError::EvidenceUriTooLong
+    );
+
+    let clock = Clock::get()?;
+
+    emit!(EvidenceSubmitted {
+        auction: ctx.accounts.auction.key(),
+        submitter: ctx.accounts.submitter.key(),
+        evidence_uri: evidence_uri.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Evidence submitted by {}", ctx.accounts.submitter.key());
+    msg!("URI: {}", evidence_uri);
+
+    Ok(())
+}
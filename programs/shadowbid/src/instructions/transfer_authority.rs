@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::AUCTION_AUTHORITY_SEED;
+use crate::errors::ShadowBidError;
+use crate::events::AuthorityTransferred;
+use crate::state::{Auction, AuctionAuthority, LoadVersioned};
+
+/// This instruction:
+/// 1. Lets the authority (the seller, for an auction without a record)
+///    hand authority over the auction on
+/// 2. Revokes every delegate the previous authority named
+///
+/// The beneficiary is unchanged
+#[derive(Accounts)]
+pub struct TransferAuthority<'info> {
+    /// The current authority, paying for the record if the auction has
+    /// none yet
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The auction being handed on
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// The auction's authority record, created with the seller in both
+    /// roles if the auction has none yet
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = AuctionAuthority::SPACE,
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_authority: Account<'info, AuctionAuthority>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
+    require!(new_authority != Pubkey::default(), ShadowBidError::InvalidAuthority);
+
+    let clock = Clock::get()?;
+    let auction_key = ctx.accounts.auction.key();
+    let seller = ctx.accounts.auction.load_versioned()?.seller;
+    let record = &mut ctx.accounts.auction_authority;
+    record.claim(auction_key, seller, ctx.bumps.auction_authority, ctx.accounts.authority.key)?;
+
+    let previous = record.authority;
+    record.authority = new_authority;
+    record.delegates = Default::default();
+
+    emit!(AuthorityTransferred {
+        auction: auction_key,
+        previous,
+        authority: new_authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Authority transferred: {} -> {}", previous, new_authority);
+
+    Ok(())
+}
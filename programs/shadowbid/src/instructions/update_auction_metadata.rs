@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::constants::{AUCTION_AUTHORITY_SEED, METADATA_SEED};
+use crate::errors::ShadowBidError;
+use crate::instructions::create_auction_metadata::AuctionMetadataParams;
+use crate::state::{Auction, AuctionAuthority, AuctionMetadata, AuctionState, LoadVersioned, Permission};
+
+#[derive(Accounts)]
+#[instruction(params: AuctionMetadataParams)]
+pub struct UpdateAuctionMetadata<'info> {
+    /// The authority updating the auction's metadata, or a delegate
+    /// allowed to; checked by the handler
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The auction being described; metadata is frozen by the first bid
+    #[account(
+        constraint = auction.load_versioned()?.state()? == AuctionState::Open @ ShadowBidError::AuctionNotOpen,
+        constraint = auction.load_versioned()?.bid_count == 0 @ ShadowBidError::MetadataLocked,
+    )]
+    pub auction: AccountLoader<'info, Auction>,
+
+    /// CHECK: The auction's authority record. Seeds are checked; without
+    /// one, `AuctionAuthority::check` requires the seller
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The auction's seller, who gets back the rent a shrinking
+    /// update frees
+    #[account(
+        mut,
+        constraint = seller.key() == auction.load_versioned()?.seller @ ShadowBidError::NotSeller,
+    )]
+    pub seller: UncheckedAccount<'info>,
+
+    /// The metadata account, resized to the new contents by the handler
+    /// (the signer pays for growth, the seller is refunded on shrink)
+    #[account(
+        mut,
+        seeds = [METADATA_SEED, auction.key().as_ref()],
+        bump = metadata.bump,
+    )]
+    pub metadata: Account<'info, AuctionMetadata>,
+
+    /// System program for rent top-ups
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<UpdateAuctionMetadata>, params: AuctionMetadataParams) -> Result<()> {
+    AuctionAuthority::check(
+        &ctx.accounts.auction_authority,
+        &ctx.accounts.auction.load_versioned()?.seller,
+        ctx.accounts.authority.key,
+        Some(Permission::UpdateMetadata),
+    )?;
+    params.validate()?;
+
+    // The metadata's rent belongs to the seller, whoever signs: a
+    // delegate pays for growth but can't collect what a shrink frees
+    let space = params.space();
+    let rent = Rent::get()?.minimum_balance(space);
+    let balance = ctx.accounts.metadata.get_lamports();
+    if rent > balance {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.metadata.to_account_info(),
+                },
+            ),
+            rent - balance,
+        )?;
+    } else if balance > rent {
+        ctx.accounts.metadata.sub_lamports(balance - rent)?;
+        ctx.accounts.seller.add_lamports(balance - rent)?;
+    }
+    ctx.accounts.metadata.to_account_info().resize(space)?;
+
+    let clock = Clock::get()?;
+    params.apply(&mut ctx.accounts.metadata, clock.unix_timestamp);
+
+    Ok(())
+}
@@ -1,12 +1,29 @@
+// `#[program]` emits its IDL instructions beside the `shadowbid` module,
+// out of reach of any narrower allow, and they still call the deprecated
+// `AccountInfo::realloc`. The crate's own modules warn again.
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
 
+#[warn(deprecated)]
+pub mod callback;
+#[warn(deprecated)]
+pub mod confidential;
+#[warn(deprecated)]
 pub mod constants;
+#[warn(deprecated)]
 pub mod errors;
+#[warn(deprecated)]
 pub mod events;
+#[warn(deprecated)]
+pub mod hook;
+#[warn(deprecated)]
 pub mod instructions;
+#[warn(deprecated)]
 pub mod state;
 
 use instructions::*;
+use state::CancellationReason;
 
 declare_id!("GGanQwYdzturA2hMuPbR69toMaiHPaGox86YifLjMVzQ");
 
@@ -19,12 +36,31 @@ declare_id!("GGanQwYdzturA2hMuPbR69toMaiHPaGox86YifLjMVzQ");
 /// 4. Winner determination: compare encrypted bids using e_ge and e_select
 /// 5. Winner is granted decryption permission
 /// 6. Winner reveals bid, pays, and settles auction
+/// 7. Escrowed auctions hold the payment until delivery is confirmed
 #[program]
+#[warn(deprecated)]
 pub mod shadowbid {
     use super::*;
 
+    /// Creates the global auction registry (permissionless, once)
+    pub fn initialize_registry(ctx: Context<InitializeRegistry>) -> Result<()> {
+        instructions::initialize_registry::handler(ctx)
+    }
+
+    /// Creates the global treasury that receives slashed bonds
+    /// (permissionless, once)
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        instructions::initialize_treasury::handler(ctx)
+    }
+
     /// Creates a new sealed-bid auction
     /// 
+    /// The registry assigns the auction ID and lists the auction on its
+    /// page until bidding closes
+    ///
+    /// The seller may be a PDA signing through its program; `payer`
+    /// funds the new accounts and the seller bond
+    ///
     /// # Arguments
     /// * `params` - Auction parameters (title, description, reserve_price, duration)
     pub fn create_auction(
@@ -34,10 +70,45 @@ pub mod shadowbid {
         instructions::create_auction::handler(ctx, params)
     }
 
+    /// Creates the auction's structured metadata (authority, or a
+    /// delegate allowed to update metadata)
+    ///
+    /// # Arguments
+    /// * `params` - Title, description, off-chain URI, its SHA-256 hash and category tags
+    ///
+    /// Only allowed while the auction is open and has no bids
+    pub fn create_auction_metadata(
+        ctx: Context<CreateAuctionMetadata>,
+        params: AuctionMetadataParams,
+    ) -> Result<()> {
+        instructions::create_auction_metadata::handler(ctx, params)
+    }
+
+    /// Replaces the auction's structured metadata (authority, or a
+    /// delegate allowed to update metadata)
+    ///
+    /// Only allowed while the auction is open and has no bids
+    pub fn update_auction_metadata(
+        ctx: Context<UpdateAuctionMetadata>,
+        params: AuctionMetadataParams,
+    ) -> Result<()> {
+        instructions::update_auction_metadata::handler(ctx, params)
+    }
+
+    /// Closes the auction's metadata and returns its rent to the seller
+    /// (authority, or a delegate allowed to update metadata)
+    ///
+    /// Only allowed while the auction is open and has no bids, or once it
+    /// is settled, cancelled or defaulted
+    pub fn close_auction_metadata(ctx: Context<CloseAuctionMetadata>) -> Result<()> {
+        instructions::close_auction_metadata::handler(ctx)
+    }
+
     /// Places or updates an encrypted bid
     /// 
     /// # Arguments
-    /// * `ciphertext` - The encrypted bid amount (encrypted client-side with Inco SDK)
+    /// * `ciphertext` - The encrypted bid amount (encrypted client-side with Inco SDK),
+    ///   or the 32-byte commitment for commit-reveal auctions
     pub fn place_bid(ctx: Context<PlaceBid>, ciphertext: Vec<u8>) -> Result<()> {
         instructions::place_bid::handler(ctx, ciphertext)
     }
@@ -49,19 +120,44 @@ pub mod shadowbid {
         instructions::close_bidding::handler(ctx)
     }
 
+    /// Reveals a commit-reveal bid and returns its collateral
+    ///
+    /// # Arguments
+    /// * `amount` - The committed bid amount in lamports
+    /// * `salt` - The salt used in the commitment
+    pub fn reveal_bid(ctx: Context<RevealBid>, amount: u64, salt: [u8; 32]) -> Result<()> {
+        instructions::reveal_bid::handler(ctx, amount, salt)
+    }
+
+    /// Closes the reveal period of a commit-reveal auction
+    ///
+    /// Permissionless - anyone can call this after reveal_end_time
+    pub fn close_reveal(ctx: Context<CloseReveal>) -> Result<()> {
+        instructions::close_reveal::handler(ctx)
+    }
+
     /// Processes one bid for winner determination
     /// 
     /// Uses encrypted comparison (e_ge) and selection (e_select)
     /// to update the highest bid without revealing any bid amounts.
     /// Must be called once per bid.
-    pub fn determine_winner(ctx: Context<DetermineWinner>) -> Result<()> {
+    ///
+    /// Commit-reveal auctions compare revealed amounts in plaintext and
+    /// forfeit unrevealed collateral to the seller (first remaining account)
+    pub fn determine_winner<'info>(
+        ctx: Context<'_, '_, '_, 'info, DetermineWinner<'info>>,
+    ) -> Result<()> {
         instructions::determine_winner::handler(ctx)
     }
 
     /// Finalizes the winner after all bids are processed
     /// 
-    /// Grants decryption permission to the winner via Inco's allow()
-    pub fn finalize_winner(ctx: Context<FinalizeWinner>) -> Result<()> {
+    /// Grants decryption permission to the winner via Inco's allow(),
+    /// and optionally to the seller when their allowance account and
+    /// address are passed as remaining accounts
+    pub fn finalize_winner<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeWinner<'info>>,
+    ) -> Result<()> {
         instructions::finalize_winner::handler(ctx)
     }
 
@@ -71,19 +167,201 @@ pub mod shadowbid {
     /// * `handle_bytes` - The winning bid handle as bytes
     /// * `plaintext_bytes` - The decrypted winning amount
     /// 
-    /// Requires Ed25519 signature verification instruction in the transaction
-    pub fn settle_auction(
-        ctx: Context<SettleAuction>,
+    /// Requires Ed25519 signature verification instruction in the transaction.
+    /// The payment goes to the auction's beneficiary; escrowed auctions pay
+    /// into the escrow and await delivery instead.
+    /// Auctions issuing receipts mint one to the winner.
+    /// Auctions with a settlement callback or a hook invoke it afterwards,
+    /// passing on the remaining accounts
+    pub fn settle_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleAuction<'info>>,
         handle_bytes: Vec<u8>,
         plaintext_bytes: Vec<u8>,
     ) -> Result<()> {
         instructions::settle_auction::handler(ctx, handle_bytes, plaintext_bytes)
     }
 
-    /// Cancels an auction (seller only)
+    /// Settles a sealed auction from the winner's bid deposit (seller only)
+    ///
+    /// # Arguments
+    /// * `handle_bytes` - The winning bid handle as bytes
+    /// * `plaintext_bytes` - The decrypted winning amount
+    ///
+    /// Requires Ed25519 signature verification instruction in the
+    /// transaction, for a decryption the seller was allowed by
+    /// `finalize_winner`. The winner doesn't sign: the payment goes from
+    /// their deposit to the beneficiary and the rest back to them.
+    /// Auctions with a settlement callback or a hook invoke it first,
+    /// passing on the remaining accounts
+    pub fn settle_from_deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleFromDeposit<'info>>,
+        handle_bytes: Vec<u8>,
+        plaintext_bytes: Vec<u8>,
+    ) -> Result<()> {
+        instructions::settle_from_deposit::handler(ctx, handle_bytes, plaintext_bytes)
+    }
+
+    /// Returns a sealed bid's deposit once it no longer backs a
+    /// settlement (bidder only)
+    ///
+    /// Losing bids reclaim once the winner is determined, the winning bid
+    /// once the auction is settled or cancelled
+    pub fn reclaim_deposit(ctx: Context<ReclaimDeposit>) -> Result<()> {
+        instructions::reclaim_deposit::handler(ctx)
+    }
+
+    /// Marks the auction's receipt NFT fulfilled (authority only)
+    pub fn redeem_receipt(ctx: Context<RedeemReceipt>) -> Result<()> {
+        instructions::redeem_receipt::handler(ctx)
+    }
+
+    /// Declares the winner in default once the settlement window ends
+    /// without settlement (permissionless)
+    ///
+    /// Counts the default in the winner's profile and frees the seller
+    /// bond
+    pub fn declare_default(ctx: Context<DeclareDefault>) -> Result<()> {
+        instructions::declare_default::handler(ctx)
+    }
+
+    /// Cancels a sealed auction whose winning bid is below the reserve
+    /// price (seller or winner)
+    ///
+    /// # Arguments
+    /// * `handle_bytes` - The winning bid handle as bytes
+    /// * `plaintext_bytes` - The decrypted winning amount
+    ///
+    /// Requires Ed25519 signature verification instruction in the transaction.
+    /// Withdraws the default of a winner already declared in one, and frees
+    /// the seller bond
+    pub fn cancel_below_reserve(
+        ctx: Context<CancelBelowReserve>,
+        handle_bytes: Vec<u8>,
+        plaintext_bytes: Vec<u8>,
+    ) -> Result<()> {
+        instructions::cancel_below_reserve::handler(ctx, handle_bytes, plaintext_bytes)
+    }
+
+    /// Releases an escrowed payment to the beneficiary once the winner
+    /// confirms delivery (winner only)
+    ///
+    /// Auctions with a hook invoke it afterwards, passing on the remaining
+    /// accounts
+    pub fn confirm_delivery<'info>(ctx: Context<'_, '_, '_, 'info, ConfirmDelivery<'info>>) -> Result<()> {
+        instructions::confirm_delivery::handler(ctx)
+    }
+
+    /// Releases an escrowed payment to the beneficiary after the
+    /// delivery window ends without a dispute (permissionless)
+    ///
+    /// Auctions with a hook invoke it afterwards, passing on the remaining
+    /// accounts
+    pub fn release_escrow<'info>(ctx: Context<'_, '_, '_, 'info, ReleaseEscrow<'info>>) -> Result<()> {
+        instructions::release_escrow::handler(ctx)
+    }
+
+    /// Disputes the delivery of an escrowed auction, freezing the payment
+    /// until the arbitrator rules (winner only, before the delivery window
+    /// ends)
+    pub fn dispute(ctx: Context<Dispute>, evidence_uri: String) -> Result<()> {
+        instructions::dispute::handler(ctx, evidence_uri)
+    }
+
+    /// Records more evidence for an open dispute (winner or seller)
+    pub fn submit_evidence(ctx: Context<SubmitEvidence>, evidence_uri: String) -> Result<()> {
+        instructions::submit_evidence::handler(ctx, evidence_uri)
+    }
+
+    /// Splits a disputed payment between winner and beneficiary, after
+    /// the arbitration fee (arbitrator only)
+    ///
+    /// Auctions with a hook invoke it afterwards, passing on the remaining
+    /// accounts
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, '_, 'info, ResolveDispute<'info>>,
+        buyer_bps: u16,
+    ) -> Result<()> {
+        instructions::resolve_dispute::handler(ctx, buyer_bps)
+    }
+
+    /// Cancels an auction (authority, or a delegate allowed to cancel)
     /// 
-    /// Can only cancel if no winner has been determined
-    pub fn cancel_auction(ctx: Context<CancelAuction>, reason: String) -> Result<()> {
-        instructions::cancel_auction::handler(ctx, reason)
+    /// Can only cancel if no winner has been determined. Without bids the
+    /// seller bond is returned; once the auction has bids, only a bonded
+    /// seller can cancel, and the bond is slashed between the bidders.
+    /// Auctions with a hook invoke it afterwards, passing on the remaining
+    /// accounts. `category` is counted in the seller's profile
+    pub fn cancel_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelAuction<'info>>,
+        reason: String,
+        category: CancellationReason,
+    ) -> Result<()> {
+        instructions::cancel_auction::handler(ctx, reason, category)
+    }
+
+    /// Pays a bidder their share of the seller bond after the seller
+    /// cancelled with bids (bidder only)
+    pub fn claim_compensation(ctx: Context<ClaimCompensation>) -> Result<()> {
+        instructions::claim_compensation::handler(ctx)
+    }
+
+    /// Returns the seller bond once the auction is settled or its winner
+    /// defaulted (seller only)
+    pub fn release_seller_bond(ctx: Context<ReleaseSellerBond>) -> Result<()> {
+        instructions::release_seller_bond::handler(ctx)
+    }
+
+    /// Hands authority over the auction on, revoking its delegates
+    /// (authority only)
+    ///
+    /// The seller of an auction without an authority record creates it,
+    /// keeping the beneficiary role
+    pub fn transfer_authority(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::transfer_authority::handler(ctx, new_authority)
+    }
+
+    /// Grants a delegate exactly `permissions` (`Permission` bits), or
+    /// revokes it with 0 (authority only)
+    ///
+    /// The seller of an auction without an authority record creates it
+    pub fn set_delegate(ctx: Context<SetDelegate>, delegate: Pubkey, permissions: u8) -> Result<()> {
+        instructions::set_delegate::handler(ctx, delegate, permissions)
+    }
+
+    /// Grants the seller decryption permission on the encrypted auction
+    /// statistics after settlement (seller only)
+    ///
+    /// Individual bids are never revealed, only the aggregates
+    pub fn reveal_auction_stats(ctx: Context<RevealAuctionStats>) -> Result<()> {
+        instructions::reveal_auction_stats::handler(ctx)
+    }
+
+    /// Publishes the decrypted auction statistics (seller only)
+    ///
+    /// # Arguments
+    /// * `plaintext_bytes` - Decrypted bid sum, lowest bid and above-reserve count
+    ///
+    /// Requires Ed25519 signature verification instructions in the transaction
+    pub fn publish_auction_stats(
+        ctx: Context<PublishAuctionStats>,
+        plaintext_bytes: Vec<Vec<u8>>,
+    ) -> Result<()> {
+        instructions::publish_auction_stats::handler(ctx, plaintext_bytes)
+    }
+
+    /// Migrates an auction created before account versioning to the
+    /// current layout
+    ///
+    /// Permissionless - the caller pays the extra rent
+    pub fn migrate_auction(ctx: Context<MigrateAuction>) -> Result<()> {
+        instructions::migrate_auction::handler(ctx)
+    }
+
+    /// Migrates a bid created before account versioning to the current
+    /// layout
+    ///
+    /// Permissionless - the caller pays the extra rent
+    pub fn migrate_bid(ctx: Context<MigrateBid>) -> Result<()> {
+        instructions::migrate_bid::handler(ctx)
     }
 }
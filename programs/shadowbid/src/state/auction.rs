@@ -1,11 +1,17 @@
 use anchor_lang::prelude::*;
 
 use crate::constants::{MAX_TITLE_LENGTH, MAX_DESCRIPTION_LENGTH};
+use crate::errors::ShadowBidError;
+use crate::state::{LegacyField, Versioned};
 
 /// Auction state machine
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+///
+/// Stored in `Auction` as its `u8` discriminant.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[repr(u8)]
 pub enum AuctionState {
     /// Auction is open for bidding
+    #[default]
     Open,
     /// Bidding period has ended, awaiting winner determination
     Closed,
@@ -15,16 +21,124 @@ pub enum AuctionState {
     Settled,
     /// Auction was cancelled
     Cancelled,
+    /// Commit-reveal only: bidding has ended, bidders reveal their commitments
+    Revealing,
+    /// Escrow only: the payment is held until the winner confirms delivery
+    /// or the delivery window ends
+    AwaitingDelivery,
+    /// Escrow only: the winner disputed the delivery, the payment is frozen
+    Disputed,
+    /// The winner didn't settle within the settlement window
+    Defaulted,
 }
 
-impl Default for AuctionState {
-    fn default() -> Self {
-        AuctionState::Open
+/// How bids are sealed
+///
+/// Stored in `Auction` as its `u8` discriminant.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[repr(u8)]
+pub enum AuctionMode {
+    /// Bids are encrypted with Inco Lightning and compared confidentially
+    #[default]
+    Sealed,
+    /// Bids are hash commitments backed by collateral, revealed after bidding
+    CommitReveal,
+}
+
+/// What the seller may do once an auction has bids
+///
+/// Stored in `Auction` as its `u8` discriminant.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[repr(u8)]
+pub enum CancellationPolicy {
+    /// The first bid locks the auction in; only no-bid auctions can be cancelled
+    #[default]
+    NoCancelAfterBid,
+    /// The seller posted a `SellerBond`, which cancelling with bids
+    /// splits evenly between the bidders
+    Bonded,
+}
+
+/// What happens to an auction's completion when its hook program fails
+///
+/// Stored in `Auction` as its `u8` discriminant.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[repr(u8)]
+pub enum HookMode {
+    /// The auction has no hook
+    #[default]
+    None,
+    /// The hook must run: its failure aborts completion or cancellation
+    Abort,
+    /// The hook may be left out, so its failure doesn't block completion
+    Ignore,
+}
+
+impl TryFrom<u8> for AuctionState {
+    type Error = ShadowBidError;
+
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Self::Open,
+            1 => Self::Closed,
+            2 => Self::WinnerDetermined,
+            3 => Self::Settled,
+            4 => Self::Cancelled,
+            5 => Self::Revealing,
+            6 => Self::AwaitingDelivery,
+            7 => Self::Disputed,
+            8 => Self::Defaulted,
+            _ => return Err(ShadowBidError::InvalidDiscriminant),
+        })
+    }
+}
+
+impl TryFrom<u8> for CancellationPolicy {
+    type Error = ShadowBidError;
+
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Self::NoCancelAfterBid,
+            1 => Self::Bonded,
+            _ => return Err(ShadowBidError::InvalidDiscriminant),
+        })
+    }
+}
+
+impl TryFrom<u8> for HookMode {
+    type Error = ShadowBidError;
+
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Self::None,
+            1 => Self::Abort,
+            2 => Self::Ignore,
+            _ => return Err(ShadowBidError::InvalidDiscriminant),
+        })
+    }
+}
+
+impl TryFrom<u8> for AuctionMode {
+    type Error = ShadowBidError;
+
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Self::Sealed,
+            1 => Self::CommitReveal,
+            _ => return Err(ShadowBidError::InvalidDiscriminant),
+        })
     }
 }
 
 /// Main auction account storing all auction metadata and state
-#[account]
+///
+/// Zero-copy: instructions access the account data in place through an
+/// `AccountLoader` instead of Borsh-decoding all of it. The struct is
+/// packed, so its layout is byte-for-byte the Borsh layout accounts were
+/// created with; enums are stored as their `u8` discriminant behind the
+/// `state()` / `mode()` accessors. Copy packed integer fields out (e.g.
+/// `{ auction.bid_count }`) before taking references to them.
+#[account(zero_copy(unsafe))]
 pub struct Auction {
     /// The seller who created this auction
     pub seller: Pubkey,
@@ -47,8 +161,8 @@ pub struct Auction {
     /// Unix timestamp when bidding ends
     pub end_time: i64,
     
-    /// Current state of the auction
-    pub state: AuctionState,
+    /// Current state of the auction (`AuctionState`)
+    state: u8,
     
     /// Total number of bids placed
     pub bid_count: u32,
@@ -69,12 +183,84 @@ pub struct Auction {
     
     /// The winning bid amount (revealed only after settlement)
     pub winning_amount: u64,
+
+    /// Handle to the encrypted sum of all processed bids (e_add)
+    pub bid_sum_handle: u128,
+
+    /// Handle to the encrypted lowest processed bid
+    pub lowest_bid_handle: u128,
+
+    /// Handle to the encrypted count of processed bids at or above the reserve price
+    pub above_reserve_count_handle: u128,
+
+    /// How bids are sealed (`AuctionMode`)
+    mode: u8,
+
+    /// Commit-reveal only: Unix timestamp when the reveal window ends
+    pub reveal_end_time: i64,
+
+    /// Collateral each bidder deposits with their commitment, or in a
+    /// sealed auction the deposit backing each bid (0 for none)
+    pub bid_collateral: u64,
+
+    /// Commit-reveal only: highest revealed amount at or above the reserve price
+    pub highest_revealed_amount: u64,
+
+    /// Commit-reveal only: number of bids revealed
+    pub bids_revealed: u32,
+
+    /// Commit-reveal only: total collateral forfeited by unrevealed bids
+    pub forfeited_collateral: u64,
     
     /// Unique auction ID (used in PDA derivation)
     pub auction_id: u64,
     
     /// Bump seed for PDA
     pub bump: u8,
+
+    /// Account layout version (`Auction::VERSION`)
+    pub version: u8,
+
+    /// What the seller may do once the auction has bids (`CancellationPolicy`)
+    cancellation_policy: u8,
+
+    /// Lamports the seller locked in the `SellerBond` vault at creation
+    /// (0 without a bond)
+    pub seller_bond: u64,
+
+    /// Escrow only: seconds the winner has to confirm delivery after
+    /// settling (0 pays the seller directly)
+    pub delivery_timeout: i64,
+
+    /// Escrow only: Unix timestamp after which the escrowed payment can
+    /// be released to the seller (set at settlement)
+    pub delivery_deadline: i64,
+
+    /// Escrow only: who resolves disputes; a multisig signs through its
+    /// program (default pubkey means disputes aren't allowed)
+    pub arbitrator: Pubkey,
+
+    /// Escrow only: share of a disputed payment the arbitrator takes, in
+    /// basis points
+    pub arbitration_fee_bps: u16,
+
+    /// Whether `settle_auction` invokes the program in the auction's
+    /// `SettlementCallback` (0 or 1)
+    settlement_callback: u8,
+
+    /// How the instructions completing or cancelling the auction treat
+    /// the program in the auction's `AuctionHook` (`HookMode`)
+    hook_mode: u8,
+
+    /// Whether `settle_auction` mints a receipt NFT to the winner (0 or 1)
+    issues_receipt: u8,
+
+    /// Most defaults a bidder's profile may show to bid, plus one (0 for
+    /// no limit)
+    bidder_default_cap: u8,
+
+    /// Reserved for future fields
+    pub reserved: [u8; Auction::RESERVED],
 }
 
 impl Auction {
@@ -88,19 +274,92 @@ impl Auction {
         8 +                        // reserve_price
         8 +                        // start_time
         8 +                        // end_time
-        1 +                        // state (enum)
+        1 +                        // state (u8)
         4 +                        // bid_count
         4 +                        // bids_processed
         16 +                       // highest_bid_handle (u128)
         32 +                       // current_leader
         32 +                       // winner
         8 +                        // winning_amount
+        16 +                       // bid_sum_handle (u128)
+        16 +                       // lowest_bid_handle (u128)
+        16 +                       // above_reserve_count_handle (u128)
+        1 +                        // mode (u8)
+        8 +                        // reveal_end_time
+        8 +                        // bid_collateral
+        8 +                        // highest_revealed_amount
+        4 +                        // bids_revealed
+        8 +                        // forfeited_collateral
         8 +                        // auction_id
-        1;                         // bump
+        1 +                        // bump
+        1 +                        // version
+        1 +                        // cancellation_policy (u8)
+        8 +                        // seller_bond
+        8 +                        // delivery_timeout
+        8 +                        // delivery_deadline
+        32 +                       // arbitrator
+        2 +                        // arbitration_fee_bps
+        1 +                        // settlement_callback (bool)
+        1 +                        // hook_mode (u8)
+        1 +                        // issues_receipt (bool)
+        1 +                        // bidder_default_cap (u8)
+        Self::RESERVED;            // reserved
+
+    /// Bytes of padding reserved for future fields
+    pub const RESERVED: usize = 1;
+
+    /// Current layout version
+    pub const VERSION: u8 = 1;
+
+    /// Space of an Auction account in the original deployed layout,
+    /// created before versioning
+    pub const LEGACY_SPACE: usize = 522;
+
+    /// Offset of the version byte in the current layout
+    pub const VERSION_OFFSET: usize = 607;
+
+    /// Where the original layout's fields live now: everything up to
+    /// `winning_amount` stayed put, while `auction_id` and `bump` moved
+    /// behind the statistics and commit-reveal fields
+    pub const LEGACY_FIELDS: [LegacyField; 3] = [
+        // discriminator through winning_amount
+        LegacyField { legacy: 0, current: 0, len: 513 },
+        // auction_id
+        LegacyField { legacy: 513, current: 598, len: 8 },
+        // bump
+        LegacyField { legacy: 521, current: 606, len: 1 },
+    ];
+
+    /// Current state of the auction
+    pub fn state(&self) -> Result<AuctionState> {
+        Ok(AuctionState::try_from(self.state)?)
+    }
+
+    pub fn set_state(&mut self, state: AuctionState) {
+        self.state = state as u8;
+    }
+
+    /// How bids are sealed (Inco encryption or commit-reveal)
+    pub fn mode(&self) -> Result<AuctionMode> {
+        Ok(AuctionMode::try_from(self.mode)?)
+    }
+
+    pub fn set_mode(&mut self, mode: AuctionMode) {
+        self.mode = mode as u8;
+    }
+
+    /// What the seller may do once the auction has bids
+    pub fn cancellation_policy(&self) -> Result<CancellationPolicy> {
+        Ok(CancellationPolicy::try_from(self.cancellation_policy)?)
+    }
+
+    pub fn set_cancellation_policy(&mut self, policy: CancellationPolicy) {
+        self.cancellation_policy = policy as u8;
+    }
 
     /// Check if the auction is currently accepting bids
     pub fn is_bidding_open(&self, current_time: i64) -> bool {
-        self.state == AuctionState::Open 
+        self.state == AuctionState::Open as u8
             && current_time >= self.start_time 
             && current_time < self.end_time
     }
@@ -110,11 +369,94 @@ impl Auction {
         current_time >= self.end_time
     }
 
+    /// Check if the reveal window is open (commit-reveal only)
+    pub fn is_reveal_open(&self, current_time: i64) -> bool {
+        self.state == AuctionState::Revealing as u8 && current_time < self.reveal_end_time
+    }
+
+    /// Check if this auction uses commit-reveal bidding
+    pub fn is_commit_reveal(&self) -> bool {
+        self.mode == AuctionMode::CommitReveal as u8
+    }
+
+    /// Check if the seller posted a `SellerBond`
+    pub fn is_bonded(&self) -> bool {
+        self.cancellation_policy == CancellationPolicy::Bonded as u8
+    }
+
+    /// Check if sealed bids lock a deposit the seller can settle from
+    pub fn takes_bid_deposits(&self) -> bool {
+        !self.is_commit_reveal() && self.bid_collateral > 0
+    }
+
+    /// Check if settlement holds the payment in escrow
+    pub fn is_escrowed(&self) -> bool {
+        self.delivery_timeout > 0
+    }
+
+    /// Check if disputes can be raised and resolved (escrow only)
+    pub fn has_arbitrator(&self) -> bool {
+        self.arbitrator != Pubkey::default()
+    }
+
+    /// Check if settlement calls back into a seller program
+    pub fn has_settlement_callback(&self) -> bool {
+        self.settlement_callback != 0
+    }
+
+    pub fn set_settlement_callback(&mut self, enabled: bool) {
+        self.settlement_callback = enabled as u8;
+    }
+
+    pub fn hook_mode(&self) -> Result<HookMode> {
+        Ok(HookMode::try_from(self.hook_mode)?)
+    }
+
+    pub fn set_hook_mode(&mut self, mode: HookMode) {
+        self.hook_mode = mode as u8;
+    }
+
+    /// Check if completion and cancellation invoke a hook program
+    pub fn has_hook(&self) -> bool {
+        self.hook_mode != HookMode::None as u8
+    }
+
+    /// Check if settlement mints a receipt NFT to the winner
+    pub fn issues_receipt(&self) -> bool {
+        self.issues_receipt != 0
+    }
+
+    pub fn set_issues_receipt(&mut self, enabled: bool) {
+        self.issues_receipt = enabled as u8;
+    }
+
+    /// Most defaults a bidder may have to bid (`None` for no limit)
+    pub fn max_bidder_defaults(&self) -> Option<u8> {
+        self.bidder_default_cap.checked_sub(1)
+    }
+
+    /// Sets the limit; callers ensure it is below `u8::MAX`
+    pub fn set_max_bidder_defaults(&mut self, max: Option<u8>) {
+        self.bidder_default_cap = max.map_or(0, |max| max + 1);
+    }
+
+    /// Check if the delivery window has ended (escrow only)
+    pub fn is_delivery_window_ended(&self, current_time: i64) -> bool {
+        current_time >= self.delivery_deadline
+    }
+
     /// Check if all bids have been processed
     pub fn all_bids_processed(&self) -> bool {
         self.bids_processed >= self.bid_count
     }
 
+    /// Check if the encrypted statistics have been accumulated
+    pub fn has_stats(&self) -> bool {
+        self.bid_sum_handle != 0
+            && self.lowest_bid_handle != 0
+            && self.above_reserve_count_handle != 0
+    }
+
     /// Get the title as a string (trimmed)
     pub fn get_title(&self) -> String {
         String::from_utf8_lossy(&self.title)
@@ -129,3 +471,23 @@ impl Auction {
             .to_string()
     }
 }
+
+// The packed struct must match the account size exactly
+const _: () = assert!(Auction::SPACE == 8 + std::mem::size_of::<Auction>());
+
+// The legacy field map must agree with the current layout
+const _: () = {
+    use std::mem::offset_of;
+    assert!(Auction::LEGACY_FIELDS[0].len == 8 + offset_of!(Auction, bid_sum_handle));
+    assert!(Auction::LEGACY_FIELDS[1].current == 8 + offset_of!(Auction, auction_id));
+    assert!(Auction::LEGACY_FIELDS[2].current == 8 + offset_of!(Auction, bump));
+    assert!(Auction::VERSION_OFFSET == 8 + offset_of!(Auction, version));
+};
+
+impl Versioned for Auction {
+    const VERSION: u8 = Auction::VERSION;
+    const SPACE: usize = Auction::SPACE;
+    const LEGACY_SPACE: usize = Auction::LEGACY_SPACE;
+    const VERSION_OFFSET: usize = Auction::VERSION_OFFSET;
+    const LEGACY_FIELDS: &'static [LegacyField] = &Auction::LEGACY_FIELDS;
+}
@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_DELEGATES;
+use crate::errors::ShadowBidError;
+
+/// An administrative action the authority can delegate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum Permission {
+    /// `cancel_auction`
+    Cancel,
+    /// `create_auction_metadata` and `update_auction_metadata`
+    UpdateMetadata,
+}
+
+impl Permission {
+    /// Bits of every permission; a delegate can't be granted any other
+    pub const ALL: u8 = Self::Cancel.bit() | Self::UpdateMetadata.bit();
+
+    /// This permission's bit in `Delegate::permissions`
+    pub const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// An operator acting for the authority, within its permissions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Delegate {
+    /// The delegate's key (the default pubkey marks a free slot)
+    pub delegate: Pubkey,
+
+    /// `Permission` bits granted to the delegate
+    pub permissions: u8,
+}
+
+impl Delegate {
+    /// Check if the delegate was granted `permission`
+    pub fn has(&self, permission: Permission) -> bool {
+        self.permissions & permission.bit() != 0
+    }
+}
+
+/// Who administers an auction and who is paid for it, when that isn't the
+/// seller - at most one per auction
+///
+/// Created with the auction when `CreateAuctionParams::authority` is set,
+/// or by the seller's first `transfer_authority` or `set_delegate`. An
+/// auction without one is administered by, and pays, its seller.
+///
+/// The authority (a wallet, or a multisig signing through its program)
+/// cancels the auction, manages its metadata and receipt, names
+/// delegates and hands authority on. The beneficiary, fixed at creation,
+/// receives the winner's payment directly or from escrow.
+#[account]
+pub struct AuctionAuthority {
+    /// The auction this record administers
+    pub auction: Pubkey,
+
+    /// Who takes administrative actions on the auction
+    pub authority: Pubkey,
+
+    /// Who receives the winner's payment
+    pub beneficiary: Pubkey,
+
+    /// Operators the authority granted some of its permissions
+    pub delegates: [Delegate; MAX_DELEGATES],
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl AuctionAuthority {
+    /// Space required for the AuctionAuthority account
+    pub const SPACE: usize = 8 +  // discriminator
+        32 +                       // auction
+        32 +                       // authority
+        32 +                       // beneficiary
+        (32 + 1) * MAX_DELEGATES + // delegates
+        1;                         // bump
+
+    /// Check if `signer` may act: the authority, or a delegate granted
+    /// `permission` (`None` for actions only the authority takes)
+    pub fn allows(&self, signer: &Pubkey, permission: Option<Permission>) -> bool {
+        *signer == self.authority
+            || permission.is_some_and(|permission| {
+                self.delegates
+                    .iter()
+                    .any(|delegate| delegate.delegate == *signer && delegate.has(permission))
+            })
+    }
+
+    /// Grants `delegate` exactly `permissions`, taking a free slot for a
+    /// new delegate; no permissions removes it
+    pub fn set_delegate(&mut self, delegate: Pubkey, permissions: u8) -> Result<()> {
+        require!(delegate != Pubkey::default(), ShadowBidError::InvalidAuthority);
+        require!(permissions & !Permission::ALL == 0, ShadowBidError::InvalidPermissions);
+
+        let slot = match self.delegates.iter().position(|slot| slot.delegate == delegate) {
+            Some(slot) => slot,
+            None if permissions == 0 => return Ok(()),
+            None => self
+                .delegates
+                .iter()
+                .position(|slot| slot.delegate == Pubkey::default())
+                .ok_or(ShadowBidError::TooManyDelegates)?,
+        };
+        self.delegates[slot] = if permissions == 0 {
+            Delegate::default()
+        } else {
+            Delegate { delegate, permissions }
+        };
+        Ok(())
+    }
+
+    /// Checks `signer` is the authority, first filling in a record just
+    /// created by `init_if_needed` with the seller in both roles
+    pub(crate) fn claim(&mut self, auction: Pubkey, seller: Pubkey, bump: u8, signer: &Pubkey) -> Result<()> {
+        if self.auction == Pubkey::default() {
+            require_keys_eq!(*signer, seller, ShadowBidError::NotSeller);
+            self.auction = auction;
+            self.authority = seller;
+            self.beneficiary = seller;
+            self.bump = bump;
+        }
+        require_keys_eq!(*signer, self.authority, ShadowBidError::NotAuthority);
+        Ok(())
+    }
+
+    /// Reads the record account `record`, if the auction has one
+    ///
+    /// Instructions pass the seeds-checked address either way, so an
+    /// account the program doesn't own means the seller holds both roles.
+    pub fn load_from(record: &AccountInfo) -> Result<Option<Self>> {
+        if record.owner != &crate::ID || record.data_is_empty() {
+            return Ok(None);
+        }
+        let data = record.try_borrow_data()?;
+        Ok(Some(Self::try_deserialize(&mut &data[..])?))
+    }
+
+    /// Checks `signer` may take an administrative action on an auction
+    /// sold by `seller`, whose record account is `record`
+    pub fn check(
+        record: &AccountInfo,
+        seller: &Pubkey,
+        signer: &Pubkey,
+        permission: Option<Permission>,
+    ) -> Result<()> {
+        match Self::load_from(record)? {
+            Some(record) => {
+                require!(record.allows(signer, permission), ShadowBidError::NotAuthority)
+            }
+            None => require_keys_eq!(*signer, *seller, ShadowBidError::NotSeller),
+        }
+        Ok(())
+    }
+
+    /// The beneficiary of an auction sold by `seller`, whose record
+    /// account is `record`
+    pub fn beneficiary_from(record: &AccountInfo, seller: &Pubkey) -> Result<Pubkey> {
+        Ok(Self::load_from(record)?.map_or(*seller, |record| record.beneficiary))
+    }
+}
@@ -1,4 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::constants::SETTLEMENT_WINDOW;
+use crate::state::{LegacyField, Versioned};
 
 /// Individual bid account - one per bidder per auction
 #[account]
@@ -22,9 +26,35 @@ pub struct Bid {
     /// Whether this bid has been processed during winner determination
     /// Prevents double-counting in the comparison loop
     pub processed: bool,
+
+    /// Commit-reveal only: hash of (amount, salt, bidder)
+    pub commitment: [u8; 32],
+
+    /// Collateral held in this account until reveal, or a sealed bid's
+    /// deposit, held until settlement
+    pub collateral: u64,
+
+    /// Commit-reveal only: the revealed bid amount
+    pub revealed_amount: u64,
+
+    /// Commit-reveal only: whether the commitment has been revealed
+    pub revealed: bool,
     
     /// Bump seed for PDA derivation
     pub bump: u8,
+
+    /// Account layout version (`Bid::VERSION`)
+    pub version: u8,
+
+    /// Whether the bidder claimed their share of a slashed seller bond
+    pub compensated: bool,
+
+    /// Unix timestamp this bid was determined the winner at (0 unless it
+    /// won); the settlement window runs from it
+    pub won_at: i64,
+
+    /// Reserved for future fields
+    pub reserved: [u8; Bid::RESERVED],
 }
 
 impl Bid {
@@ -36,7 +66,50 @@ impl Bid {
         8 +                        // created_at
         8 +                        // updated_at
         1 +                        // processed
-        1;                         // bump
+        32 +                       // commitment
+        8 +                        // collateral
+        8 +                        // revealed_amount
+        1 +                        // revealed
+        1 +                        // bump
+        1 +                        // version
+        1 +                        // compensated
+        8 +                        // won_at
+        Self::RESERVED;            // reserved
+
+    /// Bytes of padding reserved for future fields
+    pub const RESERVED: usize = 23;
+
+    /// Current layout version
+    pub const VERSION: u8 = 1;
+
+    /// Space of a Bid account in the original deployed layout, created
+    /// before versioning
+    pub const LEGACY_SPACE: usize = 106;
+
+    /// Offset of the version byte in the current layout
+    pub const VERSION_OFFSET: usize = 155;
+
+    /// Where the original layout's fields live now: everything up to
+    /// `processed` stayed put, while `bump` moved behind the
+    /// commit-reveal fields
+    pub const LEGACY_FIELDS: [LegacyField; 2] = [
+        // discriminator through processed
+        LegacyField { legacy: 0, current: 0, len: 105 },
+        // bump
+        LegacyField { legacy: 105, current: 154, len: 1 },
+    ];
+
+    /// Compute the commit-reveal commitment for a bid
+    ///
+    /// sha256(amount (u64 LE) || salt || bidder)
+    pub fn commitment_hash(amount: u64, salt: &[u8; 32], bidder: &Pubkey) -> [u8; 32] {
+        hashv(&[&amount.to_le_bytes(), salt, bidder.as_ref()]).to_bytes()
+    }
+
+    /// Check if the winner's settlement window has ended
+    pub fn is_settlement_window_ended(&self, current_time: i64) -> bool {
+        self.won_at != 0 && current_time >= self.won_at + SETTLEMENT_WINDOW
+    }
 
     /// Check if this bid has been processed
     pub fn is_processed(&self) -> bool {
@@ -53,3 +126,11 @@ impl Bid {
         self.bidder == *bidder
     }
 }
+
+impl Versioned for Bid {
+    const VERSION: u8 = Bid::VERSION;
+    const SPACE: usize = Bid::SPACE;
+    const LEGACY_SPACE: usize = Bid::LEGACY_SPACE;
+    const VERSION_OFFSET: usize = Bid::VERSION_OFFSET;
+    const LEGACY_FIELDS: &'static [LegacyField] = &Bid::LEGACY_FIELDS;
+}
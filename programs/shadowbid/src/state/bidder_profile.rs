@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+/// A bidder's participation and payment history - one per bidder,
+/// created with their first bid
+///
+/// Lets sellers keep out bidders who win and then never pay: an auction
+/// created with `max_bidder_defaults` rejects bids from profiles with
+/// more defaults than that.
+#[account]
+pub struct BidderProfile {
+    /// The bidder this profile belongs to
+    pub bidder: Pubkey,
+
+    /// Number of bids placed (updating a bid doesn't count)
+    pub bids_placed: u64,
+
+    /// Number of auctions the bidder was determined the winner of
+    pub auctions_won: u64,
+
+    /// Number of won auctions the bidder paid for
+    pub auctions_settled: u64,
+
+    /// Number of won auctions declared in default for not settling in
+    /// time
+    pub auctions_defaulted: u64,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl BidderProfile {
+    /// Space required for the BidderProfile account
+    pub const SPACE: usize = 8 +  // discriminator
+        32 +                       // bidder
+        8 +                        // bids_placed
+        8 +                        // auctions_won
+        8 +                        // auctions_settled
+        8 +                        // auctions_defaulted
+        1;                         // bump
+
+    /// Check if the bidder may bid on an auction allowing at most
+    /// `max_defaults` defaults (`None` for no limit)
+    pub fn is_eligible(&self, max_defaults: Option<u8>) -> bool {
+        max_defaults.is_none_or(|max| self.auctions_defaulted <= max as u64)
+    }
+
+    /// Applies `update` to the profile account `profile`, if the bidder
+    /// has one
+    ///
+    /// Winners whose only bids predate profiles have none, so an account
+    /// the program doesn't own is left alone.
+    pub fn update_from(profile: &AccountInfo, update: impl FnOnce(&mut Self)) -> Result<()> {
+        super::update_if_owned(profile, update)
+    }
+}
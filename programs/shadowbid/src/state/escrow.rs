@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+/// Vault holding the winner's payment for an escrowed auction
+///
+/// Created by `settle_auction`, which the winner pays for. The payment is
+/// the account's lamports above rent; it goes to the beneficiary when the
+/// winner confirms delivery or the delivery window ends, and the rent
+/// goes back to the winner.
+#[account]
+pub struct Escrow {
+    /// The auction this payment settles
+    pub auction: Pubkey,
+
+    /// The winner who paid
+    pub buyer: Pubkey,
+
+    /// The beneficiary the payment is released to: the seller, unless
+    /// the auction's authority record names another
+    pub beneficiary: Pubkey,
+
+    /// Escrowed payment in lamports
+    pub amount: u64,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl Escrow {
+    /// Space required for the Escrow account
+    pub const SPACE: usize = 8 +  // discriminator
+        32 +                       // auction
+        32 +                       // buyer
+        32 +                       // beneficiary
+        8 +                        // amount
+        1;                         // bump
+}
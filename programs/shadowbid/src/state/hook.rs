@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+/// The program an auction calls when it completes or is cancelled - one per
+/// auction created with a `hook`
+///
+/// Lets partners run their own logic on completion in the same
+/// transaction, e.g. minting a membership to the winner or updating
+/// on-chain inventory. See `crate::hook` for the interface the program
+/// implements.
+#[account]
+pub struct AuctionHook {
+    /// The auction whose completion is reported
+    pub auction: Pubkey,
+
+    /// The program invoked by `settle_auction` and `cancel_auction`
+    pub program: Pubkey,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl AuctionHook {
+    /// Space required for the AuctionHook account
+    pub const SPACE: usize = 8 +  // discriminator
+        32 +                       // auction
+        32 +                       // program
+        1;                         // bump
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+/// Structured auction metadata - one per auction, sized to its contents
+#[account]
+pub struct AuctionMetadata {
+    /// The auction this metadata describes
+    pub auction: Pubkey,
+
+    /// Full auction title
+    pub title: String,
+
+    /// Full auction description
+    pub description: String,
+
+    /// Off-chain metadata document (Arweave, IPFS or HTTPS URI)
+    pub uri: String,
+
+    /// SHA-256 of the document at `uri`
+    pub content_hash: [u8; 32],
+
+    /// Category tags for discovery
+    pub tags: Vec<String>,
+
+    /// Unix timestamp when the metadata was last written
+    pub updated_at: i64,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl AuctionMetadata {
+    /// Space required for an AuctionMetadata account with these contents
+    pub fn space(title: &str, description: &str, uri: &str, tags: &[String]) -> usize {
+        8 +                                               // discriminator
+            32 +                                          // auction
+            4 + title.len() +                             // title
+            4 + description.len() +                       // description
+            4 + uri.len() +                               // uri
+            32 +                                          // content_hash
+            4 + tags.iter().map(|tag| 4 + tag.len()).sum::<usize>() + // tags
+            8 +                                           // updated_at
+            1                                             // bump
+    }
+
+    /// Check a fetched metadata document against the recorded hash
+    pub fn matches_content(&self, content: &[u8]) -> bool {
+        hash(content).to_bytes() == self.content_hash
+    }
+}
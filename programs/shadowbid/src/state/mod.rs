@@ -1,5 +1,48 @@
 pub mod auction;
+pub mod auction_authority;
 pub mod bid;
+pub mod bidder_profile;
+pub mod escrow;
+pub mod hook;
+pub mod metadata;
+pub mod receipt;
+pub mod registry;
+pub mod seller_bond;
+pub mod settlement_callback;
+pub mod seller_profile;
+pub mod versioning;
 
 pub use auction::*;
+pub use auction_authority::*;
 pub use bid::*;
+pub use bidder_profile::*;
+pub use escrow::*;
+pub use hook::*;
+pub use metadata::*;
+pub use receipt::*;
+pub use registry::*;
+pub use seller_bond::*;
+pub use settlement_callback::*;
+pub use seller_profile::*;
+pub use versioning::*;
+
+use anchor_lang::prelude::*;
+
+/// Applies `update` to the program account `info` holds, if it holds one
+///
+/// Accounts the program doesn't own or that are empty are left alone, so
+/// optional records that predate their instruction (like profiles) are
+/// skipped instead of failing it.
+pub fn update_if_owned<T: AccountSerialize + AccountDeserialize>(
+    info: &AccountInfo,
+    update: impl FnOnce(&mut T),
+) -> Result<()> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(());
+    }
+    let mut data = info.try_borrow_mut_data()?;
+    let mut account = T::try_deserialize(&mut &data[..])?;
+    update(&mut account);
+    account.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+/// Proof of purchase for an auction created with `issue_receipt`
+///
+/// Created by `settle_auction`, which mints the receipt NFT (a Token-2022
+/// mint whose metadata names the auction, its title and the winning
+/// amount) to the winner. The NFT can change hands; this account records
+/// whether the seller has fulfilled it.
+#[account]
+pub struct Receipt {
+    /// The auction the receipt proves
+    pub auction: Pubkey,
+
+    /// The receipt NFT mint
+    pub mint: Pubkey,
+
+    /// The winner the receipt was minted to
+    pub winner: Pubkey,
+
+    /// Winning amount in lamports
+    pub amount: u64,
+
+    /// Unix timestamp the seller redeemed the receipt at (0 until then)
+    pub redeemed_at: i64,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl Receipt {
+    /// Space required for the Receipt account
+    pub const SPACE: usize = 8 +  // discriminator
+        32 +                       // auction
+        32 +                       // mint
+        32 +                       // winner
+        8 +                        // amount
+        8 +                        // redeemed_at
+        1;                         // bump
+
+    /// Metadata field naming the auction PDA
+    pub const AUCTION_FIELD: &'static str = "auction";
+
+    /// Metadata field holding the winning amount in lamports
+    pub const AMOUNT_FIELD: &'static str = "amount";
+
+    /// Metadata field set to "true" once the seller redeems the receipt
+    pub const FULFILLED_FIELD: &'static str = "fulfilled";
+
+    /// Check if the seller has marked the receipt fulfilled
+    pub fn is_redeemed(&self) -> bool {
+        self.redeemed_at != 0
+    }
+}
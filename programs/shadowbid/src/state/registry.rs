@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::AUCTIONS_PER_PAGE;
+
+/// Global registry - assigns every auction its sequential ID
+#[account]
+pub struct Registry {
+    /// Number of auctions created, and the ID of the next one
+    pub auction_count: u64,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl Registry {
+    /// Space required for the Registry account
+    pub const SPACE: usize = 8 +  // discriminator
+        8 +                        // auction_count
+        1;                         // bump
+
+    /// Number of auction pages created so far
+    pub fn page_count(&self) -> u64 {
+        self.auction_count.div_ceil(AUCTIONS_PER_PAGE as u64)
+    }
+}
+
+/// Per-seller auction counter
+#[account]
+pub struct SellerCounter {
+    /// The seller this counter belongs to
+    pub seller: Pubkey,
+
+    /// Number of auctions the seller has created
+    pub auction_count: u64,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl SellerCounter {
+    /// Space required for the SellerCounter account
+    pub const SPACE: usize = 8 +  // discriminator
+        32 +                       // seller
+        8 +                        // auction_count
+        1;                         // bump
+}
+
+/// Fixed-size page of open auctions
+///
+/// Auction `id` lives in page `id / AUCTIONS_PER_PAGE` at slot
+/// `id % AUCTIONS_PER_PAGE`; the slot is cleared once bidding closes
+/// or the auction is cancelled.
+#[account]
+pub struct AuctionPage {
+    /// Page index (PDA seed)
+    pub index: u64,
+
+    /// Number of occupied slots
+    pub open_count: u32,
+
+    /// Open auction addresses, `Pubkey::default()` for empty slots
+    pub auctions: [Pubkey; AUCTIONS_PER_PAGE],
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl AuctionPage {
+    /// Space required for an AuctionPage account
+    pub const SPACE: usize = 8 +  // discriminator
+        8 +                        // index
+        4 +                        // open_count
+        32 * AUCTIONS_PER_PAGE +   // auctions
+        1;                         // bump
+
+    /// Index of the page listing auction `auction_id`
+    pub fn index_of(auction_id: u64) -> u64 {
+        auction_id / AUCTIONS_PER_PAGE as u64
+    }
+
+    fn slot_of(auction_id: u64) -> usize {
+        (auction_id % AUCTIONS_PER_PAGE as u64) as usize
+    }
+
+    /// Lists auction `auction_id` in its slot
+    pub fn list(&mut self, auction_id: u64, auction: Pubkey) {
+        self.auctions[Self::slot_of(auction_id)] = auction;
+        self.open_count += 1;
+    }
+
+    /// Clears auction `auction_id`'s slot, returning whether it was listed
+    pub fn unlist(&mut self, auction_id: u64, auction: &Pubkey) -> bool {
+        let slot = &mut self.auctions[Self::slot_of(auction_id)];
+        if slot != auction {
+            return false;
+        }
+        *slot = Pubkey::default();
+        self.open_count -= 1;
+        true
+    }
+
+    /// Clears the auction from the page account `page`, if listed there
+    ///
+    /// Auctions created before the registry have no page, so an account
+    /// the program doesn't own is left alone.
+    pub fn unlist_from(page: &AccountInfo, auction_id: u64, auction: &Pubkey) -> Result<()> {
+        if page.owner != &crate::ID || page.data_is_empty() {
+            return Ok(());
+        }
+        let mut data = page.try_borrow_mut_data()?;
+        let mut listing = Self::try_deserialize(&mut &data[..])?;
+        if listing.unlist(auction_id, auction) {
+            listing.try_serialize(&mut &mut data[..])?;
+        }
+        Ok(())
+    }
+
+    /// Addresses of the open auctions on this page
+    pub fn open_auctions(&self) -> impl Iterator<Item = &Pubkey> {
+        self.auctions.iter().filter(|auction| **auction != Pubkey::default())
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+/// Vault holding a seller's good-faith bond - one per bonded auction
+///
+/// The bond is the account's lamports above rent. It goes back to the
+/// seller once the auction settles, ends without a sale or is cancelled
+/// before any bid, and is slashed when the seller cancels with bids: split
+/// evenly between the bidders, with the indivisible remainder paid to the
+/// `Treasury`. A dispute ruling pays the winner their share of it.
+#[account]
+pub struct SellerBond {
+    /// The auction this bond backs
+    pub auction: Pubkey,
+
+    /// The seller who posted the bond and receives the rent back
+    pub seller: Pubkey,
+
+    /// Lamports still held as the bond
+    pub amount: u64,
+
+    /// Lamports each bidder can claim once the bond has been slashed
+    pub compensation_per_bid: u64,
+
+    /// Bidders who have not claimed their share yet; the vault closes
+    /// to the seller after the last claim
+    pub claims_remaining: u32,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl SellerBond {
+    /// Space required for the SellerBond account
+    pub const SPACE: usize = 8 +  // discriminator
+        32 +                       // auction
+        32 +                       // seller
+        8 +                        // amount
+        8 +                        // compensation_per_bid
+        4 +                        // claims_remaining
+        1;                         // bump
+
+    /// Slashes the bond between `bidders` bidders, returning the
+    /// indivisible remainder owed to the treasury
+    pub fn slash(&mut self, bidders: u32) -> u64 {
+        let bidders = bidders as u64;
+        let remainder = self.amount % bidders;
+        self.compensation_per_bid = self.amount / bidders;
+        self.claims_remaining = if self.compensation_per_bid > 0 { bidders as u32 } else { 0 };
+        self.amount -= remainder;
+        remainder
+    }
+}
+
+/// Protocol treasury - receives the part of slashed bonds no bidder can claim
+#[account]
+pub struct Treasury {
+    /// Lamports received from slashed bonds
+    pub total_slashed: u64,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl Treasury {
+    /// Space required for the Treasury account
+    pub const SPACE: usize = 8 +  // discriminator
+        8 +                        // total_slashed
+        1;                         // bump
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::BPS_DENOMINATOR;
+
+/// Why the seller cancelled an auction, given to `cancel_auction`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[repr(u8)]
+pub enum CancellationReason {
+    /// The item is no longer available, damaged or already sold elsewhere
+    ItemUnavailable,
+    /// The listing was wrong: title, reserve price or duration
+    ListingError,
+    /// Too few or too low bids
+    LowDemand,
+    /// The seller is relisting the item in another auction
+    Relisting,
+    /// Any other reason
+    #[default]
+    Other,
+}
+
+impl CancellationReason {
+    /// Number of reasons, the length of `SellerProfile::cancellations`
+    pub const COUNT: usize = 5;
+}
+
+/// A seller's track record - one per seller, created with their first
+/// auction
+///
+/// Updated as the seller's auctions complete, so bidders and marketplaces
+/// can tell established sellers from new ones. Auctions created before
+/// the seller had a profile count towards it once they complete.
+#[account]
+pub struct SellerProfile {
+    /// The seller this profile belongs to
+    pub seller: Pubkey,
+
+    /// Number of auctions the seller has created
+    pub auctions_created: u64,
+
+    /// Number of auctions settled (paid by the winner, into escrow or not)
+    pub auctions_settled: u64,
+
+    /// Number of auctions cancelled after bids were placed
+    pub cancelled_with_bids: u64,
+
+    /// Number of settled auctions whose dispute the arbitrator ruled,
+    /// at least in part, for the winner
+    pub auctions_defaulted: u64,
+
+    /// Winning amounts of the settled auctions in lamports, less what
+    /// disputes refunded to winners
+    pub total_volume: u64,
+
+    /// Cancellations per `CancellationReason`, with or without bids
+    pub cancellations: [u32; CancellationReason::COUNT],
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl SellerProfile {
+    /// Space required for the SellerProfile account
+    pub const SPACE: usize = 8 +  // discriminator
+        32 +                       // seller
+        8 +                        // auctions_created
+        8 +                        // auctions_settled
+        8 +                        // cancelled_with_bids
+        8 +                        // auctions_defaulted
+        8 +                        // total_volume
+        4 * CancellationReason::COUNT + // cancellations
+        1;                         // bump
+
+    /// Records a settlement for `amount` lamports
+    pub fn record_settlement(&mut self, amount: u64) {
+        self.auctions_settled += 1;
+        self.total_volume = self.total_volume.saturating_add(amount);
+    }
+
+    /// Records a cancellation, after bids if `bid_count > 0`
+    pub fn record_cancellation(&mut self, reason: CancellationReason, bid_count: u32) {
+        self.cancellations[reason as usize] += 1;
+        if bid_count > 0 {
+            self.cancelled_with_bids += 1;
+        }
+    }
+
+    /// Records a dispute ruled for the winner, who was refunded `refund`
+    /// lamports
+    pub fn record_default(&mut self, refund: u64) {
+        self.auctions_defaulted += 1;
+        self.total_volume = self.total_volume.saturating_sub(refund);
+    }
+
+    /// Number of cancellations given `reason`
+    pub fn cancellation_count(&self, reason: CancellationReason) -> u32 {
+        self.cancellations[reason as usize]
+    }
+
+    /// Share of the seller's completed auctions with bids that were
+    /// settled without a default, in basis points (`BPS_DENOMINATOR`
+    /// for a seller with none yet)
+    pub fn reliability_bps(&self) -> u16 {
+        let completed = self.auctions_settled + self.cancelled_with_bids;
+        if completed == 0 {
+            return BPS_DENOMINATOR;
+        }
+        let kept = self.auctions_settled.saturating_sub(self.auctions_defaulted);
+        (kept as u128 * BPS_DENOMINATOR as u128 / completed as u128) as u16
+    }
+
+    /// Applies `update` to the profile account `profile`, if the seller
+    /// has one
+    ///
+    /// Sellers whose only auctions predate profiles have none yet, so an
+    /// account the program doesn't own is left alone.
+    pub fn update_from(profile: &AccountInfo, update: impl FnOnce(&mut Self)) -> Result<()> {
+        super::update_if_owned(profile, update)
+    }
+}
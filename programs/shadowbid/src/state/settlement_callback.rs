@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+/// The program `settle_auction` calls back with the settlement result -
+/// one per auction created with `settlement_callback`
+///
+/// Lets a seller program (e.g. a lending protocol selling liquidated
+/// collateral from a PDA) act on the payment in the same transaction.
+/// See `crate::callback` for the interface the program implements.
+#[account]
+pub struct SettlementCallback {
+    /// The auction whose settlement is reported
+    pub auction: Pubkey,
+
+    /// The program invoked by `settle_auction`
+    pub program: Pubkey,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl SettlementCallback {
+    /// Space required for the SettlementCallback account
+    pub const SPACE: usize = 8 +  // discriminator
+        32 +                       // auction
+        32 +                       // program
+        1;                         // bump
+}
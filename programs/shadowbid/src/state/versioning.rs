@@ -0,0 +1,126 @@
+use std::cell::{Ref, RefMut};
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::{Discriminator, ZeroCopy};
+
+use crate::errors::ShadowBidError;
+
+/// A run of bytes that moved between the legacy and the current layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LegacyField {
+    /// Offset in the legacy account data (discriminator included)
+    pub legacy: usize,
+    /// Offset in the current account data (discriminator included)
+    pub current: usize,
+    pub len: usize,
+}
+
+/// Accounts whose layout ends in a version byte and reserved padding
+///
+/// Accounts created before versioning (`LEGACY_SPACE` bytes) use the
+/// original deployed layout, whose trailing fields now sit after fields
+/// added since. `LEGACY_FIELDS` maps every legacy byte to its place in
+/// the current layout; everything else, the version byte included,
+/// starts zeroed. Legacy accounts decode as version 0 here, and are
+/// rewritten in place by `migrate`. New fields should take their bytes
+/// from the reserved padding, so the account size doesn't change again.
+pub trait Versioned: AccountDeserialize + Discriminator {
+    /// Current layout version
+    const VERSION: u8;
+
+    /// Size of the current layout
+    const SPACE: usize;
+
+    /// Size of the original layout, before versioning
+    const LEGACY_SPACE: usize;
+
+    /// Offset of the version byte in the current layout
+    const VERSION_OFFSET: usize;
+
+    /// Where the bytes of the original layout live in the current one
+    const LEGACY_FIELDS: &'static [LegacyField];
+
+    /// Rearranges legacy account data into the current layout, as
+    /// version 0
+    fn upgrade_legacy(legacy: &[u8]) -> Vec<u8> {
+        let mut data = vec![0; Self::SPACE];
+        for field in Self::LEGACY_FIELDS {
+            data[field.current..field.current + field.len]
+                .copy_from_slice(&legacy[field.legacy..field.legacy + field.len]);
+        }
+        data
+    }
+
+    /// Deserializes account data in either layout; a legacy account
+    /// reads as version 0, with the fields it lacks zeroed
+    fn try_deserialize_versioned(data: &[u8]) -> Result<Self> {
+        if data.len() != Self::LEGACY_SPACE {
+            return Self::try_deserialize(&mut &data[..]);
+        }
+        Self::try_deserialize(&mut &Self::upgrade_legacy(data)[..])
+    }
+}
+
+/// Rewrites a legacy account in the current layout and stamps its
+/// version, with `payer` topping up the rent
+pub fn migrate<'info, T: Versioned>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let mut data = {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == *T::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        require!(data.len() != T::SPACE, ShadowBidError::AccountAlreadyMigrated);
+        require!(data.len() == T::LEGACY_SPACE, ErrorCode::AccountDidNotDeserialize);
+        T::upgrade_legacy(&data)
+    };
+    data[T::VERSION_OFFSET] = T::VERSION;
+
+    let rent = Rent::get()?.minimum_balance(T::SPACE);
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    account.resize(T::SPACE)?;
+    account.try_borrow_mut_data()?.copy_from_slice(&data);
+
+    Ok(())
+}
+
+/// `AccountLoader` access for versioned zero-copy accounts
+///
+/// `AccountLoader` slices `size_of::<T>()` bytes out of the account and
+/// panics on shorter data, so legacy accounts are rejected with
+/// `AccountDidNotDeserialize` first, as a Borsh `Account` would.
+pub trait LoadVersioned<T> {
+    fn load_versioned(&self) -> Result<Ref<'_, T>>;
+
+    fn load_versioned_mut(&self) -> Result<RefMut<'_, T>>;
+}
+
+impl<'info, T: Versioned + ZeroCopy + Owner> LoadVersioned<T> for AccountLoader<'info, T> {
+    fn load_versioned(&self) -> Result<Ref<'_, T>> {
+        require!(self.as_ref().data_len() == T::SPACE, ErrorCode::AccountDidNotDeserialize);
+        self.load()
+    }
+
+    fn load_versioned_mut(&self) -> Result<RefMut<'_, T>> {
+        require!(self.as_ref().data_len() == T::SPACE, ErrorCode::AccountDidNotDeserialize);
+        self.load_mut()
+    }
+}
@@ -0,0 +1,100 @@
+//! Dispute resolution: the arbitrator named at creation splits a disputed
+//! payment between the winner and the seller after taking its fee
+//!
+//! Disputed → Settled
+
+mod common;
+
+use common::*;
+use shadowbid::state::AuctionState;
+use shadowbid_client::pda;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn arbitrator_splits_payment_after_fee() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.insecure_clone();
+    let arbitrator = h.arbitrator.pubkey();
+    let escrow_rent = h.escrow_rent().await;
+    let (auction, winner) = h.escrowed_sale(10_000).await;
+
+    let state = h.auction(&auction).await;
+    assert_eq!(state.arbitrator, arbitrator);
+    assert_eq!({ state.arbitration_fee_bps }, ARBITRATION_FEE_BPS);
+
+    h.dispute(&auction, &winner).await.unwrap();
+    h.submit_evidence(&auction, &seller, "ar://tracking-receipt").await.unwrap();
+    h.submit_evidence(&auction, &winner, "ar://unboxing-video").await.unwrap();
+
+    let seller_before = h.lamports(&seller.pubkey()).await;
+    let winner_before = h.lamports(&winner.pubkey()).await;
+    let arbitrator_before = h.lamports(&arbitrator).await;
+    h.resolve(&auction, 7_500).await.unwrap();
+
+    // 2.5% fee, then 75% of the rest back to the winner, rounded down
+    let fee = 250;
+    let to_buyer = (10_000 - fee) * 3 / 4;
+    let to_seller = 10_000 - fee - to_buyer;
+    assert_eq!(h.lamports(&arbitrator).await, arbitrator_before + fee);
+    assert_eq!(h.lamports(&winner.pubkey()).await, winner_before + to_buyer + escrow_rent);
+    assert_eq!(h.lamports(&seller.pubkey()).await, seller_before + to_seller);
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+    let (escrow, _) = pda::escrow_address(&auction);
+    assert_eq!(h.lamports(&escrow).await, 0);
+}
+
+#[tokio::test]
+async fn full_refund_pays_seller_nothing() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let (auction, winner) = h.escrowed_sale(10_000).await;
+    h.dispute(&auction, &winner).await.unwrap();
+
+    let seller_before = h.lamports(&seller).await;
+    let winner_before = h.lamports(&winner.pubkey()).await;
+    let escrow_rent = h.escrow_rent().await;
+    h.resolve(&auction, 10_000).await.unwrap();
+
+    assert_eq!(h.lamports(&seller).await, seller_before);
+    assert_eq!(h.lamports(&winner.pubkey()).await, winner_before + 9_750 + escrow_rent);
+    assert!(h.escrow(&auction).await.is_none());
+}
+
+#[tokio::test]
+async fn ruling_for_the_winner_slashes_the_seller_bond() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let bond_rent = h.bond_rent().await;
+    let escrow_rent = h.escrow_rent().await;
+    let mut params = h.escrowed_params();
+    params.seller_bond = Some(SELLER_BOND);
+    let auction = h.create_with(params).await.unwrap();
+
+    let winner = h.keypair();
+    h.place_sealed_bid(&auction, &winner, 10_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, std::slice::from_ref(&winner)).await;
+    h.settle(&auction, &winner).await.unwrap();
+    h.dispute(&auction, &winner).await.unwrap();
+
+    let seller_before = h.lamports(&seller).await;
+    let winner_before = h.lamports(&winner.pubkey()).await;
+    h.resolve(&auction, 7_500).await.unwrap();
+
+    // The winner takes the ruling's share of the bond as well; the rest
+    // goes back to the seller with the vault's rent
+    let to_buyer = (10_000 - 250) * 3 / 4;
+    let to_seller = 10_000 - 250 - to_buyer;
+    let slashed = SELLER_BOND * 3 / 4;
+    assert_eq!(
+        h.lamports(&winner.pubkey()).await,
+        winner_before + to_buyer + escrow_rent + slashed
+    );
+    assert_eq!(
+        h.lamports(&seller).await,
+        seller_before + to_seller + SELLER_BOND - slashed + bond_rent
+    );
+    assert!(h.seller_bond(&auction).await.is_none());
+}
@@ -0,0 +1,178 @@
+//! Auction authority: a separate authority for administrative actions and
+//! beneficiary for the payment, delegates with scoped permissions, and
+//! handing authority on
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::instructions::{ArbitrationParams, AuthorityParams};
+use shadowbid::state::{AuctionState, CancellationReason, Permission};
+use shadowbid_client::instructions as ix;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Creates a sealed auction administered by `authority` and paying
+/// `beneficiary`, escrowed with the harness arbitrator if `escrowed`
+async fn create_governed(h: &mut Harness, authority: &Pubkey, beneficiary: &Pubkey, escrowed: bool) -> Pubkey {
+    let mut params = h.params(false);
+    params.authority = Some(AuthorityParams {
+        authority: *authority,
+        beneficiary: *beneficiary,
+    });
+    if escrowed {
+        params.delivery_timeout = Some(DELIVERY_TIMEOUT);
+        params.arbitration = Some(ArbitrationParams {
+            arbitrator: h.arbitrator.pubkey(),
+            fee_bps: ARBITRATION_FEE_BPS,
+        });
+    }
+    h.create_with(params).await.unwrap()
+}
+
+/// Places a single bid of `amount` and runs the auction through
+/// finalization, returning the winner
+async fn single_winner(h: &mut Harness, auction: &Pubkey, amount: u64) -> Keypair {
+    let winner = h.keypair();
+    h.place_sealed_bid(auction, &winner, amount).await.unwrap();
+    h.warp_to_end(auction).await;
+    h.close_bidding(auction).await.unwrap();
+    h.determine_and_finalize(auction, std::slice::from_ref(&winner)).await;
+    winner
+}
+
+async fn cancel_as(h: &mut Harness, auction: &Pubkey, signer: &Keypair) -> TxResult {
+    let state = h.auction(auction).await;
+    h.send(
+        ix::cancel_auction(
+            &signer.pubkey(),
+            auction,
+            &state,
+            "withdrawn".to_string(),
+            CancellationReason::Other,
+        ),
+        &[signer],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn settlement_pays_the_beneficiary() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let (authority, beneficiary) = (h.keypair(), h.keypair());
+    let auction = create_governed(&mut h, &authority.pubkey(), &beneficiary.pubkey(), false).await;
+
+    let record = h.auction_authority(&auction).await.unwrap();
+    assert_eq!(record.auction, auction);
+    assert_eq!(record.authority, authority.pubkey());
+    assert_eq!(record.beneficiary, beneficiary.pubkey());
+
+    let winner = single_winner(&mut h, &auction, 4_000).await;
+    let seller_before = h.lamports(&seller).await;
+    h.settle(&auction, &winner).await.unwrap();
+
+    // The sale still counts towards the seller's track record
+    assert_eq!(h.lamports(&beneficiary.pubkey()).await, STARTING_BALANCE + 4_000);
+    assert_eq!(h.lamports(&seller).await, seller_before);
+    assert_eq!(h.seller_profile(&seller).await.unwrap().total_volume, 4_000);
+}
+
+#[tokio::test]
+async fn escrow_releases_to_the_beneficiary() {
+    let mut h = Harness::new().await;
+    let (authority, beneficiary) = (h.keypair(), h.keypair());
+    let auction = create_governed(&mut h, &authority.pubkey(), &beneficiary.pubkey(), true).await;
+    let winner = single_winner(&mut h, &auction, 10_000).await;
+    h.settle(&auction, &winner).await.unwrap();
+    assert_eq!(h.escrow(&auction).await.unwrap().beneficiary, beneficiary.pubkey());
+
+    h.dispute(&auction, &winner).await.unwrap();
+    h.resolve(&auction, 5_000).await.unwrap();
+
+    // 2.5% fee, then half of the rest to each side
+    assert_eq!(h.lamports(&beneficiary.pubkey()).await, STARTING_BALANCE + 4_875);
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+}
+
+#[tokio::test]
+async fn authority_administers_instead_of_seller() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.insecure_clone();
+    let authority = h.keypair();
+    let auction = create_governed(&mut h, &authority.pubkey(), &seller.pubkey(), false).await;
+
+    assert_error(cancel_as(&mut h, &auction, &seller).await, ShadowBidError::NotAuthority);
+    assert_error(
+        h.create_metadata(&auction, metadata_params(b"{}", &["art"])).await,
+        ShadowBidError::NotAuthority,
+    );
+
+    let create = ix::create_auction_metadata(&authority.pubkey(), &auction, metadata_params(b"{}", &["art"]));
+    h.send(create, &[&authority]).await.unwrap();
+    cancel_as(&mut h, &auction, &authority).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+}
+
+#[tokio::test]
+async fn delegates_act_within_their_permissions() {
+    let mut h = Harness::new().await;
+    let authority = h.keypair();
+    let (canceller, editor) = (h.keypair(), h.keypair());
+    let beneficiary = h.seller.pubkey();
+    let auction = create_governed(&mut h, &authority.pubkey(), &beneficiary, false).await;
+
+    for (delegate, permission) in [(&canceller, Permission::Cancel), (&editor, Permission::UpdateMetadata)] {
+        let grant = ix::set_delegate(&authority.pubkey(), &auction, &delegate.pubkey(), permission.bit());
+        h.send(grant, &[&authority]).await.unwrap();
+    }
+    let record = h.auction_authority(&auction).await.unwrap();
+    assert!(record.delegates[0].has(Permission::Cancel));
+    assert!(!record.delegates[0].has(Permission::UpdateMetadata));
+
+    // The editor describes the auction, but can't cancel it
+    let params = metadata_params(b"{}", &["art"]);
+    let create = ix::create_auction_metadata(&editor.pubkey(), &auction, params.clone());
+    h.send(create, &[&editor]).await.unwrap();
+    let update = ix::update_auction_metadata(&canceller.pubkey(), &auction, &h.seller.pubkey(), params);
+    assert_error(h.send(update, &[&canceller]).await, ShadowBidError::NotAuthority);
+    assert_error(cancel_as(&mut h, &auction, &editor).await, ShadowBidError::NotAuthority);
+
+    // A revoked delegate loses its permissions
+    let revoke = ix::set_delegate(&authority.pubkey(), &auction, &canceller.pubkey(), 0);
+    h.send(revoke, &[&authority]).await.unwrap();
+    assert_error(cancel_as(&mut h, &auction, &canceller).await, ShadowBidError::NotAuthority);
+
+    let grant = ix::set_delegate(&authority.pubkey(), &auction, &canceller.pubkey(), Permission::Cancel.bit());
+    h.send(grant, &[&authority]).await.unwrap();
+    cancel_as(&mut h, &auction, &canceller).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+}
+
+#[tokio::test]
+async fn seller_hands_authority_on() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.insecure_clone();
+    let (operator, treasury) = (h.keypair(), h.keypair());
+    let auction = h.create(false).await;
+    assert!(h.auction_authority(&auction).await.is_none());
+
+    // The seller's first delegate creates the record, keeping both roles
+    let grant = ix::set_delegate(&seller.pubkey(), &auction, &operator.pubkey(), Permission::Cancel.bit());
+    h.send(grant, &[&seller]).await.unwrap();
+    let record = h.auction_authority(&auction).await.unwrap();
+    assert_eq!(record.authority, seller.pubkey());
+    assert_eq!(record.beneficiary, seller.pubkey());
+
+    // Handing authority on revokes the previous authority's delegates
+    let transfer = ix::transfer_authority(&seller.pubkey(), &auction, &treasury.pubkey());
+    h.send(transfer, &[&seller]).await.unwrap();
+    let record = h.auction_authority(&auction).await.unwrap();
+    assert_eq!(record.authority, treasury.pubkey());
+    assert_eq!(record.beneficiary, seller.pubkey());
+    assert!(!record.allows(&operator.pubkey(), Some(Permission::Cancel)));
+
+    assert_error(cancel_as(&mut h, &auction, &seller).await, ShadowBidError::NotAuthority);
+    assert_error(cancel_as(&mut h, &auction, &operator).await, ShadowBidError::NotAuthority);
+    cancel_as(&mut h, &auction, &treasury).await.unwrap();
+}
@@ -0,0 +1,180 @@
+//! Bidder profiles: each bidder's bids, wins, settlements and defaults,
+//! the settlement window after which a winner can be declared in default,
+//! winners below the reserve who can't settle and so don't default, and
+//! auctions keeping out bidders with too many defaults
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use mock_inco::MockIncoError;
+use shadowbid::constants::SETTLEMENT_WINDOW;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::state::AuctionState;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Runs a bonded auction with one bid of `amount` through finalization,
+/// then lets the winner's settlement window run out
+async fn unpaid_win(h: &mut Harness, amount: u64) -> (Pubkey, Keypair) {
+    let auction = h.create_bonded().await;
+    let winner = h.keypair();
+    h.place_sealed_bid(&auction, &winner, amount).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, std::slice::from_ref(&winner)).await;
+    let won_at = h.bid(&auction, &winner.pubkey()).await.won_at;
+    h.warp(won_at + SETTLEMENT_WINDOW).await;
+    (auction, winner)
+}
+
+#[tokio::test]
+async fn profile_counts_bids_wins_and_settlements() {
+    let mut h = Harness::new().await;
+    let (auction, bidders) = h.closed_sealed_auction(&[3_000, 4_000]).await;
+    let (loser, winner) = (&bidders[0], &bidders[1]);
+
+    let profile = h.bidder_profile(&winner.pubkey()).await.unwrap();
+    assert_eq!(profile.bidder, winner.pubkey());
+    assert_eq!(profile.bids_placed, 1);
+    assert_eq!(profile.auctions_won, 0);
+
+    h.determine_and_finalize(&auction, &bidders).await;
+    assert_eq!(h.bid(&auction, &winner.pubkey()).await.won_at, h.now().await);
+    assert_eq!(h.bid(&auction, &loser.pubkey()).await.won_at, 0);
+    h.settle(&auction, winner).await.unwrap();
+
+    let profile = h.bidder_profile(&winner.pubkey()).await.unwrap();
+    assert_eq!(profile.auctions_won, 1);
+    assert_eq!(profile.auctions_settled, 1);
+    assert_eq!(profile.auctions_defaulted, 0);
+    let profile = h.bidder_profile(&loser.pubkey()).await.unwrap();
+    assert_eq!(profile.bids_placed, 1);
+    assert_eq!(profile.auctions_won, 0);
+}
+
+#[tokio::test]
+async fn updating_a_bid_is_not_counted() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    h.place_sealed_bid(&auction, &bidder, 3_000).await.unwrap();
+
+    assert_eq!(h.bidder_profile(&bidder.pubkey()).await.unwrap().bids_placed, 1);
+}
+
+#[tokio::test]
+async fn unpaid_winner_is_declared_in_default() {
+    let mut h = Harness::new().await;
+    let (auction, winner) = unpaid_win(&mut h, 4_000).await;
+
+    h.declare_default(&auction).await.unwrap();
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Defaulted);
+    let profile = h.bidder_profile(&winner.pubkey()).await.unwrap();
+    assert_eq!(profile.auctions_won, 1);
+    assert_eq!(profile.auctions_defaulted, 1);
+
+    // The winner can no longer pay, and the seller gets the bond back
+    assert_error(h.settle(&auction, &winner).await, ShadowBidError::WinnerNotDetermined);
+    h.release_bond(&auction).await.unwrap();
+    assert!(h.seller_bond(&auction).await.is_none());
+}
+
+#[tokio::test]
+async fn default_waits_for_settlement_window() {
+    let mut h = Harness::new().await;
+    let (auction, bidders) = h.closed_sealed_auction(&[4_000]).await;
+    // Without a winner there is no winning bid to check the window of
+    assert_custom_error(h.declare_default(&auction).await, ErrorCode::AccountNotInitialized.into());
+
+    h.determine_and_finalize(&auction, &bidders).await;
+    let won_at = h.bid(&auction, &bidders[0].pubkey()).await.won_at;
+    h.warp(won_at + SETTLEMENT_WINDOW - 1).await;
+    assert_error(h.declare_default(&auction).await, ShadowBidError::SettlementWindowOpen);
+
+    // A late settlement still goes through until someone declares the default
+    h.warp(won_at + SETTLEMENT_WINDOW).await;
+    h.settle(&auction, &bidders[0]).await.unwrap();
+    assert_error(h.declare_default(&auction).await, ShadowBidError::WinnerNotDetermined);
+}
+
+#[tokio::test]
+async fn winner_below_reserve_is_cancelled_without_default() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.insecure_clone();
+    let (auction, winner) = unpaid_win(&mut h, RESERVE_PRICE - 1).await;
+
+    // The seller proves the winning bid can never settle
+    h.cancel_below_reserve(&auction, &seller, RESERVE_PRICE - 1).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+    assert_error(h.declare_default(&auction).await, ShadowBidError::WinnerNotDetermined);
+    assert_eq!(h.bidder_profile(&winner.pubkey()).await.unwrap().auctions_defaulted, 0);
+
+    // The bond goes back to the seller
+    h.release_bond(&auction).await.unwrap();
+    assert!(h.seller_bond(&auction).await.is_none());
+}
+
+#[tokio::test]
+async fn proof_below_reserve_withdraws_default() {
+    let mut h = Harness::new().await;
+    let (auction, winner) = unpaid_win(&mut h, RESERVE_PRICE - 1).await;
+    h.declare_default(&auction).await.unwrap();
+    assert_eq!(h.bidder_profile(&winner.pubkey()).await.unwrap().auctions_defaulted, 1);
+
+    h.cancel_below_reserve(&auction, &winner, RESERVE_PRICE - 1).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+    assert_eq!(h.bidder_profile(&winner.pubkey()).await.unwrap().auctions_defaulted, 0);
+}
+
+#[tokio::test]
+async fn reserve_exit_needs_winning_bid_below_reserve() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.insecure_clone();
+    let (auction, winner) = unpaid_win(&mut h, RESERVE_PRICE).await;
+
+    assert_error(
+        h.cancel_below_reserve(&auction, &seller, RESERVE_PRICE).await,
+        ShadowBidError::ReserveMet,
+    );
+    let stranger = h.keypair();
+    assert_error(
+        h.cancel_below_reserve(&auction, &stranger, RESERVE_PRICE - 1).await,
+        ShadowBidError::NotAuctionParty,
+    );
+    // A plaintext that isn't the winning handle's fails the attestation
+    assert_custom_error(
+        h.cancel_below_reserve(&auction, &winner, RESERVE_PRICE - 1).await,
+        MockIncoError::InvalidSignature.into(),
+    );
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::WinnerDetermined);
+}
+
+#[tokio::test]
+async fn auction_rejects_bidders_over_default_limit() {
+    let mut h = Harness::new().await;
+    let (auction, winner) = unpaid_win(&mut h, 4_000).await;
+    h.declare_default(&auction).await.unwrap();
+
+    let mut params = h.params(false);
+    params.max_bidder_defaults = Some(0);
+    let strict = h.create_with(params).await.unwrap();
+    assert_error(
+        h.place_sealed_bid(&strict, &winner, 2_000).await,
+        ShadowBidError::TooManyDefaults,
+    );
+    let newcomer = h.keypair();
+    h.place_sealed_bid(&strict, &newcomer, 2_000).await.unwrap();
+
+    let mut params = h.params(false);
+    params.max_bidder_defaults = Some(1);
+    let lenient = h.create_with(params).await.unwrap();
+    h.place_sealed_bid(&lenient, &winner, 2_000).await.unwrap();
+
+    let open = h.create(false).await;
+    h.place_sealed_bid(&open, &winner, 2_000).await.unwrap();
+    assert_eq!(h.auction(&strict).await.max_bidder_defaults(), Some(0));
+    assert_eq!(h.auction(&open).await.max_bidder_defaults(), None);
+}
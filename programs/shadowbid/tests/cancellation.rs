@@ -0,0 +1,185 @@
+//! Seller bonds: the first bid locks in an unbonded auction, a bonded
+//! seller who cancels with bids has the bond slashed to the bidders and
+//! the treasury, and settled, unsold or cleanly cancelled auctions
+//! release it
+
+mod common;
+
+use common::*;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::state::{AuctionState, CancellationPolicy};
+use shadowbid_client::pda;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn first_bid_locks_in_unbonded_auction() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+    assert_eq!(
+        h.auction(&auction).await.cancellation_policy().unwrap(),
+        CancellationPolicy::NoCancelAfterBid
+    );
+
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    assert_error(h.cancel(&auction, "changed my mind").await, ShadowBidError::CancellationNotAllowed);
+
+    // Nor once bidding has closed
+    h.close_bidding(&auction).await.unwrap();
+    assert_error(h.cancel(&auction, "changed my mind").await, ShadowBidError::CancellationNotAllowed);
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Closed);
+}
+
+#[tokio::test]
+async fn bond_is_slashed_between_bidders() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let bond_rent = h.bond_rent().await;
+    let auction = h.create_bonded().await;
+
+    // The bond sits in its own vault on top of the vault's rent
+    let (vault, _) = pda::seller_bond_address(&auction);
+    assert_eq!(h.lamports(&vault).await, bond_rent + SELLER_BOND);
+    let state = h.auction(&auction).await;
+    assert_eq!(state.cancellation_policy().unwrap(), CancellationPolicy::Bonded);
+    assert_eq!({ state.seller_bond }, SELLER_BOND);
+
+    let mut bidders = Vec::new();
+    for amount in [2_000, 3_000, 4_000] {
+        let bidder = h.keypair();
+        h.place_sealed_bid(&auction, &bidder, amount).await.unwrap();
+        bidders.push(bidder);
+    }
+    h.warp_to_end(&auction).await;
+    h.cancel(&auction, "Item damaged").await.unwrap();
+
+    // Equal shares; the odd lamport goes to the treasury
+    let share = SELLER_BOND / 3;
+    let bond = h.seller_bond(&auction).await.unwrap();
+    assert_eq!(bond.compensation_per_bid, share);
+    assert_eq!(bond.claims_remaining, 3);
+    assert_eq!(h.treasury().await.total_slashed, SELLER_BOND % 3);
+
+    let seller_before = h.lamports(&seller).await;
+    for bidder in &bidders {
+        let before = h.lamports(&bidder.pubkey()).await;
+        h.claim_compensation(&auction, bidder).await.unwrap();
+        assert_eq!(h.lamports(&bidder.pubkey()).await, before + share);
+        assert!(h.bid(&auction, &bidder.pubkey()).await.compensated);
+    }
+
+    // The last claim closes the vault and returns its rent to the seller
+    assert!(h.seller_bond(&auction).await.is_none());
+    assert_eq!(h.lamports(&seller).await, seller_before + bond_rent);
+}
+
+#[tokio::test]
+async fn bond_smaller_than_bidders_goes_to_treasury() {
+    let mut h = Harness::new().await;
+    let mut params = h.params(false);
+    params.seller_bond = Some(2);
+    let auction = h.create_with(params).await.unwrap();
+    for amount in [2_000, 3_000, 4_000] {
+        let bidder = h.keypair();
+        h.place_sealed_bid(&auction, &bidder, amount).await.unwrap();
+    }
+    h.warp_to_end(&auction).await;
+    h.cancel(&auction, "Item damaged").await.unwrap();
+
+    // No share to claim, so the vault closes right away
+    assert!(h.seller_bond(&auction).await.is_none());
+    assert_eq!(h.treasury().await.total_slashed, 2);
+}
+
+#[tokio::test]
+async fn bond_is_released_when_not_slashed() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let bond_rent = h.bond_rent().await;
+
+    // Held until the auction settles
+    let auction = h.create_bonded().await;
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    assert_error(h.release_bond(&auction).await, ShadowBidError::BondLocked);
+
+    // Settlement releases all of it, with the vault's rent
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, std::slice::from_ref(&bidder)).await;
+    h.settle(&auction, &bidder).await.unwrap();
+    let before = h.lamports(&seller).await;
+    h.release_bond(&auction).await.unwrap();
+    assert_eq!(h.lamports(&seller).await, before + SELLER_BOND + bond_rent);
+    assert!(h.seller_bond(&auction).await.is_none());
+
+    // A cancellation before any bid releases it straight away
+    let auction = h.create_bonded().await;
+    let before = h.lamports(&seller).await;
+    h.cancel(&auction, "Listed twice").await.unwrap();
+    assert_eq!(h.lamports(&seller).await, before + SELLER_BOND + bond_rent);
+    assert!(h.seller_bond(&auction).await.is_none());
+    assert_eq!(h.treasury().await.total_slashed, 0);
+}
+
+#[tokio::test]
+async fn bond_is_released_when_bidding_closes_without_bids() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let bond_rent = h.bond_rent().await;
+    let auction = h.create_bonded().await;
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+
+    let before = h.lamports(&seller).await;
+    h.release_bond(&auction).await.unwrap();
+    assert_eq!(h.lamports(&seller).await, before + SELLER_BOND + bond_rent);
+    assert!(h.seller_bond(&auction).await.is_none());
+}
+
+#[tokio::test]
+async fn bond_is_released_when_no_reveal_meets_the_reserve() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let bond_rent = h.bond_rent().await;
+    let mut params = h.params(true);
+    params.seller_bond = Some(SELLER_BOND);
+    let auction = h.create_with(params).await.unwrap();
+
+    let low = h.keypair();
+    h.place_commitment(&auction, &low, RESERVE_PRICE - 1, [7; 32]).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.reveal(&auction, &low, RESERVE_PRICE - 1, [7; 32]).await.unwrap();
+    h.warp_to_reveal_end(&auction).await;
+    h.close_reveal(&auction).await.unwrap();
+
+    // Held until finalize cancels the auction
+    assert_error(h.release_bond(&auction).await, ShadowBidError::BondLocked);
+    h.determine(&auction, &low.pubkey()).await.unwrap();
+    h.finalize(&auction, false).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+
+    let before = h.lamports(&seller).await;
+    h.release_bond(&auction).await.unwrap();
+    assert_eq!(h.lamports(&seller).await, before + SELLER_BOND + bond_rent);
+    assert!(h.seller_bond(&auction).await.is_none());
+}
+
+#[tokio::test]
+async fn slashed_bond_cannot_be_released() {
+    let mut h = Harness::new().await;
+    let auction = h.create_bonded().await;
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.cancel(&auction, "Item damaged").await.unwrap();
+
+    // The bidder's share stays claimable
+    assert_error(h.release_bond(&auction).await, ShadowBidError::BondLocked);
+    h.claim_compensation(&auction, &bidder).await.unwrap();
+    assert!(h.seller_bond(&auction).await.is_none());
+}
@@ -0,0 +1,738 @@
+//! Shared harness for the offline integration tests
+//!
+//! ShadowBid runs under `solana-program-test` next to the `mock-inco`
+//! program, which is registered at the Inco Lightning address so the
+//! program's address constraints accept it unchanged. Both run as native
+//! builtins; set `SBF_OUT_DIR` to load the compiled `.so` files instead.
+//!
+//! The harness payer covers every transaction fee, so seller and bidder
+//! balances only move by what the program itself transfers.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::{AccountInfo, Clock, Pubkey, Rent};
+use anchor_lang::system_program;
+use mock_inco::codec;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::instructions::{
+    ArbitrationParams, AuctionMetadataParams, CommitRevealParams, CreateAuctionParams,
+};
+use shadowbid::state::{
+    Auction, AuctionAuthority, AuctionMetadata, AuctionPage, Bid, BidderProfile, CancellationReason, Escrow, Receipt, Registry,
+    SellerBond, SellerProfile, Treasury,
+};
+use shadowbid_client::{accounts, instructions as ix, pda};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+/// Balance given to every keypair from `Harness::keypair`
+pub const STARTING_BALANCE: u64 = 10_000_000_000;
+
+/// Reserve price of auctions created by `Harness::create`
+pub const RESERVE_PRICE: u64 = 1_000;
+
+/// Bidding window of auctions created by `Harness::create`
+pub const DURATION: i64 = 600;
+
+/// Reveal window of commit-reveal auctions created by `Harness::create`
+pub const REVEAL_DURATION: i64 = 300;
+
+/// Collateral of commit-reveal auctions created by `Harness::create`
+pub const BID_COLLATERAL: u64 = 50_000;
+
+/// Bid deposit of auctions created by `Harness::create_with_deposits`
+pub const BID_DEPOSIT: u64 = 20_000;
+
+/// Seller bond of auctions created by `Harness::create_bonded`
+pub const SELLER_BOND: u64 = 1_000_000;
+
+/// Delivery window of auctions created by `Harness::create_escrowed`
+pub const DELIVERY_TIMEOUT: i64 = 86_400;
+
+/// Arbitration fee of auctions created by `Harness::create_escrowed`
+pub const ARBITRATION_FEE_BPS: u16 = 250;
+
+/// Evidence URI sent by `Harness::dispute`
+pub const EVIDENCE_URI: &str = "ar://Qm4bYh8kVvSx3rXoGKpZ1nD7yE2cFwTtLmU9aHjR6sPe";
+
+// The entrypoints take accounts for the whole `'info` lifetime, which
+// the builtin processor signature can't express
+fn with_info_lifetime<'a, 'info>(accounts: &'a [AccountInfo<'info>]) -> &'info [AccountInfo<'info>] {
+    // SAFETY: the slice outlives the entrypoint call, which is the only
+    // place the widened reference is used
+    unsafe { std::mem::transmute::<&'a [AccountInfo<'info>], &'info [AccountInfo<'info>]>(accounts) }
+}
+
+fn shadowbid_processor(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    shadowbid::entry(program_id, with_info_lifetime(accounts), data)
+}
+
+fn mock_inco_processor(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    mock_inco::entry(program_id, with_info_lifetime(accounts), data)
+}
+
+pub type TxResult = Result<(), BanksClientError>;
+
+pub struct Harness {
+    pub ctx: ProgramTestContext,
+    /// Seller of every auction created through the harness
+    pub seller: Keypair,
+    /// Arbitrator of every escrowed auction created through the harness
+    pub arbitrator: Keypair,
+    /// Compute unit price bumped per transaction so identical
+    /// transactions within one blockhash aren't deduplicated
+    nonce: u64,
+}
+
+impl Harness {
+    pub async fn new() -> Self {
+        Self::with_programs(|_| {}).await
+    }
+
+    /// A harness with extra programs registered by `add_programs`, such
+    /// as programs that call ShadowBid
+    pub async fn with_programs(add_programs: impl FnOnce(&mut ProgramTest)) -> Self {
+        let mut program_test =
+            ProgramTest::new("shadowbid", shadowbid::ID, processor!(shadowbid_processor));
+        program_test.add_program("mock_inco", mock_inco::ID, processor!(mock_inco_processor));
+        add_programs(&mut program_test);
+
+        let mut harness = Self {
+            ctx: program_test.start_with_context().await,
+            seller: Keypair::new(),
+            arbitrator: Keypair::new(),
+            nonce: 0,
+        };
+        harness.seller = harness.keypair();
+        harness.arbitrator = harness.keypair();
+        let payer = harness.ctx.payer.pubkey();
+        harness
+            .send(ix::initialize_registry(&payer), &[])
+            .await
+            .expect("initialize_registry failed");
+        harness
+            .send(ix::initialize_treasury(&payer), &[])
+            .await
+            .expect("initialize_treasury failed");
+        harness
+    }
+
+    /// A fresh keypair funded with `STARTING_BALANCE`
+    pub fn keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        self.ctx.set_account(
+            &keypair.pubkey(),
+            &AccountSharedData::new(STARTING_BALANCE, 0, &system_program::ID),
+        );
+        keypair
+    }
+
+    /// Sends one instruction, paid for by the harness payer
+    pub async fn send(&mut self, instruction: Instruction, signers: &[&Keypair]) -> TxResult {
+        self.nonce += 1;
+        let payer = self.ctx.payer.insecure_clone();
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+
+        let blockhash = self.ctx.banks_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_price(self.nonce),
+                instruction,
+            ],
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(transaction).await
+    }
+
+    /// Creation parameters with the harness defaults
+    pub fn params(&mut self, commit_reveal: bool) -> CreateAuctionParams {
+        CreateAuctionParams {
+            title: "Test auction".to_string(),
+            description: "Offline integration test".to_string(),
+            reserve_price: RESERVE_PRICE,
+            duration: DURATION,
+            item_mint: None,
+            commit_reveal: commit_reveal.then_some(CommitRevealParams {
+                reveal_duration: REVEAL_DURATION,
+                bid_collateral: BID_COLLATERAL,
+            }),
+            bid_deposit: None,
+            seller_bond: None,
+            delivery_timeout: None,
+            arbitration: None,
+            settlement_callback: None,
+            hook: None,
+            issue_receipt: false,
+            max_bidder_defaults: None,
+            authority: None,
+        }
+    }
+
+    /// Sends `create_auction` for `params` and returns the auction address
+    pub async fn create_with(&mut self, params: CreateAuctionParams) -> Result<Pubkey, BanksClientError> {
+        let seller = self.seller.insecure_clone();
+        self.create_as(&seller, params).await
+    }
+
+    /// Sends `create_auction` from another seller
+    pub async fn create_as(
+        &mut self,
+        seller: &Keypair,
+        params: CreateAuctionParams,
+    ) -> Result<Pubkey, BanksClientError> {
+        let registry = self.registry().await;
+        let (address, _) = pda::auction_address(&seller.pubkey(), registry.auction_count);
+        self.send(ix::create_auction(&seller.pubkey(), &registry, params), &[seller])
+            .await?;
+        Ok(address)
+    }
+
+    /// Creates an auction with the harness defaults
+    pub async fn create(&mut self, commit_reveal: bool) -> Pubkey {
+        let params = self.params(commit_reveal);
+        self.create_with(params).await.expect("create_auction failed")
+    }
+
+    /// Creates a sealed auction whose seller posts `SELLER_BOND`
+    pub async fn create_bonded(&mut self) -> Pubkey {
+        let mut params = self.params(false);
+        params.seller_bond = Some(SELLER_BOND);
+        self.create_with(params).await.expect("create_auction failed")
+    }
+
+    /// Creates a sealed auction taking `BID_DEPOSIT` with each bid
+    pub async fn create_with_deposits(&mut self) -> Pubkey {
+        let mut params = self.params(false);
+        params.bid_deposit = Some(BID_DEPOSIT);
+        self.create_with(params).await.expect("create_auction failed")
+    }
+
+    /// Parameters for a sealed auction that escrows the payment for
+    /// `DELIVERY_TIMEOUT`, with the harness arbitrator
+    pub fn escrowed_params(&mut self) -> CreateAuctionParams {
+        let mut params = self.params(false);
+        params.delivery_timeout = Some(DELIVERY_TIMEOUT);
+        params.arbitration = Some(ArbitrationParams {
+            arbitrator: self.arbitrator.pubkey(),
+            fee_bps: ARBITRATION_FEE_BPS,
+        });
+        params
+    }
+
+    /// Creates an auction with `escrowed_params`
+    pub async fn create_escrowed(&mut self) -> Pubkey {
+        let params = self.escrowed_params();
+        self.create_with(params).await.expect("create_auction failed")
+    }
+
+    pub async fn auction(&mut self, address: &Pubkey) -> Auction {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .expect("auction account missing");
+        accounts::decode_auction(&account.data).unwrap()
+    }
+
+    pub async fn bid(&mut self, auction: &Pubkey, bidder: &Pubkey) -> Bid {
+        let (address, _) = pda::bid_address(auction, bidder);
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .expect("bid account missing");
+        accounts::decode_bid(&account.data).unwrap()
+    }
+
+    /// The auction's seller bond, while its vault is open
+    pub async fn seller_bond(&mut self, auction: &Pubkey) -> Option<SellerBond> {
+        let (address, _) = pda::seller_bond_address(auction);
+        let account = self.ctx.banks_client.get_account(address).await.unwrap()?;
+        Some(accounts::decode_seller_bond(&account.data).unwrap())
+    }
+
+    /// The auction's authority record, if it has one
+    pub async fn auction_authority(&mut self, auction: &Pubkey) -> Option<AuctionAuthority> {
+        let (address, _) = pda::auction_authority_address(auction);
+        let account = self.ctx.banks_client.get_account(address).await.unwrap()?;
+        Some(accounts::decode_auction_authority(&account.data).unwrap())
+    }
+
+    /// Who the auction pays: its authority record's beneficiary, or the
+    /// seller without one
+    pub async fn beneficiary(&mut self, auction: &Pubkey) -> Pubkey {
+        match self.auction_authority(auction).await {
+            Some(record) => record.beneficiary,
+            None => self.auction(auction).await.seller,
+        }
+    }
+
+    /// The auction's escrow, while it holds the payment
+    pub async fn escrow(&mut self, auction: &Pubkey) -> Option<Escrow> {
+        let (address, _) = pda::escrow_address(auction);
+        let account = self.ctx.banks_client.get_account(address).await.unwrap()?;
+        Some(accounts::decode_escrow(&account.data).unwrap())
+    }
+
+    /// The auction's receipt, once settlement minted it
+    pub async fn receipt(&mut self, auction: &Pubkey) -> Option<Receipt> {
+        let (address, _) = pda::receipt_address(auction);
+        let account = self.ctx.banks_client.get_account(address).await.unwrap()?;
+        Some(accounts::decode_receipt(&account.data).unwrap())
+    }
+
+    pub async fn treasury(&mut self) -> Treasury {
+        let (address, _) = pda::treasury_address();
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .expect("treasury account missing");
+        accounts::decode_treasury(&account.data).unwrap()
+    }
+
+    pub async fn registry(&mut self) -> Registry {
+        let (address, _) = pda::registry_address();
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .expect("registry account missing");
+        accounts::decode_registry(&account.data).unwrap()
+    }
+
+    /// `seller`'s profile, once they have created an auction
+    pub async fn seller_profile(&mut self, seller: &Pubkey) -> Option<SellerProfile> {
+        let (address, _) = pda::seller_profile_address(seller);
+        let account = self.ctx.banks_client.get_account(address).await.unwrap()?;
+        Some(accounts::decode_seller_profile(&account.data).unwrap())
+    }
+
+    /// `bidder`'s profile, once they have placed a bid
+    pub async fn bidder_profile(&mut self, bidder: &Pubkey) -> Option<BidderProfile> {
+        let (address, _) = pda::bidder_profile_address(bidder);
+        let account = self.ctx.banks_client.get_account(address).await.unwrap()?;
+        Some(accounts::decode_bidder_profile(&account.data).unwrap())
+    }
+
+    /// Number of auctions `seller` has created (0 before the first)
+    pub async fn seller_auction_count(&mut self, seller: &Pubkey) -> u64 {
+        let (address, _) = pda::seller_counter_address(seller);
+        match self.ctx.banks_client.get_account(address).await.unwrap() {
+            Some(account) => accounts::decode_seller_counter(&account.data).unwrap().auction_count,
+            None => 0,
+        }
+    }
+
+    pub async fn page(&mut self, index: u64) -> AuctionPage {
+        let (address, _) = pda::page_address(index);
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .expect("auction page missing");
+        accounts::decode_auction_page(&account.data).unwrap()
+    }
+
+    /// Open auctions listed across every registry page
+    pub async fn open_auctions(&mut self) -> Vec<Pubkey> {
+        let mut open = Vec::new();
+        for index in 0..self.registry().await.page_count() {
+            open.extend(self.page(index).await.open_auctions().copied());
+        }
+        open
+    }
+
+    pub async fn metadata(&mut self, auction: &Pubkey) -> AuctionMetadata {
+        let (address, _) = pda::metadata_address(auction);
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .expect("metadata account missing");
+        accounts::decode_auction_metadata(&account.data).unwrap()
+    }
+
+    pub async fn create_metadata(&mut self, auction: &Pubkey, params: AuctionMetadataParams) -> TxResult {
+        let seller = self.seller.insecure_clone();
+        self.send(ix::create_auction_metadata(&seller.pubkey(), auction, params), &[&seller])
+            .await
+    }
+
+    pub async fn update_metadata(&mut self, auction: &Pubkey, params: AuctionMetadataParams) -> TxResult {
+        let seller = self.seller.insecure_clone();
+        self.send(ix::update_auction_metadata(&seller.pubkey(), auction, &seller.pubkey(), params), &[&seller])
+            .await
+    }
+
+    /// Replaces an account's data, as if it had been created with
+    /// `data`, with rent for that size
+    pub async fn replace_data(&mut self, address: &Pubkey, data: Vec<u8>) {
+        let rent: Rent = self.ctx.banks_client.get_sysvar().await.unwrap();
+        let mut account = self
+            .ctx
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .expect("account missing");
+        let extra = account.lamports - rent.minimum_balance(account.data.len());
+        account.lamports = rent.minimum_balance(data.len()) + extra;
+        account.data = data;
+        self.ctx.set_account(address, &account.into());
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.ctx.banks_client.get_balance(*address).await.unwrap()
+    }
+
+    /// Rent-exempt minimum of a bid account
+    pub async fn bid_rent(&mut self) -> u64 {
+        let rent: Rent = self.ctx.banks_client.get_sysvar().await.unwrap();
+        rent.minimum_balance(Bid::SPACE)
+    }
+
+    /// Rent-exempt minimum of a bidder profile
+    pub async fn bidder_profile_rent(&mut self) -> u64 {
+        let rent: Rent = self.ctx.banks_client.get_sysvar().await.unwrap();
+        rent.minimum_balance(BidderProfile::SPACE)
+    }
+
+    /// Rent-exempt minimum of a seller bond vault
+    pub async fn bond_rent(&mut self) -> u64 {
+        let rent: Rent = self.ctx.banks_client.get_sysvar().await.unwrap();
+        rent.minimum_balance(SellerBond::SPACE)
+    }
+
+    /// Rent-exempt minimum of an escrow account
+    pub async fn escrow_rent(&mut self) -> u64 {
+        let rent: Rent = self.ctx.banks_client.get_sysvar().await.unwrap();
+        rent.minimum_balance(Escrow::SPACE)
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    /// Moves the cluster clock to `unix_timestamp`
+    pub async fn warp(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    /// Moves the clock to the auction's end_time
+    pub async fn warp_to_end(&mut self, auction: &Pubkey) {
+        let end_time = self.auction(auction).await.end_time;
+        self.warp(end_time).await;
+    }
+
+    /// Moves the clock to the auction's reveal_end_time
+    pub async fn warp_to_reveal_end(&mut self, auction: &Pubkey) {
+        let reveal_end_time = self.auction(auction).await.reveal_end_time;
+        self.warp(reveal_end_time).await;
+    }
+
+    /// Places (or updates) a sealed bid of `amount`
+    pub async fn place_sealed_bid(&mut self, auction: &Pubkey, bidder: &Keypair, amount: u64) -> TxResult {
+        self.send(
+            ix::place_bid(&bidder.pubkey(), auction, codec::encrypt(amount)),
+            &[bidder],
+        )
+        .await
+    }
+
+    /// Places (or updates) a commitment to `amount` with `salt`
+    pub async fn place_commitment(
+        &mut self,
+        auction: &Pubkey,
+        bidder: &Keypair,
+        amount: u64,
+        salt: [u8; 32],
+    ) -> TxResult {
+        let commitment = Bid::commitment_hash(amount, &salt, &bidder.pubkey());
+        self.send(
+            ix::place_bid(&bidder.pubkey(), auction, commitment.to_vec()),
+            &[bidder],
+        )
+        .await
+    }
+
+    pub async fn reveal(&mut self, auction: &Pubkey, bidder: &Keypair, amount: u64, salt: [u8; 32]) -> TxResult {
+        self.send(ix::reveal_bid(&bidder.pubkey(), auction, amount, salt), &[bidder])
+            .await
+    }
+
+    pub async fn close_bidding(&mut self, auction: &Pubkey) -> TxResult {
+        let caller = self.ctx.payer.pubkey();
+        let state = self.auction(auction).await;
+        self.send(ix::close_bidding(&caller, auction, &state), &[]).await
+    }
+
+    pub async fn close_reveal(&mut self, auction: &Pubkey) -> TxResult {
+        let caller = self.ctx.payer.pubkey();
+        self.send(ix::close_reveal(&caller, auction), &[]).await
+    }
+
+    pub async fn determine(&mut self, auction: &Pubkey, bidder: &Pubkey) -> TxResult {
+        let caller = self.ctx.payer.pubkey();
+        let state = self.auction(auction).await;
+        self.send(ix::determine_winner(&caller, auction, &state, bidder), &[])
+            .await
+    }
+
+    pub async fn finalize(&mut self, auction: &Pubkey, reveal_to_seller: bool) -> TxResult {
+        let caller = self.ctx.payer.pubkey();
+        let state = self.auction(auction).await;
+        self.send(ix::finalize_winner(&caller, auction, &state, reveal_to_seller), &[])
+            .await
+    }
+
+    /// Settles as `winner`, claiming the plaintext of the winning handle
+    pub async fn settle(&mut self, auction: &Pubkey, winner: &Keypair) -> TxResult {
+        let state = self.auction(auction).await;
+        let plaintext = codec::decrypt(state.highest_bid_handle).unwrap_or_default();
+        let beneficiary = self.beneficiary(auction).await;
+        self.send(
+            settle_instruction_to(auction, &state, &beneficiary, winner, plaintext),
+            &[winner],
+        )
+        .await
+    }
+
+    /// Settles from the winner's deposit as the harness seller, claiming
+    /// the winning handle decrypts to `plaintext`
+    pub async fn settle_from_deposit(&mut self, auction: &Pubkey, plaintext: u64) -> TxResult {
+        let seller = self.seller.insecure_clone();
+        let state = self.auction(auction).await;
+        let beneficiary = self.beneficiary(auction).await;
+        self.send(
+            ix::settle_from_deposit(
+                auction,
+                &state,
+                &beneficiary,
+                shadowbid::confidential::handle_to_bytes(state.highest_bid_handle),
+                codec::plaintext_bytes(plaintext),
+            ),
+            &[&seller],
+        )
+        .await
+    }
+
+    pub async fn reclaim_deposit(&mut self, auction: &Pubkey, bidder: &Keypair) -> TxResult {
+        self.send(ix::reclaim_deposit(&bidder.pubkey(), auction), &[bidder])
+            .await
+    }
+
+    pub async fn cancel(&mut self, auction: &Pubkey, reason: &str) -> TxResult {
+        self.cancel_for(auction, reason, CancellationReason::Other).await
+    }
+
+    pub async fn cancel_for(&mut self, auction: &Pubkey, reason: &str, category: CancellationReason) -> TxResult {
+        let seller = self.seller.insecure_clone();
+        let state = self.auction(auction).await;
+        self.send(
+            ix::cancel_auction(&seller.pubkey(), auction, &state, reason.to_string(), category),
+            &[&seller],
+        )
+        .await
+    }
+
+    pub async fn claim_compensation(&mut self, auction: &Pubkey, bidder: &Keypair) -> TxResult {
+        let state = self.auction(auction).await;
+        self.send(ix::claim_compensation(&bidder.pubkey(), auction, &state), &[bidder])
+            .await
+    }
+
+    pub async fn release_bond(&mut self, auction: &Pubkey) -> TxResult {
+        let seller = self.seller.insecure_clone();
+        let state = self.auction(auction).await;
+        self.send(ix::release_seller_bond(auction, &state), &[&seller])
+            .await
+    }
+
+    pub async fn confirm_delivery(&mut self, auction: &Pubkey, winner: &Keypair) -> TxResult {
+        let mut state = self.auction(auction).await;
+        state.winner = winner.pubkey();
+        let beneficiary = self.beneficiary(auction).await;
+        self.send(ix::confirm_delivery(auction, &state, &beneficiary), &[winner])
+            .await
+    }
+
+    pub async fn release_escrow(&mut self, auction: &Pubkey) -> TxResult {
+        let caller = self.ctx.payer.pubkey();
+        let state = self.auction(auction).await;
+        let beneficiary = self.beneficiary(auction).await;
+        self.send(ix::release_escrow(&caller, auction, &state, &beneficiary), &[])
+            .await
+    }
+
+    /// Cancels below the reserve as `caller`, claiming the winning
+    /// handle decrypts to `plaintext`
+    pub async fn cancel_below_reserve(&mut self, auction: &Pubkey, caller: &Keypair, plaintext: u64) -> TxResult {
+        let state = self.auction(auction).await;
+        self.send(
+            ix::cancel_below_reserve(
+                &caller.pubkey(),
+                auction,
+                &state,
+                shadowbid::confidential::handle_to_bytes(state.highest_bid_handle),
+                codec::plaintext_bytes(plaintext),
+            ),
+            &[caller],
+        )
+        .await
+    }
+
+    pub async fn declare_default(&mut self, auction: &Pubkey) -> TxResult {
+        let caller = self.ctx.payer.pubkey();
+        let state = self.auction(auction).await;
+        self.send(ix::declare_default(&caller, auction, &state), &[]).await
+    }
+
+    pub async fn dispute(&mut self, auction: &Pubkey, winner: &Keypair) -> TxResult {
+        let mut state = self.auction(auction).await;
+        state.winner = winner.pubkey();
+        self.send(ix::dispute(auction, &state, EVIDENCE_URI.to_string()), &[winner])
+            .await
+    }
+
+    pub async fn submit_evidence(&mut self, auction: &Pubkey, submitter: &Keypair, uri: &str) -> TxResult {
+        self.send(
+            ix::submit_evidence(&submitter.pubkey(), auction, uri.to_string()),
+            &[submitter],
+        )
+        .await
+    }
+
+    /// Redeems the auction's receipt as the harness seller
+    pub async fn redeem(&mut self, auction: &Pubkey) -> TxResult {
+        let seller = self.seller.insecure_clone();
+        self.send(ix::redeem_receipt(&seller.pubkey(), auction), &[&seller])
+            .await
+    }
+
+    /// Resolves a dispute as the harness arbitrator
+    pub async fn resolve(&mut self, auction: &Pubkey, buyer_bps: u16) -> TxResult {
+        let arbitrator = self.arbitrator.insecure_clone();
+        let state = self.auction(auction).await;
+        let beneficiary = self.beneficiary(auction).await;
+        self.send(
+            ix::resolve_dispute(auction, &state, &beneficiary, buyer_bps),
+            &[&arbitrator],
+        )
+        .await
+    }
+
+    /// Runs an escrowed auction with a single bid of `amount` through
+    /// settlement, leaving it AwaitingDelivery
+    pub async fn escrowed_sale(&mut self, amount: u64) -> (Pubkey, Keypair) {
+        let auction = self.create_escrowed().await;
+        let winner = self.keypair();
+        self.place_sealed_bid(&auction, &winner, amount).await.unwrap();
+        self.warp_to_end(&auction).await;
+        self.close_bidding(&auction).await.unwrap();
+        self.determine_and_finalize(&auction, std::slice::from_ref(&winner)).await;
+        self.settle(&auction, &winner).await.unwrap();
+        (auction, winner)
+    }
+
+    /// Places one sealed bid per amount from fresh bidders, then closes
+    /// bidding, leaving the auction Closed
+    pub async fn closed_sealed_auction(&mut self, amounts: &[u64]) -> (Pubkey, Vec<Keypair>) {
+        let auction = self.create(false).await;
+        let mut bidders = Vec::new();
+        for &amount in amounts {
+            let bidder = self.keypair();
+            self.place_sealed_bid(&auction, &bidder, amount).await.unwrap();
+            bidders.push(bidder);
+        }
+        self.warp_to_end(&auction).await;
+        self.close_bidding(&auction).await.unwrap();
+        (auction, bidders)
+    }
+
+    /// Processes every bid of a closed auction and finalizes it
+    pub async fn determine_and_finalize(&mut self, auction: &Pubkey, bidders: &[Keypair]) {
+        for bidder in bidders {
+            self.determine(auction, &bidder.pubkey()).await.unwrap();
+        }
+        self.finalize(auction, false).await.unwrap();
+    }
+}
+
+/// Metadata describing `document`, hosted at an Arweave-style URI
+pub fn metadata_params(document: &[u8], tags: &[&str]) -> AuctionMetadataParams {
+    AuctionMetadataParams {
+        title: "Vintage camera".to_string(),
+        description: "Film rangefinder, serviced last year".to_string(),
+        uri: "ar://Qm4bYh8kVvSx3rXoGKpZ1nD7yE2cFwTtLmU9aHjR6sPe".to_string(),
+        content_hash: solana_sdk::hash::hash(document).to_bytes(),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+    }
+}
+
+/// `settle_auction` with the winning handle and an arbitrary claimed
+/// plaintext, paying the seller
+pub fn settle_instruction(auction: &Pubkey, state: &Auction, winner: &Keypair, plaintext: u64) -> Instruction {
+    settle_instruction_to(auction, state, &state.seller, winner, plaintext)
+}
+
+/// `settle_instruction`, paying `beneficiary`
+pub fn settle_instruction_to(
+    auction: &Pubkey,
+    state: &Auction,
+    beneficiary: &Pubkey,
+    winner: &Keypair,
+    plaintext: u64,
+) -> Instruction {
+    let mut instruction = ix::settle_auction(
+        auction,
+        state,
+        beneficiary,
+        shadowbid::confidential::handle_to_bytes(state.highest_bid_handle),
+        codec::plaintext_bytes(plaintext),
+    );
+    // The builder assumes the recorded winner signs
+    instruction.accounts[0].pubkey = winner.pubkey();
+    instruction
+}
+
+/// Asserts that a transaction failed with the given custom error code
+pub fn assert_custom_error<T: std::fmt::Debug>(result: Result<T, BanksClientError>, code: u32) {
+    match result {
+        Err(err) => match err.unwrap() {
+            TransactionError::InstructionError(_, InstructionError::Custom(actual)) => {
+                assert_eq!(actual, code, "expected error {code}, got {actual}")
+            }
+            other => panic!("expected custom error {code}, got {other:?}"),
+        },
+        Ok(value) => panic!("expected custom error {code}, transaction succeeded: {value:?}"),
+    }
+}
+
+/// Asserts that a transaction failed with `error`
+pub fn assert_error<T: std::fmt::Debug>(result: Result<T, BanksClientError>, error: ShadowBidError) {
+    assert_custom_error(result, error.into());
+}
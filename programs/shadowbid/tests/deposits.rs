@@ -0,0 +1,186 @@
+//! Bid deposits: sealed auctions where each bidder locks a deposit, the
+//! seller settles from the winner's without the winner's signature, and
+//! bidders reclaim theirs once they no longer back a settlement
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use shadowbid::constants::SETTLEMENT_WINDOW;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::state::AuctionState;
+use shadowbid_client::pda;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Runs an auction taking deposits with one bid per amount through
+/// finalization, revealing the winning bid to the seller
+async fn deposit_auction(h: &mut Harness, amounts: &[u64]) -> (Pubkey, Vec<Keypair>) {
+    let auction = h.create_with_deposits().await;
+    let mut bidders = Vec::new();
+    for &amount in amounts {
+        let bidder = h.keypair();
+        h.place_sealed_bid(&auction, &bidder, amount).await.unwrap();
+        bidders.push(bidder);
+    }
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    for bidder in &bidders {
+        h.determine(&auction, &bidder.pubkey()).await.unwrap();
+    }
+    h.finalize(&auction, true).await.unwrap();
+    (auction, bidders)
+}
+
+#[tokio::test]
+async fn each_bid_locks_the_deposit_once() {
+    let mut h = Harness::new().await;
+    let bid_rent = h.bid_rent().await;
+    let auction = h.create_with_deposits().await;
+    let bidder = h.keypair();
+
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    h.place_sealed_bid(&auction, &bidder, 3_000).await.unwrap();
+
+    // Updating the bid doesn't take a second deposit
+    let (bid, _) = pda::bid_address(&auction, &bidder.pubkey());
+    assert_eq!(h.bid(&auction, &bidder.pubkey()).await.collateral, BID_DEPOSIT);
+    assert_eq!(h.lamports(&bid).await, bid_rent + BID_DEPOSIT);
+    assert!(h.auction(&auction).await.takes_bid_deposits());
+}
+
+#[tokio::test]
+async fn seller_settles_from_winner_deposit() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let bid_rent = h.bid_rent().await;
+    let (auction, bidders) = deposit_auction(&mut h, &[3_000, 5_000]).await;
+    let winner = &bidders[1];
+    let seller_before = h.lamports(&seller).await;
+    let winner_before = h.lamports(&winner.pubkey()).await;
+
+    // Only the seller signs
+    h.settle_from_deposit(&auction, 5_000).await.unwrap();
+
+    let state = h.auction(&auction).await;
+    assert_eq!(state.state().unwrap(), AuctionState::Settled);
+    assert_eq!({ state.winning_amount }, 5_000);
+    assert_eq!(h.lamports(&seller).await, seller_before + 5_000);
+    assert_eq!(h.lamports(&winner.pubkey()).await, winner_before + BID_DEPOSIT - 5_000);
+    let (bid, _) = pda::bid_address(&auction, &winner.pubkey());
+    assert_eq!(h.lamports(&bid).await, bid_rent);
+    assert_eq!(h.seller_profile(&seller).await.unwrap().auctions_settled, 1);
+    assert_eq!(h.bidder_profile(&winner.pubkey()).await.unwrap().auctions_settled, 1);
+
+    // The deposit paid for the auction, so nothing is left to settle or reclaim
+    assert_error(h.settle(&auction, winner).await, ShadowBidError::WinnerNotDetermined);
+    assert_error(h.reclaim_deposit(&auction, winner).await, ShadowBidError::NothingToClaim);
+}
+
+#[tokio::test]
+async fn deposit_must_cover_winning_amount() {
+    let mut h = Harness::new().await;
+    let (auction, bidders) = deposit_auction(&mut h, &[BID_DEPOSIT + 1]).await;
+
+    assert_error(
+        h.settle_from_deposit(&auction, BID_DEPOSIT + 1).await,
+        ShadowBidError::DepositTooSmall,
+    );
+
+    // The winner still settles themselves, and then gets the deposit back
+    h.settle(&auction, &bidders[0]).await.unwrap();
+    let winner_before = h.lamports(&bidders[0].pubkey()).await;
+    h.reclaim_deposit(&auction, &bidders[0]).await.unwrap();
+    assert_eq!(h.lamports(&bidders[0].pubkey()).await, winner_before + BID_DEPOSIT);
+}
+
+#[tokio::test]
+async fn losers_reclaim_once_winner_is_determined() {
+    let mut h = Harness::new().await;
+    let auction = h.create_with_deposits().await;
+    let loser = h.keypair();
+    let winner = h.keypair();
+    h.place_sealed_bid(&auction, &loser, 3_000).await.unwrap();
+    h.place_sealed_bid(&auction, &winner, 5_000).await.unwrap();
+
+    // Every deposit backs the auction until the winner is known
+    assert_error(h.reclaim_deposit(&auction, &loser).await, ShadowBidError::DepositLocked);
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    assert_error(h.reclaim_deposit(&auction, &loser).await, ShadowBidError::DepositLocked);
+
+    h.determine_and_finalize(&auction, &[loser.insecure_clone(), winner.insecure_clone()])
+        .await;
+    let loser_before = h.lamports(&loser.pubkey()).await;
+    h.reclaim_deposit(&auction, &loser).await.unwrap();
+    assert_eq!(h.lamports(&loser.pubkey()).await, loser_before + BID_DEPOSIT);
+    assert_eq!(h.bid(&auction, &loser.pubkey()).await.collateral, 0);
+    assert_error(h.reclaim_deposit(&auction, &loser).await, ShadowBidError::NothingToClaim);
+
+    // The winner's stays locked for the seller to settle from
+    assert_error(h.reclaim_deposit(&auction, &winner).await, ShadowBidError::DepositLocked);
+}
+
+#[tokio::test]
+async fn winner_below_reserve_reclaims_after_cancellation() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.insecure_clone();
+    let (auction, bidders) = deposit_auction(&mut h, &[RESERVE_PRICE - 1]).await;
+
+    // The deposit can't pay for a bid below the reserve
+    assert_error(
+        h.settle_from_deposit(&auction, RESERVE_PRICE - 1).await,
+        ShadowBidError::InvalidDecryptionProof,
+    );
+
+    h.cancel_below_reserve(&auction, &seller, RESERVE_PRICE - 1).await.unwrap();
+    let winner_before = h.lamports(&bidders[0].pubkey()).await;
+    h.reclaim_deposit(&auction, &bidders[0]).await.unwrap();
+    assert_eq!(h.lamports(&bidders[0].pubkey()).await, winner_before + BID_DEPOSIT);
+}
+
+#[tokio::test]
+async fn winner_below_reserve_keeps_deposit_through_default() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.insecure_clone();
+    let (auction, bidders) = deposit_auction(&mut h, &[RESERVE_PRICE - 1]).await;
+    let winner = &bidders[0];
+    let seller_before = h.lamports(&seller.pubkey()).await;
+    let winner_before = h.lamports(&winner.pubkey()).await;
+
+    // The seller declares the default before proving the bid below the reserve
+    let won_at = h.bid(&auction, &winner.pubkey()).await.won_at;
+    h.warp(won_at + SETTLEMENT_WINDOW).await;
+    h.declare_default(&auction).await.unwrap();
+    h.cancel_below_reserve(&auction, &seller, RESERVE_PRICE - 1).await.unwrap();
+
+    // The default is withdrawn and the whole deposit goes back to the winner
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+    assert_eq!(h.bidder_profile(&winner.pubkey()).await.unwrap().auctions_defaulted, 0);
+    h.reclaim_deposit(&auction, winner).await.unwrap();
+    assert_eq!(h.lamports(&winner.pubkey()).await, winner_before + BID_DEPOSIT);
+    assert_eq!(h.lamports(&seller.pubkey()).await, seller_before);
+}
+
+#[tokio::test]
+async fn defaulted_winner_reclaims_deposit() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let (auction, bidders) = deposit_auction(&mut h, &[4_000]).await;
+    let winner = &bidders[0];
+
+    let won_at = h.bid(&auction, &winner.pubkey()).await.won_at;
+    h.warp(won_at + SETTLEMENT_WINDOW).await;
+    let seller_before = h.lamports(&seller).await;
+    h.declare_default(&auction).await.unwrap();
+
+    // A seller declaring the default gives up settling from the deposit
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Defaulted);
+    assert_eq!(h.lamports(&seller).await, seller_before);
+    assert_error(
+        h.settle_from_deposit(&auction, 4_000).await,
+        ShadowBidError::WinnerNotDetermined,
+    );
+    let winner_before = h.lamports(&winner.pubkey()).await;
+    h.reclaim_deposit(&auction, winner).await.unwrap();
+    assert_eq!(h.lamports(&winner.pubkey()).await, winner_before + BID_DEPOSIT);
+}
@@ -0,0 +1,983 @@
+//! Every reachable `ShadowBidError`, grouped by instruction
+//!
+//! Variants no instruction can currently return:
+//! - `AuctionNotStarted`, `AuctionCancelled`, `InvalidBidCiphertext`,
+//!   `EncryptionFailed`, `ComparisonFailed` and `AttestationVerificationFailed`
+//!   are never returned (Inco failures surface as the Inco program's errors)
+//! - `BidAuctionMismatch` is shadowed by the bid PDA seeds, which already
+//!   bind the bid to the auction
+//! - `WinnerNotSet`: the first bid always becomes the current leader, so a
+//!   Closed auction always has one
+//! - `InvalidDiscriminant`: instructions only store known enum values; it
+//!   guards the accessors against corrupted account data
+
+mod common;
+
+use common::*;
+use mock_inco::codec;
+use mock_inco::MockIncoError;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::constants::MAX_DELEGATES;
+use shadowbid::instructions::{ArbitrationParams, AuthorityParams, HookParams};
+use shadowbid::state::{AuctionMode, AuctionState, CancellationPolicy, CancellationReason, HookMode, Permission};
+use shadowbid_client::{instructions as ix, pda};
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[test]
+fn unknown_discriminants_are_rejected() {
+    assert!(matches!(AuctionState::try_from(AuctionState::Defaulted as u8), Ok(AuctionState::Defaulted)));
+    assert!(matches!(AuctionMode::try_from(AuctionMode::CommitReveal as u8), Ok(AuctionMode::CommitReveal)));
+    assert!(matches!(CancellationPolicy::try_from(CancellationPolicy::Bonded as u8), Ok(CancellationPolicy::Bonded)));
+    assert!(matches!(HookMode::try_from(HookMode::Ignore as u8), Ok(HookMode::Ignore)));
+
+    assert!(matches!(AuctionState::try_from(9), Err(ShadowBidError::InvalidDiscriminant)));
+    assert!(matches!(AuctionMode::try_from(2), Err(ShadowBidError::InvalidDiscriminant)));
+    assert!(matches!(CancellationPolicy::try_from(2), Err(ShadowBidError::InvalidDiscriminant)));
+    assert!(matches!(HookMode::try_from(3), Err(ShadowBidError::InvalidDiscriminant)));
+}
+
+#[tokio::test]
+async fn create_auction_validates_params() {
+    let mut h = Harness::new().await;
+
+    let mut params = h.params(false);
+    params.title = "t".repeat(65);
+    assert_error(h.create_with(params).await, ShadowBidError::TitleTooLong);
+
+    let mut params = h.params(false);
+    params.description = "d".repeat(257);
+    assert_error(h.create_with(params).await, ShadowBidError::DescriptionTooLong);
+
+    let mut params = h.params(false);
+    params.duration = 119;
+    assert_error(h.create_with(params).await, ShadowBidError::DurationTooShort);
+
+    let mut params = h.params(false);
+    params.duration = 604_801;
+    assert_error(h.create_with(params).await, ShadowBidError::DurationTooLong);
+
+    let mut params = h.params(false);
+    params.reserve_price = 0;
+    assert_error(h.create_with(params).await, ShadowBidError::InvalidReservePrice);
+
+    for reveal_duration in [59, 86_401] {
+        let mut params = h.params(true);
+        params.commit_reveal.as_mut().unwrap().reveal_duration = reveal_duration;
+        assert_error(h.create_with(params).await, ShadowBidError::InvalidRevealDuration);
+    }
+
+    let mut params = h.params(false);
+    params.max_bidder_defaults = Some(u8::MAX);
+    assert_error(h.create_with(params).await, ShadowBidError::InvalidBidderDefaultLimit);
+
+    // Commit-reveal bids lock collateral instead of a deposit
+    for commit_reveal in [false, true] {
+        let mut params = h.params(commit_reveal);
+        params.bid_deposit = Some(if commit_reveal { BID_DEPOSIT } else { 0 });
+        assert_error(h.create_with(params).await, ShadowBidError::InvalidBidDeposit);
+    }
+
+    // The limits themselves are accepted
+    let mut params = h.params(false);
+    params.title = "t".repeat(64);
+    params.description = "d".repeat(256);
+    params.duration = 120;
+    h.create_with(params).await.unwrap();
+}
+
+#[tokio::test]
+async fn place_bid_errors() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+
+    let seller = h.seller.insecure_clone();
+    assert_error(
+        h.place_sealed_bid(&auction, &seller, 2_000).await,
+        ShadowBidError::SellerCannotBid,
+    );
+
+    // Still Open, but past end_time
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    assert_error(
+        h.place_sealed_bid(&auction, &bidder, 3_000).await,
+        ShadowBidError::BiddingEnded,
+    );
+
+    h.close_bidding(&auction).await.unwrap();
+    assert_error(
+        h.place_sealed_bid(&auction, &bidder, 3_000).await,
+        ShadowBidError::AuctionNotOpen,
+    );
+
+    // A commitment must be exactly 32 bytes, and not all zero
+    let auction = h.create(true).await;
+    for commitment in [vec![1; 31], vec![0; 32]] {
+        assert_error(
+            h.send(ix::place_bid(&bidder.pubkey(), &auction, commitment), &[&bidder])
+                .await,
+            ShadowBidError::InvalidCommitment,
+        );
+    }
+}
+
+#[tokio::test]
+async fn close_bidding_errors() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+
+    assert_error(h.close_bidding(&auction).await, ShadowBidError::BiddingNotEnded);
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    assert_error(h.close_bidding(&auction).await, ShadowBidError::AuctionNotOpen);
+}
+
+#[tokio::test]
+async fn cancel_auction_errors() {
+    let mut h = Harness::new().await;
+
+    // Only the seller
+    let auction = h.create(false).await;
+    let stranger = h.keypair();
+    let state = h.auction(&auction).await;
+    assert_error(
+        h.send(
+            ix::cancel_auction(
+                &stranger.pubkey(),
+                &auction,
+                &state,
+                "mine now".to_string(),
+                CancellationReason::Other,
+            ),
+            &[&stranger],
+        )
+        .await,
+        ShadowBidError::NotSeller,
+    );
+
+    // A reason that fits in the event
+    assert_error(
+        h.cancel(&auction, &"r".repeat(129)).await,
+        ShadowBidError::CancelReasonTooLong,
+    );
+
+    // Not after the first bid without a bond
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    assert_error(h.cancel(&auction, "early").await, ShadowBidError::CancellationNotAllowed);
+
+    // With one, not while bids can still come in
+    let auction = h.create_bonded().await;
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    assert_error(h.cancel(&auction, "early").await, ShadowBidError::BiddingNotEnded);
+
+    // Not once winner determination has started
+    let auction = h.create_bonded().await;
+    let bidders = [h.keypair(), h.keypair()];
+    for (bidder, amount) in bidders.iter().zip([2_000, 3_000]) {
+        h.place_sealed_bid(&auction, bidder, amount).await.unwrap();
+    }
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine(&auction, &bidders[0].pubkey()).await.unwrap();
+    assert_error(h.cancel(&auction, "too late").await, ShadowBidError::AuctionAlreadySettled);
+
+    // Nor after it
+    h.determine(&auction, &bidders[1].pubkey()).await.unwrap();
+    h.finalize(&auction, false).await.unwrap();
+    assert_error(h.cancel(&auction, "too late").await, ShadowBidError::AuctionAlreadySettled);
+
+    // Commit-reveal collateral has to be returned or forfeited first
+    let auction = h.create(true).await;
+    let bidder = h.keypair();
+    h.place_commitment(&auction, &bidder, 2_000, [1; 32]).await.unwrap();
+    assert_error(h.cancel(&auction, "locked").await, ShadowBidError::CollateralLocked);
+}
+
+#[tokio::test]
+async fn seller_bond_errors() {
+    let mut h = Harness::new().await;
+
+    let mut params = h.params(false);
+    params.seller_bond = Some(0);
+    assert_error(h.create_with(params).await, ShadowBidError::InvalidSellerBond);
+
+    // The vault comes with the bond and only with it
+    let seller = h.seller.insecure_clone();
+    let registry = h.registry().await;
+    let mut params = h.params(false);
+    params.seller_bond = Some(SELLER_BOND);
+    let mut create = ix::create_auction(&seller.pubkey(), &registry, params);
+    create.data = ix::create_auction(&seller.pubkey(), &registry, h.params(false)).data;
+    assert_error(h.send(create, &[&seller]).await, ShadowBidError::SellerBondMismatch);
+
+    // Compensation only follows a cancellation with bids
+    let auction = h.create_bonded().await;
+    let bidders = [h.keypair(), h.keypair()];
+    for bidder in &bidders {
+        h.place_sealed_bid(&auction, bidder, 2_000).await.unwrap();
+    }
+    assert_error(h.claim_compensation(&auction, &bidders[0]).await, ShadowBidError::NothingToClaim);
+
+    // The bond stays put until the auction settles
+    assert_error(h.release_bond(&auction).await, ShadowBidError::BondLocked);
+
+    // Only the seller releases it
+    let stranger = h.keypair();
+    let mut state = h.auction(&auction).await;
+    state.seller = stranger.pubkey();
+    assert_error(
+        h.send(ix::release_seller_bond(&auction, &state), &[&stranger]).await,
+        ShadowBidError::NotSeller,
+    );
+
+    // A bonded auction can't be cancelled without its vault
+    h.warp_to_end(&auction).await;
+    let mut state = h.auction(&auction).await;
+    state.set_cancellation_policy(CancellationPolicy::NoCancelAfterBid);
+    assert_error(
+        h.send(
+            ix::cancel_auction(
+                &seller.pubkey(),
+                &auction,
+                &state,
+                "Item damaged".to_string(),
+                CancellationReason::ItemUnavailable,
+            ),
+            &[&seller],
+        )
+        .await,
+        ShadowBidError::SellerBondMismatch,
+    );
+
+    // Once per bid
+    h.cancel(&auction, "Item damaged").await.unwrap();
+    h.claim_compensation(&auction, &bidders[0]).await.unwrap();
+    assert_error(
+        h.claim_compensation(&auction, &bidders[0]).await,
+        ShadowBidError::CompensationAlreadyClaimed,
+    );
+}
+
+#[tokio::test]
+async fn determine_winner_errors() {
+    let mut h = Harness::new().await;
+
+    let auction = h.create(false).await;
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    assert_error(
+        h.determine(&auction, &bidder.pubkey()).await,
+        ShadowBidError::AuctionNotClosed,
+    );
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine(&auction, &bidder.pubkey()).await.unwrap();
+    assert_error(
+        h.determine(&auction, &bidder.pubkey()).await,
+        ShadowBidError::BidAlreadyProcessed,
+    );
+
+    // Unrevealed collateral can only be forfeited to the seller
+    let auction = h.create(true).await;
+    let silent = h.keypair();
+    h.place_commitment(&auction, &silent, 2_000, [1; 32]).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.warp_to_reveal_end(&auction).await;
+    h.close_reveal(&auction).await.unwrap();
+
+    let state = h.auction(&auction).await;
+    let caller = h.ctx.payer.pubkey();
+    let mut instruction = ix::determine_winner(&caller, &auction, &state, &silent.pubkey());
+    instruction.accounts.pop();
+    assert_error(
+        h.send(instruction.clone(), &[]).await,
+        ShadowBidError::InsufficientRemainingAccounts,
+    );
+
+    instruction.accounts.push(AccountMeta::new(silent.pubkey(), false));
+    assert_error(h.send(instruction, &[]).await, ShadowBidError::NotSeller);
+}
+
+#[tokio::test]
+async fn finalize_winner_errors() {
+    let mut h = Harness::new().await;
+
+    let auction = h.create(false).await;
+    assert_error(h.finalize(&auction, false).await, ShadowBidError::AuctionNotClosed);
+
+    let (auction, bidders) = h.closed_sealed_auction(&[2_000, 3_000]).await;
+    h.determine(&auction, &bidders[0].pubkey()).await.unwrap();
+    assert_error(h.finalize(&auction, false).await, ShadowBidError::NoBidsPlaced);
+    h.determine(&auction, &bidders[1].pubkey()).await.unwrap();
+
+    let state = h.auction(&auction).await;
+    let caller = h.ctx.payer.pubkey();
+
+    // winner_address must be the current leader
+    let mut instruction = ix::finalize_winner(&caller, &auction, &state, false);
+    instruction.accounts[3].pubkey = bidders[0].pubkey();
+    assert_error(h.send(instruction, &[]).await, ShadowBidError::NotWinner);
+
+    // The seller's allowance needs both remaining accounts
+    let mut instruction = ix::finalize_winner(&caller, &auction, &state, true);
+    instruction.accounts.pop();
+    assert_error(
+        h.send(instruction, &[]).await,
+        ShadowBidError::InsufficientRemainingAccounts,
+    );
+
+    // ...and the second one must be the seller
+    let mut instruction = ix::finalize_winner(&caller, &auction, &state, true);
+    let last = instruction.accounts.len() - 1;
+    instruction.accounts[last].pubkey = bidders[0].pubkey();
+    assert_error(h.send(instruction, &[]).await, ShadowBidError::NotSeller);
+
+    h.finalize(&auction, false).await.unwrap();
+}
+
+#[tokio::test]
+async fn settle_auction_errors() {
+    let mut h = Harness::new().await;
+    let (auction, bidders) = h.closed_sealed_auction(&[2_000, 3_000]).await;
+    let (loser, winner) = (&bidders[0], &bidders[1]);
+
+    h.determine(&auction, &loser.pubkey()).await.unwrap();
+    h.determine(&auction, &winner.pubkey()).await.unwrap();
+    h.finalize(&auction, false).await.unwrap();
+    assert_error(h.settle(&auction, loser).await, ShadowBidError::NotWinner);
+
+    let state = h.auction(&auction).await;
+
+    // Payment goes to the seller only
+    let mut instruction = settle_instruction(&auction, &state, winner, 3_000);
+    instruction.accounts[2].pubkey = loser.pubkey();
+    assert_error(h.send(instruction, &[winner]).await, ShadowBidError::NotSeller);
+
+    // Only the winning handle settles, even with a valid decryption of another
+    let cheap = codec::make_handle(&[b"cheap"], RESERVE_PRICE);
+    let instruction = ix::settle_auction(
+        &auction,
+        &state,
+        &state.seller,
+        shadowbid::confidential::handle_to_bytes(cheap),
+        codec::plaintext_bytes(RESERVE_PRICE),
+    );
+    assert_error(
+        h.send(instruction, &[winner]).await,
+        ShadowBidError::InvalidDecryptionProof,
+    );
+
+    // An amount past u64 is rejected, not truncated to a cheap one
+    let instruction = ix::settle_auction(
+        &auction,
+        &state,
+        &state.seller,
+        shadowbid::confidential::handle_to_bytes(state.highest_bid_handle),
+        ((u64::MAX as u128) + 1 + RESERVE_PRICE as u128).to_le_bytes().to_vec(),
+    );
+    assert_error(h.send(instruction, &[winner]).await, ShadowBidError::AmountOverflow);
+
+    // A plaintext that doesn't match the handle fails attestation
+    let instruction = settle_instruction(&auction, &state, winner, RESERVE_PRICE);
+    assert_custom_error(
+        h.send(instruction, &[winner]).await,
+        MockIncoError::InvalidSignature.into(),
+    );
+
+    h.settle(&auction, winner).await.unwrap();
+    assert_error(h.settle(&auction, winner).await, ShadowBidError::WinnerNotDetermined);
+}
+
+#[tokio::test]
+async fn bid_deposit_errors() {
+    let mut h = Harness::new().await;
+    let stranger = h.keypair();
+
+    // Auctions without deposits have none to settle from or reclaim
+    let (auction, bidders) = h.closed_sealed_auction(&[3_000]).await;
+    h.determine_and_finalize(&auction, &bidders).await;
+    assert_error(h.settle_from_deposit(&auction, 3_000).await, ShadowBidError::NoBidDeposit);
+    assert_error(h.reclaim_deposit(&auction, &bidders[0]).await, ShadowBidError::NoBidDeposit);
+
+    let auction = h.create_with_deposits().await;
+    let (loser, winner) = (h.keypair(), h.keypair());
+    h.place_sealed_bid(&auction, &loser, 2_000).await.unwrap();
+    h.place_sealed_bid(&auction, &winner, 3_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, &[loser.insecure_clone(), winner.insecure_clone()])
+        .await;
+
+    let state = h.auction(&auction).await;
+    let settle = || {
+        ix::settle_from_deposit(
+            &auction,
+            &state,
+            &state.seller,
+            shadowbid::confidential::handle_to_bytes(state.highest_bid_handle),
+            codec::plaintext_bytes(3_000),
+        )
+    };
+
+    // Only the seller settles from the deposit, for the recorded winner
+    let mut instruction = settle();
+    instruction.accounts[0].pubkey = stranger.pubkey();
+    assert_error(h.send(instruction, &[&stranger]).await, ShadowBidError::NotSeller);
+    let seller = h.seller.insecure_clone();
+    let mut instruction = settle();
+    instruction.accounts[2].pubkey = loser.pubkey();
+    instruction.accounts[3].pubkey = pda::bid_address(&auction, &loser.pubkey()).0;
+    assert_error(h.send(instruction, &[&seller]).await, ShadowBidError::NotWinner);
+
+    // Payment goes to the beneficiary only
+    let mut instruction = settle();
+    instruction.accounts[7].pubkey = stranger.pubkey();
+    assert_error(h.send(instruction, &[&seller]).await, ShadowBidError::NotBeneficiary);
+
+    // Escrowed payments are made by the winner
+    let mut params = h.escrowed_params();
+    params.bid_deposit = Some(BID_DEPOSIT);
+    let auction = h.create_with(params).await.unwrap();
+    h.place_sealed_bid(&auction, &winner, 3_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, std::slice::from_ref(&winner)).await;
+    assert_error(h.settle_from_deposit(&auction, 3_000).await, ShadowBidError::WinnerMustSettle);
+}
+
+#[tokio::test]
+async fn escrow_errors() {
+    let mut h = Harness::new().await;
+
+    for delivery_timeout in [3_599, 2_592_001] {
+        let mut params = h.params(false);
+        params.delivery_timeout = Some(delivery_timeout);
+        assert_error(h.create_with(params).await, ShadowBidError::InvalidDeliveryTimeout);
+    }
+
+    // An escrowed auction can't be settled without its escrow
+    let auction = h.create_escrowed().await;
+    let winner = h.keypair();
+    h.place_sealed_bid(&auction, &winner, 2_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, std::slice::from_ref(&winner)).await;
+    let mut state = h.auction(&auction).await;
+    state.delivery_timeout = 0;
+    assert_error(
+        h.send(settle_instruction(&auction, &state, &winner, 2_000), &[&winner]).await,
+        ShadowBidError::EscrowMismatch,
+    );
+
+    h.settle(&auction, &winner).await.unwrap();
+    let stranger = h.keypair();
+    assert_error(h.confirm_delivery(&auction, &stranger).await, ShadowBidError::NotWinner);
+    assert_error(h.dispute(&auction, &stranger).await, ShadowBidError::NotWinner);
+    assert_error(h.release_escrow(&auction).await, ShadowBidError::DeliveryWindowOpen);
+
+    // Past the deadline the payment can only be released
+    let deadline = h.auction(&auction).await.delivery_deadline;
+    h.warp(deadline).await;
+    assert_error(h.dispute(&auction, &winner).await, ShadowBidError::DeliveryWindowEnded);
+
+    // A disputed payment stays frozen
+    let (auction, winner) = h.escrowed_sale(2_000).await;
+    h.dispute(&auction, &winner).await.unwrap();
+    assert_error(
+        h.confirm_delivery(&auction, &winner).await,
+        ShadowBidError::NotAwaitingDelivery,
+    );
+}
+
+#[tokio::test]
+async fn arbitration_errors() {
+    let mut h = Harness::new().await;
+    let arbitration = |arbitrator, fee_bps| Some(ArbitrationParams { arbitrator, fee_bps });
+
+    let mut params = h.params(false);
+    params.arbitration = arbitration(h.arbitrator.pubkey(), ARBITRATION_FEE_BPS);
+    assert_error(h.create_with(params).await, ShadowBidError::ArbitrationRequiresEscrow);
+
+    for arbitrator in [Pubkey::default(), h.seller.pubkey()] {
+        let mut params = h.params(false);
+        params.delivery_timeout = Some(DELIVERY_TIMEOUT);
+        params.arbitration = arbitration(arbitrator, ARBITRATION_FEE_BPS);
+        assert_error(h.create_with(params).await, ShadowBidError::InvalidArbitrator);
+    }
+
+    let mut params = h.params(false);
+    params.delivery_timeout = Some(DELIVERY_TIMEOUT);
+    params.arbitration = arbitration(h.arbitrator.pubkey(), 1_001);
+    assert_error(h.create_with(params).await, ShadowBidError::InvalidArbitrationFee);
+
+    let auction = h.create_escrowed().await;
+    let arbitrator = h.arbitrator.insecure_clone();
+    assert_error(
+        h.place_sealed_bid(&auction, &arbitrator, 2_000).await,
+        ShadowBidError::ArbitratorCannotBid,
+    );
+
+    // Without an arbitrator nobody could unfreeze a disputed payment
+    let mut params = h.params(false);
+    params.delivery_timeout = Some(DELIVERY_TIMEOUT);
+    let auction = h.create_with(params).await.unwrap();
+    let winner = h.keypair();
+    h.place_sealed_bid(&auction, &winner, 2_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, std::slice::from_ref(&winner)).await;
+    h.settle(&auction, &winner).await.unwrap();
+    assert_error(h.dispute(&auction, &winner).await, ShadowBidError::NoArbitrator);
+
+    let (auction, winner) = h.escrowed_sale(2_000).await;
+    let seller = h.seller.insecure_clone();
+    assert_error(h.resolve(&auction, 5_000).await, ShadowBidError::NotDisputed);
+    assert_error(
+        h.submit_evidence(&auction, &seller, EVIDENCE_URI).await,
+        ShadowBidError::NotDisputed,
+    );
+
+    let state = h.auction(&auction).await;
+    assert_error(
+        h.send(ix::dispute(&auction, &state, "u".repeat(201)), &[&winner]).await,
+        ShadowBidError::EvidenceUriTooLong,
+    );
+    h.dispute(&auction, &winner).await.unwrap();
+    assert_error(
+        h.submit_evidence(&auction, &seller, &"u".repeat(201)).await,
+        ShadowBidError::EvidenceUriTooLong,
+    );
+
+    // Only the parties add evidence, and only the arbitrator rules
+    let stranger = h.keypair();
+    assert_error(
+        h.submit_evidence(&auction, &stranger, EVIDENCE_URI).await,
+        ShadowBidError::NotDisputeParty,
+    );
+    let mut state = h.auction(&auction).await;
+    state.arbitrator = stranger.pubkey();
+    assert_error(
+        h.send(ix::resolve_dispute(&auction, &state, &state.seller, 5_000), &[&stranger]).await,
+        ShadowBidError::NotArbitrator,
+    );
+    assert_error(h.resolve(&auction, 10_001).await, ShadowBidError::InvalidBuyerShare);
+}
+
+#[tokio::test]
+async fn settlement_callback_errors() {
+    let mut h = Harness::new().await;
+
+    for program in [Pubkey::default(), shadowbid::ID] {
+        let mut params = h.params(false);
+        params.settlement_callback = Some(program);
+        assert_error(h.create_with(params).await, ShadowBidError::InvalidSettlementCallback);
+    }
+
+    // Escrowed payments reach the seller later, outside settle_auction
+    let mut params = h.params(false);
+    params.delivery_timeout = Some(DELIVERY_TIMEOUT);
+    params.settlement_callback = Some(mock_inco::ID);
+    assert_error(h.create_with(params).await, ShadowBidError::CallbackRequiresDirectPayment);
+
+    // A hook hears of settlement too, so an auction takes one or the other
+    let mut params = h.params(false);
+    params.settlement_callback = Some(mock_inco::ID);
+    params.hook = Some(HookParams {
+        program: mock_inco::ID,
+        ignore_failures: false,
+    });
+    assert_error(h.create_with(params).await, ShadowBidError::CallbackWithHook);
+
+    // The callback account comes with the parameter and only with it
+    let seller = h.seller.insecure_clone();
+    let registry = h.registry().await;
+    let mut params = h.params(false);
+    params.settlement_callback = Some(mock_inco::ID);
+    let mut create = ix::create_auction(&seller.pubkey(), &registry, params);
+    create.data = ix::create_auction(&seller.pubkey(), &registry, h.params(false)).data;
+    assert_error(h.send(create, &[&seller]).await, ShadowBidError::SettlementCallbackMismatch);
+}
+
+#[tokio::test]
+async fn hook_errors() {
+    let mut h = Harness::new().await;
+    let hook = |program| HookParams {
+        program,
+        ignore_failures: false,
+    };
+
+    for program in [Pubkey::default(), shadowbid::ID] {
+        let mut params = h.params(false);
+        params.hook = Some(hook(program));
+        assert_error(h.create_with(params).await, ShadowBidError::InvalidHookProgram);
+    }
+
+    // The hook program is passed to be checked
+    let seller = h.seller.insecure_clone();
+    let registry = h.registry().await;
+    let mut params = h.params(false);
+    params.hook = Some(hook(mock_inco::ID));
+    let mut create = ix::create_auction(&seller.pubkey(), &registry, params);
+    create.accounts.iter_mut().find(|meta| meta.pubkey == mock_inco::ID).unwrap().pubkey = shadowbid::ID;
+    assert_error(h.send(create, &[&seller]).await, ShadowBidError::InvalidHookProgram);
+
+    // The hook account comes with the parameter and only with it
+    let mut params = h.params(false);
+    params.hook = Some(hook(mock_inco::ID));
+    let mut create = ix::create_auction(&seller.pubkey(), &registry, params);
+    create.data = ix::create_auction(&seller.pubkey(), &registry, h.params(false)).data;
+    assert_error(h.send(create, &[&seller]).await, ShadowBidError::HookMismatch);
+}
+
+#[tokio::test]
+async fn receipt_errors() {
+    let mut h = Harness::new().await;
+    let mut params = h.params(false);
+    params.issue_receipt = true;
+    params.delivery_timeout = Some(DELIVERY_TIMEOUT);
+    params.arbitration = Some(ArbitrationParams {
+        arbitrator: h.arbitrator.pubkey(),
+        fee_bps: ARBITRATION_FEE_BPS,
+    });
+    let auction = h.create_with(params).await.unwrap();
+    let winner = h.keypair();
+    h.place_sealed_bid(&auction, &winner, 2_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, std::slice::from_ref(&winner)).await;
+
+    // The winner can't settle without taking the receipt
+    let mut state = h.auction(&auction).await;
+    state.set_issues_receipt(false);
+    assert_error(
+        h.send(settle_instruction(&auction, &state, &winner, 2_000), &[&winner]).await,
+        ShadowBidError::ReceiptMismatch,
+    );
+    h.settle(&auction, &winner).await.unwrap();
+
+    // Only the seller redeems, and not while the payment is disputed
+    let stranger = h.keypair();
+    assert_error(
+        h.send(ix::redeem_receipt(&stranger.pubkey(), &auction), &[&stranger]).await,
+        ShadowBidError::NotSeller,
+    );
+    h.dispute(&auction, &winner).await.unwrap();
+    assert_error(h.redeem(&auction).await, ShadowBidError::ReceiptDisputed);
+}
+
+#[tokio::test]
+async fn bidder_profile_errors() {
+    let mut h = Harness::new().await;
+    let (auction, bidders) = h.closed_sealed_auction(&[2_000, 3_000]).await;
+    let (loser, winner) = (&bidders[0], &bidders[1]);
+    h.determine(&auction, &loser.pubkey()).await.unwrap();
+    h.determine(&auction, &winner.pubkey()).await.unwrap();
+
+    // The winner's bid records the win
+    let state = h.auction(&auction).await;
+    let caller = h.ctx.payer.pubkey();
+    let mut instruction = ix::finalize_winner(&caller, &auction, &state, false);
+    instruction.accounts[4] = AccountMeta::new_readonly(shadowbid::ID, false);
+    assert_error(h.send(instruction, &[]).await, ShadowBidError::WinnerBidMismatch);
+    h.finalize(&auction, false).await.unwrap();
+
+    // Only the winner defaults, and only once their window is over
+    assert_error(h.declare_default(&auction).await, ShadowBidError::SettlementWindowOpen);
+    let mut state = h.auction(&auction).await;
+    state.winner = loser.pubkey();
+    assert_error(
+        h.send(ix::declare_default(&caller, &auction, &state), &[]).await,
+        ShadowBidError::NotWinner,
+    );
+}
+
+#[tokio::test]
+async fn auction_stats_errors() {
+    let mut h = Harness::new().await;
+    let (auction, bidders) = h.closed_sealed_auction(&[2_000, 3_000]).await;
+    h.determine_and_finalize(&auction, &bidders).await;
+
+    let seller = h.seller.insecure_clone();
+    let state = h.auction(&auction).await;
+    assert_error(
+        h.send(ix::reveal_auction_stats(&auction, &state), &[&seller]).await,
+        ShadowBidError::WinnerNotDetermined,
+    );
+
+    h.settle(&auction, &bidders[1]).await.unwrap();
+    let state = h.auction(&auction).await;
+
+    let stranger = h.keypair();
+    let mut instruction = ix::reveal_auction_stats(&auction, &state);
+    instruction.accounts[0].pubkey = stranger.pubkey();
+    assert_error(h.send(instruction, &[&stranger]).await, ShadowBidError::NotSeller);
+
+    // All three aggregates are published together
+    let plaintexts = [5_000, 2_000].map(codec::plaintext_bytes).to_vec();
+    assert_error(
+        h.send(ix::publish_auction_stats(&auction, &state, plaintexts), &[&seller])
+            .await,
+        ShadowBidError::InvalidDecryptionProof,
+    );
+
+    // Commit-reveal auctions don't accumulate encrypted statistics
+    let auction = h.create(true).await;
+    let bidder = h.keypair();
+    h.place_commitment(&auction, &bidder, 2_000, [1; 32]).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.reveal(&auction, &bidder, 2_000, [1; 32]).await.unwrap();
+    h.warp_to_reveal_end(&auction).await;
+    h.close_reveal(&auction).await.unwrap();
+    h.determine(&auction, &bidder.pubkey()).await.unwrap();
+    h.finalize(&auction, false).await.unwrap();
+    h.settle(&auction, &bidder).await.unwrap();
+
+    let state = h.auction(&auction).await;
+    assert_eq!(state.state().unwrap(), AuctionState::Settled);
+    assert_error(
+        h.send(ix::reveal_auction_stats(&auction, &state), &[&seller]).await,
+        ShadowBidError::StatsNotAvailable,
+    );
+}
+
+#[tokio::test]
+async fn reveal_bid_errors() {
+    let mut h = Harness::new().await;
+    let bidder = h.keypair();
+
+    let sealed = h.create(false).await;
+    h.place_sealed_bid(&sealed, &bidder, 2_000).await.unwrap();
+    assert_error(
+        h.reveal(&sealed, &bidder, 2_000, [1; 32]).await,
+        ShadowBidError::WrongAuctionMode,
+    );
+
+    let auction = h.create(true).await;
+    h.place_commitment(&auction, &bidder, 2_000, [1; 32]).await.unwrap();
+    assert_error(
+        h.reveal(&auction, &bidder, 2_000, [1; 32]).await,
+        ShadowBidError::AuctionNotRevealing,
+    );
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    assert_error(
+        h.reveal(&auction, &bidder, 2_000, [2; 32]).await,
+        ShadowBidError::InvalidCommitment,
+    );
+    assert_error(
+        h.reveal(&auction, &bidder, 2_001, [1; 32]).await,
+        ShadowBidError::InvalidCommitment,
+    );
+
+    h.reveal(&auction, &bidder, 2_000, [1; 32]).await.unwrap();
+    assert_error(
+        h.reveal(&auction, &bidder, 2_000, [1; 32]).await,
+        ShadowBidError::BidAlreadyRevealed,
+    );
+
+    // A second bidder misses the window
+    let auction = h.create(true).await;
+    let late = h.keypair();
+    h.place_commitment(&auction, &late, 2_000, [1; 32]).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.warp_to_reveal_end(&auction).await;
+    assert_error(
+        h.reveal(&auction, &late, 2_000, [1; 32]).await,
+        ShadowBidError::RevealEnded,
+    );
+}
+
+#[tokio::test]
+async fn close_reveal_errors() {
+    let mut h = Harness::new().await;
+
+    let (sealed, _) = h.closed_sealed_auction(&[2_000]).await;
+    assert_error(h.close_reveal(&sealed).await, ShadowBidError::AuctionNotRevealing);
+
+    let auction = h.create(true).await;
+    let bidder = h.keypair();
+    h.place_commitment(&auction, &bidder, 2_000, [1; 32]).await.unwrap();
+    assert_error(h.close_reveal(&auction).await, ShadowBidError::AuctionNotRevealing);
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    assert_error(h.close_reveal(&auction).await, ShadowBidError::RevealNotEnded);
+
+    h.warp_to_reveal_end(&auction).await;
+    h.close_reveal(&auction).await.unwrap();
+}
+
+#[tokio::test]
+async fn auction_metadata_errors() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+    let params = metadata_params(b"{}", &["art"]);
+
+    // Only the seller
+    let stranger = h.keypair();
+    assert_error(
+        h.send(
+            ix::create_auction_metadata(&stranger.pubkey(), &auction, params.clone()),
+            &[&stranger],
+        )
+        .await,
+        ShadowBidError::NotSeller,
+    );
+
+    let mut long = params.clone();
+    long.title = "t".repeat(101);
+    assert_error(h.create_metadata(&auction, long).await, ShadowBidError::MetadataTooLong);
+    let mut long = params.clone();
+    long.description = "d".repeat(301);
+    assert_error(h.create_metadata(&auction, long).await, ShadowBidError::MetadataTooLong);
+    let mut long = params.clone();
+    long.uri = "u".repeat(201);
+    assert_error(h.create_metadata(&auction, long).await, ShadowBidError::MetadataTooLong);
+
+    let mut tags = params.clone();
+    tags.tags = vec!["tag".to_string(); 9];
+    assert_error(h.create_metadata(&auction, tags).await, ShadowBidError::TooManyTags);
+    for tag in ["", "seventeen-bytes!!"] {
+        let mut tags = params.clone();
+        tags.tags = vec![tag.to_string()];
+        assert_error(h.create_metadata(&auction, tags).await, ShadowBidError::InvalidTag);
+    }
+
+    // The limits themselves fit one transaction
+    let mut max = params.clone();
+    max.title = "t".repeat(100);
+    max.description = "d".repeat(300);
+    max.uri = "u".repeat(200);
+    max.tags = vec!["sixteen-bytes!!!".to_string(); 8];
+    h.create_metadata(&auction, max).await.unwrap();
+    let mut invalid = params.clone();
+    invalid.tags = vec![String::new()];
+    assert_error(h.update_metadata(&auction, invalid).await, ShadowBidError::InvalidTag);
+
+    // Frozen by the first bid, whether or not it was created
+    let auction = h.create(false).await;
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    assert_error(h.create_metadata(&auction, params.clone()).await, ShadowBidError::MetadataLocked);
+
+    // And never for auctions that are no longer open
+    let auction = h.create(false).await;
+    h.cancel(&auction, "withdrawn").await.unwrap();
+    assert_error(h.create_metadata(&auction, params).await, ShadowBidError::AuctionNotOpen);
+}
+
+#[tokio::test]
+async fn migration_errors() {
+    let mut h = Harness::new().await;
+    let payer = h.ctx.payer.pubkey();
+
+    // Accounts created now already have the current layout
+    let auction = h.create(false).await;
+    assert_error(
+        h.send(ix::migrate_auction(&payer, &auction), &[]).await,
+        ShadowBidError::AccountAlreadyMigrated,
+    );
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    let (bid, _) = pda::bid_address(&auction, &bidder.pubkey());
+    assert_error(
+        h.send(ix::migrate_bid(&payer, &bid), &[]).await,
+        ShadowBidError::AccountAlreadyMigrated,
+    );
+}
+
+#[tokio::test]
+async fn authority_errors() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.insecure_clone();
+    let authority = h.keypair();
+    let roles = |authority, beneficiary| Some(AuthorityParams { authority, beneficiary });
+
+    for (authority, beneficiary) in [
+        (Pubkey::default(), seller.pubkey()),
+        (authority.pubkey(), Pubkey::default()),
+    ] {
+        let mut params = h.params(false);
+        params.authority = roles(authority, beneficiary);
+        assert_error(h.create_with(params).await, ShadowBidError::InvalidAuthority);
+    }
+
+    // The arbitrator can't rule on payments to themselves
+    let mut params = h.params(false);
+    params.delivery_timeout = Some(DELIVERY_TIMEOUT);
+    params.arbitration = Some(ArbitrationParams {
+        arbitrator: h.arbitrator.pubkey(),
+        fee_bps: ARBITRATION_FEE_BPS,
+    });
+    params.authority = roles(authority.pubkey(), h.arbitrator.pubkey());
+    assert_error(h.create_with(params).await, ShadowBidError::InvalidArbitrator);
+
+    // The record comes with the parameter and only with it
+    let registry = h.registry().await;
+    let mut params = h.params(false);
+    params.authority = roles(authority.pubkey(), seller.pubkey());
+    let mut create = ix::create_auction(&seller.pubkey(), &registry, params);
+    create.data = ix::create_auction(&seller.pubkey(), &registry, h.params(false)).data;
+    assert_error(h.send(create, &[&seller]).await, ShadowBidError::AuthorityMismatch);
+
+    // Without a record only the seller claims authority
+    let plain = h.create(false).await;
+    let stranger = h.keypair();
+    assert_error(
+        h.send(ix::transfer_authority(&stranger.pubkey(), &plain, &stranger.pubkey()), &[&stranger]).await,
+        ShadowBidError::NotSeller,
+    );
+
+    // With one, the seller is just another stranger
+    let mut params = h.params(false);
+    params.authority = roles(authority.pubkey(), seller.pubkey());
+    let auction = h.create_with(params).await.unwrap();
+    let grant = ix::set_delegate(&seller.pubkey(), &auction, &stranger.pubkey(), Permission::Cancel.bit());
+    assert_error(h.send(grant, &[&seller]).await, ShadowBidError::NotAuthority);
+    let transfer = ix::transfer_authority(&seller.pubkey(), &auction, &seller.pubkey());
+    assert_error(h.send(transfer, &[&seller]).await, ShadowBidError::NotAuthority);
+    assert_error(h.cancel(&auction, "withdrawn").await, ShadowBidError::NotAuthority);
+
+    let transfer = ix::transfer_authority(&authority.pubkey(), &auction, &Pubkey::default());
+    assert_error(h.send(transfer, &[&authority]).await, ShadowBidError::InvalidAuthority);
+    let grant = ix::set_delegate(&authority.pubkey(), &auction, &Pubkey::default(), Permission::Cancel.bit());
+    assert_error(h.send(grant, &[&authority]).await, ShadowBidError::InvalidAuthority);
+    let grant = ix::set_delegate(&authority.pubkey(), &auction, &stranger.pubkey(), Permission::ALL << 1);
+    assert_error(h.send(grant, &[&authority]).await, ShadowBidError::InvalidPermissions);
+
+    for _ in 0..MAX_DELEGATES {
+        let delegate = h.keypair().pubkey();
+        let grant = ix::set_delegate(&authority.pubkey(), &auction, &delegate, Permission::Cancel.bit());
+        h.send(grant, &[&authority]).await.unwrap();
+    }
+    let grant = ix::set_delegate(&authority.pubkey(), &auction, &stranger.pubkey(), Permission::Cancel.bit());
+    assert_error(h.send(grant, &[&authority]).await, ShadowBidError::TooManyDelegates);
+
+    // The winner pays the beneficiary on record, not whoever they name
+    let winner = h.keypair();
+    h.place_sealed_bid(&auction, &winner, 2_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, std::slice::from_ref(&winner)).await;
+    let state = h.auction(&auction).await;
+    assert_error(
+        h.send(settle_instruction_to(&auction, &state, &stranger.pubkey(), &winner, 2_000), &[&winner]).await,
+        ShadowBidError::NotBeneficiary,
+    );
+
+    // Escrowed payments go to the beneficiary recorded at settlement
+    let (escrowed, winner) = h.escrowed_sale(2_000).await;
+    let state = h.auction(&escrowed).await;
+    let confirm = ix::confirm_delivery(&escrowed, &state, &stranger.pubkey());
+    assert_error(h.send(confirm, &[&winner]).await, ShadowBidError::NotBeneficiary);
+}
@@ -0,0 +1,85 @@
+//! Physical-goods escrow: settlement holds the payment until the winner
+//! confirms delivery, the delivery window ends, or the winner disputes
+//!
+//! WinnerDetermined → AwaitingDelivery → Settled (confirmed or timed out)
+//! AwaitingDelivery → Disputed
+
+mod common;
+
+use common::*;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::state::AuctionState;
+use shadowbid_client::pda;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn confirmed_delivery_releases_payment_to_seller() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let escrow_rent = h.escrow_rent().await;
+    let (auction, winner) = h.escrowed_sale(5_000).await;
+    let seller_before = h.lamports(&seller).await;
+    let winner_after_settle = h.lamports(&winner.pubkey()).await;
+
+    // Settlement pays into the escrow, not the seller
+    let state = h.auction(&auction).await;
+    assert_eq!(state.state().unwrap(), AuctionState::AwaitingDelivery);
+    assert_eq!({ state.winning_amount }, 5_000);
+    assert_eq!({ state.delivery_deadline }, h.now().await + DELIVERY_TIMEOUT);
+    let escrow = h.escrow(&auction).await.unwrap();
+    assert_eq!(escrow.amount, 5_000);
+    assert_eq!(escrow.buyer, winner.pubkey());
+    assert_eq!(escrow.beneficiary, seller);
+    let (address, _) = pda::escrow_address(&auction);
+    assert_eq!(h.lamports(&address).await, escrow_rent + 5_000);
+
+    h.confirm_delivery(&auction, &winner).await.unwrap();
+
+    // The seller is paid and the winner gets the escrow's rent back
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+    assert!(h.escrow(&auction).await.is_none());
+    assert_eq!(h.lamports(&seller).await, seller_before + 5_000);
+    assert_eq!(h.lamports(&winner.pubkey()).await, winner_after_settle + escrow_rent);
+}
+
+#[tokio::test]
+async fn payment_is_released_once_delivery_window_ends() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let (auction, winner) = h.escrowed_sale(5_000).await;
+    let seller_before = h.lamports(&seller).await;
+
+    let deadline = h.auction(&auction).await.delivery_deadline;
+    h.warp(deadline - 1).await;
+    assert_error(h.release_escrow(&auction).await, ShadowBidError::DeliveryWindowOpen);
+
+    // Anyone can release it once the window has ended
+    h.warp(deadline).await;
+    h.release_escrow(&auction).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+    assert!(h.escrow(&auction).await.is_none());
+    assert_eq!(h.lamports(&seller).await, seller_before + 5_000);
+    assert_eq!({ h.auction(&auction).await.winner }, winner.pubkey());
+}
+
+#[tokio::test]
+async fn dispute_freezes_payment() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let (auction, winner) = h.escrowed_sale(5_000).await;
+    let seller_before = h.lamports(&seller).await;
+
+    h.dispute(&auction, &winner).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Disputed);
+
+    // Neither the winner nor the timeout can release a disputed payment
+    let deadline = h.auction(&auction).await.delivery_deadline;
+    h.warp(deadline).await;
+    assert_error(h.release_escrow(&auction).await, ShadowBidError::NotAwaitingDelivery);
+    assert_error(
+        h.confirm_delivery(&auction, &winner).await,
+        ShadowBidError::NotAwaitingDelivery,
+    );
+    assert_eq!(h.escrow(&auction).await.unwrap().amount, 5_000);
+    assert_eq!(h.lamports(&seller).await, seller_before);
+}
@@ -0,0 +1,408 @@
+//! Hook programs invoked when an auction settles, pays out its escrow or
+//! is cancelled, in the same transaction
+//!
+//! The hook here is a mock liquidator, a native program that signs for its
+//! vault PDA to sell through ShadowBid and records the completions it is
+//! told about.
+
+mod common;
+
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use anchor_lang::AnchorDeserialize;
+use common::*;
+use mock_inco::codec;
+use shadowbid::confidential::handle_to_bytes;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::hook::{AuctionCompletion, AuctionOutcome, ON_AUCTION_COMPLETED_DISCRIMINATOR};
+use shadowbid::instructions::HookParams;
+use shadowbid::state::{Auction, AuctionState, CancellationReason, HookMode};
+use shadowbid_client::{instructions as ix, pda};
+use solana_program_test::processor;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::program_error::ProgramError;
+use solana_sdk::signature::{Keypair, Signer};
+
+const LIQUIDATOR_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+
+const VAULT_SEED: &[u8] = b"vault";
+
+/// Completion record: the amount (u64 LE), the winner, then the outcome
+const RECORD_SPACE: usize = 8 + 32 + 1;
+
+fn vault_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED], &LIQUIDATOR_ID)
+}
+
+/// Records the completion in the record account passed after the fixed
+/// hook accounts; any other instruction is forwarded to ShadowBid (the
+/// last account) signed by the vault
+fn liquidator_processor(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (vault, bump) = vault_address();
+
+    if let Some(mut payload) = data.strip_prefix(&ON_AUCTION_COMPLETED_DISCRIMINATOR) {
+        let [auction, winner, _seller, record, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if !auction.is_signer || *auction.owner != shadowbid::ID {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let completion = AuctionCompletion::deserialize(&mut payload)
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+        assert_eq!(completion.winner, *winner.key);
+        let mut data = record.try_borrow_mut_data()?;
+        data[..8].copy_from_slice(&completion.amount.to_le_bytes());
+        data[8..40].copy_from_slice(completion.winner.as_ref());
+        data[40] = completion.outcome as u8 + 1;
+        return Ok(());
+    }
+
+    let (program, accounts) = accounts.split_last().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let metas = accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer || *account.key == vault,
+            is_writable: account.is_writable,
+        })
+        .collect();
+    invoke_signed(
+        &Instruction {
+            program_id: *program.key,
+            accounts: metas,
+            data: data.to_vec(),
+        },
+        accounts,
+        &[&[VAULT_SEED, &[bump]]],
+    )
+}
+
+async fn liquidator_harness() -> Harness {
+    let mut h = Harness::with_programs(|program_test| {
+        program_test.add_program("mock_liquidator", LIQUIDATOR_ID, processor!(liquidator_processor));
+    })
+    .await;
+    // The vault receives payments, so it starts rent-exempt
+    h.ctx.set_account(
+        &vault_address().0,
+        &AccountSharedData::new(STARTING_BALANCE, 0, &system_program::ID),
+    );
+    h
+}
+
+fn liquidator_hook(ignore_failures: bool) -> Option<HookParams> {
+    Some(HookParams {
+        program: LIQUIDATOR_ID,
+        ignore_failures,
+    })
+}
+
+/// Creates an auction sold by the liquidator vault, with `payer` paying
+async fn create_from_vault(h: &mut Harness, payer: &Keypair) -> Pubkey {
+    let (vault, _) = vault_address();
+    let registry = h.registry().await;
+    let mut params = h.params(false);
+    params.hook = liquidator_hook(false);
+    let (auction, _) = pda::auction_address(&vault, registry.auction_count);
+
+    let mut create = ix::create_auction_with_payer(&payer.pubkey(), &vault, &registry, params);
+    create.program_id = LIQUIDATOR_ID;
+    for meta in &mut create.accounts {
+        meta.is_signer = meta.pubkey == payer.pubkey();
+    }
+    create.accounts.push(AccountMeta::new_readonly(shadowbid::ID, false));
+    h.send(create, &[payer]).await.expect("create through the liquidator failed");
+    auction
+}
+
+/// Runs the auction with one bid of `amount` up to settlement
+async fn sell(h: &mut Harness, auction: &Pubkey, amount: u64) -> Keypair {
+    let winner = h.keypair();
+    h.place_sealed_bid(auction, &winner, amount).await.unwrap();
+    h.warp_to_end(auction).await;
+    h.close_bidding(auction).await.unwrap();
+    h.determine_and_finalize(auction, std::slice::from_ref(&winner)).await;
+    winner
+}
+
+/// A fresh record account for the liquidator to write to
+fn new_record(h: &mut Harness) -> Pubkey {
+    let record = Pubkey::new_unique();
+    h.ctx.set_account(
+        &record,
+        &AccountSharedData::new(STARTING_BALANCE, RECORD_SPACE, &LIQUIDATOR_ID),
+    );
+    record
+}
+
+async fn record_data(h: &mut Harness, record: Pubkey) -> Vec<u8> {
+    h.ctx.banks_client.get_account(record).await.unwrap().unwrap().data
+}
+
+/// `settle_auction` for the winning `amount`, invoking the liquidator
+/// with `extra` accounts
+fn settle_with_hook(auction: &Pubkey, state: &Auction, amount: u64, extra: Vec<AccountMeta>) -> Instruction {
+    ix::settle_auction_with_hook(
+        auction,
+        state,
+        &state.seller,
+        handle_to_bytes(state.highest_bid_handle),
+        codec::plaintext_bytes(amount),
+        &LIQUIDATOR_ID,
+        extra,
+    )
+}
+
+#[tokio::test]
+async fn hook_runs_on_settlement_to_pda_seller() {
+    let mut h = liquidator_harness().await;
+    let payer = h.keypair();
+    let (vault, _) = vault_address();
+    let auction = create_from_vault(&mut h, &payer).await;
+
+    let state = h.auction(&auction).await;
+    assert_eq!(state.seller, vault);
+    assert_eq!(state.hook_mode().unwrap(), HookMode::Abort);
+    assert_eq!(h.seller_auction_count(&vault).await, 1);
+
+    let winner = sell(&mut h, &auction, 4_000).await;
+    let record = new_record(&mut h);
+
+    let vault_before = h.lamports(&vault).await;
+    let state = h.auction(&auction).await;
+    let settle = settle_with_hook(&auction, &state, 4_000, vec![AccountMeta::new(record, false)]);
+    h.send(settle, &[&winner]).await.unwrap();
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+    assert_eq!(h.lamports(&vault).await, vault_before + 4_000);
+    let data = record_data(&mut h, record).await;
+    assert_eq!(data[..8], 4_000u64.to_le_bytes());
+    assert_eq!(data[8..40], winner.pubkey().to_bytes());
+    assert_eq!(data[40], AuctionOutcome::Settled as u8 + 1);
+}
+
+#[tokio::test]
+async fn hook_runs_on_cancellation() {
+    let mut h = liquidator_harness().await;
+    let mut params = h.params(false);
+    params.hook = liquidator_hook(false);
+    let auction = h.create_with(params).await.unwrap();
+    let record = new_record(&mut h);
+
+    // The seller can't leave the hook out...
+    assert_error(h.cancel(&auction, "").await, ShadowBidError::HookMismatch);
+
+    let seller = h.seller.insecure_clone();
+    let state = h.auction(&auction).await;
+    let cancel = ix::cancel_auction_with_hook(
+        &seller.pubkey(),
+        &auction,
+        &state,
+        "withdrawn".to_string(),
+        CancellationReason::Other,
+        &LIQUIDATOR_ID,
+        vec![AccountMeta::new(record, false)],
+    );
+    h.send(cancel, &[&seller]).await.unwrap();
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+    let data = record_data(&mut h, record).await;
+    assert_eq!(data[..8], 0u64.to_le_bytes());
+    assert_eq!(data[8..40], Pubkey::default().to_bytes());
+    assert_eq!(data[40], AuctionOutcome::Cancelled as u8 + 1);
+}
+
+#[tokio::test]
+async fn hook_runs_on_cancellation_returning_the_bond() {
+    let mut h = liquidator_harness().await;
+    let mut params = h.params(false);
+    params.seller_bond = Some(SELLER_BOND);
+    params.hook = liquidator_hook(false);
+    let bond_rent = h.bond_rent().await;
+    let auction = h.create_with(params).await.unwrap();
+    let record = new_record(&mut h);
+
+    let seller = h.seller.insecure_clone();
+    let seller_before = h.lamports(&seller.pubkey()).await;
+    let state = h.auction(&auction).await;
+    let cancel = ix::cancel_auction_with_hook(
+        &seller.pubkey(),
+        &auction,
+        &state,
+        "withdrawn".to_string(),
+        CancellationReason::Other,
+        &LIQUIDATOR_ID,
+        vec![AccountMeta::new(record, false)],
+    );
+    h.send(cancel, &[&seller]).await.unwrap();
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+    assert!(h.seller_bond(&auction).await.is_none());
+    assert_eq!(h.lamports(&seller.pubkey()).await, seller_before + bond_rent + SELLER_BOND);
+    assert_eq!(record_data(&mut h, record).await[40], AuctionOutcome::Cancelled as u8 + 1);
+}
+
+#[tokio::test]
+async fn failed_hook_reverts_settlement() {
+    let mut h = liquidator_harness().await;
+    let payer = h.keypair();
+    let auction = create_from_vault(&mut h, &payer).await;
+    let winner = sell(&mut h, &auction, 4_000).await;
+    let state = h.auction(&auction).await;
+
+    // Without its record account the liquidator rejects the call
+    let winner_before = h.lamports(&winner.pubkey()).await;
+    let settle = settle_with_hook(&auction, &state, 4_000, Vec::new());
+    assert!(h.send(settle, &[&winner]).await.is_err());
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::WinnerDetermined);
+    assert_eq!(h.lamports(&winner.pubkey()).await, winner_before);
+}
+
+#[tokio::test]
+async fn aborting_hook_cannot_be_skipped() {
+    let mut h = liquidator_harness().await;
+    let payer = h.keypair();
+    let auction = create_from_vault(&mut h, &payer).await;
+    let winner = sell(&mut h, &auction, 4_000).await;
+    let state = h.auction(&auction).await;
+
+    let settle = settle_instruction(&auction, &state, &winner, 4_000);
+    assert_error(h.send(settle, &[&winner]).await, ShadowBidError::HookMismatch);
+
+    // ...nor redirected to another program
+    let mut settle = settle_with_hook(&auction, &state, 4_000, Vec::new());
+    let decoy = settle
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == LIQUIDATOR_ID)
+        .unwrap();
+    decoy.pubkey = mock_inco::ID;
+    assert_error(h.send(settle, &[&winner]).await, ShadowBidError::HookMismatch);
+}
+
+#[tokio::test]
+async fn ignored_hook_can_be_left_out_after_failing() {
+    let mut h = liquidator_harness().await;
+    let mut params = h.params(false);
+    params.hook = liquidator_hook(true);
+    let auction = h.create_with(params).await.unwrap();
+    assert_eq!(h.auction(&auction).await.hook_mode().unwrap(), HookMode::Ignore);
+
+    let winner = sell(&mut h, &auction, 4_000).await;
+    let state = h.auction(&auction).await;
+
+    // The hook still runs when passed, and its failure still fails the
+    // transaction...
+    let settle = settle_with_hook(&auction, &state, 4_000, Vec::new());
+    assert!(h.send(settle, &[&winner]).await.is_err());
+
+    // ...but settlement can go ahead without it
+    let seller = h.seller.pubkey();
+    let seller_before = h.lamports(&seller).await;
+    let settle = settle_instruction(&auction, &state, &winner, 4_000);
+    h.send(settle, &[&winner]).await.unwrap();
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+    assert_eq!(h.lamports(&seller).await, seller_before + 4_000);
+}
+
+/// Runs an escrowed auction with a liquidator hook and one bid of `amount`
+/// through settlement, leaving it AwaitingDelivery
+async fn escrowed_sale_with_hook(h: &mut Harness, amount: u64) -> (Pubkey, Keypair) {
+    let mut params = h.escrowed_params();
+    params.hook = liquidator_hook(false);
+    let auction = h.create_with(params).await.unwrap();
+    let winner = sell(h, &auction, amount).await;
+
+    // The payment only reaches the seller once the escrow pays out
+    h.settle(&auction, &winner).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::AwaitingDelivery);
+    (auction, winner)
+}
+
+#[tokio::test]
+async fn hook_runs_when_delivery_is_confirmed() {
+    let mut h = liquidator_harness().await;
+    let (auction, winner) = escrowed_sale_with_hook(&mut h, 4_000).await;
+    let record = new_record(&mut h);
+
+    // The winner can't leave the hook out
+    assert_error(h.confirm_delivery(&auction, &winner).await, ShadowBidError::HookMismatch);
+
+    let seller = h.seller.pubkey();
+    let seller_before = h.lamports(&seller).await;
+    let state = h.auction(&auction).await;
+    let confirm = ix::confirm_delivery_with_hook(
+        &auction,
+        &state,
+        &seller,
+        &LIQUIDATOR_ID,
+        vec![AccountMeta::new(record, false)],
+    );
+    h.send(confirm, &[&winner]).await.unwrap();
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+    assert_eq!(h.lamports(&seller).await, seller_before + 4_000);
+    let data = record_data(&mut h, record).await;
+    assert_eq!(data[..8], 4_000u64.to_le_bytes());
+    assert_eq!(data[8..40], winner.pubkey().to_bytes());
+    assert_eq!(data[40], AuctionOutcome::Settled as u8 + 1);
+}
+
+#[tokio::test]
+async fn hook_runs_when_escrow_is_released() {
+    let mut h = liquidator_harness().await;
+    let (auction, winner) = escrowed_sale_with_hook(&mut h, 4_000).await;
+    let record = new_record(&mut h);
+    let state = h.auction(&auction).await;
+    h.warp(state.delivery_deadline).await;
+
+    let caller = h.keypair();
+    let release = ix::release_escrow_with_hook(
+        &caller.pubkey(),
+        &auction,
+        &state,
+        &state.seller,
+        &LIQUIDATOR_ID,
+        vec![AccountMeta::new(record, false)],
+    );
+    h.send(release, &[&caller]).await.unwrap();
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+    let data = record_data(&mut h, record).await;
+    assert_eq!(data[..8], 4_000u64.to_le_bytes());
+    assert_eq!(data[8..40], winner.pubkey().to_bytes());
+    assert_eq!(data[40], AuctionOutcome::Settled as u8 + 1);
+}
+
+#[tokio::test]
+async fn hook_hears_of_a_full_refund_on_ruling() {
+    let mut h = liquidator_harness().await;
+    let (auction, winner) = escrowed_sale_with_hook(&mut h, 4_000).await;
+    h.dispute(&auction, &winner).await.unwrap();
+    let record = new_record(&mut h);
+
+    assert_error(h.resolve(&auction, 10_000).await, ShadowBidError::HookMismatch);
+
+    let arbitrator = h.arbitrator.insecure_clone();
+    let state = h.auction(&auction).await;
+    let resolve = ix::resolve_dispute_with_hook(
+        &auction,
+        &state,
+        &state.seller,
+        10_000,
+        &LIQUIDATOR_ID,
+        vec![AccountMeta::new(record, false)],
+    );
+    h.send(resolve, &[&arbitrator]).await.unwrap();
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+    let data = record_data(&mut h, record).await;
+    assert_eq!(data[..8], 0u64.to_le_bytes());
+    assert_eq!(data[8..40], winner.pubkey().to_bytes());
+    assert_eq!(data[40], AuctionOutcome::Refunded as u8 + 1);
+}
@@ -0,0 +1,320 @@
+//! Every `AuctionState` transition, driven through the clock
+//!
+//! Open → Closed → WinnerDetermined → Settled (sealed)
+//! Open → Revealing → Closed → WinnerDetermined → Settled (commit-reveal)
+//! Open → Cancelled (no bids at close, or by the seller)
+//! Closed → Cancelled (by the seller, or no valid reveal at finalize)
+
+mod common;
+
+use common::*;
+use mock_inco::codec;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::state::{AuctionMode, AuctionState};
+use shadowbid_client::pda;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn sealed_auction_settles_at_the_highest_bid() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+
+    let state = h.auction(&auction).await;
+    assert_eq!(state.state().unwrap(), AuctionState::Open);
+    assert_eq!(state.mode().unwrap(), AuctionMode::Sealed);
+    assert_eq!({ state.end_time }, state.start_time + DURATION);
+
+    let alice = h.keypair();
+    let bob = h.keypair();
+    let carol = h.keypair();
+    h.place_sealed_bid(&auction, &alice, 2_000).await.unwrap();
+    h.place_sealed_bid(&auction, &bob, 5_000).await.unwrap();
+    h.place_sealed_bid(&auction, &carol, 3_000).await.unwrap();
+
+    // Updating a bid replaces its handle without counting a new bid
+    h.place_sealed_bid(&auction, &alice, 4_000).await.unwrap();
+    let state = h.auction(&auction).await;
+    assert_eq!({ state.bid_count }, 3);
+    let alice_bid = h.bid(&auction, &alice.pubkey()).await;
+    assert_eq!(codec::decrypt(alice_bid.encrypted_amount), Some(4_000));
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Closed);
+
+    for (processed, bidder) in [&alice, &bob, &carol].into_iter().enumerate() {
+        h.determine(&auction, &bidder.pubkey()).await.unwrap();
+        assert_eq!({ h.auction(&auction).await.bids_processed }, processed as u32 + 1);
+        assert!(h.bid(&auction, &bidder.pubkey()).await.processed);
+    }
+
+    h.finalize(&auction, true).await.unwrap();
+    let state = h.auction(&auction).await;
+    assert_eq!(state.state().unwrap(), AuctionState::WinnerDetermined);
+    assert_eq!(state.winner, bob.pubkey());
+    assert_eq!(codec::decrypt(state.highest_bid_handle), Some(5_000));
+
+    // Both the winner and the seller were allowed on the winning handle
+    for allowed in [bob.pubkey(), h.seller.pubkey()] {
+        let (allowance, _) = pda::allowance_address(state.highest_bid_handle, &allowed);
+        let account = h.ctx.banks_client.get_account(allowance).await.unwrap();
+        assert_eq!(account.expect("allowance missing").owner, mock_inco::ID);
+    }
+
+    let seller = h.seller.pubkey();
+    let seller_before = h.lamports(&seller).await;
+    let bob_before = h.lamports(&bob.pubkey()).await;
+    h.settle(&auction, &bob).await.unwrap();
+
+    let state = h.auction(&auction).await;
+    assert_eq!(state.state().unwrap(), AuctionState::Settled);
+    assert_eq!({ state.winning_amount }, 5_000);
+    assert_eq!(h.lamports(&seller).await, seller_before + 5_000);
+    assert_eq!(h.lamports(&bob.pubkey()).await, bob_before - 5_000);
+
+    // The encrypted statistics cover every processed bid
+    assert_eq!(codec::decrypt(state.bid_sum_handle), Some(12_000));
+    assert_eq!(codec::decrypt(state.lowest_bid_handle), Some(3_000));
+    assert_eq!(codec::decrypt(state.above_reserve_count_handle), Some(3));
+
+    let seller_keypair = h.seller.insecure_clone();
+    h.send(
+        shadowbid_client::instructions::reveal_auction_stats(&auction, &state),
+        &[&seller_keypair],
+    )
+    .await
+    .unwrap();
+    let plaintexts = [12_000, 3_000, 3].map(codec::plaintext_bytes).to_vec();
+    h.send(
+        shadowbid_client::instructions::publish_auction_stats(&auction, &state, plaintexts),
+        &[&seller_keypair],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn first_bidder_lowering_their_bid_loses() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+
+    // The first bid seeds the leader; its old handle must not survive the update
+    let first = h.keypair();
+    let second = h.keypair();
+    h.place_sealed_bid(&auction, &first, 9_000).await.unwrap();
+    h.place_sealed_bid(&auction, &second, 5_000).await.unwrap();
+    h.place_sealed_bid(&auction, &first, 2_000).await.unwrap();
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, &[first, second.insecure_clone()]).await;
+
+    let state = h.auction(&auction).await;
+    assert_eq!(state.winner, second.pubkey());
+    assert_eq!(codec::decrypt(state.highest_bid_handle), Some(5_000));
+}
+
+#[tokio::test]
+async fn bidding_window_follows_the_clock() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+    let bidder = h.keypair();
+    let end_time = h.auction(&auction).await.end_time;
+
+    h.warp(end_time - 1).await;
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    assert_error(h.close_bidding(&auction).await, ShadowBidError::BiddingNotEnded);
+
+    // end_time itself is past the bidding window
+    h.warp(end_time).await;
+    let late = h.keypair();
+    assert_error(
+        h.place_sealed_bid(&auction, &late, 3_000).await,
+        ShadowBidError::BiddingEnded,
+    );
+    h.close_bidding(&auction).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Closed);
+}
+
+#[tokio::test]
+async fn closing_without_bids_cancels() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+}
+
+#[tokio::test]
+async fn seller_cancels_open_auction() {
+    let mut h = Harness::new().await;
+
+    // Without bids, any time before the end
+    let auction = h.create(false).await;
+    h.cancel(&auction, "changed my mind").await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+
+    // With bids, only with a bond and once bidding has ended
+    let auction = h.create_bonded().await;
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.cancel(&auction, "nobody closed it").await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+}
+
+#[tokio::test]
+async fn seller_cancels_closed_auction_before_processing() {
+    let mut h = Harness::new().await;
+    let auction = h.create_bonded().await;
+    for amount in [2_000, 3_000] {
+        let bidder = h.keypair();
+        h.place_sealed_bid(&auction, &bidder, amount).await.unwrap();
+    }
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+
+    h.cancel(&auction, "item unavailable").await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Cancelled);
+}
+
+#[tokio::test]
+async fn winning_bid_below_reserve_cannot_settle() {
+    let mut h = Harness::new().await;
+    let (auction, bidders) = h
+        .closed_sealed_auction(&[RESERVE_PRICE / 2, RESERVE_PRICE - 1])
+        .await;
+    h.determine_and_finalize(&auction, &bidders).await;
+
+    assert_error(
+        h.settle(&auction, &bidders[1]).await,
+        ShadowBidError::InvalidDecryptionProof,
+    );
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::WinnerDetermined);
+}
+
+#[tokio::test]
+async fn commit_reveal_auction_settles_at_the_highest_reveal() {
+    let mut h = Harness::new().await;
+    let auction = h.create(true).await;
+    assert_eq!(h.auction(&auction).await.mode().unwrap(), AuctionMode::CommitReveal);
+
+    let alice = h.keypair();
+    let bob = h.keypair();
+    let carol = h.keypair();
+    // A first bid also pays for the bidder's profile
+    let bid_rent = h.bid_rent().await + h.bidder_profile_rent().await;
+    for (bidder, amount, salt) in [(&alice, 2_000, [1; 32]), (&bob, 6_000, [2; 32]), (&carol, 9_000, [3; 32])] {
+        h.place_commitment(&auction, bidder, amount, salt).await.unwrap();
+        assert_eq!(h.lamports(&bidder.pubkey()).await, STARTING_BALANCE - bid_rent - BID_COLLATERAL);
+    }
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Revealing);
+
+    // Revealing returns the collateral; carol never reveals
+    h.reveal(&auction, &alice, 2_000, [1; 32]).await.unwrap();
+    h.reveal(&auction, &bob, 6_000, [2; 32]).await.unwrap();
+    assert_eq!(h.lamports(&bob.pubkey()).await, STARTING_BALANCE - bid_rent);
+    assert_eq!({ h.auction(&auction).await.bids_revealed }, 2);
+
+    assert_error(h.close_reveal(&auction).await, ShadowBidError::RevealNotEnded);
+    h.warp_to_reveal_end(&auction).await;
+    h.close_reveal(&auction).await.unwrap();
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Closed);
+
+    // Carol's unrevealed collateral is forfeited to the seller
+    let seller = h.seller.pubkey();
+    let seller_before = h.lamports(&seller).await;
+    for bidder in [&alice, &bob, &carol] {
+        h.determine(&auction, &bidder.pubkey()).await.unwrap();
+    }
+    let state = h.auction(&auction).await;
+    assert_eq!({ state.forfeited_collateral }, BID_COLLATERAL);
+    assert_eq!({ state.highest_revealed_amount }, 6_000);
+    assert_eq!(h.lamports(&seller).await, seller_before + BID_COLLATERAL);
+
+    h.finalize(&auction, false).await.unwrap();
+    let state = h.auction(&auction).await;
+    assert_eq!(state.state().unwrap(), AuctionState::WinnerDetermined);
+    assert_eq!(state.winner, bob.pubkey());
+
+    h.settle(&auction, &bob).await.unwrap();
+    let state = h.auction(&auction).await;
+    assert_eq!(state.state().unwrap(), AuctionState::Settled);
+    assert_eq!({ state.winning_amount }, 6_000);
+    assert_eq!(h.lamports(&seller).await, seller_before + BID_COLLATERAL + 6_000);
+}
+
+#[tokio::test]
+async fn updated_commitment_keeps_one_deposit() {
+    let mut h = Harness::new().await;
+    let auction = h.create(true).await;
+    let bidder = h.keypair();
+    let first_bid = h.bid_rent().await + h.bidder_profile_rent().await;
+
+    h.place_commitment(&auction, &bidder, 2_000, [1; 32]).await.unwrap();
+    h.place_commitment(&auction, &bidder, 3_000, [2; 32]).await.unwrap();
+
+    assert_eq!({ h.auction(&auction).await.bid_count }, 1);
+    assert_eq!({ h.bid(&auction, &bidder.pubkey()).await.collateral }, BID_COLLATERAL);
+    assert_eq!(h.lamports(&bidder.pubkey()).await, STARTING_BALANCE - first_bid - BID_COLLATERAL);
+}
+
+#[tokio::test]
+async fn commit_reveal_without_valid_reveal_cancels_at_finalize() {
+    let mut h = Harness::new().await;
+    let auction = h.create(true).await;
+
+    // One bid below the reserve, one never revealed
+    let low = h.keypair();
+    let silent = h.keypair();
+    h.place_commitment(&auction, &low, RESERVE_PRICE - 1, [7; 32]).await.unwrap();
+    h.place_commitment(&auction, &silent, 8_000, [8; 32]).await.unwrap();
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.reveal(&auction, &low, RESERVE_PRICE - 1, [7; 32]).await.unwrap();
+    h.warp_to_reveal_end(&auction).await;
+    h.close_reveal(&auction).await.unwrap();
+
+    h.determine(&auction, &low.pubkey()).await.unwrap();
+    h.determine(&auction, &silent.pubkey()).await.unwrap();
+    h.finalize(&auction, false).await.unwrap();
+
+    let state = h.auction(&auction).await;
+    assert_eq!(state.state().unwrap(), AuctionState::Cancelled);
+    assert_eq!(state.winner, Default::default());
+}
+
+#[tokio::test]
+async fn terminal_states_are_final() {
+    let mut h = Harness::new().await;
+    let (settled, bidders) = h.closed_sealed_auction(&[2_000]).await;
+    h.determine_and_finalize(&settled, &bidders).await;
+    h.settle(&settled, &bidders[0]).await.unwrap();
+
+    let cancelled = h.create(false).await;
+    h.cancel(&cancelled, "test").await.unwrap();
+
+    for auction in [settled, cancelled] {
+        let before = h.auction(&auction).await.state().unwrap();
+        let bidder = h.keypair();
+        assert_error(
+            h.place_sealed_bid(&auction, &bidder, 2_000).await,
+            ShadowBidError::AuctionNotOpen,
+        );
+        assert_error(h.close_bidding(&auction).await, ShadowBidError::AuctionNotOpen);
+        assert_error(h.cancel(&auction, "again").await, ShadowBidError::AuctionAlreadySettled);
+        assert_error(h.finalize(&auction, false).await, ShadowBidError::AuctionNotClosed);
+        assert_eq!(h.auction(&auction).await.state().unwrap(), before);
+    }
+
+    // The winner can't pay twice
+    assert_error(
+        h.settle(&settled, &bidders[0]).await,
+        ShadowBidError::WinnerNotDetermined,
+    );
+}
@@ -0,0 +1,130 @@
+//! `AuctionMetadata`: creation, resizing updates, the first-bid freeze and
+//! closing
+
+mod common;
+
+use anchor_lang::prelude::Rent;
+use common::*;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::state::Permission;
+use shadowbid_client::{instructions as ix, pda};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn seller_creates_and_resizes_metadata() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+    let seller = h.seller.pubkey();
+    let rent: Rent = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let (address, _) = pda::metadata_address(&auction);
+
+    let document = br#"{"image":"ar://camera.png","attributes":[]}"#;
+    let params = metadata_params(document, &["cameras", "vintage"]);
+    let before = h.lamports(&seller).await;
+    h.create_metadata(&auction, params.clone()).await.unwrap();
+
+    let metadata = h.metadata(&auction).await;
+    assert_eq!(metadata.auction, auction);
+    assert_eq!(metadata.title, params.title);
+    assert_eq!(metadata.uri, params.uri);
+    assert_eq!(metadata.tags, ["cameras", "vintage"]);
+    assert!(metadata.matches_content(document));
+    assert!(!metadata.matches_content(b"tampered"));
+    // The account is sized to its contents, paid for by the seller
+    assert_eq!(before - h.lamports(&seller).await, rent.minimum_balance(params.space()));
+
+    // Growing charges the seller the extra rent, shrinking refunds it
+    let mut longer = params.clone();
+    longer.description = "d".repeat(300);
+    longer.tags.push("film".to_string());
+    h.update_metadata(&auction, longer.clone()).await.unwrap();
+    assert_eq!(h.metadata(&auction).await.description, longer.description);
+    assert_eq!(h.lamports(&address).await, rent.minimum_balance(longer.space()));
+
+    let mut shorter = params.clone();
+    shorter.tags.clear();
+    let before = h.lamports(&seller).await;
+    h.update_metadata(&auction, shorter.clone()).await.unwrap();
+    assert!(h.metadata(&auction).await.tags.is_empty());
+    assert_eq!(
+        h.lamports(&seller).await - before,
+        rent.minimum_balance(longer.space()) - rent.minimum_balance(shorter.space())
+    );
+}
+
+#[tokio::test]
+async fn first_bid_freezes_metadata() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+    let params = metadata_params(b"{}", &["art"]);
+    h.create_metadata(&auction, params.clone()).await.unwrap();
+
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+
+    // Bidders bid on exactly what was described
+    let mut changed = params.clone();
+    changed.title = "Something else".to_string();
+    assert_error(h.update_metadata(&auction, changed).await, ShadowBidError::MetadataLocked);
+    assert_eq!(h.metadata(&auction).await.title, params.title);
+}
+
+#[tokio::test]
+async fn shrinking_refunds_the_seller_not_the_delegate() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+    let seller = h.seller.insecure_clone();
+    let editor = h.keypair();
+    let rent: Rent = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let grant = ix::set_delegate(&seller.pubkey(), &auction, &editor.pubkey(), Permission::UpdateMetadata.bit());
+    h.send(grant, &[&seller]).await.unwrap();
+
+    let params = metadata_params(b"{}", &["art"]);
+    let create = ix::create_auction_metadata(&editor.pubkey(), &auction, params.clone());
+    h.send(create, &[&editor]).await.unwrap();
+
+    // The delegate pays for growth...
+    let mut longer = params.clone();
+    longer.description = "d".repeat(300);
+    let before = h.lamports(&editor.pubkey()).await;
+    let update = ix::update_auction_metadata(&editor.pubkey(), &auction, &seller.pubkey(), longer.clone());
+    h.send(update, &[&editor]).await.unwrap();
+    let growth = rent.minimum_balance(longer.space()) - rent.minimum_balance(params.space());
+    assert_eq!(before - h.lamports(&editor.pubkey()).await, growth);
+
+    // ...but the rent a shrink frees goes to the seller
+    let (editor_before, seller_before) = (h.lamports(&editor.pubkey()).await, h.lamports(&seller.pubkey()).await);
+    let update = ix::update_auction_metadata(&editor.pubkey(), &auction, &seller.pubkey(), params.clone());
+    h.send(update, &[&editor]).await.unwrap();
+    assert_eq!(h.lamports(&editor.pubkey()).await, editor_before);
+    assert_eq!(h.lamports(&seller.pubkey()).await - seller_before, growth);
+
+    let other = h.keypair();
+    let update = ix::update_auction_metadata(&editor.pubkey(), &auction, &other.pubkey(), params);
+    assert_error(h.send(update, &[&editor]).await, ShadowBidError::NotSeller);
+}
+
+#[tokio::test]
+async fn closing_metadata_returns_its_rent_to_the_seller() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.insecure_clone();
+    let auction = h.create(false).await;
+    let (address, _) = pda::metadata_address(&auction);
+    h.create_metadata(&auction, metadata_params(b"{}", &["art"])).await.unwrap();
+
+    // Bidders bid on what the metadata describes, so it stays until the end
+    let winner = h.keypair();
+    h.place_sealed_bid(&auction, &winner, 2_000).await.unwrap();
+    let close = ix::close_auction_metadata(&seller.pubkey(), &auction, &seller.pubkey());
+    assert_error(h.send(close.clone(), &[&seller]).await, ShadowBidError::MetadataLocked);
+
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, std::slice::from_ref(&winner)).await;
+    h.settle(&auction, &winner).await.unwrap();
+
+    let (rent, before) = (h.lamports(&address).await, h.lamports(&seller.pubkey()).await);
+    h.send(close, &[&seller]).await.unwrap();
+    assert_eq!(h.lamports(&seller.pubkey()).await - before, rent);
+    assert!(h.ctx.banks_client.get_account(address).await.unwrap().is_none());
+}
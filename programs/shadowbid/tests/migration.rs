@@ -0,0 +1,145 @@
+//! Account versioning: Auction and Bid accounts in the original deployed
+//! layout are decoded as version 0 and rewritten in place by
+//! `migrate_auction` / `migrate_bid`
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Rent;
+use anchor_lang::Discriminator;
+use common::*;
+use shadowbid::state::{Auction, Bid};
+use shadowbid_client::{instructions as ix, pda};
+use solana_sdk::signature::Signer;
+
+/// Account data of `auction` as the baseline program wrote it: its
+/// Borsh fields in their original order
+fn baseline_auction(auction: &Auction) -> Vec<u8> {
+    let mut data = Auction::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(auction.seller.as_ref());
+    data.extend_from_slice(auction.item_mint.as_ref());
+    data.extend_from_slice(&auction.title);
+    data.extend_from_slice(&auction.description);
+    data.extend_from_slice(&{ auction.reserve_price }.to_le_bytes());
+    data.extend_from_slice(&{ auction.start_time }.to_le_bytes());
+    data.extend_from_slice(&{ auction.end_time }.to_le_bytes());
+    data.push(auction.state().unwrap() as u8);
+    data.extend_from_slice(&{ auction.bid_count }.to_le_bytes());
+    data.extend_from_slice(&{ auction.bids_processed }.to_le_bytes());
+    data.extend_from_slice(&{ auction.highest_bid_handle }.to_le_bytes());
+    data.extend_from_slice(auction.current_leader.as_ref());
+    data.extend_from_slice(auction.winner.as_ref());
+    data.extend_from_slice(&{ auction.winning_amount }.to_le_bytes());
+    data.extend_from_slice(&{ auction.auction_id }.to_le_bytes());
+    data.push(auction.bump);
+    data
+}
+
+/// Account data of `bid` as the baseline program wrote it
+fn baseline_bid(bid: &Bid) -> Vec<u8> {
+    let mut data = Bid::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bid.auction.as_ref());
+    data.extend_from_slice(bid.bidder.as_ref());
+    data.extend_from_slice(&bid.encrypted_amount.to_le_bytes());
+    data.extend_from_slice(&bid.created_at.to_le_bytes());
+    data.extend_from_slice(&bid.updated_at.to_le_bytes());
+    data.push(bid.processed as u8);
+    data.push(bid.bump);
+    data
+}
+
+#[test]
+fn baseline_layouts_have_the_deployed_sizes() {
+    assert_eq!(Auction::LEGACY_SPACE, 522);
+    assert_eq!(Bid::LEGACY_SPACE, 106);
+}
+
+#[tokio::test]
+async fn legacy_auction_is_migrated_in_place() {
+    let mut h = Harness::new().await;
+    let rent: Rent = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let auction = h.create(false).await;
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    let current = h.auction(&auction).await;
+    assert_eq!(current.version, Auction::VERSION);
+    let baseline = baseline_auction(&current);
+    assert_eq!(baseline.len(), Auction::LEGACY_SPACE);
+    // auction_id and bump ended the baseline layout
+    assert_eq!(baseline[513..521], current.auction_id.to_le_bytes());
+    assert_eq!(baseline[521], current.bump);
+    h.replace_data(&auction, baseline).await;
+
+    // Decoders read the legacy layout as version 0; the program doesn't
+    let legacy = h.auction(&auction).await;
+    assert_eq!(legacy.version, 0);
+    assert_eq!({ legacy.bid_count }, 1);
+    assert_eq!({ legacy.auction_id }, { current.auction_id });
+    assert_eq!(legacy.bump, current.bump);
+    let other = h.keypair();
+    assert_custom_error(
+        h.place_sealed_bid(&auction, &other, 3_000).await,
+        ErrorCode::AccountDidNotDeserialize.into(),
+    );
+
+    // Anyone can migrate, paying the extra rent
+    let payer = h.keypair();
+    let before = h.lamports(&auction).await;
+    h.send(ix::migrate_auction(&payer.pubkey(), &auction), &[&payer])
+        .await
+        .unwrap();
+    let top_up = rent.minimum_balance(Auction::SPACE) - rent.minimum_balance(Auction::LEGACY_SPACE);
+    assert_eq!(h.lamports(&auction).await, before + top_up);
+    assert_eq!(STARTING_BALANCE - h.lamports(&payer.pubkey()).await, top_up);
+
+    // Every baseline field survives; the fields added since start zeroed
+    let migrated = h.auction(&auction).await;
+    assert_eq!(migrated.version, Auction::VERSION);
+    assert_eq!(baseline_auction(&migrated), baseline_auction(&current));
+    assert_eq!({ migrated.bid_sum_handle }, 0);
+    assert_eq!({ migrated.reveal_end_time }, 0);
+    assert_eq!({ migrated.seller_bond }, 0);
+    assert_eq!(migrated.reserved, [0; Auction::RESERVED]);
+
+    // The auction carries on
+    h.place_sealed_bid(&auction, &other, 3_000).await.unwrap();
+    assert_eq!({ h.auction(&auction).await.bid_count }, 2);
+}
+
+#[tokio::test]
+async fn legacy_bid_is_migrated_in_place() {
+    let mut h = Harness::new().await;
+    let auction = h.create(false).await;
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    let (bid, _) = pda::bid_address(&auction, &bidder.pubkey());
+    let current = h.bid(&auction, &bidder.pubkey()).await;
+    assert_eq!(current.version, Bid::VERSION);
+    let baseline = baseline_bid(&current);
+    assert_eq!(baseline.len(), Bid::LEGACY_SPACE);
+    assert_eq!(baseline[105], current.bump);
+    h.replace_data(&bid, baseline).await;
+
+    let legacy = h.bid(&auction, &bidder.pubkey()).await;
+    assert_eq!(legacy.version, 0);
+    assert_eq!(legacy.bump, current.bump);
+    assert_custom_error(
+        h.place_sealed_bid(&auction, &bidder, 2_500).await,
+        ErrorCode::AccountDidNotDeserialize.into(),
+    );
+
+    // An auction migration can't be pointed at a bid
+    let payer = h.ctx.payer.pubkey();
+    assert_custom_error(
+        h.send(ix::migrate_auction(&payer, &bid), &[]).await,
+        ErrorCode::AccountDiscriminatorMismatch.into(),
+    );
+
+    h.send(ix::migrate_bid(&payer, &bid), &[]).await.unwrap();
+    let migrated = h.bid(&auction, &bidder.pubkey()).await;
+    assert_eq!(migrated.version, Bid::VERSION);
+    assert_eq!(baseline_bid(&migrated), baseline_bid(&current));
+    assert_eq!(migrated.commitment, [0; 32]);
+    assert_eq!(migrated.won_at, 0);
+    h.place_sealed_bid(&auction, &bidder, 2_500).await.unwrap();
+}
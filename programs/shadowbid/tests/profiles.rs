@@ -0,0 +1,113 @@
+//! Seller profiles: each seller's track record, kept by creation,
+//! settlement, cancellation and dispute resolution
+
+mod common;
+
+use common::*;
+use shadowbid::constants::BPS_DENOMINATOR;
+use shadowbid::state::{AuctionState, CancellationReason};
+use shadowbid_client::pda;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn profile_counts_created_and_settled_auctions() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    assert!(h.seller_profile(&seller).await.is_none());
+
+    let (auction, bidders) = h.closed_sealed_auction(&[3_000, 4_000]).await;
+    h.create(false).await;
+    let profile = h.seller_profile(&seller).await.unwrap();
+    assert_eq!(profile.seller, seller);
+    assert_eq!(profile.auctions_created, 2);
+    assert_eq!(profile.auctions_settled, 0);
+    assert_eq!(profile.reliability_bps(), BPS_DENOMINATOR);
+
+    h.determine_and_finalize(&auction, &bidders).await;
+    h.settle(&auction, &bidders[1]).await.unwrap();
+
+    let profile = h.seller_profile(&seller).await.unwrap();
+    assert_eq!(profile.auctions_settled, 1);
+    assert_eq!(profile.total_volume, 4_000);
+    assert_eq!(profile.reliability_bps(), BPS_DENOMINATOR);
+}
+
+#[tokio::test]
+async fn profile_counts_cancellations_by_reason() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+
+    let auction = h.create(false).await;
+    h.cancel_for(&auction, "Sold elsewhere", CancellationReason::ItemUnavailable)
+        .await
+        .unwrap();
+
+    let auction = h.create_bonded().await;
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auction, &bidder, 2_000).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.cancel_for(&auction, "Bids too low", CancellationReason::LowDemand)
+        .await
+        .unwrap();
+
+    let profile = h.seller_profile(&seller).await.unwrap();
+    assert_eq!(profile.auctions_created, 2);
+    assert_eq!(profile.cancelled_with_bids, 1);
+    assert_eq!(profile.cancellation_count(CancellationReason::ItemUnavailable), 1);
+    assert_eq!(profile.cancellation_count(CancellationReason::LowDemand), 1);
+    assert_eq!(profile.cancellation_count(CancellationReason::Other), 0);
+    // The only completed auction with bids was cancelled
+    assert_eq!(profile.reliability_bps(), 0);
+}
+
+#[tokio::test]
+async fn lost_dispute_counts_as_default() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let (auction, winner) = h.escrowed_sale(10_000).await;
+    assert_eq!(h.seller_profile(&seller).await.unwrap().total_volume, 10_000);
+
+    h.dispute(&auction, &winner).await.unwrap();
+    h.resolve(&auction, 5_000).await.unwrap();
+
+    // 2.5% fee, then half of the rest refunded
+    let profile = h.seller_profile(&seller).await.unwrap();
+    assert_eq!(profile.auctions_settled, 1);
+    assert_eq!(profile.auctions_defaulted, 1);
+    assert_eq!(profile.total_volume, 10_000 - 4_875);
+    assert_eq!(profile.reliability_bps(), 0);
+}
+
+#[tokio::test]
+async fn dispute_ruled_for_seller_is_no_default() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let (auction, winner) = h.escrowed_sale(10_000).await;
+    h.dispute(&auction, &winner).await.unwrap();
+    h.resolve(&auction, 0).await.unwrap();
+
+    let profile = h.seller_profile(&seller).await.unwrap();
+    assert_eq!(profile.auctions_defaulted, 0);
+    assert_eq!(profile.total_volume, 10_000);
+}
+
+#[tokio::test]
+async fn sellers_without_profile_still_complete_auctions() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.pubkey();
+    let (auction, bidders) = h.closed_sealed_auction(&[4_000]).await;
+    let cancelled = h.create(false).await;
+
+    // As for a seller whose auctions predate profiles
+    let (profile, _) = pda::seller_profile_address(&seller);
+    h.ctx.set_account(&profile, &AccountSharedData::default());
+
+    h.determine_and_finalize(&auction, &bidders).await;
+    h.settle(&auction, &bidders[0]).await.unwrap();
+    h.cancel(&cancelled, "Withdrawn").await.unwrap();
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+    assert_eq!(h.auction(&cancelled).await.state().unwrap(), AuctionState::Cancelled);
+    assert!(h.seller_profile(&seller).await.is_none());
+}
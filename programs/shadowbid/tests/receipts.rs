@@ -0,0 +1,108 @@
+//! Receipt NFTs: settlement mints a Token-2022 NFT to the winner, whose
+//! metadata names the auction, its title and the winning amount, and the
+//! seller redeems it once the purchase is fulfilled
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::state::{Account as TokenAccount, Mint};
+use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use common::*;
+use shadowbid::constants::RECEIPT_SYMBOL;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::state::{AuctionState, Receipt};
+use shadowbid_client::pda;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Runs an auction issuing a receipt, with one bid of `amount`, through
+/// settlement
+async fn receipt_sale(h: &mut Harness, amount: u64) -> (Pubkey, Keypair) {
+    let mut params = h.params(false);
+    params.issue_receipt = true;
+    let auction = h.create_with(params).await.unwrap();
+    let winner = h.keypair();
+    h.place_sealed_bid(&auction, &winner, amount).await.unwrap();
+    h.warp_to_end(&auction).await;
+    h.close_bidding(&auction).await.unwrap();
+    h.determine_and_finalize(&auction, std::slice::from_ref(&winner)).await;
+    h.settle(&auction, &winner).await.unwrap();
+    (auction, winner)
+}
+
+async fn receipt_metadata(h: &mut Harness, auction: &Pubkey) -> TokenMetadata {
+    let (mint, _) = pda::receipt_mint_address(auction);
+    let data = h.ctx.banks_client.get_account(mint).await.unwrap().unwrap().data;
+    let mint = StateWithExtensions::<Mint>::unpack(&data).unwrap();
+    assert_eq!(mint.base.supply, 1);
+    assert_eq!(mint.base.decimals, 0);
+    mint.get_variable_len_extension::<TokenMetadata>().unwrap()
+}
+
+fn field<'a>(metadata: &'a TokenMetadata, key: &str) -> &'a str {
+    metadata
+        .additional_metadata
+        .iter()
+        .find(|(field, _)| field == key)
+        .map(|(_, value)| value.as_str())
+        .unwrap_or_else(|| panic!("missing metadata field {key}"))
+}
+
+#[tokio::test]
+async fn settlement_mints_receipt_to_winner() {
+    let mut h = Harness::new().await;
+    let (auction, winner) = receipt_sale(&mut h, 4_000).await;
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+
+    let receipt = h.receipt(&auction).await.unwrap();
+    let (mint, _) = pda::receipt_mint_address(&auction);
+    assert_eq!(receipt.auction, auction);
+    assert_eq!(receipt.mint, mint);
+    assert_eq!(receipt.winner, winner.pubkey());
+    assert_eq!(receipt.amount, 4_000);
+    assert!(!receipt.is_redeemed());
+
+    let token = pda::receipt_token_address(&auction, &winner.pubkey());
+    let data = h.ctx.banks_client.get_account(token).await.unwrap().unwrap().data;
+    assert_eq!(StateWithExtensions::<TokenAccount>::unpack(&data).unwrap().base.amount, 1);
+
+    let state = h.auction(&auction).await;
+    let metadata = receipt_metadata(&mut h, &auction).await;
+    assert_eq!(metadata.name, state.get_title());
+    assert_eq!(metadata.symbol, RECEIPT_SYMBOL);
+    assert_eq!(field(&metadata, Receipt::AUCTION_FIELD), auction.to_string());
+    assert_eq!(field(&metadata, Receipt::AMOUNT_FIELD), "4000");
+    assert_eq!(field(&metadata, Receipt::FULFILLED_FIELD), "false");
+    assert_eq!(
+        Option::<Pubkey>::from(metadata.update_authority),
+        Some(pda::receipt_authority_address().0)
+    );
+}
+
+#[tokio::test]
+async fn auctions_without_receipts_mint_nothing() {
+    let mut h = Harness::new().await;
+    let (auction, bidders) = h.closed_sealed_auction(&[4_000]).await;
+    h.determine_and_finalize(&auction, &bidders).await;
+    h.settle(&auction, &bidders[0]).await.unwrap();
+
+    assert!(h.receipt(&auction).await.is_none());
+    let (mint, _) = pda::receipt_mint_address(&auction);
+    assert!(h.ctx.banks_client.get_account(mint).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn seller_redeems_receipt() {
+    let mut h = Harness::new().await;
+    let (auction, _) = receipt_sale(&mut h, 4_000).await;
+
+    h.redeem(&auction).await.unwrap();
+
+    let receipt = h.receipt(&auction).await.unwrap();
+    assert!(receipt.is_redeemed());
+    assert_eq!(receipt.redeemed_at, h.now().await);
+    let metadata = receipt_metadata(&mut h, &auction).await;
+    assert_eq!(field(&metadata, Receipt::FULFILLED_FIELD), "true");
+
+    assert_error(h.redeem(&auction).await, ShadowBidError::ReceiptAlreadyRedeemed);
+}
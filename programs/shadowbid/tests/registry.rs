@@ -0,0 +1,80 @@
+//! Registry: sequential auction IDs, seller counters and the open-auction
+//! pages
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use common::*;
+use shadowbid::constants::AUCTIONS_PER_PAGE;
+use shadowbid_client::instructions as ix;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn registry_assigns_sequential_ids() {
+    let mut h = Harness::new().await;
+    let other_seller = h.keypair();
+
+    let first = h.create(false).await;
+    let params = h.params(true);
+    let second = h.create_as(&other_seller, params).await.unwrap();
+    let third = h.create(false).await;
+
+    // IDs are global, in creation order, whoever the seller is
+    for (id, address) in [first, second, third].iter().enumerate() {
+        assert_eq!({ h.auction(address).await.auction_id }, id as u64);
+    }
+    assert_eq!(h.registry().await.auction_count, 3);
+    let seller = h.seller.pubkey();
+    assert_eq!(h.seller_auction_count(&seller).await, 2);
+    assert_eq!(h.seller_auction_count(&other_seller.pubkey()).await, 1);
+    let newcomer = h.keypair().pubkey();
+    assert_eq!(h.seller_auction_count(&newcomer).await, 0);
+}
+
+#[tokio::test]
+async fn stale_registry_is_rejected() {
+    let mut h = Harness::new().await;
+    let seller = h.seller.insecure_clone();
+    let stale = h.registry().await;
+    h.create(false).await;
+
+    // Built for ID 0, which is taken - refetch the registry and retry
+    let params = h.params(false);
+    assert_custom_error(
+        h.send(ix::create_auction(&seller.pubkey(), &stale, params), &[&seller])
+            .await,
+        ErrorCode::ConstraintSeeds.into(),
+    );
+    assert_eq!(h.registry().await.auction_count, 1);
+}
+
+#[tokio::test]
+async fn pages_list_open_auctions() {
+    let mut h = Harness::new().await;
+
+    // One more than a page holds
+    let mut auctions = Vec::new();
+    for _ in 0..=AUCTIONS_PER_PAGE {
+        auctions.push(h.create(false).await);
+    }
+    assert_eq!(h.registry().await.page_count(), 2);
+    assert_eq!(h.page(0).await.open_count as usize, AUCTIONS_PER_PAGE);
+    let last_page = h.page(1).await;
+    assert_eq!(last_page.index, 1);
+    assert_eq!(last_page.open_count, 1);
+    assert_eq!(last_page.auctions[0], auctions[AUCTIONS_PER_PAGE]);
+    assert_eq!(h.open_auctions().await, auctions);
+
+    // Cancelling and closing bidding both leave the page
+    h.cancel(&auctions[0], "Withdrawn").await.unwrap();
+    let bidder = h.keypair();
+    h.place_sealed_bid(&auctions[1], &bidder, 2_000).await.unwrap();
+    h.warp_to_end(&auctions[1]).await;
+    h.close_bidding(&auctions[1]).await.unwrap();
+    assert_eq!(h.open_auctions().await, auctions[2..]);
+    assert_eq!(h.page(0).await.open_count as usize, AUCTIONS_PER_PAGE - 2);
+
+    // Freed slots aren't reused; new auctions go to the latest page
+    let newest = h.create(false).await;
+    assert_eq!(h.page(1).await.auctions[1], newest);
+}
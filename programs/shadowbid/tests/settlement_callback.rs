@@ -0,0 +1,209 @@
+//! Programs selling through ShadowBid: a PDA seller creates the auction
+//! over CPI, and settlement calls its program back in the same transaction
+//!
+//! The seller here is a mock liquidator, a native program that signs for
+//! its vault PDA and records the settlement result it is called back with.
+
+mod common;
+
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use anchor_lang::AnchorDeserialize;
+use common::*;
+use mock_inco::codec;
+use shadowbid::callback::{SettlementResult, ON_AUCTION_SETTLED_DISCRIMINATOR};
+use shadowbid::confidential::handle_to_bytes;
+use shadowbid::errors::ShadowBidError;
+use shadowbid::state::{Auction, AuctionState};
+use shadowbid_client::{instructions as ix, pda};
+use solana_program_test::processor;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::program_error::ProgramError;
+use solana_sdk::signature::{Keypair, Signer};
+
+const LIQUIDATOR_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+
+const VAULT_SEED: &[u8] = b"vault";
+
+/// Settlement record: the amount (u64 LE) then the winner
+const RECORD_SPACE: usize = 8 + 32;
+
+fn vault_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED], &LIQUIDATOR_ID)
+}
+
+/// Records the settlement result in the record account passed after the
+/// fixed callback accounts; any other instruction is forwarded to
+/// ShadowBid (the last account) signed by the vault
+fn liquidator_processor(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (vault, bump) = vault_address();
+
+    if let Some(mut payload) = data.strip_prefix(&ON_AUCTION_SETTLED_DISCRIMINATOR) {
+        let [auction, winner, seller, record, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if !auction.is_signer || *auction.owner != shadowbid::ID || *seller.key != vault {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let result = SettlementResult::deserialize(&mut payload)
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+        assert_eq!(result.winner, *winner.key);
+        let mut data = record.try_borrow_mut_data()?;
+        data[..8].copy_from_slice(&result.amount.to_le_bytes());
+        data[8..].copy_from_slice(result.winner.as_ref());
+        return Ok(());
+    }
+
+    let (program, accounts) = accounts.split_last().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let metas = accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer || *account.key == vault,
+            is_writable: account.is_writable,
+        })
+        .collect();
+    invoke_signed(
+        &Instruction {
+            program_id: *program.key,
+            accounts: metas,
+            data: data.to_vec(),
+        },
+        accounts,
+        &[&[VAULT_SEED, &[bump]]],
+    )
+}
+
+async fn liquidator_harness() -> Harness {
+    let mut h = Harness::with_programs(|program_test| {
+        program_test.add_program("mock_liquidator", LIQUIDATOR_ID, processor!(liquidator_processor));
+    })
+    .await;
+    // The vault receives payments, so it starts rent-exempt
+    h.ctx.set_account(
+        &vault_address().0,
+        &AccountSharedData::new(STARTING_BALANCE, 0, &system_program::ID),
+    );
+    h
+}
+
+/// Creates an auction sold by the liquidator vault, with `payer` paying
+async fn create_from_vault(h: &mut Harness, payer: &Keypair) -> Pubkey {
+    let (vault, _) = vault_address();
+    let registry = h.registry().await;
+    let mut params = h.params(false);
+    params.settlement_callback = Some(LIQUIDATOR_ID);
+    let (auction, _) = pda::auction_address(&vault, registry.auction_count);
+
+    let mut create = ix::create_auction_with_payer(&payer.pubkey(), &vault, &registry, params);
+    create.program_id = LIQUIDATOR_ID;
+    for meta in &mut create.accounts {
+        meta.is_signer = meta.pubkey == payer.pubkey();
+    }
+    create.accounts.push(AccountMeta::new_readonly(shadowbid::ID, false));
+    h.send(create, &[payer]).await.expect("create through the liquidator failed");
+    auction
+}
+
+/// Runs the auction with one bid of `amount` up to settlement
+async fn sell(h: &mut Harness, auction: &Pubkey, amount: u64) -> Keypair {
+    let winner = h.keypair();
+    h.place_sealed_bid(auction, &winner, amount).await.unwrap();
+    h.warp_to_end(auction).await;
+    h.close_bidding(auction).await.unwrap();
+    h.determine_and_finalize(auction, std::slice::from_ref(&winner)).await;
+    winner
+}
+
+/// `settle_auction` for the winning `amount`, calling back the liquidator
+/// with `extra` accounts
+fn settle_with_callback(auction: &Pubkey, state: &Auction, amount: u64, extra: Vec<AccountMeta>) -> Instruction {
+    ix::settle_auction_with_callback(
+        auction,
+        state,
+        &state.seller,
+        handle_to_bytes(state.highest_bid_handle),
+        codec::plaintext_bytes(amount),
+        &LIQUIDATOR_ID,
+        extra,
+    )
+}
+
+#[tokio::test]
+async fn pda_seller_is_called_back_on_settlement() {
+    let mut h = liquidator_harness().await;
+    let payer = h.keypair();
+    let (vault, _) = vault_address();
+    let auction = create_from_vault(&mut h, &payer).await;
+
+    let state = h.auction(&auction).await;
+    assert_eq!(state.seller, vault);
+    assert!(state.has_settlement_callback());
+    assert_eq!(h.seller_auction_count(&vault).await, 1);
+
+    let winner = sell(&mut h, &auction, 4_000).await;
+    let record = Pubkey::new_unique();
+    h.ctx.set_account(
+        &record,
+        &AccountSharedData::new(STARTING_BALANCE, RECORD_SPACE, &LIQUIDATOR_ID),
+    );
+
+    let vault_before = h.lamports(&vault).await;
+    let state = h.auction(&auction).await;
+    let settle = settle_with_callback(&auction, &state, 4_000, vec![AccountMeta::new(record, false)]);
+    h.send(settle, &[&winner]).await.unwrap();
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::Settled);
+    assert_eq!(h.lamports(&vault).await, vault_before + 4_000);
+    let data = h.ctx.banks_client.get_account(record).await.unwrap().unwrap().data;
+    assert_eq!(data[..8], 4_000u64.to_le_bytes());
+    assert_eq!(data[8..], winner.pubkey().to_bytes());
+}
+
+#[tokio::test]
+async fn failed_callback_reverts_settlement() {
+    let mut h = liquidator_harness().await;
+    let payer = h.keypair();
+    let auction = create_from_vault(&mut h, &payer).await;
+    let winner = sell(&mut h, &auction, 4_000).await;
+    let state = h.auction(&auction).await;
+
+    // Without its record account the liquidator rejects the callback
+    let winner_before = h.lamports(&winner.pubkey()).await;
+    let settle = settle_with_callback(&auction, &state, 4_000, Vec::new());
+    assert!(h.send(settle, &[&winner]).await.is_err());
+
+    assert_eq!(h.auction(&auction).await.state().unwrap(), AuctionState::WinnerDetermined);
+    assert_eq!(h.lamports(&winner.pubkey()).await, winner_before);
+}
+
+#[tokio::test]
+async fn callback_cannot_be_skipped() {
+    let mut h = liquidator_harness().await;
+    let payer = h.keypair();
+    let auction = create_from_vault(&mut h, &payer).await;
+    let winner = sell(&mut h, &auction, 4_000).await;
+    let state = h.auction(&auction).await;
+
+    let settle = settle_instruction(&auction, &state, &winner, 4_000);
+    assert_error(
+        h.send(settle, &[&winner]).await,
+        ShadowBidError::SettlementCallbackMismatch,
+    );
+
+    // ...nor redirected to another program
+    let mut settle = settle_with_callback(&auction, &state, 4_000, Vec::new());
+    let decoy = settle
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == LIQUIDATOR_ID)
+        .unwrap();
+    decoy.pubkey = mock_inco::ID;
+    assert_error(
+        h.send(settle, &[&winner]).await,
+        ShadowBidError::SettlementCallbackMismatch,
+    );
+}
@@ -0,0 +1,420 @@
+//! Property tests of the auction state machine
+//!
+//! Random sequences of create/bid/reveal/close/determine/finalize/settle/
+//! cancel with random signers and clock warps run against a few auctions.
+//! Most steps are expected to fail; after every step, successful or not,
+//! the on-chain state is checked against a model of the accepted bids:
+//!
+//! - `bids_processed <= bid_count`, and the counters match the model
+//! - the registry pages list exactly the Open auctions
+//! - only legal `AuctionState` transitions, so Settled and Cancelled are
+//!   never left
+//! - auctions with bids are only cancelled by finalize (commit-reveal
+//!   without a valid reveal), since the sellers post no bond
+//! - the winner holds the highest bid (highest valid reveal for
+//!   commit-reveal), the winning handle carries it, and settlement pays
+//!   exactly that amount
+//! - lamports are conserved across the seller, bidders, auctions, bids
+//!   and the registry and profile accounts they pay for
+//!
+//! Longer runs: `PROPTEST_CASES=2000 cargo test -p shadowbid --test state_machine`
+
+mod common;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use mock_inco::codec;
+use proptest::prelude::*;
+use shadowbid::state::{AuctionState, CancellationReason};
+use shadowbid_client::{instructions as ix, pda};
+use solana_sdk::signature::{Keypair, Signer};
+
+/// The seller (index 0) and three bidders
+const PARTICIPANTS: usize = 4;
+
+const MAX_AUCTIONS: usize = 2;
+
+const DEFAULT_CASES: u32 = 32;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Create { commit_reveal: bool },
+    Bid { auction: usize, bidder: usize, amount: u64 },
+    /// Reveals the `bid`-th accepted bid (modulo the number of bids)
+    Reveal { auction: usize, bid: usize, honest: bool },
+    CloseBidding { auction: usize },
+    CloseReveal { auction: usize },
+    /// Processes the `bid`-th unprocessed bid (modulo their number)
+    Determine { auction: usize, bid: usize },
+    Finalize { auction: usize, reveal_to_seller: bool },
+    /// Settles as the recorded winner, or as `signer` when that's None
+    Settle { auction: usize, signer: Option<usize> },
+    Cancel { auction: usize, signer: usize },
+    Warp { seconds: i64 },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let auction = 0..MAX_AUCTIONS;
+    let participant = 0..PARTICIPANTS;
+    prop_oneof![
+        1 => any::<bool>().prop_map(|commit_reveal| Op::Create { commit_reveal }),
+        5 => (auction.clone(), participant.clone(), 1..=3 * RESERVE_PRICE)
+            .prop_map(|(auction, bidder, amount)| Op::Bid { auction, bidder, amount }),
+        3 => (auction.clone(), 0..PARTICIPANTS, prop::bool::weighted(0.8))
+            .prop_map(|(auction, bid, honest)| Op::Reveal { auction, bid, honest }),
+        2 => auction.clone().prop_map(|auction| Op::CloseBidding { auction }),
+        2 => auction.clone().prop_map(|auction| Op::CloseReveal { auction }),
+        6 => (auction.clone(), 0..PARTICIPANTS)
+            .prop_map(|(auction, bid)| Op::Determine { auction, bid }),
+        3 => (auction.clone(), any::<bool>())
+            .prop_map(|(auction, reveal_to_seller)| Op::Finalize { auction, reveal_to_seller }),
+        2 => (auction.clone(), prop::option::weighted(0.3, participant.clone()))
+            .prop_map(|(auction, signer)| Op::Settle { auction, signer }),
+        1 => (auction, participant).prop_map(|(auction, signer)| Op::Cancel { auction, signer }),
+        3 => (0..=DURATION).prop_map(|seconds| Op::Warp { seconds }),
+    ]
+}
+
+/// Every sequence starts by creating an auction
+fn ops() -> impl Strategy<Value = Vec<Op>> {
+    (any::<bool>(), prop::collection::vec(op(), 16..80)).prop_map(|(commit_reveal, mut ops)| {
+        ops.insert(0, Op::Create { commit_reveal });
+        ops
+    })
+}
+
+/// What the program should have accepted for one auction
+struct AuctionModel {
+    address: Pubkey,
+    commit_reveal: bool,
+    /// Latest accepted bid per participant: amount and salt
+    bids: BTreeMap<usize, (u64, [u8; 32])>,
+    /// Accepted reveals per participant
+    revealed: BTreeMap<usize, u64>,
+    /// Participants whose bid was processed by determine_winner
+    processed: BTreeSet<usize>,
+    /// State after the previous step
+    state: AuctionState,
+}
+
+impl AuctionModel {
+    /// The amount that must win: the highest bid, or for commit-reveal
+    /// the highest reveal at or above the reserve price
+    fn best(&self) -> Option<u64> {
+        if self.commit_reveal {
+            self.revealed.values().copied().filter(|&amount| amount >= RESERVE_PRICE).max()
+        } else {
+            self.bids.values().map(|&(amount, _)| amount).max()
+        }
+    }
+
+    /// The participant with the `n`-th accepted bid, wrapping around
+    fn nth_bidder(&self, n: usize) -> usize {
+        match self.bids.len() {
+            0 => 1,
+            len => *self.bids.keys().nth(n % len).unwrap(),
+        }
+    }
+
+    /// The participant with the `n`-th unprocessed bid, wrapping around;
+    /// a processed bid once none are left
+    fn nth_unprocessed(&self, n: usize) -> usize {
+        let unprocessed: Vec<usize> = self
+            .bids
+            .keys()
+            .copied()
+            .filter(|bidder| !self.processed.contains(bidder))
+            .collect();
+        match unprocessed.len() {
+            0 => self.nth_bidder(n),
+            len => unprocessed[n % len],
+        }
+    }
+
+    fn amount_of(&self, participant: usize) -> Option<u64> {
+        if self.commit_reveal {
+            self.revealed.get(&participant).copied()
+        } else {
+            self.bids.get(&participant).map(|&(amount, _)| amount)
+        }
+    }
+}
+
+fn salt(bidder: usize, amount: u64) -> [u8; 32] {
+    let mut salt = [bidder as u8; 32];
+    salt[..8].copy_from_slice(&amount.to_le_bytes());
+    salt
+}
+
+fn is_legal(commit_reveal: bool, from: AuctionState, to: AuctionState) -> bool {
+    use AuctionState::*;
+    from == to
+        || matches!(
+            (from, to),
+            (Open, Cancelled)
+                | (Revealing, Closed)
+                | (Closed, WinnerDetermined)
+                | (Closed, Cancelled)
+                | (WinnerDetermined, Settled)
+        )
+        || (from, to) == (Open, if commit_reveal { Revealing } else { Closed })
+}
+
+struct Fuzzer {
+    h: Harness,
+    /// The seller, then the bidders
+    participants: Vec<Keypair>,
+    auctions: Vec<AuctionModel>,
+}
+
+impl Fuzzer {
+    async fn new() -> Self {
+        let mut h = Harness::new().await;
+        let mut participants = vec![h.seller.insecure_clone()];
+        participants.extend((1..PARTICIPANTS).map(|_| h.keypair()));
+        Self {
+            h,
+            participants,
+            auctions: Vec::new(),
+        }
+    }
+
+    /// Applies one step; failed transactions are ignored
+    async fn apply(&mut self, op: &Op) -> Result<(), TestCaseError> {
+        let (auction, op) = match *op {
+            Op::Create { commit_reveal } => {
+                if self.auctions.len() < MAX_AUCTIONS {
+                    let address = self.h.create(commit_reveal).await;
+                    self.auctions.push(AuctionModel {
+                        address,
+                        commit_reveal,
+                        bids: BTreeMap::new(),
+                        revealed: BTreeMap::new(),
+                        processed: BTreeSet::new(),
+                        state: AuctionState::Open,
+                    });
+                }
+                return Ok(());
+            }
+            Op::Warp { seconds } => {
+                let now = self.h.now().await;
+                self.h.warp(now + seconds).await;
+                return Ok(());
+            }
+            Op::Bid { auction, .. }
+            | Op::Reveal { auction, .. }
+            | Op::CloseBidding { auction }
+            | Op::CloseReveal { auction }
+            | Op::Determine { auction, .. }
+            | Op::Finalize { auction, .. }
+            | Op::Settle { auction, .. }
+            | Op::Cancel { auction, .. } => (auction % self.auctions.len(), op),
+        };
+
+        let h = &mut self.h;
+        let model = &mut self.auctions[auction];
+        let address = model.address;
+
+        match *op {
+            Op::Bid { bidder, amount, .. } => {
+                let keypair = &self.participants[bidder];
+                let salt = salt(bidder, amount);
+                let result = if model.commit_reveal {
+                    h.place_commitment(&address, keypair, amount, salt).await
+                } else {
+                    h.place_sealed_bid(&address, keypair, amount).await
+                };
+                if result.is_ok() {
+                    model.bids.insert(bidder, (amount, salt));
+                }
+            }
+            Op::Reveal { bid, honest, .. } => {
+                let bidder = model.nth_bidder(bid);
+                let (amount, salt) = model.bids.get(&bidder).copied().unwrap_or((1, [0; 32]));
+                let amount = if honest { amount } else { amount + 1 };
+                let result = h.reveal(&address, &self.participants[bidder], amount, salt).await;
+                if result.is_ok() {
+                    prop_assert!(honest, "reveal of an uncommitted amount accepted");
+                    model.revealed.insert(bidder, amount);
+                }
+            }
+            Op::CloseBidding { .. } => {
+                let _ = h.close_bidding(&address).await;
+            }
+            Op::CloseReveal { .. } => {
+                let _ = h.close_reveal(&address).await;
+            }
+            Op::Determine { bid, .. } => {
+                let bidder = model.nth_unprocessed(bid);
+                let result = h.determine(&address, &self.participants[bidder].pubkey()).await;
+                if result.is_ok() {
+                    prop_assert!(model.processed.insert(bidder), "bid processed twice");
+                }
+            }
+            Op::Finalize { reveal_to_seller, .. } => {
+                let _ = h.finalize(&address, reveal_to_seller).await;
+            }
+            Op::Settle { signer, .. } => {
+                let winner = h.auction(&address).await.winner;
+                let signer = signer.unwrap_or_else(|| {
+                    self.participants
+                        .iter()
+                        .position(|participant| participant.pubkey() == winner)
+                        .unwrap_or(1)
+                });
+                let keypair = &self.participants[signer];
+                if h.settle(&address, keypair).await.is_ok() {
+                    prop_assert_eq!(keypair.pubkey(), winner, "settled by a non-winner");
+                }
+            }
+            Op::Cancel { signer, .. } => {
+                let keypair = &self.participants[signer];
+                let state = h.auction(&address).await;
+                let instruction = ix::cancel_auction(
+                    &keypair.pubkey(),
+                    &address,
+                    &state,
+                    "fuzz".to_string(),
+                    CancellationReason::Other,
+                );
+                if h.send(instruction, &[keypair]).await.is_ok() {
+                    prop_assert_eq!(signer, 0, "cancelled by a non-seller");
+                }
+            }
+            Op::Create { .. } | Op::Warp { .. } => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Checks every auction against its model and advances the model state
+    async fn check_auctions(&mut self) -> Result<(), TestCaseError> {
+        let open = self.h.open_auctions().await;
+        for model in &mut self.auctions {
+            let state = self.h.auction(&model.address).await;
+
+            prop_assert_eq!(
+                open.contains(&model.address),
+                state.state().unwrap() == AuctionState::Open,
+                "registry pages out of sync with {:?}",
+                state.state().unwrap()
+            );
+
+            prop_assert!(state.bids_processed <= state.bid_count);
+            prop_assert_eq!(state.bids_processed as usize, model.processed.len());
+            prop_assert_eq!(state.bid_count as usize, model.bids.len());
+            prop_assert_eq!(state.bids_revealed as usize, model.revealed.len());
+            prop_assert!(
+                is_legal(model.commit_reveal, model.state, state.state().unwrap()),
+                "illegal transition {:?} -> {:?}",
+                model.state,
+                state.state().unwrap()
+            );
+
+            if matches!(state.state().unwrap(), AuctionState::WinnerDetermined | AuctionState::Settled) {
+                let winner = self
+                    .participants
+                    .iter()
+                    .position(|participant| participant.pubkey() == state.winner);
+                let best = model.best();
+                prop_assert!(best.is_some(), "winner determined without a valid bid");
+                prop_assert_eq!(winner.and_then(|winner| model.amount_of(winner)), best);
+                if !model.commit_reveal {
+                    prop_assert_eq!(codec::decrypt(state.highest_bid_handle), best);
+                }
+                if state.state().unwrap() == AuctionState::Settled {
+                    prop_assert_eq!(Some(state.winning_amount), best);
+                }
+            }
+
+            // Commit-reveal auctions with bids can't be cancelled by the
+            // seller, only by finalize when no reveal met the reserve
+            if model.commit_reveal
+                && (model.state, state.state().unwrap()) == (AuctionState::Closed, AuctionState::Cancelled)
+            {
+                prop_assert_eq!(model.best(), None);
+            }
+
+            // Harness auctions post no seller bond, so the first bid
+            // rules out the seller cancelling; sealed auctions with bids
+            // are never cancelled at all
+            if !model.bids.is_empty()
+                && model.state != AuctionState::Cancelled
+                && state.state().unwrap() == AuctionState::Cancelled
+            {
+                prop_assert!(
+                    model.commit_reveal && model.state == AuctionState::Closed,
+                    "auction with bids cancelled from {:?}",
+                    model.state
+                );
+            }
+
+            model.state = state.state().unwrap();
+        }
+        Ok(())
+    }
+
+    /// Lamports held by the participants, auctions, bid accounts, the
+    /// seller's registry accounts and everyone's profiles
+    ///
+    /// Fees and Inco allowance rent are paid by the harness payer, so
+    /// this only changes if the program creates or destroys lamports.
+    async fn funds(&mut self) -> u64 {
+        let mut addresses: Vec<Pubkey> = self.participants.iter().map(Keypair::pubkey).collect();
+        addresses.push(pda::seller_counter_address(&self.participants[0].pubkey()).0);
+        addresses.push(pda::seller_profile_address(&self.participants[0].pubkey()).0);
+        for participant in &self.participants {
+            addresses.push(pda::bidder_profile_address(&participant.pubkey()).0);
+        }
+        // MAX_AUCTIONS fit on the first page
+        addresses.push(pda::page_address(0).0);
+        for model in &self.auctions {
+            addresses.push(model.address);
+            for participant in &self.participants {
+                addresses.push(pda::bid_address(&model.address, &participant.pubkey()).0);
+            }
+        }
+
+        let mut total = 0;
+        for address in addresses {
+            total += self.h.lamports(&address).await;
+        }
+        total
+    }
+}
+
+async fn run(ops: Vec<Op>) -> Result<(), TestCaseError> {
+    let mut fuzzer = Fuzzer::new().await;
+    let funds = fuzzer.funds().await;
+
+    for op in &ops {
+        fuzzer.apply(op).await?;
+        fuzzer.check_auctions().await?;
+        prop_assert_eq!(fuzzer.funds().await, funds, "lamports changed after {:?}", op);
+    }
+    Ok(())
+}
+
+fn cases() -> u32 {
+    std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(DEFAULT_CASES)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: cases(),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn auction_invariants_hold(ops in ops()) {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(run(ops))?;
+    }
+}